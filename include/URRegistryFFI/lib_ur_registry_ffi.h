@@ -20,20 +20,42 @@ const char* parse_hd_path(struct ExternError*, const char* hd_path);
 // BTC
 const char* generate_crypto_psbt(struct ExternError*, const char* psbt_hex);
 const char* parse_crypto_psbt(struct ExternError*, const char* ur_type, const char* cbor_hex);
+const char* generate_crypto_psbt_extend(struct ExternError*, const char* psbt_hex, const int coin_id);
+const char* parse_crypto_psbt_extend(struct ExternError*, const char* ur_type, const char* cbor_hex);
 const char* generate_btc_sign_request(struct ExternError*, const char* request_id, const char* sign_data, const int data_type, const char* accounts, const char* origin);
 const char* parse_btc_signature(struct ExternError*, const char* ur_type, const char* cbor_hex);
+const char* verify_btc_message_signature(struct ExternError*, const char* ur_type, const char* cbor_hex, const char* message_hex, const char* address, const int data_type);
+const char* combine_psbt(struct ExternError*, const char* original_psbt_hex, const char* signed_psbt_hex);
+const char* finalize_psbt(struct ExternError*, const char* original_psbt_hex, const char* signed_psbt_hex);
 
 // ETH
 const char* generate_eth_sign_request(struct ExternError*, const char* request_id, const char* sign_data, const int data_type, const int chain_id, const char* path, const char* xfp, const char* address, const char* origin);
 const char* parse_eth_signature(struct ExternError*, const char* ur_type, const char* cbor_hex);
+const char* generate_eth_batch_sign_request(struct ExternError*, const char* requests);
+const char* parse_eth_batch_signature(struct ExternError*, const char* ur_type, const char* cbor_hex);
+const char* decode_eth_sign_request(struct ExternError*, const char* ur_type, const char* cbor_hex);
+const char* parse_eth_typed_data(struct ExternError*, const char* typed_data);
+const char* verify_eth_signature(struct ExternError*, const char* sign_request_cbor_hex, const char* ur_type, const char* cbor_hex);
+const char* assemble_eth_signed_transaction(struct ExternError*, const char* sign_request_cbor_hex, const char* ur_type, const char* cbor_hex);
+const char* register_eth_abi(struct ExternError*, const char* abi);
+const char* decode_eth_calldata(struct ExternError*, const char* ur_type, const char* cbor_hex);
+const char* get_evm_chain(struct ExternError*, const char* chain_id);
 
 // SOL
 const char* generate_sol_sign_request(struct ExternError*, const char* request_id, const char* sign_data, const char* path, const char* xfp, const char* address, const char* origin, const int sign_type);
 const char* parse_sol_signature(struct ExternError*, const char* ur_type, const char* cbor_hex);
+const char* decode_sol_sign_request(struct ExternError*, const char* ur_type, const char* cbor_hex);
+const char* generate_sol_offchain_message(struct ExternError*, const char* application_domain, const char* signers, const char* message);
+const char* verify_sol_offchain_message_signature(struct ExternError*, const char* sign_request_cbor_hex, const char* ur_type, const char* cbor_hex);
 
 // Cosmos
 const char* generate_cosmos_sign_request(struct ExternError*, const char* request_id, const char* sign_data, const int data_type, const char* accounts, const char* origin);
 const char* parse_cosmos_signature(struct ExternError*, const char* ur_type, const char* cbor_hex);
+const char* decode_cosmos_sign_request(struct ExternError*, const char* ur_type, const char* cbor_hex, const char* chain_id);
+const char* get_cosmos_chain(struct ExternError*, const char* chain_id);
+const char* register_cosmos_chain(struct ExternError*, const char* chain);
+const char* derive_cosmos_address(struct ExternError*, const char* public_key, const char* hrp, const char* key_type);
+const char* convert_cosmos_address(struct ExternError*, const char* address, const char* hrp);
 
 // Cosmos
 const char* generate_evm_sign_request(struct ExternError*, const char* request_id, const char* sign_data, const int data_type, const int custom_chain_identifier, const char* account, const char* origin);
@@ -46,10 +68,17 @@ const char* parse_tron_signature(struct ExternError*, const char* ur_type, const
 // Aptos
 const char* generate_aptos_sign_request(struct ExternError*, const char* request_id, const char* sign_data, const char* accounts, const char* origin, const int sign_type);
 const char* parse_aptos_signature(struct ExternError*, const char* ur_type, const char* cbor_hex);
+const char* decode_aptos_sign_request(struct ExternError*, const char* ur_type, const char* cbor_hex);
 
 // Sui
 const char* generate_sui_sign_request(struct ExternError*, const char* request_id, const char* intent_message, const char* accounts, const char* origin);
 const char* parse_sui_signature(struct ExternError*, const char* ur_type, const char* cbor_hex);
+const char* decode_sui_sign_request(struct ExternError*, const char* ur_type, const char* cbor_hex);
+
+// IOTA
+const char* generate_iota_sign_request(struct ExternError*, const char* request_id, const char* intent_message, const char* accounts, const char* origin);
+const char* generate_iota_sign_hash_request(struct ExternError*, const char* request_id, const char* message_hash, const char* accounts, const char* origin);
+const char* parse_iota_signature(struct ExternError*, const char* ur_type, const char* cbor_hex);
 
 // Keystone
 const char* generate_keystone_sign_request(struct ExternError*, const char* request_id, const int coin_type, const char* sign_data, const char* xfp, const char* origin, const int64_t timestamp);
@@ -58,6 +87,23 @@ const char* parse_keystone_sign_result(struct ExternError*, const char* ur_type,
 // Near
 const char* generate_near_sign_request(struct ExternError*, const char* request_id, const char* sign_data, const char* path, const char* xfp, const char* account, const char* origin);
 const char* parse_near_signature(struct ExternError*, const char* ur_type, const char* cbor_hex);
+const char* decode_near_sign_request(struct ExternError*, const char* ur_type, const char* cbor_hex);
+const char* generate_near_nep413_payload(struct ExternError*, const char* message, const char* nonce, const char* recipient, const char* callback_url);
+const char* verify_near_message_signature(struct ExternError*, const char* sign_request_cbor_hex, const char* public_key, const char* ur_type, const char* cbor_hex);
+
+// Ton
+const char* generate_ton_sign_request(struct ExternError*, const char* request_id, const char* sign_data, const int data_type, const char* address, const char* derivation_path, const char* xfp, const char* origin);
+const char* parse_ton_signature(struct ExternError*, const char* ur_type, const char* cbor_hex);
+const char* decode_ton_sign_request(struct ExternError*, const char* ur_type, const char* cbor_hex);
+const char* generate_ton_proof(struct ExternError*, const char* address, const char* domain, const int64_t timestamp, const char* payload);
+const char* verify_ton_proof(struct ExternError*, const char* sign_request_cbor_hex, const char* public_key, const char* ur_type, const char* cbor_hex);
+
+// Stellar
+const char* generate_stellar_sign_request(struct ExternError*, const char* request_id, const char* sign_data, const char* path, const char* xfp, const char* address, const char* origin, const int sign_type);
+const char* parse_stellar_signature(struct ExternError*, const char* ur_type, const char* cbor_hex);
+const char* decode_stellar_sign_request(struct ExternError*, const char* ur_type, const char* cbor_hex, const char* network);
+const char* get_stellar_transaction_hash(struct ExternError*, const char* envelope_hex, const char* network);
+const char* attach_stellar_signature(struct ExternError*, const char* sign_request_cbor_hex, const char* public_key, const char* network, const char* ur_type, const char* cbor_hex);
 
 // Arweave
 const char* parse_arweave_account(struct ExternError*, const char* ur_type, const char* cbor_hex);
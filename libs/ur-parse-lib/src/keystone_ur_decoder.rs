@@ -129,6 +129,9 @@ mod tests {
     use alloc::vec;
     use alloc::{string::String, vec::Vec};
     use ur_registry::crypto_psbt::CryptoPSBT;
+    use ur_registry::ethereum::eth_batch_signature::EthBatchSignature;
+    use ur_registry::ethereum::eth_signature::EthSignature;
+    use ur_registry::iota::iota_signature::IotaSignature;
     use ur_registry::ethereum::eth_sign_request::EthSignRequest;
     use ur_registry::sui::sui_sign_request::SuiSignRequest;
     use ur_registry::traits::RegistryItem;
@@ -231,6 +234,39 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_decode_eth_batch_signature() {
+        let signatures = vec![
+            EthSignature::new(Some(vec![1; 16]), vec![2; 65], None),
+            EthSignature::new(Some(vec![3; 16]), vec![4; 65], None),
+        ];
+        let cbor: Vec<u8> = EthBatchSignature::new(signatures).try_into().unwrap();
+        let encoded =
+            probe_encode(&cbor, 400, EthBatchSignature::get_registry_type().get_type()).unwrap();
+
+        assert!(!encoded.is_multi_part);
+        let decoded: URParseResult<EthBatchSignature> = probe_decode(encoded.data).unwrap();
+        assert_eq!(
+            decoded.ur_type.unwrap().get_type_str(),
+            "eth-batch-signature"
+        );
+        let signatures = decoded.data.unwrap().get_signatures().clone();
+        assert_eq!(2, signatures.len());
+        assert_eq!(Some(vec![3; 16]), signatures[1].get_request_id());
+    }
+
+    #[test]
+    fn test_decode_iota_signature() {
+        let signature = IotaSignature::new(Some(vec![1; 16]), vec![2; 64], Some(vec![3; 32]));
+        let cbor: Vec<u8> = signature.try_into().unwrap();
+        let encoded =
+            probe_encode(&cbor, 400, IotaSignature::get_registry_type().get_type()).unwrap();
+
+        let decoded: URParseResult<IotaSignature> = probe_decode(encoded.data).unwrap();
+        assert_eq!(decoded.ur_type.unwrap().get_type_str(), "iota-signature");
+        assert_eq!(Some(vec![3; 32]), decoded.data.unwrap().get_public_key());
+    }
+
     #[test]
     fn test_decode_sui_sign_request() {
         let ur3 = "UR:SUI-SIGN-REQUEST/1268-2/LPCFAAWKAOCFADTTCYBENTKOSPHDWLATROUTDAGDSAIOSPYKDYRTDLBDSAGWCAENTPNNVWMOAYCEWETPIDYTTADWGHBZWFKBHFGROTHLOEAHRSDLFRADKNGHAAVLGOAHHDEMMWYLBZBKWLLKDKGESBHFAHNBLSWLOXAALUBWFLSRCTWELFVOKKLEURENZTCSTIPRNTRSOTGEZTHNGLDIWTMYDSQDFDMDWYPDIYTEMKMSFMFHETSALFCAKIGUCFCTGDFDLNSOSFZEGLSSCTJSGEPSZCSTEYNSYTFESGJPGTDETSFDWETOCESNUEWSYAQZYANELGZCOEAOAOADAEDPDYAEADAEADADAOAXLYTPAXDYWZGABNTTRTRPOLWKCYAMLBNBLNONMWESETIATOSEAXKNYNTELUDWLSESYTUOAENERYZEWTQDWKQZDMLUAETOGYGTIHGMHTFZBNWTSOPYMWZEPRHTQDKTSBCFGMURIDLTWTWPLPUEWFKN";
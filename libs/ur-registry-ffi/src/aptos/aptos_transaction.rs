@@ -0,0 +1,625 @@
+use anyhow::{format_err, Error};
+use serde_json::{json, Value};
+use sha3::{Digest, Sha3_256};
+use ur_registry::aptos::aptos_sign_request::{AptosSignRequest, SignType};
+use ur_registry::registry_types::APTOS_SIGN_REQUEST;
+
+use crate::export;
+use crate::util_internal::bcs::BcsReader;
+use crate::util_internal::move_types::{format_address, format_short_address, TypeTag};
+
+const RAW_TRANSACTION_SALT: &[u8] = b"APTOS::RawTransaction";
+const RAW_TRANSACTION_WITH_DATA_SALT: &[u8] = b"APTOS::RawTransactionWithData";
+const ED25519_SCHEME: u8 = 0;
+
+/// The prefix Aptos prepends to BCS bytes before signing: `sha3_256(salt)`.
+pub fn signing_message_prefix(salt: &[u8]) -> [u8; 32] {
+    Sha3_256::digest(salt).into()
+}
+
+/// Derives the account address of a single Ed25519 public key.
+pub fn ed25519_address(public_key: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha3_256::new();
+    hasher.update(public_key);
+    hasher.update([ED25519_SCHEME]);
+    hasher.finalize().into()
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TransactionArgument {
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    U128(u128),
+    U256([u8; 32]),
+    Address([u8; 32]),
+    U8Vector(Vec<u8>),
+    Bool(bool),
+}
+
+impl TransactionArgument {
+    fn decode(reader: &mut BcsReader) -> Result<Self, Error> {
+        match reader.uleb128()? {
+            0 => Ok(TransactionArgument::U8(reader.u8()?)),
+            1 => Ok(TransactionArgument::U64(reader.u64()?)),
+            2 => Ok(TransactionArgument::U128(reader.u128()?)),
+            3 => Ok(TransactionArgument::Address(reader.fixed()?)),
+            4 => Ok(TransactionArgument::U8Vector(reader.bytes()?)),
+            5 => Ok(TransactionArgument::Bool(reader.bool()?)),
+            6 => Ok(TransactionArgument::U16(reader.u16()?)),
+            7 => Ok(TransactionArgument::U32(reader.u32()?)),
+            8 => Ok(TransactionArgument::U256(reader.fixed()?)),
+            x => Err(format_err!("unsupported transaction argument {x}")),
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        let (arg_type, value) = match self {
+            TransactionArgument::U8(v) => ("u8", json!(v)),
+            TransactionArgument::U16(v) => ("u16", json!(v)),
+            TransactionArgument::U32(v) => ("u32", json!(v)),
+            TransactionArgument::U64(v) => ("u64", json!(v.to_string())),
+            TransactionArgument::U128(v) => ("u128", json!(v.to_string())),
+            TransactionArgument::U256(v) => {
+                let mut be = *v;
+                be.reverse();
+                ("u256", json!(format!("0x{}", hex::encode(be))))
+            }
+            TransactionArgument::Address(v) => ("address", json!(format_address(v))),
+            TransactionArgument::U8Vector(v) => {
+                ("vector<u8>", json!(format!("0x{}", hex::encode(v))))
+            }
+            TransactionArgument::Bool(v) => ("bool", json!(v)),
+        };
+        json!({"type": arg_type, "value": value})
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct EntryFunction {
+    pub module_address: [u8; 32],
+    pub module_name: String,
+    pub function: String,
+    pub type_arguments: Vec<TypeTag>,
+    pub arguments: Vec<Vec<u8>>,
+}
+
+impl EntryFunction {
+    fn decode(reader: &mut BcsReader) -> Result<Self, Error> {
+        Ok(EntryFunction {
+            module_address: reader.fixed()?,
+            module_name: reader.string()?,
+            function: reader.string()?,
+            type_arguments: reader.vec(TypeTag::decode)?,
+            arguments: reader.vec(|r| r.bytes())?,
+        })
+    }
+
+    pub fn get_target(&self) -> String {
+        format!(
+            "{}::{}::{}",
+            format_short_address(&self.module_address),
+            self.module_name,
+            self.function
+        )
+    }
+
+    /// Resolves `0x1::aptos_account::transfer` and `0x1::coin::transfer` calls.
+    pub fn get_transfer(&self) -> Option<Value> {
+        let coin = match (self.get_target().as_str(), self.type_arguments.as_slice()) {
+            ("0x1::aptos_account::transfer", []) => "0x1::aptos_coin::AptosCoin".to_string(),
+            ("0x1::aptos_account::transfer_coins" | "0x1::coin::transfer", [coin]) => {
+                coin.to_string()
+            }
+            _ => return None,
+        };
+        match self.arguments.as_slice() {
+            [recipient, amount] => {
+                let recipient = <[u8; 32]>::try_from(recipient.as_slice()).ok()?;
+                let amount = <[u8; 8]>::try_from(amount.as_slice()).ok()?;
+                Some(json!({
+                    "recipient": format_address(&recipient),
+                    "amount": u64::from_le_bytes(amount).to_string(),
+                    "coin": coin,
+                }))
+            }
+            _ => None,
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "function": self.get_target(),
+            "type_arguments": self.type_arguments.iter().map(|t| t.to_string()).collect::<Vec<_>>(),
+            "arguments": self.arguments.iter().map(|a| format!("0x{}", hex::encode(a))).collect::<Vec<_>>(),
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TransactionPayload {
+    Script {
+        code: Vec<u8>,
+        type_arguments: Vec<TypeTag>,
+        arguments: Vec<TransactionArgument>,
+    },
+    EntryFunction(EntryFunction),
+    Multisig {
+        multisig_address: [u8; 32],
+        entry_function: Option<EntryFunction>,
+    },
+}
+
+impl TransactionPayload {
+    fn decode(reader: &mut BcsReader) -> Result<Self, Error> {
+        match reader.uleb128()? {
+            0 => Ok(TransactionPayload::Script {
+                code: reader.bytes()?,
+                type_arguments: reader.vec(TypeTag::decode)?,
+                arguments: reader.vec(TransactionArgument::decode)?,
+            }),
+            2 => Ok(TransactionPayload::EntryFunction(EntryFunction::decode(
+                reader,
+            )?)),
+            3 => Ok(TransactionPayload::Multisig {
+                multisig_address: reader.fixed()?,
+                entry_function: reader.option(|r| match r.uleb128()? {
+                    0 => EntryFunction::decode(r),
+                    x => Err(format_err!("unsupported multisig payload {x}")),
+                })?,
+            }),
+            x => Err(format_err!("unsupported transaction payload {x}")),
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        match self {
+            TransactionPayload::Script {
+                code,
+                type_arguments,
+                arguments,
+            } => json!({
+                "type": "script",
+                "code": hex::encode(code),
+                "type_arguments": type_arguments.iter().map(|t| t.to_string()).collect::<Vec<_>>(),
+                "arguments": arguments.iter().map(TransactionArgument::to_json).collect::<Vec<_>>(),
+            }),
+            TransactionPayload::EntryFunction(entry_function) => {
+                let mut result = entry_function.to_json();
+                result["type"] = json!("entry_function");
+                result
+            }
+            TransactionPayload::Multisig {
+                multisig_address,
+                entry_function,
+            } => json!({
+                "type": "multisig",
+                "multisig_address": format_address(multisig_address),
+                "entry_function": entry_function.as_ref().map(EntryFunction::to_json),
+            }),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RawTransaction {
+    pub sender: [u8; 32],
+    pub sequence_number: u64,
+    pub payload: TransactionPayload,
+    pub max_gas_amount: u64,
+    pub gas_unit_price: u64,
+    pub expiration_timestamp_secs: u64,
+    pub chain_id: u8,
+}
+
+impl RawTransaction {
+    fn decode(reader: &mut BcsReader) -> Result<Self, Error> {
+        Ok(RawTransaction {
+            sender: reader.fixed()?,
+            sequence_number: reader.u64()?,
+            payload: TransactionPayload::decode(reader)?,
+            max_gas_amount: reader.u64()?,
+            gas_unit_price: reader.u64()?,
+            expiration_timestamp_secs: reader.u64()?,
+            chain_id: reader.u8()?,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TransactionKind {
+    Single,
+    MultiAgent,
+    FeePayer,
+}
+
+impl TransactionKind {
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            TransactionKind::Single => "raw_transaction",
+            TransactionKind::MultiAgent => "multi_agent",
+            TransactionKind::FeePayer => "fee_payer",
+        }
+    }
+}
+
+/// A `RawTransaction` or `RawTransactionWithData` signing message.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AptosTransaction {
+    kind: TransactionKind,
+    raw_transaction: RawTransaction,
+    secondary_signers: Vec<[u8; 32]>,
+    fee_payer: Option<[u8; 32]>,
+    salted: bool,
+}
+
+impl AptosTransaction {
+    /// Decodes a signing message. Data with neither salt prefix is read as a bare
+    /// BCS `RawTransaction`.
+    pub fn decode(sign_data: &[u8]) -> Result<Self, Error> {
+        let raw_prefix = signing_message_prefix(RAW_TRANSACTION_SALT);
+        let with_data_prefix = signing_message_prefix(RAW_TRANSACTION_WITH_DATA_SALT);
+
+        let (with_data, salted, bytes) = if let Some(bytes) = sign_data.strip_prefix(&raw_prefix) {
+            (false, true, bytes)
+        } else if let Some(bytes) = sign_data.strip_prefix(&with_data_prefix) {
+            (true, true, bytes)
+        } else {
+            (false, false, sign_data)
+        };
+
+        let mut reader = BcsReader::new(bytes);
+        let kind = match with_data {
+            false => TransactionKind::Single,
+            true => match reader.uleb128()? {
+                0 => TransactionKind::MultiAgent,
+                1 => TransactionKind::FeePayer,
+                x => return Err(format_err!("unsupported raw transaction with data {x}")),
+            },
+        };
+        let raw_transaction = RawTransaction::decode(&mut reader)?;
+        let secondary_signers = match kind {
+            TransactionKind::Single => vec![],
+            _ => reader.vec(|r| r.fixed())?,
+        };
+        let fee_payer = match kind {
+            TransactionKind::FeePayer => Some(reader.fixed()?),
+            _ => None,
+        };
+        reader.finish()?;
+
+        Ok(AptosTransaction {
+            kind,
+            raw_transaction,
+            secondary_signers,
+            fee_payer,
+            salted,
+        })
+    }
+
+    /// Decodes the request sign data, checking that every single-key account in the
+    /// request is an Ed25519 public key.
+    pub fn from_sign_request(request: &AptosSignRequest) -> Result<Self, Error> {
+        if let SignType::Message = request.get_sign_type() {
+            return Err(format_err!("sign type is not a transaction"));
+        }
+        let transaction = Self::decode(&request.get_sign_data())?;
+        if matches!(request.get_sign_type(), SignType::Single)
+            && request
+                .get_accounts()
+                .unwrap_or_default()
+                .iter()
+                .any(|account| account.len() != 32)
+        {
+            return Err(format_err!("invalid account public key"));
+        }
+        Ok(transaction)
+    }
+
+    /// Warns when a single-key account in the request is not the sender, a secondary signer
+    /// or the fee payer. The address is derived from the public key, which only holds until
+    /// the account rotates its authentication key, so a mismatch cannot be told apart from
+    /// a rotated account and is not treated as an error.
+    pub fn get_signer_warning(&self, request: &AptosSignRequest) -> Option<String> {
+        if !matches!(request.get_sign_type(), SignType::Single) {
+            return None;
+        }
+        let signers = self.get_signers();
+        request
+            .get_accounts()
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|account| <[u8; 32]>::try_from(account.as_slice()).ok())
+            .any(|account| !signers.contains(&ed25519_address(&account)))
+            .then(|| "request account does not derive a transaction signer".to_string())
+    }
+
+    pub fn get_kind(&self) -> TransactionKind {
+        self.kind
+    }
+
+    pub fn get_raw_transaction(&self) -> RawTransaction {
+        self.raw_transaction.clone()
+    }
+
+    pub fn get_secondary_signers(&self) -> Vec<[u8; 32]> {
+        self.secondary_signers.clone()
+    }
+
+    pub fn get_fee_payer(&self) -> Option<[u8; 32]> {
+        self.fee_payer
+    }
+
+    pub fn is_salted(&self) -> bool {
+        self.salted
+    }
+
+    /// The sender, then secondary signers, then the fee payer.
+    pub fn get_signers(&self) -> Vec<[u8; 32]> {
+        [self.raw_transaction.sender]
+            .into_iter()
+            .chain(self.secondary_signers.iter().copied())
+            .chain(self.fee_payer)
+            .collect()
+    }
+
+    pub fn to_json(&self) -> Value {
+        let raw = &self.raw_transaction;
+        let transfer = match &raw.payload {
+            TransactionPayload::EntryFunction(entry_function) => entry_function.get_transfer(),
+            _ => None,
+        };
+        json!({
+            "type": self.kind.get_name(),
+            "salted": self.salted,
+            "sender": format_address(&raw.sender),
+            "sequence_number": raw.sequence_number.to_string(),
+            "payload": raw.payload.to_json(),
+            "max_gas_amount": raw.max_gas_amount.to_string(),
+            "gas_unit_price": raw.gas_unit_price.to_string(),
+            "expiration_timestamp_secs": raw.expiration_timestamp_secs.to_string(),
+            "chain_id": raw.chain_id,
+            "secondary_signers": self.secondary_signers.iter().map(format_address).collect::<Vec<_>>(),
+            "fee_payer": self.fee_payer.as_ref().map(format_address),
+            "transfer": transfer,
+        })
+    }
+}
+
+export! {
+    @Java_com_keystone_sdk_KeystoneNativeSDK_decodeAptosSignRequest
+    fn decode_aptos_sign_request(ur_type: &str, cbor_hex: &str) -> String {
+        if APTOS_SIGN_REQUEST.get_type() != ur_type {
+            return json!({"error": "type not match"}).to_string();
+        }
+
+        let decode = || -> Result<Value, Error> {
+            let cbor = hex::decode(cbor_hex)?;
+            let request = AptosSignRequest::try_from(cbor).map_err(|e| format_err!(e.to_string()))?;
+            let transaction = AptosTransaction::from_sign_request(&request)?;
+            let mut result = transaction.to_json();
+            if let Some(warning) = transaction.get_signer_warning(&request) {
+                result["warning"] = json!(warning);
+            }
+            Ok(result)
+        };
+        match decode() {
+            Ok(transaction) => transaction.to_string(),
+            Err(e) => json!({"error": e.to_string()}).to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util_internal::ed25519::sign_ed25519;
+    use ur_registry::crypto_key_path::CryptoKeyPath;
+
+    fn framework() -> [u8; 32] {
+        let mut address = [0u8; 32];
+        address[31] = 1;
+        address
+    }
+
+    fn raw_transaction(sender: &[u8; 32]) -> Vec<u8> {
+        let mut bytes = sender.to_vec();
+        bytes.extend_from_slice(&7u64.to_le_bytes());
+        // 0x1::coin::transfer<0x1::aptos_coin::AptosCoin>(recipient, 1000)
+        bytes.push(2);
+        bytes.extend_from_slice(&framework());
+        bytes.extend_from_slice(b"\x04coin\x08transfer\x01\x07");
+        bytes.extend_from_slice(&framework());
+        bytes.extend_from_slice(b"\x0aaptos_coin\x09AptosCoin\x00\x02\x20");
+        bytes.extend_from_slice(&[0x22; 32]);
+        bytes.push(8);
+        bytes.extend_from_slice(&1000u64.to_le_bytes());
+        bytes.extend_from_slice(&2000u64.to_le_bytes());
+        bytes.extend_from_slice(&100u64.to_le_bytes());
+        bytes.extend_from_slice(&1700000000u64.to_le_bytes());
+        bytes.push(1);
+        bytes
+    }
+
+    fn sign_request(sign_data: &[u8], key: &[u8], sign_type: SignType) -> String {
+        let path = CryptoKeyPath::from_path("m/44'/637'/0'/0'/0'".to_string(), None).unwrap();
+        let request = AptosSignRequest::new(
+            vec![],
+            sign_data.to_vec(),
+            vec![path],
+            Some(vec![key.to_vec()]),
+            None,
+            sign_type,
+        );
+        let cbor: Vec<u8> = request.try_into().unwrap();
+        hex::encode(cbor)
+    }
+
+    #[test]
+    fn test_signing_message_prefix() {
+        assert_eq!(
+            "b5e97db07fa0bd0e5598aa3643a9bc6f6693bddc1a9fec9e674a461eaa00b193",
+            hex::encode(signing_message_prefix(RAW_TRANSACTION_SALT))
+        );
+        assert_eq!(
+            "5efa3c4f02f83a0f4b2d69fc95c607cc02825cc4e7be536ef0992df050d9e67c",
+            hex::encode(signing_message_prefix(RAW_TRANSACTION_WITH_DATA_SALT))
+        );
+    }
+
+    #[test]
+    fn test_decode_aptos_sign_request() {
+        let (key, _) = sign_ed25519(&[1u8; 32], b"");
+        let sender = ed25519_address(&key);
+        let sign_data = [
+            signing_message_prefix(RAW_TRANSACTION_SALT).as_slice(),
+            &raw_transaction(&sender),
+        ]
+        .concat();
+
+        let result: Value = serde_json::from_str(&decode_aptos_sign_request(
+            "aptos-sign-request",
+            &sign_request(&sign_data, &key, SignType::Single),
+        ))
+        .unwrap();
+        assert_eq!("raw_transaction", result["type"]);
+        assert_eq!(true, result["salted"]);
+        assert_eq!(format_address(&sender), result["sender"]);
+        assert_eq!("7", result["sequence_number"]);
+        assert_eq!(
+            json!({
+                "type": "entry_function",
+                "function": "0x1::coin::transfer",
+                "type_arguments": ["0x1::aptos_coin::AptosCoin"],
+                "arguments": [format!("0x{}", "22".repeat(32)), "0xe803000000000000"],
+            }),
+            result["payload"]
+        );
+        assert_eq!(
+            json!({
+                "recipient": format!("0x{}", "22".repeat(32)),
+                "amount": "1000",
+                "coin": "0x1::aptos_coin::AptosCoin",
+            }),
+            result["transfer"]
+        );
+        assert_eq!("1700000000", result["expiration_timestamp_secs"]);
+        assert_eq!(1, result["chain_id"]);
+        assert_eq!(Value::Null, result["warning"]);
+
+        let unsalted = AptosTransaction::decode(&raw_transaction(&sender)).unwrap();
+        assert!(!unsalted.is_salted());
+        assert_eq!(2000, unsalted.get_raw_transaction().max_gas_amount);
+    }
+
+    #[test]
+    fn test_decode_fee_payer_transaction() {
+        let (key, _) = sign_ed25519(&[2u8; 32], b"");
+        let fee_payer = ed25519_address(&key);
+        let mut sign_data = signing_message_prefix(RAW_TRANSACTION_WITH_DATA_SALT).to_vec();
+        sign_data.push(1);
+        sign_data.extend_from_slice(&raw_transaction(&[0x33; 32]));
+        sign_data.push(1);
+        sign_data.extend_from_slice(&[0x44; 32]);
+        sign_data.extend_from_slice(&fee_payer);
+
+        let transaction = AptosTransaction::decode(&sign_data).unwrap();
+        assert_eq!(TransactionKind::FeePayer, transaction.get_kind());
+        assert_eq!(vec![[0x44; 32]], transaction.get_secondary_signers());
+        assert_eq!(Some(fee_payer), transaction.get_fee_payer());
+
+        let result: Value = serde_json::from_str(&decode_aptos_sign_request(
+            "aptos-sign-request",
+            &sign_request(&sign_data, &key, SignType::Single),
+        ))
+        .unwrap();
+        assert_eq!("fee_payer", result["type"]);
+        assert_eq!(format_address(&fee_payer), result["fee_payer"]);
+
+        let mut multi_agent = sign_data[..sign_data.len() - 32].to_vec();
+        multi_agent[32] = 0;
+        let transaction = AptosTransaction::decode(&multi_agent).unwrap();
+        assert_eq!(TransactionKind::MultiAgent, transaction.get_kind());
+        assert_eq!(None, transaction.get_fee_payer());
+        assert_eq!(2, transaction.get_signers().len());
+    }
+
+    #[test]
+    fn test_decode_script_transaction() {
+        let mut bytes = [0x55; 32].to_vec();
+        bytes.extend_from_slice(&0u64.to_le_bytes());
+        // A script with type argument `vector<u64>` and arguments (address, true).
+        bytes.extend_from_slice(&[0, 2, 0xa1, 0x1c, 1, 6, 2, 2, 3]);
+        bytes.extend_from_slice(&[0x66; 32]);
+        bytes.extend_from_slice(b"\x05\x01");
+        bytes.extend_from_slice(&[0u8; 25]);
+
+        let result = AptosTransaction::decode(&bytes).unwrap().to_json();
+        assert_eq!(
+            json!({
+                "type": "script",
+                "code": "a11c",
+                "type_arguments": ["vector<u64>"],
+                "arguments": [
+                    {"type": "address", "value": format!("0x{}", "66".repeat(32))},
+                    {"type": "bool", "value": true},
+                ],
+            }),
+            result["payload"]
+        );
+        assert_eq!(Value::Null, result["transfer"]);
+    }
+
+    #[test]
+    fn test_decode_aptos_sign_request_error() {
+        let (key, _) = sign_ed25519(&[1u8; 32], b"");
+        let sign_data = raw_transaction(&[0x33; 32]);
+        // The sender may have rotated its authentication key.
+        let result: Value = serde_json::from_str(&decode_aptos_sign_request(
+            "aptos-sign-request",
+            &sign_request(&sign_data, &key, SignType::Single),
+        ))
+        .unwrap();
+        assert_eq!(format!("0x{}", "33".repeat(32)), result["sender"]);
+        assert_eq!(
+            "request account does not derive a transaction signer",
+            result["warning"]
+        );
+        assert_eq!(
+            "{\"error\":\"invalid account public key\"}",
+            decode_aptos_sign_request(
+                "aptos-sign-request",
+                &sign_request(&sign_data, &key[..31], SignType::Single)
+            )
+        );
+        assert_eq!(
+            "{\"error\":\"sign type is not a transaction\"}",
+            decode_aptos_sign_request(
+                "aptos-sign-request",
+                &sign_request(&sign_data, &key, SignType::Message)
+            )
+        );
+        assert_eq!(
+            "{\"error\":\"type not match\"}",
+            decode_aptos_sign_request(
+                "aptos-signature",
+                &sign_request(&sign_data, &key, SignType::Single)
+            )
+        );
+        assert_eq!(
+            "unexpected trailing bytes",
+            AptosTransaction::decode(&[sign_data.as_slice(), &[0]].concat())
+                .unwrap_err()
+                .to_string()
+        );
+        let mut module_bundle = sign_data.clone();
+        module_bundle[40] = 1;
+        assert_eq!(
+            "unsupported transaction payload 1",
+            AptosTransaction::decode(&module_bundle)
+                .unwrap_err()
+                .to_string()
+        );
+    }
+}
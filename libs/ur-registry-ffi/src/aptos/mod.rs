@@ -1,2 +1,3 @@
 pub mod aptos_sign_request;
 pub mod aptos_signature;
+pub mod aptos_transaction;
@@ -0,0 +1,637 @@
+use anyhow::{format_err, Error};
+use bitcoin::bech32::{self, u5, ToBase32, Variant};
+use bitcoin::consensus::{deserialize, Decodable};
+use bitcoin::util::base58;
+use bitcoin::{Script, Transaction, TxOut, VarInt};
+use serde_json::{json, Value};
+use ur_registry::crypto_psbt_extend::{CryptoPSBTExtend, SupportedPsbtCoin};
+use ur_registry::registry_types::CRYPTO_PSBT_EXTEND;
+
+use crate::export;
+use crate::util_internal::string_helper::remove_prefix_0x;
+
+const PSBT_MAGIC: [u8; 5] = [0x70, 0x73, 0x62, 0x74, 0xff];
+const PSBT_GLOBAL_UNSIGNED_TX: u8 = 0x00;
+const PSBT_IN_NON_WITNESS_UTXO: u8 = 0x00;
+const PSBT_IN_WITNESS_UTXO: u8 = 0x01;
+const PSBT_IN_PARTIAL_SIG: u8 = 0x02;
+const PSBT_IN_SIGHASH_TYPE: u8 = 0x03;
+const SIGHASH_FORKID: u32 = 0x40;
+
+/// Address and signing rules of a coin that can be carried in a `crypto-psbt-extend`.
+pub struct CoinParams {
+    pub name: &'static str,
+    pub p2pkh_prefix: u8,
+    pub p2sh_prefix: u8,
+    pub bech32_hrp: Option<&'static str>,
+    pub cash_addr_prefix: Option<&'static str>,
+    pub fork_id: bool,
+}
+
+pub fn coin_params(coin: SupportedPsbtCoin) -> CoinParams {
+    match coin {
+        SupportedPsbtCoin::Bitcoin => CoinParams {
+            name: "BTC",
+            p2pkh_prefix: 0x00,
+            p2sh_prefix: 0x05,
+            bech32_hrp: Some("bc"),
+            cash_addr_prefix: None,
+            fork_id: false,
+        },
+        SupportedPsbtCoin::Litecoin => CoinParams {
+            name: "LTC",
+            p2pkh_prefix: 0x30,
+            p2sh_prefix: 0x32,
+            bech32_hrp: Some("ltc"),
+            cash_addr_prefix: None,
+            fork_id: false,
+        },
+        SupportedPsbtCoin::Dogecoin => CoinParams {
+            name: "DOGE",
+            p2pkh_prefix: 0x1e,
+            p2sh_prefix: 0x16,
+            bech32_hrp: None,
+            cash_addr_prefix: None,
+            fork_id: false,
+        },
+        SupportedPsbtCoin::Dash => CoinParams {
+            name: "DASH",
+            p2pkh_prefix: 0x4c,
+            p2sh_prefix: 0x10,
+            bech32_hrp: None,
+            cash_addr_prefix: None,
+            fork_id: false,
+        },
+        SupportedPsbtCoin::BitcoinCash => CoinParams {
+            name: "BCH",
+            p2pkh_prefix: 0x00,
+            p2sh_prefix: 0x05,
+            bech32_hrp: None,
+            cash_addr_prefix: Some("bitcoincash"),
+            fork_id: true,
+        },
+    }
+}
+
+fn cash_addr_polymod(values: &[u8]) -> u64 {
+    let generators: [u64; 5] = [
+        0x98f2bc8e61,
+        0x79b76d99e2,
+        0xf33e5fb3c4,
+        0xae2eabe2a8,
+        0x1e4f43e470,
+    ];
+    let mut c: u64 = 1;
+    for value in values {
+        let c0 = (c >> 35) as u8;
+        c = ((c & 0x07ffffffff) << 5) ^ (*value as u64);
+        for (i, generator) in generators.iter().enumerate() {
+            if c0 & (1 << i) != 0 {
+                c ^= generator;
+            }
+        }
+    }
+    c ^ 1
+}
+
+/// Encodes a 160-bit hash as a CashAddr; `type_bit` is 0 for P2PKH and 1 for P2SH.
+pub fn encode_cash_addr(prefix: &str, type_bit: u8, hash: &[u8]) -> String {
+    let mut payload = vec![type_bit << 3];
+    payload.extend_from_slice(hash);
+    let payload = payload
+        .to_base32()
+        .into_iter()
+        .map(|v| v.to_u8())
+        .collect::<Vec<u8>>();
+    let mut checked = prefix.bytes().map(|b| b & 0x1f).collect::<Vec<u8>>();
+    checked.push(0);
+    checked.extend_from_slice(&payload);
+    checked.extend_from_slice(&[0; 8]);
+    let checksum = cash_addr_polymod(&checked);
+    let charset = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+    let encoded = payload
+        .iter()
+        .copied()
+        .chain((0..8).map(|i| ((checksum >> (5 * (7 - i))) & 0x1f) as u8))
+        .map(|v| charset[v as usize] as char)
+        .collect::<String>();
+    format!("{prefix}:{encoded}")
+}
+
+/// Renders an output script as an address of `coin`, or `None` for scripts without one.
+pub fn script_to_address(script: &Script, coin: SupportedPsbtCoin) -> Option<String> {
+    let params = coin_params(coin);
+    let bytes = script.as_bytes();
+    let (hash, is_p2sh) = if script.is_p2pkh() {
+        (&bytes[3..23], false)
+    } else if script.is_p2sh() {
+        (&bytes[2..22], true)
+    } else if script.is_witness_program() {
+        let hrp = params.bech32_hrp?;
+        let version = script.witness_version()?.to_num();
+        let mut data = vec![u5::try_from_u8(version).ok()?];
+        data.extend((&bytes[2..]).to_base32());
+        let variant = if version == 0 {
+            Variant::Bech32
+        } else {
+            Variant::Bech32m
+        };
+        return bech32::encode(hrp, data, variant).ok();
+    } else {
+        return None;
+    };
+    if let Some(prefix) = params.cash_addr_prefix {
+        return Some(encode_cash_addr(prefix, is_p2sh as u8, hash));
+    }
+    let mut data = vec![if is_p2sh {
+        params.p2sh_prefix
+    } else {
+        params.p2pkh_prefix
+    }];
+    data.extend_from_slice(hash);
+    Some(base58::check_encode_slice(&data))
+}
+
+#[derive(Default)]
+struct RawInput {
+    non_witness_utxo: Option<Transaction>,
+    witness_utxo: Option<TxOut>,
+    sighash_type: Option<u32>,
+    signature_sighashes: Vec<u32>,
+}
+
+/// A PSBT read with the rules of the coin it was requested for.
+///
+/// rust-bitcoin rejects the non-standard sighash byte BCH signatures carry, so the
+/// key-value maps are read here directly and only the fields needed for review are kept.
+pub struct AltcoinPsbt {
+    coin: SupportedPsbtCoin,
+    unsigned_tx: Transaction,
+    inputs: Vec<RawInput>,
+}
+
+type KeyValue<'a> = (&'a [u8], &'a [u8]);
+
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn read_var_bytes(&mut self) -> Result<&'a [u8], Error> {
+        let VarInt(len) =
+            VarInt::consensus_decode(&mut self.data).map_err(|_| format_err!("PSBT is invalid"))?;
+        let len = len as usize;
+        if self.data.len() < len {
+            return Err(format_err!("PSBT is invalid"));
+        }
+        let (bytes, rest) = self.data.split_at(len);
+        self.data = rest;
+        Ok(bytes)
+    }
+
+    /// Reads one key-value pair, `None` at the map separator.
+    fn read_pair(&mut self) -> Result<Option<KeyValue<'a>>, Error> {
+        let key = self.read_var_bytes()?;
+        if key.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some((key, self.read_var_bytes()?)))
+    }
+}
+
+impl AltcoinPsbt {
+    pub fn parse(psbt: &[u8], coin: SupportedPsbtCoin) -> Result<Self, Error> {
+        let data = psbt
+            .strip_prefix(&PSBT_MAGIC)
+            .ok_or_else(|| format_err!("PSBT is invalid"))?;
+        let mut reader = Reader { data };
+        let mut unsigned_tx = None;
+        while let Some((key, value)) = reader.read_pair()? {
+            if key == [PSBT_GLOBAL_UNSIGNED_TX] {
+                unsigned_tx = Some(
+                    deserialize::<Transaction>(value)
+                        .map_err(|_| format_err!("PSBT is invalid"))?,
+                );
+            }
+        }
+        let unsigned_tx = unsigned_tx.ok_or_else(|| format_err!("PSBT is invalid"))?;
+        let mut inputs = Vec::with_capacity(unsigned_tx.input.len());
+        for _ in 0..unsigned_tx.input.len() {
+            let mut input = RawInput::default();
+            while let Some((key, value)) = reader.read_pair()? {
+                match key[0] {
+                    PSBT_IN_NON_WITNESS_UTXO if key.len() == 1 => {
+                        input.non_witness_utxo =
+                            Some(deserialize(value).map_err(|_| format_err!("PSBT is invalid"))?);
+                    }
+                    PSBT_IN_WITNESS_UTXO if key.len() == 1 => {
+                        input.witness_utxo =
+                            Some(deserialize(value).map_err(|_| format_err!("PSBT is invalid"))?);
+                    }
+                    PSBT_IN_PARTIAL_SIG => {
+                        let sighash = value.last().ok_or_else(|| format_err!("PSBT is invalid"))?;
+                        input.signature_sighashes.push(*sighash as u32);
+                    }
+                    PSBT_IN_SIGHASH_TYPE if value.len() == 4 => {
+                        input.sighash_type = Some(u32::from_le_bytes(value.try_into()?));
+                    }
+                    _ => {}
+                }
+            }
+            inputs.push(input);
+        }
+        for _ in 0..unsigned_tx.output.len() {
+            while reader.read_pair()?.is_some() {}
+        }
+        Ok(AltcoinPsbt {
+            coin,
+            unsigned_tx,
+            inputs,
+        })
+    }
+
+    pub fn get_coin(&self) -> SupportedPsbtCoin {
+        self.coin
+    }
+
+    pub fn get_unsigned_tx(&self) -> &Transaction {
+        &self.unsigned_tx
+    }
+
+    pub fn spent_output(&self, index: usize) -> Result<TxOut, Error> {
+        let input = &self.inputs[index];
+        let prevout = self.unsigned_tx.input[index].previous_output;
+        if let Some(tx) = &input.non_witness_utxo {
+            if tx.txid() != prevout.txid {
+                return Err(format_err!(
+                    "input {} has a mismatched previous transaction",
+                    index
+                ));
+            }
+            return tx
+                .output
+                .get(prevout.vout as usize)
+                .cloned()
+                .ok_or_else(|| format_err!("input {} has no utxo information", index));
+        }
+        input
+            .witness_utxo
+            .clone()
+            .ok_or_else(|| format_err!("input {} has no utxo information", index))
+    }
+
+    pub fn fee(&self) -> Result<u64, Error> {
+        let mut total_input: u64 = 0;
+        for index in 0..self.inputs.len() {
+            total_input = total_input
+                .checked_add(self.spent_output(index)?.value)
+                .ok_or_else(|| format_err!("input amount overflow"))?;
+        }
+        let total_output = self
+            .unsigned_tx
+            .output
+            .iter()
+            .try_fold(0u64, |sum, output| sum.checked_add(output.value))
+            .ok_or_else(|| format_err!("output amount overflow"))?;
+        total_input
+            .checked_sub(total_output)
+            .ok_or_else(|| format_err!("outputs exceed inputs"))
+    }
+
+    /// Checks that scripts, sighash flags and amounts are valid for the requested coin.
+    pub fn validate(&self) -> Result<(), Error> {
+        let params = coin_params(self.coin);
+        for index in 0..self.inputs.len() {
+            let spent = self.spent_output(index)?;
+            if spent.script_pubkey.is_witness_program() && params.bech32_hrp.is_none() {
+                return Err(format_err!(
+                    "{} does not support segwit inputs",
+                    params.name
+                ));
+            }
+            let input = &self.inputs[index];
+            let sighashes = input
+                .sighash_type
+                .iter()
+                .chain(input.signature_sighashes.iter());
+            for sighash in sighashes {
+                let has_fork_id = sighash & SIGHASH_FORKID != 0;
+                if params.fork_id && !has_fork_id {
+                    return Err(format_err!(
+                        "input {} sighash is missing SIGHASH_FORKID",
+                        index
+                    ));
+                }
+                if !params.fork_id && has_fork_id {
+                    return Err(format_err!(
+                        "input {} sighash must not set SIGHASH_FORKID for {}",
+                        index,
+                        params.name
+                    ));
+                }
+            }
+        }
+        for output in &self.unsigned_tx.output {
+            if output.script_pubkey.is_witness_program() && params.bech32_hrp.is_none() {
+                return Err(format_err!(
+                    "{} does not support segwit outputs",
+                    params.name
+                ));
+            }
+        }
+        self.fee()?;
+        Ok(())
+    }
+
+    pub fn to_json(&self) -> Result<Value, Error> {
+        self.validate()?;
+        let inputs = (0..self.inputs.len())
+            .map(|index| {
+                let spent = self.spent_output(index)?;
+                Ok(json!({
+                    "previous_output": self.unsigned_tx.input[index].previous_output.to_string(),
+                    "address": script_to_address(&spent.script_pubkey, self.coin),
+                    "amount": spent.value,
+                    "signed": !self.inputs[index].signature_sighashes.is_empty(),
+                }))
+            })
+            .collect::<Result<Vec<Value>, Error>>()?;
+        let outputs = self
+            .unsigned_tx
+            .output
+            .iter()
+            .map(|output| {
+                json!({
+                    "address": script_to_address(&output.script_pubkey, self.coin),
+                    "script": hex::encode(output.script_pubkey.as_bytes()),
+                    "amount": output.value,
+                })
+            })
+            .collect::<Vec<Value>>();
+        Ok(json!({
+            "coin": coin_params(self.coin).name,
+            "inputs": inputs,
+            "outputs": outputs,
+            "fee": self.fee()?,
+        }))
+    }
+}
+
+export! {
+    @Java_com_keystone_sdk_KeystoneNativeSDK_parseCryptoPSBTExtend
+    fn parse_crypto_psbt_extend(ur_type: &str, cbor_hex: &str) -> String {
+        if CRYPTO_PSBT_EXTEND.get_type() != ur_type {
+            return json!({"error": "type not match"}).to_string();
+        }
+
+        let parse = || -> Result<Value, Error> {
+            let cbor = hex::decode(remove_prefix_0x(cbor_hex))?;
+            let extend = CryptoPSBTExtend::try_from(cbor).map_err(|_| format_err!("PSBT is invalid"))?;
+            let coin = SupportedPsbtCoin::from_coin_id(extend.get_coin_id());
+            let psbt = AltcoinPsbt::parse(&extend.get_psbt(), coin)?;
+            let mut result = psbt.to_json()?;
+            result["psbt"] = json!(hex::encode(extend.get_psbt()));
+            Ok(result)
+        };
+        match parse() {
+            Ok(v) => v.to_string(),
+            Err(e) => json!({"error": e.to_string()}).to_string(),
+        }
+    }
+
+    @Java_com_keystone_sdk_KeystoneNativeSDK_generateCryptoPSBTExtend
+    fn generate_crypto_psbt_extend(psbt_hex: &str, coin_id: i32) -> String {
+        let cbor_hex = || -> Result<String, Error> {
+            let psbt = hex::decode(remove_prefix_0x(psbt_hex))?;
+            let coin_id = coin_id as i128;
+            AltcoinPsbt::parse(&psbt, SupportedPsbtCoin::from_coin_id(Some(coin_id)))?.validate()?;
+            let extend = CryptoPSBTExtend::new(psbt, Some(coin_id))
+                .map_err(|_| format_err!("coin id is not supported"))?;
+            let cbor: Vec<u8> = extend.try_into()?;
+            Ok(hex::encode(cbor))
+        };
+        match cbor_hex() {
+            Ok(v) => json!({
+                "type": CRYPTO_PSBT_EXTEND.get_type(),
+                "cbor": v,
+            }).to_string(),
+            Err(e) => json!({"error": e.to_string()}).to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitcoin::consensus::serialize;
+    use bitcoin::hashes::Hash;
+    use bitcoin::psbt::PartiallySignedTransaction;
+    use bitcoin::{
+        OutPoint, PackedLockTime, PubkeyHash, ScriptHash, Sequence, TxIn, WPubkeyHash, Witness,
+    };
+
+    fn p2pkh(byte: u8) -> Script {
+        Script::new_p2pkh(&PubkeyHash::from_inner([byte; 20]))
+    }
+
+    fn build_psbt(spent: Script, outputs: Vec<TxOut>, sighash: Option<u8>) -> Vec<u8> {
+        let previous = Transaction {
+            version: 2,
+            lock_time: PackedLockTime::ZERO,
+            input: vec![],
+            output: vec![TxOut {
+                value: 100_000,
+                script_pubkey: spent,
+            }],
+        };
+        let tx = Transaction {
+            version: 2,
+            lock_time: PackedLockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: OutPoint::new(previous.txid(), 0),
+                script_sig: Script::new(),
+                sequence: Sequence::MAX,
+                witness: Witness::new(),
+            }],
+            output: outputs,
+        };
+        let mut psbt = PartiallySignedTransaction::from_unsigned_tx(tx).unwrap();
+        psbt.inputs[0].non_witness_utxo = Some(previous);
+        let mut bytes = serialize(&psbt);
+        if let Some(sighash) = sighash {
+            // append a partial signature to the input map, right before its separator
+            let input_start = bytes.len() - 2;
+            let mut pair = vec![34, PSBT_IN_PARTIAL_SIG];
+            pair.extend_from_slice(&[0x02; 33]);
+            pair.push(9);
+            pair.extend_from_slice(&[0x30, 0x06, 0x02, 0x01, 0x01, 0x02, 0x01, 0x01, sighash]);
+            bytes.splice(input_start..input_start, pair);
+        }
+        bytes
+    }
+
+    #[test]
+    fn test_script_to_address() {
+        let p2wpkh = Script::new_v0_p2wpkh(
+            &WPubkeyHash::from_slice(
+                &hex::decode("751e76e8199196d454941c45d1b3a323f1433bd6").unwrap(),
+            )
+            .unwrap(),
+        );
+        assert_eq!(
+            "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+            script_to_address(&p2wpkh, SupportedPsbtCoin::Bitcoin).unwrap()
+        );
+        assert_eq!(
+            "ltc1qw508d6qejxtdg4y5r3zarvary0c5xw7kgmn4n9",
+            script_to_address(&p2wpkh, SupportedPsbtCoin::Litecoin).unwrap()
+        );
+        assert_eq!(
+            None,
+            script_to_address(&p2wpkh, SupportedPsbtCoin::Dogecoin)
+        );
+
+        let legacy = Script::new_p2pkh(
+            &PubkeyHash::from_slice(
+                &hex::decode("76a04053bda0a88bda5177b86a15c3b29f559873").unwrap(),
+            )
+            .unwrap(),
+        );
+        assert_eq!(
+            "1BpEi6DfDAUFd7GtittLSdBeYJvcoaVggu",
+            script_to_address(&legacy, SupportedPsbtCoin::Bitcoin).unwrap()
+        );
+        assert_eq!(
+            "bitcoincash:qpm2qsznhks23z7629mms6s4cwef74vcwvy22gdx6a",
+            script_to_address(&legacy, SupportedPsbtCoin::BitcoinCash).unwrap()
+        );
+        assert!(script_to_address(&legacy, SupportedPsbtCoin::Dogecoin)
+            .unwrap()
+            .starts_with('D'));
+        assert!(script_to_address(&legacy, SupportedPsbtCoin::Dash)
+            .unwrap()
+            .starts_with('X'));
+        let p2sh = Script::new_p2sh(&ScriptHash::from_inner([1; 20]));
+        assert!(script_to_address(&p2sh, SupportedPsbtCoin::Litecoin)
+            .unwrap()
+            .starts_with('M'));
+        assert!(script_to_address(&p2sh, SupportedPsbtCoin::BitcoinCash)
+            .unwrap()
+            .starts_with("bitcoincash:p"));
+    }
+
+    #[test]
+    fn test_fee_and_json() {
+        let psbt = build_psbt(
+            p2pkh(1),
+            vec![TxOut {
+                value: 90_000,
+                script_pubkey: p2pkh(2),
+            }],
+            None,
+        );
+        let view = AltcoinPsbt::parse(&psbt, SupportedPsbtCoin::Dogecoin).unwrap();
+        assert_eq!(10_000, view.fee().unwrap());
+        let result = view.to_json().unwrap();
+        assert_eq!("DOGE", result["coin"]);
+        assert_eq!(10_000, result["fee"]);
+        assert_eq!(false, result["inputs"][0]["signed"]);
+        assert!(result["outputs"][0]["address"]
+            .as_str()
+            .unwrap()
+            .starts_with('D'));
+
+        let overspent = build_psbt(
+            p2pkh(1),
+            vec![TxOut {
+                value: 100_001,
+                script_pubkey: p2pkh(2),
+            }],
+            None,
+        );
+        let view = AltcoinPsbt::parse(&overspent, SupportedPsbtCoin::Dogecoin).unwrap();
+        assert_eq!(
+            "outputs exceed inputs",
+            view.validate().unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn test_bch_fork_id() {
+        let outputs = vec![TxOut {
+            value: 99_000,
+            script_pubkey: p2pkh(2),
+        }];
+        let signed = build_psbt(p2pkh(1), outputs.clone(), Some(0x41));
+        let view = AltcoinPsbt::parse(&signed, SupportedPsbtCoin::BitcoinCash).unwrap();
+        assert!(view.validate().is_ok());
+        assert_eq!(true, view.to_json().unwrap()["inputs"][0]["signed"]);
+        let view = AltcoinPsbt::parse(&signed, SupportedPsbtCoin::Litecoin).unwrap();
+        assert_eq!(
+            "input 0 sighash must not set SIGHASH_FORKID for LTC",
+            view.validate().unwrap_err().to_string()
+        );
+
+        let without_fork_id = build_psbt(p2pkh(1), outputs, Some(0x01));
+        let view = AltcoinPsbt::parse(&without_fork_id, SupportedPsbtCoin::BitcoinCash).unwrap();
+        assert_eq!(
+            "input 0 sighash is missing SIGHASH_FORKID",
+            view.validate().unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn test_segwit_not_supported() {
+        let psbt = build_psbt(
+            p2pkh(1),
+            vec![TxOut {
+                value: 90_000,
+                script_pubkey: Script::new_v0_p2wpkh(&WPubkeyHash::from_inner([3; 20])),
+            }],
+            None,
+        );
+        let view = AltcoinPsbt::parse(&psbt, SupportedPsbtCoin::Dash).unwrap();
+        assert_eq!(
+            "DASH does not support segwit outputs",
+            view.validate().unwrap_err().to_string()
+        );
+        let view = AltcoinPsbt::parse(&psbt, SupportedPsbtCoin::Litecoin).unwrap();
+        assert!(view.validate().is_ok());
+    }
+
+    #[test]
+    fn test_crypto_psbt_extend_round_trip() {
+        let psbt = build_psbt(
+            p2pkh(1),
+            vec![TxOut {
+                value: 90_000,
+                script_pubkey: p2pkh(2),
+            }],
+            None,
+        );
+        let generated: Value =
+            serde_json::from_str(&generate_crypto_psbt_extend(&hex::encode(&psbt), 4)).unwrap();
+        assert_eq!("crypto-psbt-extend", generated["type"]);
+
+        let parsed: Value = serde_json::from_str(&parse_crypto_psbt_extend(
+            "crypto-psbt-extend",
+            generated["cbor"].as_str().unwrap(),
+        ))
+        .unwrap();
+        assert_eq!("DASH", parsed["coin"]);
+        assert_eq!(hex::encode(&psbt), parsed["psbt"]);
+        assert!(parsed["outputs"][0]["address"]
+            .as_str()
+            .unwrap()
+            .starts_with('X'));
+
+        assert_eq!(
+            "{\"error\":\"coin id is not supported\"}",
+            generate_crypto_psbt_extend(&hex::encode(&psbt), 0)
+        );
+        assert_eq!(
+            "{\"error\":\"type not match\"}",
+            parse_crypto_psbt_extend("crypto-psbt", generated["cbor"].as_str().unwrap())
+        );
+    }
+}
@@ -0,0 +1,510 @@
+use std::str::FromStr;
+
+use anyhow::{format_err, Error};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use bitcoin::blockdata::opcodes;
+use bitcoin::blockdata::script::{Builder, Instruction};
+use bitcoin::consensus::{deserialize, Encodable};
+use bitcoin::hashes::{sha256, sha256d, Hash, HashEngine};
+use bitcoin::secp256k1::ecdsa::{RecoverableSignature, RecoveryId, Signature};
+use bitcoin::secp256k1::{schnorr, Message, Secp256k1, XOnlyPublicKey};
+use bitcoin::util::schnorr::TapTweak;
+use bitcoin::util::sighash::{Prevouts, SighashCache};
+use bitcoin::{
+    Address, EcdsaSighashType, OutPoint, PackedLockTime, PublicKey, SchnorrSighashType, Script,
+    Sequence, Transaction, TxIn, TxOut, Txid, VarInt, Witness,
+};
+use serde_json::json;
+use ur_registry::bitcoin::btc_sign_request::DataType;
+use ur_registry::{bitcoin::btc_signature::BtcSignature, registry_types::BTC_SIGNATURE};
+use uuid::Uuid;
+
+use crate::export;
+use crate::util_internal::string_helper::remove_prefix_0x;
+
+const BITCOIN_SIGNED_MESSAGE_HEADER: &[u8] = b"\x18Bitcoin Signed Message:\n";
+const BIP322_TAG: &[u8] = b"BIP0322-signed-message";
+
+/// The legacy `signmessage` digest used by BIP-137 signatures.
+pub fn bip137_message_hash(message: &[u8]) -> sha256d::Hash {
+    let mut engine = sha256d::Hash::engine();
+    engine.input(BITCOIN_SIGNED_MESSAGE_HEADER);
+    VarInt(message.len() as u64)
+        .consensus_encode(&mut engine)
+        .expect("engines don't error");
+    engine.input(message);
+    sha256d::Hash::from_engine(engine)
+}
+
+/// The tagged hash committed to by the BIP-322 `to_spend` transaction.
+pub fn bip322_message_hash(message: &[u8]) -> sha256::Hash {
+    let tag = sha256::Hash::hash(BIP322_TAG);
+    let mut engine = sha256::Hash::engine();
+    engine.input(&tag[..]);
+    engine.input(&tag[..]);
+    engine.input(message);
+    sha256::Hash::from_engine(engine)
+}
+
+fn to_spend(script_pubkey: &Script, message: &[u8]) -> Transaction {
+    Transaction {
+        version: 0,
+        lock_time: PackedLockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: OutPoint::new(Txid::all_zeros(), 0xFFFFFFFF),
+            script_sig: Builder::new()
+                .push_opcode(opcodes::OP_FALSE)
+                .push_slice(&bip322_message_hash(message)[..])
+                .into_script(),
+            sequence: Sequence::ZERO,
+            witness: Witness::new(),
+        }],
+        output: vec![TxOut {
+            value: 0,
+            script_pubkey: script_pubkey.clone(),
+        }],
+    }
+}
+
+/// Builds the unsigned BIP-322 `to_sign` transaction for `message` and `address`.
+pub fn bip322_to_sign(address: &Address, message: &[u8]) -> Transaction {
+    Transaction {
+        version: 0,
+        lock_time: PackedLockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: OutPoint::new(to_spend(&address.script_pubkey(), message).txid(), 0),
+            script_sig: Script::new(),
+            sequence: Sequence::ZERO,
+            witness: Witness::new(),
+        }],
+        output: vec![TxOut {
+            value: 0,
+            script_pubkey: Builder::new()
+                .push_opcode(opcodes::all::OP_RETURN)
+                .into_script(),
+        }],
+    }
+}
+
+fn parse_address(address: &str) -> Result<Address, Error> {
+    Address::from_str(address).map_err(|_| format_err!("address is invalid"))
+}
+
+/// Verifies a 65-byte BIP-137 signature, returning the recovered public key.
+///
+/// The header only decides key compression; the address itself decides whether the key is
+/// checked as P2PKH, P2SH-P2WPKH or P2WPKH, as most wallets do.
+pub fn verify_bip137(message: &[u8], signature: &[u8], address: &str) -> Result<PublicKey, Error> {
+    let address = parse_address(address)?;
+    if signature.len() != 65 || !(27..=42).contains(&signature[0]) {
+        return Err(format_err!("signature is invalid"));
+    }
+    let header = signature[0] - 27;
+    let recovery_id = RecoveryId::from_i32((header % 4) as i32)?;
+    let recoverable = RecoverableSignature::from_compact(&signature[1..], recovery_id)?;
+    let digest = Message::from_slice(&bip137_message_hash(message)[..])?;
+    let secp = Secp256k1::verification_only();
+    let mut public_key = PublicKey::new(secp.recover_ecdsa(&digest, &recoverable)?);
+    public_key.compressed = header >= 4;
+
+    let script_pubkey = address.script_pubkey();
+    let expected = if script_pubkey.is_p2pkh() {
+        Script::new_p2pkh(&public_key.pubkey_hash())
+    } else {
+        let wpkh = public_key
+            .wpubkey_hash()
+            .ok_or_else(|| format_err!("segwit addresses require a compressed key"))?;
+        let p2wpkh = Script::new_v0_p2wpkh(&wpkh);
+        if script_pubkey.is_p2sh() {
+            Script::new_p2sh(&p2wpkh.script_hash())
+        } else {
+            p2wpkh
+        }
+    };
+    if expected != script_pubkey {
+        return Err(format_err!("signature does not match the address"));
+    }
+    Ok(public_key)
+}
+
+fn verify_ecdsa_input(
+    to_sign: &Transaction,
+    spent: &TxOut,
+    witness: &[Vec<u8>],
+    redeem_script: Option<&Script>,
+) -> Result<Vec<u8>, Error> {
+    let [signature, public_key] = witness else {
+        return Err(format_err!("witness is invalid"));
+    };
+    let public_key = PublicKey::from_slice(public_key)?;
+    let wpkh = public_key
+        .wpubkey_hash()
+        .ok_or_else(|| format_err!("witness is invalid"))?;
+    let p2wpkh = Script::new_v0_p2wpkh(&wpkh);
+    let matches = match redeem_script {
+        Some(redeem_script) => {
+            *redeem_script == p2wpkh
+                && Script::new_p2sh(&p2wpkh.script_hash()) == spent.script_pubkey
+        }
+        None => p2wpkh == spent.script_pubkey,
+    };
+    if !matches {
+        return Err(format_err!("signature does not match the address"));
+    }
+    let (sighash_byte, der) = signature
+        .split_last()
+        .ok_or_else(|| format_err!("witness is invalid"))?;
+    let sighash_type = EcdsaSighashType::from_standard(*sighash_byte as u32)?;
+    let script_code = Script::new_p2pkh(&public_key.pubkey_hash());
+    let sighash = SighashCache::new(to_sign).segwit_signature_hash(
+        0,
+        &script_code,
+        spent.value,
+        sighash_type,
+    )?;
+    let secp = Secp256k1::verification_only();
+    secp.verify_ecdsa(
+        &Message::from_slice(&sighash[..])?,
+        &Signature::from_der(der)?,
+        &public_key.inner,
+    )
+    .map_err(|_| format_err!("signature is invalid"))?;
+    Ok(public_key.to_bytes())
+}
+
+fn verify_taproot_input(
+    to_sign: &Transaction,
+    spent: &TxOut,
+    witness: &[Vec<u8>],
+) -> Result<Vec<u8>, Error> {
+    let [signature] = witness else {
+        return Err(format_err!("witness is invalid"));
+    };
+    let (signature, sighash_type) = match signature.len() {
+        64 => (&signature[..], SchnorrSighashType::Default),
+        65 => (
+            &signature[..64],
+            SchnorrSighashType::from_consensus_u8(signature[64])?,
+        ),
+        _ => return Err(format_err!("witness is invalid")),
+    };
+    let output_key = XOnlyPublicKey::from_slice(&spent.script_pubkey.as_bytes()[2..])?;
+    let sighash = SighashCache::new(to_sign).taproot_key_spend_signature_hash(
+        0,
+        &Prevouts::All(&[spent]),
+        sighash_type,
+    )?;
+    let secp = Secp256k1::verification_only();
+    secp.verify_schnorr(
+        &schnorr::Signature::from_slice(signature)?,
+        &Message::from_slice(&sighash[..])?,
+        &output_key,
+    )
+    .map_err(|_| format_err!("signature is invalid"))?;
+    Ok(output_key.serialize().to_vec())
+}
+
+/// Verifies a BIP-322 signature for a single-key P2WPKH, P2SH-P2WPKH or P2TR address,
+/// returning the key that signed. In simple mode `signature` is the consensus encoded
+/// witness stack, in full mode it is the whole `to_sign` transaction.
+pub fn verify_bip322(
+    message: &[u8],
+    signature: &[u8],
+    address: &str,
+    full: bool,
+) -> Result<Vec<u8>, Error> {
+    let address = parse_address(address)?;
+    let spent = TxOut {
+        value: 0,
+        script_pubkey: address.script_pubkey(),
+    };
+    let template = bip322_to_sign(&address, message);
+    let to_sign = if full {
+        let to_sign: Transaction =
+            deserialize(signature).map_err(|_| format_err!("signature is invalid"))?;
+        if to_sign.input.len() != 1 {
+            return Err(format_err!("proof of funds is not supported"));
+        }
+        if to_sign.input[0].previous_output != template.input[0].previous_output
+            || to_sign.output != template.output
+        {
+            return Err(format_err!("signature does not match the message"));
+        }
+        to_sign
+    } else {
+        let witness: Witness =
+            deserialize(signature).map_err(|_| format_err!("signature is invalid"))?;
+        let mut to_sign = template;
+        if spent.script_pubkey.is_p2sh() {
+            if let Some(public_key) = witness.last() {
+                let public_key = PublicKey::from_slice(public_key)?;
+                let wpkh = public_key
+                    .wpubkey_hash()
+                    .ok_or_else(|| format_err!("witness is invalid"))?;
+                to_sign.input[0].script_sig = Builder::new()
+                    .push_slice(Script::new_v0_p2wpkh(&wpkh).as_bytes())
+                    .into_script();
+            }
+        }
+        to_sign.input[0].witness = witness;
+        to_sign
+    };
+    let witness = to_sign.input[0].witness.to_vec();
+    if spent.script_pubkey.is_v0_p2wpkh() {
+        verify_ecdsa_input(&to_sign, &spent, &witness, None)
+    } else if spent.script_pubkey.is_p2sh() {
+        let redeem_script = to_sign.input[0]
+            .script_sig
+            .instructions()
+            .next()
+            .and_then(|instruction| match instruction {
+                Ok(Instruction::PushBytes(bytes)) => Some(Script::from(bytes.to_vec())),
+                _ => None,
+            })
+            .ok_or_else(|| format_err!("signature is invalid"))?;
+        verify_ecdsa_input(&to_sign, &spent, &witness, Some(&redeem_script))
+    } else if spent.script_pubkey.is_v1_p2tr() {
+        verify_taproot_input(&to_sign, &spent, &witness)
+    } else {
+        Err(format_err!("address type is not supported"))
+    }
+}
+
+/// Checks a `BtcSignature` returned for a `btc-sign-request` against the signed message
+/// and address, and that the signing key is the public key reported with it. A signature
+/// without a reported public key is rejected.
+pub fn verify_btc_signature(
+    signature: &BtcSignature,
+    message: &[u8],
+    address: &str,
+    data_type: DataType,
+) -> Result<(), Error> {
+    let reported_key = signature.get_public_key();
+    let signing_key = match data_type {
+        DataType::Message => {
+            verify_bip137(message, &signature.get_signature(), address)?.to_bytes()
+        }
+        DataType::Bip322Simple => {
+            verify_bip322(message, &signature.get_signature(), address, false)?
+        }
+        DataType::Bip322Full => verify_bip322(message, &signature.get_signature(), address, true)?,
+    };
+    if reported_key.is_empty() {
+        return Err(format_err!("public key is missing"));
+    }
+    if reported_key == signing_key {
+        return Ok(());
+    }
+    // taproot signatures are made with the tweaked output key, the device reports the internal key
+    let secp = Secp256k1::verification_only();
+    let internal_key = match reported_key.len() {
+        33 => XOnlyPublicKey::from_slice(&reported_key[1..]),
+        _ => XOnlyPublicKey::from_slice(&reported_key),
+    }
+    .map_err(|_| format_err!("public key is invalid"))?;
+    let (output_key, _) = internal_key.tap_tweak(&secp, None);
+    if output_key.to_inner().serialize().to_vec() != signing_key {
+        return Err(format_err!("public key does not match the signature"));
+    }
+    Ok(())
+}
+
+export! {
+    @Java_com_keystone_sdk_KeystoneNativeSDK_verifyBTCSignature
+    fn verify_btc_message_signature(
+        ur_type: &str,
+        cbor_hex: &str,
+        message_hex: &str,
+        address: &str,
+        data_type: u32
+    ) -> String {
+        if BTC_SIGNATURE.get_type() != ur_type {
+            return json!({"error": "type not match"}).to_string();
+        }
+
+        let verify = || -> Result<(String, Vec<u8>), Error> {
+            let cbor = hex::decode(remove_prefix_0x(cbor_hex))?;
+            let sig = BtcSignature::try_from(cbor)?;
+            let message = hex::decode(remove_prefix_0x(message_hex))
+                .map_err(|_| format_err!("message is invalid"))?;
+            let data_type = DataType::from_u32(data_type)
+                .map_err(|_| format_err!("data type is invalid"))?;
+            verify_btc_signature(&sig, &message, address, data_type)?;
+            let request_id = Uuid::from_slice(&sig.get_request_id())?.to_string();
+            Ok((request_id, sig.get_signature()))
+        };
+        match verify() {
+            Ok((request_id, signature)) => json!({
+                "request_id": request_id,
+                "signature": STANDARD.encode(signature),
+                "valid": true,
+            }).to_string(),
+            Err(e) => json!({"error": e.to_string()}).to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitcoin::consensus::serialize;
+    use bitcoin::secp256k1::{KeyPair, SecretKey};
+    use bitcoin::util::sighash::SighashCache;
+    use bitcoin::{Network, PrivateKey};
+
+    fn to_cbor(signature: Vec<u8>, public_key: Vec<u8>) -> String {
+        let request_id = hex::decode("9b1deb4d3b7d4bad9bdd2b0d7b3dcb6d").unwrap();
+        let cbor: Vec<u8> = BtcSignature::new(request_id, signature, public_key)
+            .try_into()
+            .unwrap();
+        hex::encode(cbor)
+    }
+
+    #[test]
+    fn test_bip322_message_hash() {
+        assert_eq!(
+            "c90c269c4f8fcbe6880f72a721ddfbf1914268a794cbb21cfafee13770ae19f1",
+            hex::encode(bip322_message_hash(b""))
+        );
+        assert_eq!(
+            "f0eb03b1a75ac6d9847f55c624a99169b5dccba2a31f5b23bea77ba270de0a7a",
+            hex::encode(bip322_message_hash(b"Hello World"))
+        );
+    }
+
+    #[test]
+    fn test_bip322_simple_vector() {
+        let address = "bc1q9vza2e8x573nczrlzms0wvx3gsqjx7vavgkx0l";
+        let private_key =
+            PrivateKey::from_wif("L3VFeEujGtevx9w18HD1fhRbCH67Az2dpCymeRE1SoPK6XQtaN2k").unwrap();
+        let signature = STANDARD
+            .decode("AkcwRAIgZRfIY3p7/DoVTty6YZbWS71bc5Vct9p9Fia83eRmw2QCICK/ENGfwLtptFluMGs2KsqoNSk89pO7F29zJLUx9a/sASECx/EgAxlkQpQ9hYjgGu6EBCPMVPwVIVJqO4XCsMvViHI=")
+            .unwrap();
+        let public_key = verify_bip322(b"Hello World", &signature, address, false).unwrap();
+        let secp = Secp256k1::new();
+        assert_eq!(private_key.public_key(&secp).to_bytes(), public_key);
+        assert_eq!(
+            "signature is invalid",
+            verify_bip322(b"Hello World!", &signature, address, false)
+                .unwrap_err()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn test_bip137() {
+        let secp = Secp256k1::new();
+        let secret_key = SecretKey::from_slice(&[1u8; 32]).unwrap();
+        let public_key = PublicKey::new(secret_key.public_key(&secp));
+        let digest = Message::from_slice(&bip137_message_hash(b"Hello Keystone")[..]).unwrap();
+        let (recovery_id, compact) = secp
+            .sign_ecdsa_recoverable(&digest, &secret_key)
+            .serialize_compact();
+
+        let sign = |header_base: u8| {
+            let mut signature = vec![header_base + recovery_id.to_i32() as u8];
+            signature.extend_from_slice(&compact);
+            signature
+        };
+        let p2pkh = Address::p2pkh(&public_key, Network::Bitcoin).to_string();
+        let p2wpkh = Address::p2wpkh(&public_key, Network::Bitcoin)
+            .unwrap()
+            .to_string();
+        let cbor = to_cbor(sign(31), public_key.to_bytes());
+        let expect_result = format!(
+            "{{\"request_id\":\"9b1deb4d-3b7d-4bad-9bdd-2b0d7b3dcb6d\",\"signature\":\"{}\",\"valid\":true}}",
+            STANDARD.encode(sign(31))
+        );
+        assert_eq!(
+            expect_result,
+            verify_btc_message_signature(
+                "btc-signature",
+                &cbor,
+                &hex::encode("Hello Keystone"),
+                &p2pkh,
+                1
+            )
+        );
+        assert!(verify_bip137(b"Hello Keystone", &sign(39), &p2wpkh).is_ok());
+        assert_eq!(
+            "{\"error\":\"signature does not match the address\"}",
+            verify_btc_message_signature(
+                "btc-signature",
+                &cbor,
+                &hex::encode("Hello Keystone!"),
+                &p2pkh,
+                1
+            )
+        );
+        assert_eq!(
+            "{\"error\":\"public key is missing\"}",
+            verify_btc_message_signature(
+                "btc-signature",
+                &to_cbor(sign(31), vec![]),
+                &hex::encode("Hello Keystone"),
+                &p2pkh,
+                1
+            )
+        );
+        // uncompressed header recovers a key whose hash differs from the compressed address
+        assert!(verify_bip137(b"Hello Keystone", &sign(27), &p2pkh).is_err());
+    }
+
+    #[test]
+    fn test_bip322_taproot() {
+        let secp = Secp256k1::new();
+        let key_pair = KeyPair::from_seckey_slice(&secp, &[2u8; 32]).unwrap();
+        let (internal_key, _) = key_pair.x_only_public_key();
+        let address = Address::p2tr(&secp, internal_key, None, Network::Bitcoin);
+        let message = b"Hello Keystone";
+
+        let mut to_sign = bip322_to_sign(&address, message);
+        let spent = TxOut {
+            value: 0,
+            script_pubkey: address.script_pubkey(),
+        };
+        let sighash = SighashCache::new(&to_sign)
+            .taproot_key_spend_signature_hash(
+                0,
+                &Prevouts::All(&[&spent]),
+                SchnorrSighashType::Default,
+            )
+            .unwrap();
+        let tweaked = key_pair.tap_tweak(&secp, None).to_inner();
+        let signature =
+            secp.sign_schnorr_no_aux_rand(&Message::from_slice(&sighash[..]).unwrap(), &tweaked);
+        to_sign.input[0].witness = Witness::from_vec(vec![signature.as_ref().to_vec()]);
+
+        let simple = serialize(&to_sign.input[0].witness);
+        let full = serialize(&to_sign);
+        let address = address.to_string();
+        let signature = BtcSignature::new(vec![], simple, internal_key.serialize().to_vec());
+        assert!(
+            verify_btc_signature(&signature, message, &address, DataType::Bip322Simple).is_ok()
+        );
+        let signature = BtcSignature::new(vec![], full, internal_key.serialize().to_vec());
+        assert!(verify_btc_signature(&signature, message, &address, DataType::Bip322Full).is_ok());
+        assert_eq!(
+            "signature does not match the message",
+            verify_btc_signature(&signature, b"Hello", &address, DataType::Bip322Full)
+                .unwrap_err()
+                .to_string()
+        );
+        let other_key = KeyPair::from_seckey_slice(&secp, &[3u8; 32])
+            .unwrap()
+            .x_only_public_key()
+            .0;
+        let signature = BtcSignature::new(
+            vec![],
+            signature.get_signature(),
+            other_key.serialize().to_vec(),
+        );
+        assert_eq!(
+            "public key does not match the signature",
+            verify_btc_signature(&signature, message, &address, DataType::Bip322Full)
+                .unwrap_err()
+                .to_string()
+        );
+    }
+}
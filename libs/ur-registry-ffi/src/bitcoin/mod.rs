@@ -1,3 +1,6 @@
+pub mod altcoin_psbt;
 pub mod btc_sign_request;
 pub mod btc_signature;
+pub mod message_signature;
 pub mod crypto_psbt;
+pub mod psbt_finalizer;
@@ -0,0 +1,432 @@
+use anyhow::{Error, format_err};
+use bitcoin::blockdata::opcodes;
+use bitcoin::blockdata::script::{Builder, Instruction};
+use bitcoin::consensus::{deserialize, serialize};
+use bitcoin::psbt::{Input, PartiallySignedTransaction};
+use bitcoin::{PublicKey, Script, Transaction, TxOut, Witness};
+use serde_json::json;
+
+use crate::export;
+use crate::util_internal::string_helper::remove_prefix_0x;
+
+pub fn decode_psbt(psbt_hex: &str) -> Result<PartiallySignedTransaction, Error> {
+    let bytes = hex::decode(remove_prefix_0x(psbt_hex))?;
+    deserialize::<PartiallySignedTransaction>(&bytes).map_err(|_| format_err!("PSBT is invalid"))
+}
+
+pub fn combine(
+    original: PartiallySignedTransaction,
+    signed: PartiallySignedTransaction,
+) -> Result<PartiallySignedTransaction, Error> {
+    if original.unsigned_tx.txid() != signed.unsigned_tx.txid()
+        || original.inputs.len() != signed.inputs.len()
+        || original.outputs.len() != signed.outputs.len()
+    {
+        return Err(format_err!("signed PSBT does not match the original one"));
+    }
+    let mut combined = original;
+    combined
+        .combine(signed)
+        .map_err(|e| format_err!("combine PSBT failed: {}", e))?;
+    Ok(combined)
+}
+
+pub fn spent_output(psbt: &PartiallySignedTransaction, index: usize) -> Result<TxOut, Error> {
+    let input = &psbt.inputs[index];
+    if let Some(utxo) = &input.witness_utxo {
+        return Ok(utxo.clone());
+    }
+    let prevout = psbt.unsigned_tx.input[index].previous_output;
+    input
+        .non_witness_utxo
+        .as_ref()
+        .filter(|tx| tx.txid() == prevout.txid)
+        .and_then(|tx| tx.output.get(prevout.vout as usize).cloned())
+        .ok_or_else(|| format_err!("input {} has no utxo information", index))
+}
+
+fn p2wpkh_witness(input: &Input, script_code: &Script) -> Option<Witness> {
+    input.partial_sigs.iter().find_map(|(public_key, sig)| {
+        let wpkh = public_key.wpubkey_hash()?;
+        if Script::new_v0_p2wpkh(&wpkh) != *script_code {
+            return None;
+        }
+        Some(Witness::from_vec(vec![sig.to_vec(), public_key.to_bytes()]))
+    })
+}
+
+fn parse_multisig(script: &Script) -> Option<(usize, Vec<PublicKey>)> {
+    let instructions = script
+        .instructions()
+        .collect::<Result<Vec<Instruction>, _>>()
+        .ok()?;
+    if instructions.len() < 4 {
+        return None;
+    }
+    let pushnum = |instruction: &Instruction| match instruction {
+        Instruction::Op(op) => {
+            let code = op.to_u8();
+            let first = opcodes::all::OP_PUSHNUM_1.to_u8();
+            let last = opcodes::all::OP_PUSHNUM_16.to_u8();
+            (first..=last)
+                .contains(&code)
+                .then(|| (code - first + 1) as usize)
+        }
+        _ => None,
+    };
+    let threshold = pushnum(&instructions[0])?;
+    let total = pushnum(&instructions[instructions.len() - 2])?;
+    match instructions[instructions.len() - 1] {
+        Instruction::Op(op) if op == opcodes::all::OP_CHECKMULTISIG => {}
+        _ => return None,
+    }
+    let keys = instructions[1..instructions.len() - 2]
+        .iter()
+        .map(|instruction| match instruction {
+            Instruction::PushBytes(bytes) => PublicKey::from_slice(bytes).ok(),
+            _ => None,
+        })
+        .collect::<Option<Vec<PublicKey>>>()?;
+    if keys.len() != total || threshold > total {
+        return None;
+    }
+    Some((threshold, keys))
+}
+
+fn p2wsh_multisig_witness(input: &Input, index: usize) -> Result<Witness, Error> {
+    let witness_script = input
+        .witness_script
+        .as_ref()
+        .ok_or_else(|| format_err!("input {} has no witness script", index))?;
+    let (threshold, keys) = parse_multisig(witness_script)
+        .ok_or_else(|| format_err!("input {} is not a multisig script", index))?;
+    let mut stack: Vec<Vec<u8>> = vec![vec![]];
+    for key in keys.iter() {
+        if stack.len() > threshold {
+            break;
+        }
+        if let Some(sig) = input.partial_sigs.get(key) {
+            stack.push(sig.to_vec());
+        }
+    }
+    if stack.len() <= threshold {
+        return Err(format_err!(
+            "input {} has {} of {} required signatures",
+            index,
+            stack.len() - 1,
+            threshold
+        ));
+    }
+    stack.push(witness_script.to_bytes());
+    Ok(Witness::from_vec(stack))
+}
+
+fn finalize_input(input: &mut Input, script_pubkey: &Script, index: usize) -> Result<(), Error> {
+    if input.final_script_witness.is_some() || input.final_script_sig.is_some() {
+        return Ok(());
+    }
+    let unsigned = || format_err!("input {} is not signed", index);
+    if script_pubkey.is_v0_p2wpkh() {
+        input.final_script_witness =
+            Some(p2wpkh_witness(input, script_pubkey).ok_or_else(unsigned)?);
+    } else if script_pubkey.is_v1_p2tr() {
+        let sig = input.tap_key_sig.ok_or_else(unsigned)?;
+        input.final_script_witness = Some(Witness::from_vec(vec![sig.to_vec()]));
+    } else if script_pubkey.is_v0_p2wsh() {
+        let witness_script = input
+            .witness_script
+            .as_ref()
+            .ok_or_else(|| format_err!("input {} has no witness script", index))?;
+        if Script::new_v0_p2wsh(&witness_script.wscript_hash()) != *script_pubkey {
+            return Err(format_err!("input {} witness script does not match", index));
+        }
+        input.final_script_witness = Some(p2wsh_multisig_witness(input, index)?);
+    } else if script_pubkey.is_p2sh() {
+        let redeem_script = input
+            .redeem_script
+            .clone()
+            .ok_or_else(|| format_err!("input {} has no redeem script", index))?;
+        if Script::new_p2sh(&redeem_script.script_hash()) != *script_pubkey
+            || !redeem_script.is_v0_p2wpkh()
+        {
+            return Err(format_err!(
+                "input {} redeem script is not supported",
+                index
+            ));
+        }
+        let witness = p2wpkh_witness(input, &redeem_script).ok_or_else(unsigned)?;
+        input.final_script_sig = Some(
+            Builder::new()
+                .push_slice(redeem_script.as_bytes())
+                .into_script(),
+        );
+        input.final_script_witness = Some(witness);
+    } else {
+        return Err(format_err!("input {} script type is not supported", index));
+    }
+
+    // BIP-174: the finalizer clears everything except the UTXO and final fields.
+    input.partial_sigs.clear();
+    input.sighash_type = None;
+    input.redeem_script = None;
+    input.witness_script = None;
+    input.bip32_derivation.clear();
+    input.tap_key_sig = None;
+    input.tap_script_sigs.clear();
+    input.tap_scripts.clear();
+    input.tap_key_origins.clear();
+    input.tap_internal_key = None;
+    input.tap_merkle_root = None;
+    Ok(())
+}
+
+pub fn finalize(psbt: &mut PartiallySignedTransaction) -> Result<(), Error> {
+    for index in 0..psbt.inputs.len() {
+        let script_pubkey = spent_output(psbt, index)?.script_pubkey;
+        finalize_input(&mut psbt.inputs[index], &script_pubkey, index)?;
+    }
+    Ok(())
+}
+
+pub fn combine_and_finalize(original_hex: &str, signed_hex: &str) -> Result<Transaction, Error> {
+    let original = decode_psbt(original_hex)?;
+    let signed = decode_psbt(signed_hex)?;
+    let mut psbt = combine(original, signed)?;
+    finalize(&mut psbt)?;
+    Ok(psbt.extract_tx())
+}
+
+export! {
+    @Java_com_keystone_sdk_KeystoneNativeSDK_combinePSBT
+    fn combine_psbt(original_psbt_hex: &str, signed_psbt_hex: &str) -> String {
+        let combined = || -> Result<String, Error> {
+            let original = decode_psbt(original_psbt_hex)?;
+            let signed = decode_psbt(signed_psbt_hex)?;
+            Ok(hex::encode(serialize(&combine(original, signed)?)))
+        };
+        match combined() {
+            Ok(psbt) => json!({
+                "psbt": psbt,
+            }).to_string(),
+            Err(e) => json!({"error": e.to_string()}).to_string(),
+        }
+    }
+
+    @Java_com_keystone_sdk_KeystoneNativeSDK_finalizePSBT
+    fn finalize_psbt(original_psbt_hex: &str, signed_psbt_hex: &str) -> String {
+        match combine_and_finalize(original_psbt_hex, signed_psbt_hex) {
+            Ok(tx) => json!({
+                "txid": tx.txid().to_string(),
+                "raw_tx": hex::encode(serialize(&tx)),
+            }).to_string(),
+            Err(e) => json!({"error": e.to_string()}).to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitcoin::hashes::Hash;
+    use bitcoin::schnorr::TapTweak;
+    use bitcoin::secp256k1::{Message, Secp256k1, SecretKey};
+    use bitcoin::util::sighash::{Prevouts, SighashCache};
+    use bitcoin::{
+        EcdsaSig, EcdsaSighashType, OutPoint, PackedLockTime, SchnorrSig, SchnorrSighashType,
+        Sequence, TxIn,
+    };
+
+    fn secret_key(byte: u8) -> SecretKey {
+        SecretKey::from_slice(&[byte; 32]).unwrap()
+    }
+
+    fn public_key(byte: u8) -> PublicKey {
+        PublicKey::new(secret_key(byte).public_key(&Secp256k1::new()))
+    }
+
+    fn unsigned_psbt(script_pubkey: Script) -> PartiallySignedTransaction {
+        let tx = Transaction {
+            version: 2,
+            lock_time: PackedLockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: OutPoint::new(
+                    "58e87a21b56daf0c23be8e7070456c336f7cbaa5c8757924f545887bb2abdd75"
+                        .parse()
+                        .unwrap(),
+                    0,
+                ),
+                script_sig: Script::new(),
+                sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+                witness: Witness::default(),
+            }],
+            output: vec![TxOut {
+                value: 90_000,
+                script_pubkey: Script::new_v0_p2wpkh(&public_key(9).wpubkey_hash().unwrap()),
+            }],
+        };
+        let mut psbt = PartiallySignedTransaction::from_unsigned_tx(tx).unwrap();
+        psbt.inputs[0].witness_utxo = Some(TxOut {
+            value: 100_000,
+            script_pubkey,
+        });
+        psbt
+    }
+
+    fn ecdsa_sign(psbt: &PartiallySignedTransaction, script_code: &Script, key: u8) -> EcdsaSig {
+        let secp = Secp256k1::new();
+        let value = psbt.inputs[0].witness_utxo.as_ref().unwrap().value;
+        let sighash = SighashCache::new(&psbt.unsigned_tx)
+            .segwit_signature_hash(0, script_code, value, EcdsaSighashType::All)
+            .unwrap();
+        let message = Message::from_slice(&sighash.into_inner()).unwrap();
+        EcdsaSig::sighash_all(secp.sign_ecdsa(&message, &secret_key(key)))
+    }
+
+    fn hex_of(psbt: &PartiallySignedTransaction) -> String {
+        hex::encode(serialize(psbt))
+    }
+
+    #[test]
+    fn test_finalize_p2wpkh() {
+        let pk = public_key(1);
+        let original = unsigned_psbt(Script::new_v0_p2wpkh(&pk.wpubkey_hash().unwrap()));
+        let mut signed = original.clone();
+        let sig = ecdsa_sign(&original, &Script::new_p2pkh(&pk.pubkey_hash()), 1);
+        signed.inputs[0].partial_sigs.insert(pk, sig);
+
+        let tx = combine_and_finalize(&hex_of(&original), &hex_of(&signed)).unwrap();
+        assert_eq!(
+            tx.input[0].witness.to_vec(),
+            vec![sig.to_vec(), pk.to_bytes()]
+        );
+        assert!(tx.input[0].script_sig.is_empty());
+        assert_eq!(tx.txid(), original.unsigned_tx.txid());
+    }
+
+    #[test]
+    fn test_finalize_p2sh_p2wpkh() {
+        let pk = public_key(2);
+        let redeem_script = Script::new_v0_p2wpkh(&pk.wpubkey_hash().unwrap());
+        let mut original = unsigned_psbt(Script::new_p2sh(&redeem_script.script_hash()));
+        original.inputs[0].redeem_script = Some(redeem_script.clone());
+        let mut signed = original.clone();
+        let sig = ecdsa_sign(&original, &Script::new_p2pkh(&pk.pubkey_hash()), 2);
+        signed.inputs[0].partial_sigs.insert(pk, sig);
+
+        let tx = combine_and_finalize(&hex_of(&original), &hex_of(&signed)).unwrap();
+        assert_eq!(
+            tx.input[0].script_sig,
+            Builder::new()
+                .push_slice(redeem_script.as_bytes())
+                .into_script()
+        );
+        assert_eq!(
+            tx.input[0].witness.to_vec(),
+            vec![sig.to_vec(), pk.to_bytes()]
+        );
+    }
+
+    #[test]
+    fn test_finalize_p2tr_key_path() {
+        let secp = Secp256k1::new();
+        let keypair = bitcoin::KeyPair::from_secret_key(&secp, &secret_key(3));
+        let (internal_key, _) = keypair.x_only_public_key();
+        let script_pubkey = Script::new_v1_p2tr(&secp, internal_key, None);
+        let original = unsigned_psbt(script_pubkey.clone());
+        let mut signed = original.clone();
+        let prevouts = [original.inputs[0].witness_utxo.clone().unwrap()];
+        let sighash = SighashCache::new(&original.unsigned_tx)
+            .taproot_key_spend_signature_hash(
+                0,
+                &Prevouts::All(&prevouts),
+                SchnorrSighashType::Default,
+            )
+            .unwrap();
+        let tweaked = keypair.tap_tweak(&secp, None).to_inner();
+        let sig = secp.sign_schnorr_no_aux_rand(
+            &Message::from_slice(&sighash.into_inner()).unwrap(),
+            &tweaked,
+        );
+        let schnorr_sig = SchnorrSig {
+            sig,
+            hash_ty: SchnorrSighashType::Default,
+        };
+        signed.inputs[0].tap_key_sig = Some(schnorr_sig);
+
+        let tx = combine_and_finalize(&hex_of(&original), &hex_of(&signed)).unwrap();
+        assert_eq!(tx.input[0].witness.to_vec(), vec![schnorr_sig.to_vec()]);
+    }
+
+    #[test]
+    fn test_finalize_p2wsh_multisig() {
+        let keys = [public_key(4), public_key(5), public_key(6)];
+        let mut builder = Builder::new().push_int(2);
+        for key in keys.iter() {
+            builder = builder.push_key(key);
+        }
+        let witness_script = builder
+            .push_int(3)
+            .push_opcode(opcodes::all::OP_CHECKMULTISIG)
+            .into_script();
+        let mut original = unsigned_psbt(Script::new_v0_p2wsh(&witness_script.wscript_hash()));
+        original.inputs[0].witness_script = Some(witness_script.clone());
+
+        let mut first = original.clone();
+        let sig_c = ecdsa_sign(&original, &witness_script, 6);
+        first.inputs[0].partial_sigs.insert(keys[2], sig_c);
+        let partially = combine(original.clone(), first).unwrap();
+        assert_eq!(
+            finalize_psbt(&hex_of(&original), &hex_of(&partially)),
+            "{\"error\":\"input 0 has 1 of 2 required signatures\"}"
+        );
+
+        let mut second = original.clone();
+        let sig_a = ecdsa_sign(&original, &witness_script, 4);
+        second.inputs[0].partial_sigs.insert(keys[0], sig_a);
+        let combined = combine(partially, second).unwrap();
+        let tx = combine_and_finalize(&hex_of(&original), &hex_of(&combined)).unwrap();
+        assert_eq!(
+            tx.input[0].witness.to_vec(),
+            vec![
+                vec![],
+                sig_a.to_vec(),
+                sig_c.to_vec(),
+                witness_script.to_bytes()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_finalize_psbt_mismatch() {
+        let pk = public_key(1);
+        let original = unsigned_psbt(Script::new_v0_p2wpkh(&pk.wpubkey_hash().unwrap()));
+        let mut other = original.clone();
+        other.unsigned_tx.output[0].value = 1;
+        let mut other = PartiallySignedTransaction::from_unsigned_tx(other.unsigned_tx).unwrap();
+        other.inputs[0].witness_utxo = original.inputs[0].witness_utxo.clone();
+
+        assert_eq!(
+            "{\"error\":\"signed PSBT does not match the original one\"}",
+            finalize_psbt(&hex_of(&original), &hex_of(&other))
+        );
+        assert_eq!(
+            "{\"error\":\"signed PSBT does not match the original one\"}",
+            combine_psbt(&hex_of(&original), &hex_of(&other))
+        );
+    }
+
+    #[test]
+    fn test_finalize_psbt_unsigned() {
+        let pk = public_key(1);
+        let original = unsigned_psbt(Script::new_v0_p2wpkh(&pk.wpubkey_hash().unwrap()));
+
+        assert_eq!(
+            "{\"error\":\"input 0 is not signed\"}",
+            finalize_psbt(&hex_of(&original), &hex_of(&original))
+        );
+        assert_eq!(
+            "{\"error\":\"PSBT is invalid\"}",
+            finalize_psbt(&hex_of(&original), "70736274ff")
+        );
+    }
+}
@@ -0,0 +1,342 @@
+use anyhow::{format_err, Error};
+use bitcoin::bech32::{self, FromBase32, ToBase32, Variant};
+use bitcoin::hashes::{hash160, Hash};
+use bitcoin::secp256k1::PublicKey;
+use serde_json::json;
+
+use crate::ethereum::eth_signature_verification::to_checksum_address;
+use crate::ethereum::eth_typed_data::keccak256;
+use crate::export;
+pub use crate::util_internal::chain::{
+    cosmos_chain_registry, find_cosmos_chain, find_cosmos_chain_by_evm_chain_id,
+    find_cosmos_chain_by_hrp, CosmosChain, CosmosChainRegistry, CosmosKeyType, COSMOS_CHAINS,
+};
+use crate::util_internal::string_helper::remove_prefix_0x;
+
+/// Derives the 20-byte account address of a compressed or uncompressed public key.
+pub fn derive_address_bytes(public_key: &[u8], key_type: CosmosKeyType) -> Result<[u8; 20], Error> {
+    let public_key =
+        PublicKey::from_slice(public_key).map_err(|_| format_err!("public key is invalid"))?;
+    match key_type {
+        CosmosKeyType::Secp256k1 => Ok(hash160::Hash::hash(&public_key.serialize()).into_inner()),
+        CosmosKeyType::EthSecp256k1 => {
+            let hash = keccak256(&public_key.serialize_uncompressed()[1..]);
+            Ok(hash[12..].try_into()?)
+        }
+    }
+}
+
+pub fn encode_bech32(hrp: &str, data: &[u8]) -> Result<String, Error> {
+    bech32::encode(hrp, data.to_base32(), Variant::Bech32)
+        .map_err(|_| format_err!("hrp is invalid"))
+}
+
+/// Splits a bech32 address into its HRP and payload.
+pub fn decode_bech32(address: &str) -> Result<(String, Vec<u8>), Error> {
+    let (hrp, data, variant) =
+        bech32::decode(address).map_err(|_| format_err!("address is invalid"))?;
+    if variant != Variant::Bech32 {
+        return Err(format_err!("address is invalid"));
+    }
+    let data = Vec::<u8>::from_base32(&data).map_err(|_| format_err!("address is invalid"))?;
+    Ok((hrp, data))
+}
+
+/// Converts between bech32 and `0x` forms: a `0x` address is encoded with `hrp`, and a
+/// bech32 address is re-encoded with `hrp`, or rendered as `0x` when `hrp` is empty.
+pub fn convert_address(address: &str, hrp: &str) -> Result<String, Error> {
+    if address.starts_with("0x") {
+        if hrp.is_empty() {
+            return Err(format_err!("hrp is required"));
+        }
+        let bytes = hex::decode(remove_prefix_0x(address))
+            .map_err(|_| format_err!("address is invalid"))?;
+        return encode_bech32(hrp, &bytes);
+    }
+    let (_, data) = decode_bech32(address)?;
+    match hrp.is_empty() {
+        false => encode_bech32(hrp, &data),
+        true => match <[u8; 20]>::try_from(data.as_slice()) {
+            Ok(bytes) => Ok(to_checksum_address(&bytes)),
+            Err(_) => Ok(format!("0x{}", hex::encode(data))),
+        },
+    }
+}
+
+/// Whether a request address, either bech32 or the hex of its payload, names `signer`.
+pub fn address_matches(address: &str, signer: &str) -> bool {
+    if address == signer {
+        return true;
+    }
+    match (
+        hex::decode(remove_prefix_0x(address)),
+        decode_bech32(signer),
+    ) {
+        (Ok(bytes), Ok((_, data))) => bytes == data,
+        _ => false,
+    }
+}
+
+fn chain_to_json(chain: &CosmosChain) -> serde_json::Value {
+    json!({
+        "chain_id": chain.chain_id,
+        "name": chain.name,
+        "hrp": chain.hrp,
+        "coin_type": chain.coin_type,
+        "key_type": chain.key_type.get_name(),
+        "evm_chain_id": chain.evm_chain_id,
+    })
+}
+
+/// Parses a chain in the JSON form returned by `get_cosmos_chain`. `key_type` defaults to
+/// `secp256k1` and `evm_chain_id` is optional.
+fn chain_from_json(chain: &str) -> Result<CosmosChain, Error> {
+    let chain: serde_json::Value =
+        serde_json::from_str(chain).map_err(|_| format_err!("chain is invalid"))?;
+    let string = |key: &str| -> Result<String, Error> {
+        chain[key]
+            .as_str()
+            .filter(|v| !v.is_empty())
+            .map(|v| v.to_string())
+            .ok_or(format_err!("{key} is invalid"))
+    };
+    let hrp = string("hrp")?;
+    encode_bech32(&hrp, &[])?;
+    let key_type = match chain["key_type"].as_str() {
+        Some(key_type) => {
+            CosmosKeyType::from_name(key_type).ok_or(format_err!("key type is invalid"))?
+        }
+        None => CosmosKeyType::Secp256k1,
+    };
+    let evm_chain_id = match &chain["evm_chain_id"] {
+        serde_json::Value::Null => None,
+        v => Some(v.as_u64().ok_or(format_err!("evm_chain_id is invalid"))?),
+    };
+    Ok(CosmosChain {
+        chain_id: string("chain_id")?.into(),
+        name: string("name")?.into(),
+        hrp: hrp.into(),
+        coin_type: chain["coin_type"]
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .ok_or(format_err!("coin_type is invalid"))?,
+        key_type,
+        evm_chain_id,
+    })
+}
+
+export! {
+    @Java_com_keystone_sdk_KeystoneNativeSDK_registerCosmosChain
+    fn register_cosmos_chain(chain: &str) -> String {
+        let register = || -> Result<String, Error> {
+            let chain = chain_from_json(chain)?;
+            let chain_id = chain.chain_id.to_string();
+            cosmos_chain_registry()
+                .lock()
+                .map_err(|_| format_err!("chain registry is unavailable"))?
+                .register(chain)?;
+            Ok(chain_id)
+        };
+        match register() {
+            Ok(chain_id) => json!({"registered": chain_id}).to_string(),
+            Err(e) => json!({"error": e.to_string()}).to_string(),
+        }
+    }
+
+    @Java_com_keystone_sdk_KeystoneNativeSDK_getCosmosChain
+    fn get_cosmos_chain(chain_id: &str) -> String {
+        let chain = match chain_id.parse::<u64>() {
+            Ok(evm_chain_id) => find_cosmos_chain_by_evm_chain_id(evm_chain_id),
+            Err(_) => find_cosmos_chain(chain_id),
+        };
+        match chain {
+            Some(chain) => chain_to_json(&chain).to_string(),
+            None => json!({"error": "chain is not supported"}).to_string(),
+        }
+    }
+
+    @Java_com_keystone_sdk_KeystoneNativeSDK_deriveCosmosAddress
+    fn derive_cosmos_address(public_key: &str, hrp: &str, key_type: &str) -> String {
+        let key_type = match key_type.is_empty() {
+            true => find_cosmos_chain_by_hrp(hrp)
+                .map(|chain| chain.key_type)
+                .unwrap_or(CosmosKeyType::Secp256k1),
+            false => match CosmosKeyType::from_name(key_type) {
+                Some(v) => v,
+                None => return json!({"error": "key type is invalid"}).to_string(),
+            },
+        };
+        let derive = || -> Result<([u8; 20], String), Error> {
+            let public_key = hex::decode(remove_prefix_0x(public_key))
+                .map_err(|_| format_err!("public key is invalid"))?;
+            let bytes = derive_address_bytes(&public_key, key_type)?;
+            Ok((bytes, encode_bech32(hrp, &bytes)?))
+        };
+        match derive() {
+            Ok((bytes, address)) => json!({
+                "address": address,
+                "hex_address": to_checksum_address(&bytes),
+                "key_type": key_type.get_name(),
+            }).to_string(),
+            Err(e) => json!({"error": e.to_string()}).to_string(),
+        }
+    }
+
+    @Java_com_keystone_sdk_KeystoneNativeSDK_convertCosmosAddress
+    fn convert_cosmos_address(address: &str, hrp: &str) -> String {
+        match convert_address(address, hrp) {
+            Ok(address) => json!({"address": address}).to_string(),
+            Err(e) => json!({"error": e.to_string()}).to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    // The public key of the private key `0x01`.
+    const PUBLIC_KEY: &str = "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+
+    #[test]
+    fn test_derive_cosmos_address() {
+        let result: Value =
+            serde_json::from_str(&derive_cosmos_address(PUBLIC_KEY, "cosmos", "")).unwrap();
+        assert_eq!(
+            "cosmos1w508d6qejxtdg4y5r3zarvary0c5xw7k6ah60c",
+            result["address"]
+        );
+        assert_eq!("secp256k1", result["key_type"]);
+
+        let result: Value =
+            serde_json::from_str(&derive_cosmos_address(PUBLIC_KEY, "evmos", "")).unwrap();
+        assert_eq!("ethsecp256k1", result["key_type"]);
+        assert_eq!(
+            "0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf",
+            result["hex_address"]
+        );
+        assert_eq!(
+            convert_address("0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf", "evmos").unwrap(),
+            result["address"]
+        );
+
+        let uncompressed = "0479be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8";
+        assert_eq!(
+            derive_cosmos_address(PUBLIC_KEY, "inj", "ethsecp256k1"),
+            derive_cosmos_address(uncompressed, "inj", "")
+        );
+    }
+
+    #[test]
+    fn test_convert_cosmos_address() {
+        let address = "cosmos1w508d6qejxtdg4y5r3zarvary0c5xw7k6ah60c";
+        assert_eq!(
+            "{\"address\":\"osmo1w508d6qejxtdg4y5r3zarvary0c5xw7kjxy2e2\"}",
+            convert_cosmos_address(address, "osmo")
+        );
+        let hex_address = convert_address(address, "").unwrap();
+        assert_eq!(address, convert_address(&hex_address, "cosmos").unwrap());
+        assert!(address_matches(
+            &hex_address.to_lowercase(),
+            "osmo1w508d6qejxtdg4y5r3zarvary0c5xw7kjxy2e2"
+        ));
+    }
+
+    #[test]
+    fn test_get_cosmos_chain() {
+        let result: Value = serde_json::from_str(&get_cosmos_chain("9001")).unwrap();
+        assert_eq!("evmos_9001-2", result["chain_id"]);
+        assert_eq!("ethsecp256k1", result["key_type"]);
+        assert_eq!(
+            "osmo",
+            find_cosmos_chain("osmosis-1").map(|c| c.hrp).unwrap()
+        );
+        assert_eq!(
+            "{\"error\":\"chain is not supported\"}",
+            get_cosmos_chain("unknown-1")
+        );
+    }
+
+    #[test]
+    fn test_cosmos_chain_registry() {
+        let chain = chain_from_json(
+            r#"{"chain_id":"mantra-1","name":"MANTRA","hrp":"mantra","coin_type":118}"#,
+        )
+        .unwrap();
+        assert_eq!(CosmosKeyType::Secp256k1, chain.key_type);
+        assert_eq!(None, chain.evm_chain_id);
+
+        let mut registry = CosmosChainRegistry::builtin();
+        assert!(registry.find("mantra-1").is_none());
+        registry.register(chain.clone()).unwrap();
+        assert_eq!(Some(&chain), registry.find_by_hrp("mantra"));
+
+        let mut renamed = chain.clone();
+        renamed.name = "MANTRA Chain".into();
+        registry.register(renamed).unwrap();
+        assert_eq!("MANTRA Chain", registry.find("mantra-1").unwrap().name);
+
+        let mut duplicate_hrp = chain;
+        duplicate_hrp.chain_id = "mantra-2".into();
+        duplicate_hrp.hrp = "osmo".into();
+        assert_eq!(
+            "chain conflicts with a registered chain",
+            registry.register(duplicate_hrp).unwrap_err().to_string()
+        );
+
+        let ethermint = chain_from_json(
+            r#"{"chain_id":"example_1234-1","name":"Example","hrp":"example","coin_type":60,"key_type":"ethsecp256k1","evm_chain_id":1234}"#,
+        )
+        .unwrap();
+        registry.register(ethermint.clone()).unwrap();
+        assert_eq!(Some(&ethermint), registry.find_by_evm_chain_id(1234));
+    }
+
+    #[test]
+    fn test_register_cosmos_chain() {
+        assert_eq!(
+            "{\"registered\":\"zigchain-1\"}",
+            register_cosmos_chain(
+                r#"{"chain_id":"zigchain-1","name":"ZIGChain","hrp":"zig","coin_type":118}"#
+            )
+        );
+        let result: Value = serde_json::from_str(&get_cosmos_chain("zigchain-1")).unwrap();
+        assert_eq!("zig", result["hrp"]);
+
+        assert_eq!(
+            "{\"error\":\"hrp is invalid\"}",
+            register_cosmos_chain(r#"{"chain_id":"a-1","name":"A","hrp":"A b","coin_type":118}"#)
+        );
+        assert_eq!(
+            "{\"error\":\"key type is invalid\"}",
+            register_cosmos_chain(
+                r#"{"chain_id":"a-1","name":"A","hrp":"a","coin_type":118,"key_type":"ed25519"}"#
+            )
+        );
+        assert_eq!(
+            "{\"error\":\"coin_type is invalid\"}",
+            register_cosmos_chain(r#"{"chain_id":"a-1","name":"A","hrp":"a"}"#)
+        );
+    }
+
+    #[test]
+    fn test_cosmos_address_error() {
+        assert_eq!(
+            "{\"error\":\"public key is invalid\"}",
+            derive_cosmos_address("02", "cosmos", "")
+        );
+        assert_eq!(
+            "{\"error\":\"key type is invalid\"}",
+            derive_cosmos_address(PUBLIC_KEY, "cosmos", "ed25519")
+        );
+        assert_eq!(
+            "{\"error\":\"hrp is required\"}",
+            convert_cosmos_address("0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf", "")
+        );
+        assert_eq!(
+            "{\"error\":\"address is invalid\"}",
+            convert_cosmos_address("cosmos1invalid", "osmo")
+        );
+    }
+}
@@ -0,0 +1,404 @@
+use anyhow::{format_err, Error};
+use serde_json::{json, Value};
+
+use crate::util_internal::protobuf::ProtoMessage;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Coin {
+    pub denom: String,
+    pub amount: String,
+}
+
+impl Coin {
+    fn from_proto(message: &ProtoMessage) -> Result<Self, Error> {
+        Ok(Coin {
+            denom: message.string(1)?,
+            amount: message.string(2)?,
+        })
+    }
+
+    fn from_amino(value: &Value) -> Result<Self, Error> {
+        Ok(Coin {
+            denom: amino_string(value, "denom")?,
+            amount: amino_string(value, "amount")?,
+        })
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({"denom": self.denom, "amount": self.amount})
+    }
+}
+
+pub(crate) fn coins_from_proto(message: &ProtoMessage, field: u32) -> Result<Vec<Coin>, Error> {
+    message
+        .repeated_messages(field)?
+        .iter()
+        .map(Coin::from_proto)
+        .collect()
+}
+
+pub(crate) fn coins_from_amino(value: &Value) -> Result<Vec<Coin>, Error> {
+    match value {
+        Value::Null => Ok(vec![]),
+        Value::Array(coins) => coins.iter().map(Coin::from_amino).collect(),
+        _ => Err(format_err!("coins are invalid")),
+    }
+}
+
+fn coin_from_proto(message: &ProtoMessage, field: u32) -> Result<Coin, Error> {
+    match message.message(field)? {
+        Some(coin) => Coin::from_proto(&coin),
+        None => Ok(Coin::default()),
+    }
+}
+
+pub(crate) fn amino_string(value: &Value, key: &str) -> Result<String, Error> {
+    match &value[key] {
+        Value::String(v) => Ok(v.clone()),
+        Value::Null => Ok(String::new()),
+        _ => Err(format_err!("{key} is not a string")),
+    }
+}
+
+/// Reads an Amino integer, which is usually encoded as a JSON string.
+pub(crate) fn amino_u64(value: &Value, key: &str) -> Result<u64, Error> {
+    match &value[key] {
+        Value::String(v) => v.parse().map_err(|_| format_err!("{key} is invalid")),
+        Value::Number(v) => v.as_u64().ok_or(format_err!("{key} is invalid")),
+        Value::Null => Ok(0),
+        _ => Err(format_err!("{key} is invalid")),
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum VoteOption {
+    Unspecified,
+    Yes,
+    Abstain,
+    No,
+    NoWithVeto,
+}
+
+impl VoteOption {
+    pub fn from_u64(option: u64) -> Result<Self, Error> {
+        match option {
+            0 => Ok(VoteOption::Unspecified),
+            1 => Ok(VoteOption::Yes),
+            2 => Ok(VoteOption::Abstain),
+            3 => Ok(VoteOption::No),
+            4 => Ok(VoteOption::NoWithVeto),
+            x => Err(format_err!("invalid vote option {x}")),
+        }
+    }
+
+    fn from_amino(value: &Value) -> Result<Self, Error> {
+        match value {
+            Value::String(option) => match option.as_str() {
+                "VOTE_OPTION_UNSPECIFIED" => Ok(VoteOption::Unspecified),
+                "VOTE_OPTION_YES" | "Yes" => Ok(VoteOption::Yes),
+                "VOTE_OPTION_ABSTAIN" | "Abstain" => Ok(VoteOption::Abstain),
+                "VOTE_OPTION_NO" | "No" => Ok(VoteOption::No),
+                "VOTE_OPTION_NO_WITH_VETO" | "NoWithVeto" => Ok(VoteOption::NoWithVeto),
+                x => Self::from_u64(
+                    x.parse()
+                        .map_err(|_| format_err!("invalid vote option {x}"))?,
+                ),
+            },
+            Value::Number(option) => Self::from_u64(option.as_u64().unwrap_or(u64::MAX)),
+            _ => Err(format_err!("invalid vote option")),
+        }
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            VoteOption::Unspecified => "unspecified",
+            VoteOption::Yes => "yes",
+            VoteOption::Abstain => "abstain",
+            VoteOption::No => "no",
+            VoteOption::NoWithVeto => "no_with_veto",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum CosmosMessage {
+    Send {
+        from_address: String,
+        to_address: String,
+        amount: Vec<Coin>,
+    },
+    Delegate {
+        delegator_address: String,
+        validator_address: String,
+        amount: Coin,
+    },
+    Undelegate {
+        delegator_address: String,
+        validator_address: String,
+        amount: Coin,
+    },
+    Redelegate {
+        delegator_address: String,
+        validator_src_address: String,
+        validator_dst_address: String,
+        amount: Coin,
+    },
+    WithdrawReward {
+        delegator_address: String,
+        validator_address: String,
+    },
+    IbcTransfer {
+        source_port: String,
+        source_channel: String,
+        token: Coin,
+        sender: String,
+        receiver: String,
+        timeout_height: (u64, u64),
+        timeout_timestamp: u64,
+        memo: String,
+    },
+    Vote {
+        proposal_id: u64,
+        voter: String,
+        option: VoteOption,
+    },
+    Unknown {
+        type_url: String,
+        value: Value,
+    },
+}
+
+impl CosmosMessage {
+    /// Decodes a protobuf `Any` from `TxBody.messages`.
+    pub fn from_any(any: &ProtoMessage) -> Result<Self, Error> {
+        let type_url = any.string(1)?;
+        let value = any.bytes(2)?;
+        let message = ProtoMessage::decode(value)?;
+        let result = match type_url.as_str() {
+            "/cosmos.bank.v1beta1.MsgSend" => CosmosMessage::Send {
+                from_address: message.string(1)?,
+                to_address: message.string(2)?,
+                amount: coins_from_proto(&message, 3)?,
+            },
+            "/cosmos.staking.v1beta1.MsgDelegate" => CosmosMessage::Delegate {
+                delegator_address: message.string(1)?,
+                validator_address: message.string(2)?,
+                amount: coin_from_proto(&message, 3)?,
+            },
+            "/cosmos.staking.v1beta1.MsgUndelegate" => CosmosMessage::Undelegate {
+                delegator_address: message.string(1)?,
+                validator_address: message.string(2)?,
+                amount: coin_from_proto(&message, 3)?,
+            },
+            "/cosmos.staking.v1beta1.MsgBeginRedelegate" => CosmosMessage::Redelegate {
+                delegator_address: message.string(1)?,
+                validator_src_address: message.string(2)?,
+                validator_dst_address: message.string(3)?,
+                amount: coin_from_proto(&message, 4)?,
+            },
+            "/cosmos.distribution.v1beta1.MsgWithdrawDelegatorReward" => {
+                CosmosMessage::WithdrawReward {
+                    delegator_address: message.string(1)?,
+                    validator_address: message.string(2)?,
+                }
+            }
+            "/ibc.applications.transfer.v1.MsgTransfer" => {
+                let timeout_height = message.message(6)?.unwrap_or_default();
+                CosmosMessage::IbcTransfer {
+                    source_port: message.string(1)?,
+                    source_channel: message.string(2)?,
+                    token: coin_from_proto(&message, 3)?,
+                    sender: message.string(4)?,
+                    receiver: message.string(5)?,
+                    timeout_height: (timeout_height.uint64(1)?, timeout_height.uint64(2)?),
+                    timeout_timestamp: message.uint64(7)?,
+                    memo: message.string(8)?,
+                }
+            }
+            "/cosmos.gov.v1beta1.MsgVote" | "/cosmos.gov.v1.MsgVote" => CosmosMessage::Vote {
+                proposal_id: message.uint64(1)?,
+                voter: message.string(2)?,
+                option: VoteOption::from_u64(message.uint64(3)?)?,
+            },
+            _ => CosmosMessage::Unknown {
+                type_url,
+                value: json!(hex::encode(value)),
+            },
+        };
+        Ok(result)
+    }
+
+    /// Decodes an Amino JSON `{"type": ..., "value": ...}` message.
+    pub fn from_amino(message: &Value) -> Result<Self, Error> {
+        let amino_type = amino_string(message, "type")?;
+        let value = &message["value"];
+        let result = match amino_type.as_str() {
+            "cosmos-sdk/MsgSend" => CosmosMessage::Send {
+                from_address: amino_string(value, "from_address")?,
+                to_address: amino_string(value, "to_address")?,
+                amount: coins_from_amino(&value["amount"])?,
+            },
+            "cosmos-sdk/MsgDelegate" => CosmosMessage::Delegate {
+                delegator_address: amino_string(value, "delegator_address")?,
+                validator_address: amino_string(value, "validator_address")?,
+                amount: Coin::from_amino(&value["amount"])?,
+            },
+            "cosmos-sdk/MsgUndelegate" => CosmosMessage::Undelegate {
+                delegator_address: amino_string(value, "delegator_address")?,
+                validator_address: amino_string(value, "validator_address")?,
+                amount: Coin::from_amino(&value["amount"])?,
+            },
+            "cosmos-sdk/MsgBeginRedelegate" => CosmosMessage::Redelegate {
+                delegator_address: amino_string(value, "delegator_address")?,
+                validator_src_address: amino_string(value, "validator_src_address")?,
+                validator_dst_address: amino_string(value, "validator_dst_address")?,
+                amount: Coin::from_amino(&value["amount"])?,
+            },
+            "cosmos-sdk/MsgWithdrawDelegationReward" => CosmosMessage::WithdrawReward {
+                delegator_address: amino_string(value, "delegator_address")?,
+                validator_address: amino_string(value, "validator_address")?,
+            },
+            "cosmos-sdk/MsgTransfer" => {
+                let timeout_height = &value["timeout_height"];
+                CosmosMessage::IbcTransfer {
+                    source_port: amino_string(value, "source_port")?,
+                    source_channel: amino_string(value, "source_channel")?,
+                    token: Coin::from_amino(&value["token"])?,
+                    sender: amino_string(value, "sender")?,
+                    receiver: amino_string(value, "receiver")?,
+                    timeout_height: (
+                        amino_u64(timeout_height, "revision_number")?,
+                        amino_u64(timeout_height, "revision_height")?,
+                    ),
+                    timeout_timestamp: amino_u64(value, "timeout_timestamp")?,
+                    memo: amino_string(value, "memo")?,
+                }
+            }
+            "cosmos-sdk/MsgVote" | "cosmos-sdk/v1/MsgVote" => CosmosMessage::Vote {
+                proposal_id: amino_u64(value, "proposal_id")?,
+                voter: amino_string(value, "voter")?,
+                option: VoteOption::from_amino(&value["option"])?,
+            },
+            _ => CosmosMessage::Unknown {
+                type_url: amino_type,
+                value: value.clone(),
+            },
+        };
+        Ok(result)
+    }
+
+    /// The address whose signature the message requires, if it is a known message.
+    pub fn get_signer(&self) -> Option<&str> {
+        match self {
+            CosmosMessage::Send { from_address, .. } => Some(from_address),
+            CosmosMessage::Delegate {
+                delegator_address, ..
+            }
+            | CosmosMessage::Undelegate {
+                delegator_address, ..
+            }
+            | CosmosMessage::Redelegate {
+                delegator_address, ..
+            }
+            | CosmosMessage::WithdrawReward {
+                delegator_address, ..
+            } => Some(delegator_address),
+            CosmosMessage::IbcTransfer { sender, .. } => Some(sender),
+            CosmosMessage::Vote { voter, .. } => Some(voter),
+            CosmosMessage::Unknown { .. } => None,
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        match self {
+            CosmosMessage::Send {
+                from_address,
+                to_address,
+                amount,
+            } => json!({
+                "type": "send",
+                "from_address": from_address,
+                "to_address": to_address,
+                "amount": amount.iter().map(Coin::to_json).collect::<Vec<_>>(),
+            }),
+            CosmosMessage::Delegate {
+                delegator_address,
+                validator_address,
+                amount,
+            } => json!({
+                "type": "delegate",
+                "delegator_address": delegator_address,
+                "validator_address": validator_address,
+                "amount": amount.to_json(),
+            }),
+            CosmosMessage::Undelegate {
+                delegator_address,
+                validator_address,
+                amount,
+            } => json!({
+                "type": "undelegate",
+                "delegator_address": delegator_address,
+                "validator_address": validator_address,
+                "amount": amount.to_json(),
+            }),
+            CosmosMessage::Redelegate {
+                delegator_address,
+                validator_src_address,
+                validator_dst_address,
+                amount,
+            } => json!({
+                "type": "redelegate",
+                "delegator_address": delegator_address,
+                "validator_src_address": validator_src_address,
+                "validator_dst_address": validator_dst_address,
+                "amount": amount.to_json(),
+            }),
+            CosmosMessage::WithdrawReward {
+                delegator_address,
+                validator_address,
+            } => json!({
+                "type": "withdraw_reward",
+                "delegator_address": delegator_address,
+                "validator_address": validator_address,
+            }),
+            CosmosMessage::IbcTransfer {
+                source_port,
+                source_channel,
+                token,
+                sender,
+                receiver,
+                timeout_height,
+                timeout_timestamp,
+                memo,
+            } => json!({
+                "type": "ibc_transfer",
+                "source_port": source_port,
+                "source_channel": source_channel,
+                "token": token.to_json(),
+                "sender": sender,
+                "receiver": receiver,
+                "timeout_height": {
+                    "revision_number": timeout_height.0.to_string(),
+                    "revision_height": timeout_height.1.to_string(),
+                },
+                "timeout_timestamp": timeout_timestamp.to_string(),
+                "memo": memo,
+            }),
+            CosmosMessage::Vote {
+                proposal_id,
+                voter,
+                option,
+            } => json!({
+                "type": "vote",
+                "proposal_id": proposal_id.to_string(),
+                "voter": voter,
+                "option": option.get_name(),
+            }),
+            CosmosMessage::Unknown { type_url, value } => json!({
+                "type": "unknown",
+                "type_url": type_url,
+                "value": value,
+            }),
+        }
+    }
+}
@@ -0,0 +1,685 @@
+use anyhow::{format_err, Error};
+use minicbor::Decoder;
+use serde_json::{json, Value};
+use ur_registry::cosmos::cosmos_sign_request::{CosmosSignRequest, DataType};
+use ur_registry::registry_types::COSMOS_SIGN_REQUEST;
+
+use crate::cosmos::cosmos_address::address_matches;
+use crate::cosmos::cosmos_message::{
+    amino_string, amino_u64, coins_from_amino, coins_from_proto, Coin, CosmosMessage,
+};
+use crate::export;
+use crate::util_internal::protobuf::ProtoMessage;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SignMode {
+    Amino,
+    Direct,
+    Textual,
+}
+
+impl SignMode {
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            SignMode::Amino => "amino",
+            SignMode::Direct => "direct",
+            SignMode::Textual => "textual",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Fee {
+    pub amount: Vec<Coin>,
+    pub gas_limit: u64,
+    pub payer: String,
+    pub granter: String,
+}
+
+impl Fee {
+    fn to_json(&self) -> Value {
+        json!({
+            "amount": self.amount.iter().map(Coin::to_json).collect::<Vec<_>>(),
+            "gas_limit": self.gas_limit.to_string(),
+            "payer": self.payer,
+            "granter": self.granter,
+        })
+    }
+}
+
+/// A `SIGN_MODE_TEXTUAL` screen.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TextualScreen {
+    pub title: String,
+    pub content: String,
+    pub indent: u64,
+    pub expert: bool,
+}
+
+impl TextualScreen {
+    fn to_json(&self) -> Value {
+        json!({
+            "title": self.title,
+            "content": self.content,
+            "indent": self.indent,
+            "expert": self.expert,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CosmosSignDoc {
+    mode: SignMode,
+    chain_id: String,
+    account_number: u64,
+    sequence: u64,
+    memo: String,
+    fee: Fee,
+    messages: Vec<CosmosMessage>,
+    timeout_height: u64,
+    public_keys: Vec<Vec<u8>>,
+    screens: Vec<TextualScreen>,
+}
+
+impl CosmosSignDoc {
+    fn empty(mode: SignMode) -> Self {
+        CosmosSignDoc {
+            mode,
+            chain_id: String::new(),
+            account_number: 0,
+            sequence: 0,
+            memo: String::new(),
+            fee: Fee::default(),
+            messages: vec![],
+            timeout_height: 0,
+            public_keys: vec![],
+            screens: vec![],
+        }
+    }
+
+    /// Parses an Amino JSON `StdSignDoc`.
+    pub fn decode_amino(sign_data: &[u8]) -> Result<Self, Error> {
+        let doc: Value =
+            serde_json::from_slice(sign_data).map_err(|_| format_err!("sign doc is not json"))?;
+        let msgs = doc["msgs"]
+            .as_array()
+            .ok_or(format_err!("msgs is invalid"))?;
+        let fee = &doc["fee"];
+        Ok(CosmosSignDoc {
+            chain_id: amino_string(&doc, "chain_id")?,
+            account_number: amino_u64(&doc, "account_number")?,
+            sequence: amino_u64(&doc, "sequence")?,
+            memo: amino_string(&doc, "memo")?,
+            fee: Fee {
+                amount: coins_from_amino(&fee["amount"])?,
+                gas_limit: amino_u64(fee, "gas")?,
+                payer: amino_string(fee, "payer")?,
+                granter: amino_string(fee, "granter")?,
+            },
+            messages: msgs
+                .iter()
+                .map(CosmosMessage::from_amino)
+                .collect::<Result<_, _>>()?,
+            timeout_height: amino_u64(&doc, "timeout_height")?,
+            ..Self::empty(SignMode::Amino)
+        })
+    }
+
+    /// Parses a protobuf `SignDoc` with its `TxBody` and `AuthInfo`.
+    pub fn decode_direct(sign_data: &[u8]) -> Result<Self, Error> {
+        let sign_doc = ProtoMessage::decode(sign_data)?;
+        let body = ProtoMessage::decode(sign_doc.bytes(1)?)?;
+        let auth_info = ProtoMessage::decode(sign_doc.bytes(2)?)?;
+
+        let signer_infos = auth_info.repeated_messages(1)?;
+        let public_keys = signer_infos
+            .iter()
+            .map(|signer_info| match signer_info.message(1)? {
+                Some(any) => Ok(ProtoMessage::decode(any.bytes(2)?)?.bytes(1)?.to_vec()),
+                None => Ok(vec![]),
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        let sequence = match signer_infos.first() {
+            Some(signer_info) => signer_info.uint64(3)?,
+            None => 0,
+        };
+        let fee = match auth_info.message(2)? {
+            Some(fee) => Fee {
+                amount: coins_from_proto(&fee, 1)?,
+                gas_limit: fee.uint64(2)?,
+                payer: fee.string(3)?,
+                granter: fee.string(4)?,
+            },
+            None => Fee::default(),
+        };
+
+        Ok(CosmosSignDoc {
+            chain_id: sign_doc.string(3)?,
+            account_number: sign_doc.uint64(4)?,
+            sequence,
+            memo: body.string(2)?,
+            fee,
+            messages: body
+                .repeated_messages(1)?
+                .iter()
+                .map(CosmosMessage::from_any)
+                .collect::<Result<_, _>>()?,
+            timeout_height: body.uint64(3)?,
+            public_keys,
+            ..Self::empty(SignMode::Direct)
+        })
+    }
+
+    /// Parses `SIGN_MODE_TEXTUAL` sign bytes, a CBOR map whose key 1 holds the screens.
+    pub fn decode_textual(sign_data: &[u8]) -> Result<Self, Error> {
+        let invalid = |_| format_err!("textual sign doc is invalid");
+        let mut decoder = Decoder::new(sign_data);
+        let mut screens = vec![];
+        let entries = decoder.map().map_err(invalid)?.ok_or(format_err!(
+            "indefinite length textual sign doc is not supported"
+        ))?;
+        for _ in 0..entries {
+            if decoder.u8().map_err(invalid)? != 1 {
+                decoder.skip().map_err(invalid)?;
+                continue;
+            }
+            let len = decoder.array().map_err(invalid)?.unwrap_or_default();
+            for _ in 0..len {
+                let mut screen = TextualScreen::default();
+                let fields = decoder.map().map_err(invalid)?.unwrap_or_default();
+                for _ in 0..fields {
+                    match decoder.u8().map_err(invalid)? {
+                        1 => screen.title = decoder.str().map_err(invalid)?.to_string(),
+                        2 => screen.content = decoder.str().map_err(invalid)?.to_string(),
+                        3 => screen.indent = decoder.u64().map_err(invalid)?,
+                        4 => screen.expert = decoder.bool().map_err(invalid)?,
+                        _ => decoder.skip().map_err(invalid)?,
+                    }
+                }
+                screens.push(screen);
+            }
+        }
+        if decoder.position() != sign_data.len() {
+            return Err(format_err!("unexpected trailing bytes"));
+        }
+
+        let find = |title: &str| {
+            screens
+                .iter()
+                .find(|screen| screen.title == title && screen.indent == 0)
+                .map(|screen| screen.content.clone())
+                .unwrap_or_default()
+        };
+        Ok(CosmosSignDoc {
+            chain_id: find("Chain id"),
+            account_number: find("Account number").parse().unwrap_or_default(),
+            sequence: find("Sequence").parse().unwrap_or_default(),
+            memo: find("Memo"),
+            screens: screens.clone(),
+            ..Self::empty(SignMode::Textual)
+        })
+    }
+
+    /// Parses the request sign data by its data type, then checks the chain id, when
+    /// given, and that each request address signs one of the messages.
+    pub fn from_sign_request(
+        request: &CosmosSignRequest,
+        chain_id: Option<&str>,
+    ) -> Result<Self, Error> {
+        let sign_data = request.get_sign_data();
+        let sign_doc = match request.get_data_type() {
+            DataType::Amino => Self::decode_amino(&sign_data)?,
+            DataType::Direct => Self::decode_direct(&sign_data)?,
+            DataType::Textual => Self::decode_textual(&sign_data)?,
+            DataType::Message => return Err(format_err!("data type is not a sign doc")),
+        };
+        if let Some(chain_id) = chain_id {
+            if sign_doc.chain_id != chain_id {
+                return Err(format_err!("chain id does not match"));
+            }
+        }
+        // Textual docs carry no messages, and the signer of an unknown message cannot be
+        // told, so the request address is only checked when every signer is known.
+        let has_unknown = sign_doc
+            .messages
+            .iter()
+            .any(|message| matches!(message, CosmosMessage::Unknown { .. }));
+        if sign_doc.mode != SignMode::Textual && !has_unknown {
+            let signers = sign_doc.get_signers();
+            for address in request.get_addresses().unwrap_or_default() {
+                if !address.is_empty()
+                    && !signers
+                        .iter()
+                        .any(|signer| address_matches(&address, signer))
+                {
+                    return Err(format_err!("request address is not a message signer"));
+                }
+            }
+        }
+        Ok(sign_doc)
+    }
+
+    pub fn get_mode(&self) -> SignMode {
+        self.mode
+    }
+
+    pub fn get_chain_id(&self) -> String {
+        self.chain_id.clone()
+    }
+
+    pub fn get_account_number(&self) -> u64 {
+        self.account_number
+    }
+
+    pub fn get_sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    pub fn get_memo(&self) -> String {
+        self.memo.clone()
+    }
+
+    pub fn get_fee(&self) -> Fee {
+        self.fee.clone()
+    }
+
+    pub fn get_messages(&self) -> Vec<CosmosMessage> {
+        self.messages.clone()
+    }
+
+    pub fn get_public_keys(&self) -> Vec<Vec<u8>> {
+        self.public_keys.clone()
+    }
+
+    pub fn get_screens(&self) -> Vec<TextualScreen> {
+        self.screens.clone()
+    }
+
+    pub fn get_signers(&self) -> Vec<String> {
+        let mut signers: Vec<String> = vec![];
+        for signer in self.messages.iter().filter_map(CosmosMessage::get_signer) {
+            if !signers.iter().any(|s| s == signer) {
+                signers.push(signer.to_string());
+            }
+        }
+        signers
+    }
+
+    pub fn to_json(&self) -> Value {
+        let mut result = json!({
+            "mode": self.mode.get_name(),
+            "chain_id": self.chain_id,
+            "account_number": self.account_number.to_string(),
+            "sequence": self.sequence.to_string(),
+            "memo": self.memo,
+        });
+        match self.mode {
+            SignMode::Textual => {
+                result["screens"] = self.screens.iter().map(TextualScreen::to_json).collect();
+            }
+            _ => {
+                result["fee"] = self.fee.to_json();
+                result["messages"] = self.messages.iter().map(CosmosMessage::to_json).collect();
+                result["timeout_height"] = json!(self.timeout_height.to_string());
+                result["signers"] = json!(self.get_signers());
+            }
+        }
+        if self.mode == SignMode::Direct {
+            result["public_keys"] = self.public_keys.iter().map(hex::encode).collect();
+        }
+        result
+    }
+}
+
+export! {
+    @Java_com_keystone_sdk_KeystoneNativeSDK_decodeCosmosSignRequest
+    fn decode_cosmos_sign_request(ur_type: &str, cbor_hex: &str, chain_id: &str) -> String {
+        if COSMOS_SIGN_REQUEST.get_type() != ur_type {
+            return json!({"error": "type not match"}).to_string();
+        }
+
+        let decode = || -> Result<Value, Error> {
+            let cbor = hex::decode(cbor_hex)?;
+            let request = CosmosSignRequest::try_from(cbor).map_err(|e| format_err!(e.to_string()))?;
+            let chain_id = if chain_id.is_empty() { None } else { Some(chain_id) };
+            Ok(CosmosSignDoc::from_sign_request(&request, chain_id)?.to_json())
+        };
+        match decode() {
+            Ok(sign_doc) => sign_doc.to_string(),
+            Err(e) => json!({"error": e.to_string()}).to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ur_registry::crypto_key_path::CryptoKeyPath;
+
+    const AMINO_SEND: &str = "7B226163636F756E745F6E756D626572223A22323930353536222C22636861696E5F6964223A226F736D6F2D746573742D34222C22666565223A7B22616D6F756E74223A5B7B22616D6F756E74223A2231303032222C2264656E6F6D223A22756F736D6F227D5D2C22676173223A22313030313936227D2C226D656D6F223A22222C226D736773223A5B7B2274797065223A22636F736D6F732D73646B2F4D736753656E64222C2276616C7565223A7B22616D6F756E74223A5B7B22616D6F756E74223A223132303030303030222C2264656E6F6D223A22756F736D6F227D5D2C2266726F6D5F61646472657373223A226F736D6F31667334396A7867797A30306C78363436336534767A767838353667756C64756C6A7A6174366D222C22746F5F61646472657373223A226F736D6F31667334396A7867797A30306C78363436336534767A767838353667756C64756C6A7A6174366D227D7D5D2C2273657175656E6365223A2230227D";
+    const SENDER: &str = "osmo1fs49jxgyz00lx6463e4vzvx856gulduljzat6m";
+
+    fn sign_request(sign_data: Vec<u8>, data_type: DataType, address: &str) -> String {
+        let path = CryptoKeyPath::from_path("m/44'/118'/0'/0/0".to_string(), None).unwrap();
+        let request = CosmosSignRequest::new(
+            vec![0u8; 16],
+            sign_data,
+            data_type,
+            vec![path],
+            Some(vec![address.to_string()]),
+            None,
+        );
+        let cbor: Vec<u8> = request.try_into().unwrap();
+        hex::encode(cbor)
+    }
+
+    fn write_varint(output: &mut Vec<u8>, mut value: u64) {
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+            if value == 0 {
+                output.push(byte);
+                break;
+            }
+            output.push(byte | 0x80);
+        }
+    }
+
+    fn field(output: &mut Vec<u8>, number: u8, bytes: &[u8]) {
+        output.push(number << 3 | 2);
+        write_varint(output, bytes.len() as u64);
+        output.extend_from_slice(bytes);
+    }
+
+    fn varint(output: &mut Vec<u8>, number: u8, value: u64) {
+        output.push(number << 3);
+        write_varint(output, value);
+    }
+
+    fn any(type_url: &str, value: &[u8]) -> Vec<u8> {
+        let mut result = vec![];
+        field(&mut result, 1, type_url.as_bytes());
+        field(&mut result, 2, value);
+        result
+    }
+
+    fn coin(denom: &str, amount: &str) -> Vec<u8> {
+        let mut result = vec![];
+        field(&mut result, 1, denom.as_bytes());
+        field(&mut result, 2, amount.as_bytes());
+        result
+    }
+
+    fn direct_sign_doc(delegator: &str) -> Vec<u8> {
+        let mut delegate = vec![];
+        field(&mut delegate, 1, delegator.as_bytes());
+        field(&mut delegate, 2, b"cosmosvaloper1validator");
+        field(&mut delegate, 3, &coin("uatom", "1000000"));
+        let mut vote = vec![];
+        varint(&mut vote, 1, 842);
+        field(&mut vote, 2, delegator.as_bytes());
+        varint(&mut vote, 3, 1);
+
+        let mut body = vec![];
+        field(
+            &mut body,
+            1,
+            &any("/cosmos.staking.v1beta1.MsgDelegate", &delegate),
+        );
+        field(&mut body, 1, &any("/cosmos.gov.v1beta1.MsgVote", &vote));
+        field(&mut body, 2, b"memo");
+
+        let mut pub_key = vec![];
+        field(&mut pub_key, 1, &[2u8; 33]);
+        let mut signer_info = vec![];
+        field(
+            &mut signer_info,
+            1,
+            &any("/cosmos.crypto.secp256k1.PubKey", &pub_key),
+        );
+        varint(&mut signer_info, 3, 12);
+        let mut fee = vec![];
+        field(&mut fee, 1, &coin("uatom", "5000"));
+        varint(&mut fee, 2, 200000);
+        let mut auth_info = vec![];
+        field(&mut auth_info, 1, &signer_info);
+        field(&mut auth_info, 2, &fee);
+
+        let mut sign_doc = vec![];
+        field(&mut sign_doc, 1, &body);
+        field(&mut sign_doc, 2, &auth_info);
+        field(&mut sign_doc, 3, b"cosmoshub-4");
+        varint(&mut sign_doc, 4, 7);
+        sign_doc
+    }
+
+    #[test]
+    fn test_decode_amino_sign_doc() {
+        let sign_data = hex::decode(AMINO_SEND).unwrap();
+        let result: Value = serde_json::from_str(&decode_cosmos_sign_request(
+            "cosmos-sign-request",
+            &sign_request(sign_data.clone(), DataType::Amino, SENDER),
+            "osmo-test-4",
+        ))
+        .unwrap();
+        assert_eq!("amino", result["mode"]);
+        assert_eq!("290556", result["account_number"]);
+        assert_eq!("100196", result["fee"]["gas_limit"]);
+        assert_eq!(
+            json!([{
+                "type": "send",
+                "from_address": SENDER,
+                "to_address": SENDER,
+                "amount": [{"denom": "uosmo", "amount": "12000000"}],
+            }]),
+            result["messages"]
+        );
+
+        // The request may carry the hex of the bech32 payload instead.
+        let hex_address = "4c2a59190413dff36aba8e6ac130c7a691cfb79f";
+        assert!(decode_cosmos_sign_request(
+            "cosmos-sign-request",
+            &sign_request(sign_data, DataType::Amino, hex_address),
+            "",
+        )
+        .contains("\"mode\":\"amino\""));
+    }
+
+    #[test]
+    fn test_decode_amino_messages() {
+        let doc = json!({
+            "account_number": "1",
+            "chain_id": "cosmoshub-4",
+            "fee": {"amount": [], "gas": "300000"},
+            "memo": "",
+            "msgs": [
+                {"type": "cosmos-sdk/MsgBeginRedelegate", "value": {
+                    "delegator_address": "cosmos1a",
+                    "validator_src_address": "cosmosvaloper1b",
+                    "validator_dst_address": "cosmosvaloper1c",
+                    "amount": {"denom": "uatom", "amount": "5"},
+                }},
+                {"type": "cosmos-sdk/MsgWithdrawDelegationReward", "value": {
+                    "delegator_address": "cosmos1a",
+                    "validator_address": "cosmosvaloper1b",
+                }},
+                {"type": "cosmos-sdk/MsgTransfer", "value": {
+                    "source_port": "transfer",
+                    "source_channel": "channel-141",
+                    "token": {"denom": "uatom", "amount": "10"},
+                    "sender": "cosmos1a",
+                    "receiver": "osmo1d",
+                    "timeout_height": {"revision_number": "1", "revision_height": "12345"},
+                    "timeout_timestamp": "0",
+                }},
+                {"type": "cosmos-sdk/MsgVote", "value": {
+                    "proposal_id": "842", "voter": "cosmos1a", "option": 3,
+                }},
+                {"type": "wasm/MsgExecuteContract", "value": {"sender": "cosmos1a"}},
+            ],
+            "sequence": "4",
+        });
+        let sign_doc = CosmosSignDoc::decode_amino(doc.to_string().as_bytes()).unwrap();
+        assert_eq!(vec!["cosmos1a".to_string()], sign_doc.get_signers());
+        let result = sign_doc.to_json();
+        assert_eq!("redelegate", result["messages"][0]["type"]);
+        assert_eq!("withdraw_reward", result["messages"][1]["type"]);
+        assert_eq!(
+            "12345",
+            result["messages"][2]["timeout_height"]["revision_height"]
+        );
+        assert_eq!("no", result["messages"][3]["option"]);
+        assert_eq!(
+            json!({
+                "type": "unknown",
+                "type_url": "wasm/MsgExecuteContract",
+                "value": {"sender": "cosmos1a"},
+            }),
+            result["messages"][4]
+        );
+    }
+
+    #[test]
+    fn test_decode_direct_sign_doc() {
+        let sign_data = direct_sign_doc("cosmos1delegator");
+        let result: Value = serde_json::from_str(&decode_cosmos_sign_request(
+            "cosmos-sign-request",
+            &sign_request(sign_data, DataType::Direct, "cosmos1delegator"),
+            "cosmoshub-4",
+        ))
+        .unwrap();
+        assert_eq!("direct", result["mode"]);
+        assert_eq!("7", result["account_number"]);
+        assert_eq!("12", result["sequence"]);
+        assert_eq!("memo", result["memo"]);
+        assert_eq!(
+            json!({
+                "amount": [{"denom": "uatom", "amount": "5000"}],
+                "gas_limit": "200000",
+                "payer": "",
+                "granter": "",
+            }),
+            result["fee"]
+        );
+        assert_eq!(
+            json!([
+                {
+                    "type": "delegate",
+                    "delegator_address": "cosmos1delegator",
+                    "validator_address": "cosmosvaloper1validator",
+                    "amount": {"denom": "uatom", "amount": "1000000"},
+                },
+                {
+                    "type": "vote",
+                    "proposal_id": "842",
+                    "voter": "cosmos1delegator",
+                    "option": "yes",
+                },
+            ]),
+            result["messages"]
+        );
+        assert_eq!(json!(["02".repeat(33)]), result["public_keys"]);
+    }
+
+    #[test]
+    fn test_decode_textual_sign_doc() {
+        let mut sign_data = vec![0xa1, 0x01, 0x83];
+        for (title, content) in [("Chain id", "cosmoshub-4"), ("Sequence", "3")] {
+            sign_data.extend_from_slice(&[0xa2, 0x01, 0x60 + title.len() as u8]);
+            sign_data.extend_from_slice(title.as_bytes());
+            sign_data.extend_from_slice(&[0x02, 0x60 + content.len() as u8]);
+            sign_data.extend_from_slice(content.as_bytes());
+        }
+        sign_data.extend_from_slice(&[0xa3, 0x02, 0x63]);
+        sign_data.extend_from_slice(b"abc");
+        sign_data.extend_from_slice(&[0x03, 0x01, 0x04, 0xf5]);
+
+        let sign_doc = CosmosSignDoc::decode_textual(&sign_data).unwrap();
+        assert_eq!("cosmoshub-4", sign_doc.get_chain_id());
+        assert_eq!(3, sign_doc.get_sequence());
+        assert_eq!(
+            TextualScreen {
+                title: "".to_string(),
+                content: "abc".to_string(),
+                indent: 1,
+                expert: true,
+            },
+            sign_doc.get_screens()[2]
+        );
+        assert_eq!("textual", sign_doc.to_json()["mode"]);
+    }
+
+    #[test]
+    fn test_decode_unknown_message_sign_doc() {
+        let mut execute = vec![];
+        field(&mut execute, 1, b"osmo1sender");
+        field(&mut execute, 2, b"osmo1contract");
+        field(&mut execute, 3, br#"{"swap":{}}"#);
+        let mut body = vec![];
+        field(
+            &mut body,
+            1,
+            &any("/cosmwasm.wasm.v1.MsgExecuteContract", &execute),
+        );
+        let mut sign_doc = vec![];
+        field(&mut sign_doc, 1, &body);
+        field(&mut sign_doc, 2, &[]);
+        field(&mut sign_doc, 3, b"osmosis-1");
+        varint(&mut sign_doc, 4, 7);
+
+        let result: Value = serde_json::from_str(&decode_cosmos_sign_request(
+            "cosmos-sign-request",
+            &sign_request(sign_doc, DataType::Direct, SENDER),
+            "osmosis-1",
+        ))
+        .unwrap();
+        assert_eq!("unknown", result["messages"][0]["type"]);
+        assert_eq!(
+            "/cosmwasm.wasm.v1.MsgExecuteContract",
+            result["messages"][0]["type_url"]
+        );
+    }
+
+    #[test]
+    fn test_decode_cosmos_sign_request_error() {
+        let sign_data = hex::decode(AMINO_SEND).unwrap();
+        assert_eq!(
+            "{\"error\":\"chain id does not match\"}",
+            decode_cosmos_sign_request(
+                "cosmos-sign-request",
+                &sign_request(sign_data.clone(), DataType::Amino, SENDER),
+                "osmosis-1",
+            )
+        );
+        assert_eq!(
+            "{\"error\":\"request address is not a message signer\"}",
+            decode_cosmos_sign_request(
+                "cosmos-sign-request",
+                &sign_request(sign_data.clone(), DataType::Amino, "osmo1other"),
+                "",
+            )
+        );
+        assert_eq!(
+            "{\"error\":\"data type is not a sign doc\"}",
+            decode_cosmos_sign_request(
+                "cosmos-sign-request",
+                &sign_request(sign_data.clone(), DataType::Message, ""),
+                "",
+            )
+        );
+        assert_eq!(
+            "{\"error\":\"type not match\"}",
+            decode_cosmos_sign_request(
+                "cosmos-signature",
+                &sign_request(sign_data, DataType::Amino, ""),
+                "",
+            )
+        );
+        assert_eq!(
+            "sign doc is not json",
+            CosmosSignDoc::decode_amino(&direct_sign_doc("cosmos1a"))
+                .unwrap_err()
+                .to_string()
+        );
+    }
+}
@@ -1,2 +1,5 @@
 pub mod cosmos_sign_request;
 pub mod cosmos_signature;
+pub mod cosmos_address;
+pub mod cosmos_message;
+pub mod cosmos_sign_doc;
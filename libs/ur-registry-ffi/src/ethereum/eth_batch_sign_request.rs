@@ -0,0 +1,122 @@
+use serde::Deserialize;
+use serde_json::json;
+use ur_registry::crypto_key_path::CryptoKeyPath;
+use ur_registry::ethereum::eth_batch_sign_requests::EthBatchSignRequest;
+use ur_registry::ethereum::eth_sign_request::{DataType, EthSignRequest};
+use uuid::Uuid;
+
+use crate::export;
+use crate::util_internal::string_helper::remove_prefix_0x;
+
+#[derive(Deserialize)]
+struct Request {
+    request_id: String,
+    sign_data: String,
+    data_type: u32,
+    #[serde(default)]
+    chain_id: i32,
+    path: String,
+    xfp: String,
+    #[serde(default)]
+    address: String,
+    #[serde(default)]
+    origin: String,
+}
+
+fn build_request(request: &Request) -> Result<EthSignRequest, String> {
+    let xfp: [u8; 4] = hex::decode(&request.xfp)
+        .map_err(|_| "xfp is invalid".to_string())?
+        .as_slice()
+        .try_into()
+        .map_err(|_| "length of xfp must be exactly 8".to_string())?;
+    let derivation_path = CryptoKeyPath::from_path(request.path.clone(), Some(xfp))
+        .map_err(|_| "path is invalid".to_string())?;
+    let data_type =
+        DataType::from_u32(request.data_type).map_err(|_| "data type is invalid".to_string())?;
+    let request_id = Uuid::parse_str(&request.request_id)
+        .map_err(|_| "uuid is invalid".to_string())?
+        .as_bytes()
+        .to_vec();
+    let sign_data = hex::decode(remove_prefix_0x(&request.sign_data))
+        .map_err(|_| "sign_data is invalid".to_string())?;
+
+    let chain_id = (request.chain_id != 0).then(|| i128::from(request.chain_id));
+    let address = (!request.address.is_empty()).then(|| request.address.as_bytes().to_vec());
+    let origin = (!request.origin.is_empty()).then(|| request.origin.clone());
+    Ok(EthSignRequest::new(
+        Some(request_id),
+        sign_data,
+        data_type,
+        chain_id,
+        derivation_path,
+        address,
+        origin,
+    ))
+}
+
+export! {
+    @Java_com_keystone_sdk_KeystoneNativeSDK_generateETHBatchSignRequest
+    fn generate_eth_batch_sign_request(requests: &str) -> String {
+        let requests = match serde_json::from_str::<Vec<Request>>(requests) {
+            Ok(v) if !v.is_empty() => v,
+            _ => return json!({"error": "requests is invalid"}).to_string(),
+        };
+        let requests = match requests.iter().map(build_request).collect::<Result<Vec<_>, _>>() {
+            Ok(v) => v,
+            Err(e) => return json!({"error": e}).to_string(),
+        };
+
+        let cbor: Vec<u8> = match EthBatchSignRequest::new(requests).try_into() {
+            Ok(v) => v,
+            Err(_) => return json!({"error": "cbor serialization failed"}).to_string(),
+        };
+        json!({
+            "type": "eth-batch-sign-request",
+            "cbor": hex::encode(cbor),
+        }).to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_eth_batch_sign_request() {
+        let requests = r#"[
+            {"request_id": "9b1deb4d-3b7d-4bad-9bdd-2b0d7b3dcb6d", "sign_data": "0x48656c6c6f", "data_type": 3, "chain_id": 1, "path": "m/44'/60'/0'/0/0", "xfp": "12345678", "address": "0x9d8A62f656a8d1615C1294fd71e9CFb3E4855A4F", "origin": "metamask"},
+            {"request_id": "1b1deb4d-3b7d-4bad-9bdd-2b0d7b3dcb6d", "sign_data": "48656c6c6f", "data_type": 3, "path": "m/44'/60'/0'/0/1", "xfp": "12345678"}
+        ]"#;
+        let result: serde_json::Value =
+            serde_json::from_str(&generate_eth_batch_sign_request(requests)).unwrap();
+        assert_eq!("eth-batch-sign-request", result["type"]);
+
+        let cbor = hex::decode(result["cbor"].as_str().unwrap()).unwrap();
+        let batch = EthBatchSignRequest::try_from(cbor).unwrap();
+        let requests = batch.get_requests();
+        assert_eq!(2, requests.len());
+        assert_eq!(Some(1), requests[0].get_chain_id());
+        assert_eq!(Some("metamask".to_string()), requests[0].get_origin());
+        assert_eq!(None, requests[1].get_chain_id());
+        assert_eq!(None, requests[1].get_address());
+        assert_eq!(
+            "1b1deb4d-3b7d-4bad-9bdd-2b0d7b3dcb6d",
+            Uuid::from_slice(&requests[1].get_request_id().unwrap())
+                .unwrap()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn test_generate_eth_batch_sign_request_error() {
+        assert_eq!(
+            "{\"error\":\"requests is invalid\"}",
+            generate_eth_batch_sign_request("[]")
+        );
+        let requests = r#"[{"request_id": "9b1deb4d-3b7d-4bad-9bdd-2b0d7b3dcb6d", "sign_data": "48656c6c6f", "data_type": 3, "path": "m/44'/60'/0'/0/0", "xfp": "1234"}]"#;
+        assert_eq!(
+            "{\"error\":\"length of xfp must be exactly 8\"}",
+            generate_eth_batch_sign_request(requests)
+        );
+    }
+}
@@ -0,0 +1,95 @@
+use anyhow::{format_err, Error};
+use serde_json::{json, Value};
+use ur_registry::ethereum::eth_batch_signature::EthBatchSignature;
+use ur_registry::registry_types::ETH_BATCH_SIGNATURE;
+use uuid::Uuid;
+
+use crate::export;
+
+export! {
+    @Java_com_keystone_sdk_KeystoneNativeSDK_parseETHBatchSignature
+    fn parse_eth_batch_signature(ur_type: &str, cbor_hex: &str) -> String {
+        if ETH_BATCH_SIGNATURE.get_type() != ur_type {
+            return json!({"error": "type not match"}).to_string();
+        }
+
+        let parse_signatures = || -> Result<Vec<Value>, Error> {
+            let cbor = hex::decode(cbor_hex)?;
+            let batch = EthBatchSignature::try_from(cbor).map_err(|_| format_err!(""))?;
+            let mut request_ids: Vec<String> = vec![];
+            batch.get_signatures().iter().map(|eth_signature| {
+                let uuid = eth_signature.get_request_id().ok_or(format_err!(""))?;
+                let request_id = Uuid::from_slice(&uuid)?.to_string();
+                if request_ids.contains(&request_id) {
+                    return Err(format_err!(""));
+                }
+                request_ids.push(request_id.clone());
+                Ok(json!({
+                    "request_id": request_id,
+                    "signature": hex::encode(eth_signature.get_signature()),
+                }))
+            }).collect()
+        };
+        match parse_signatures() {
+            Ok(signatures) => json!({"signatures": signatures}).to_string(),
+            Err(_) => json!({"error": "signature is invalid"}).to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ur_registry::ethereum::eth_signature::EthSignature;
+
+    fn batch_signature_cbor(request_ids: &[&str]) -> String {
+        let signatures = request_ids
+            .iter()
+            .enumerate()
+            .map(|(index, request_id)| {
+                EthSignature::new(
+                    Some(Uuid::parse_str(request_id).unwrap().as_bytes().to_vec()),
+                    vec![index as u8; 65],
+                    None,
+                )
+            })
+            .collect();
+        let cbor: Vec<u8> = EthBatchSignature::new(signatures).try_into().unwrap();
+        hex::encode(cbor)
+    }
+
+    #[test]
+    fn test_parse_eth_batch_signature() {
+        let cbor = batch_signature_cbor(&[
+            "9b1deb4d-3b7d-4bad-9bdd-2b0d7b3dcb6d",
+            "1b1deb4d-3b7d-4bad-9bdd-2b0d7b3dcb6d",
+        ]);
+        let expect_result = format!(
+            "{{\"signatures\":[{{\"request_id\":\"9b1deb4d-3b7d-4bad-9bdd-2b0d7b3dcb6d\",\"signature\":\"{}\"}},{{\"request_id\":\"1b1deb4d-3b7d-4bad-9bdd-2b0d7b3dcb6d\",\"signature\":\"{}\"}}]}}",
+            "00".repeat(65),
+            "01".repeat(65)
+        );
+        assert_eq!(
+            expect_result,
+            parse_eth_batch_signature("eth-batch-signature", &cbor)
+        );
+    }
+
+    #[test]
+    fn test_parse_eth_batch_signature_error() {
+        let cbor = batch_signature_cbor(&["9b1deb4d-3b7d-4bad-9bdd-2b0d7b3dcb6d"]);
+        assert_eq!(
+            "{\"error\":\"type not match\"}",
+            parse_eth_batch_signature("eth-signature", &cbor)
+        );
+
+        let cbor = batch_signature_cbor(&[
+            "9b1deb4d-3b7d-4bad-9bdd-2b0d7b3dcb6d",
+            "9b1deb4d-3b7d-4bad-9bdd-2b0d7b3dcb6d",
+        ]);
+        assert_eq!(
+            "{\"error\":\"signature is invalid\"}",
+            parse_eth_batch_signature("eth-batch-signature", &cbor)
+        );
+    }
+}
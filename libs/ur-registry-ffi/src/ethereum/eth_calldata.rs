@@ -0,0 +1,588 @@
+use std::collections::BTreeMap;
+use std::sync::{Mutex, OnceLock};
+
+use anyhow::{format_err, Error};
+use ethabi::ethereum_types::{H160, U256};
+use ethabi::param_type::Reader;
+use ethabi::{Contract, Function, Param, ParamType, StateMutability, Token};
+use serde_json::{json, Value};
+use ur_registry::cosmos::evm_sign_request::{EvmSignRequest, SignDataType};
+use ur_registry::ethereum::eth_sign_request::{DataType, EthSignRequest};
+use ur_registry::registry_types::{ETH_SIGN_REQUEST, EVM_SIGN_REQUEST};
+
+use crate::ethereum::eth_transaction::EthTransaction;
+use crate::export;
+
+const ERC20_ABI: [&str; 5] = [
+    "transfer(address to,uint256 amount)",
+    "approve(address spender,uint256 amount)",
+    "transferFrom(address from,address to,uint256 amount)",
+    "increaseAllowance(address spender,uint256 addedValue)",
+    "decreaseAllowance(address spender,uint256 subtractedValue)",
+];
+const ERC721_ABI: [&str; 3] = [
+    "safeTransferFrom(address from,address to,uint256 tokenId)",
+    "safeTransferFrom(address from,address to,uint256 tokenId,bytes data)",
+    "setApprovalForAll(address operator,bool approved)",
+];
+const ERC1155_ABI: [&str; 2] = [
+    "safeTransferFrom(address from,address to,uint256 id,uint256 amount,bytes data)",
+    "safeBatchTransferFrom(address from,address to,uint256[] ids,uint256[] amounts,bytes data)",
+];
+const PERMIT_ABI: [&str; 6] = [
+    // EIP-2612
+    "permit(address owner,address spender,uint256 value,uint256 deadline,uint8 v,bytes32 r,bytes32 s)",
+    // DAI
+    "permit(address holder,address spender,uint256 nonce,uint256 expiry,bool allowed,uint8 v,bytes32 r,bytes32 s)",
+    // Permit2
+    "permit(address owner,((address,uint160,uint48,uint48),address,uint256) permitSingle,bytes signature)",
+    "permit(address owner,((address,uint160,uint48,uint48)[],address,uint256) permitBatch,bytes signature)",
+    "approve(address token,address spender,uint160 amount,uint48 expiration)",
+    "transferFrom(address from,address to,uint160 amount,address token)",
+];
+const WETH_ABI: [&str; 2] = ["deposit()", "withdraw(uint256 wad)"];
+const MULTICALL_ABI: [&str; 5] = [
+    "multicall(bytes[] data)",
+    "multicall(uint256 deadline,bytes[] data)",
+    "aggregate((address,bytes)[] calls)",
+    "aggregate3((address,bool,bytes)[] calls)",
+    "tryAggregate(bool requireSuccess,(address,bytes)[] calls)",
+];
+const MAX_CALL_DEPTH: usize = 4;
+
+/// Splits a parameter list on the commas that are not nested inside a tuple.
+fn split_params(params: &str) -> Vec<&str> {
+    let mut result = vec![];
+    let mut depth = 0;
+    let mut start = 0;
+    for (index, c) in params.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                result.push(params[start..index].trim());
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    let last = params[start..].trim();
+    if !last.is_empty() {
+        result.push(last);
+    }
+    result
+}
+
+/// Parses a human-readable signature such as `transfer(address to,uint256 amount)`.
+pub fn parse_function_signature(signature: &str) -> Result<Function, Error> {
+    let invalid = || format_err!("invalid function signature: {signature}");
+    let open = signature.find('(').ok_or_else(invalid)?;
+    let params = signature[open + 1..]
+        .strip_suffix(')')
+        .ok_or_else(invalid)?;
+    let name = signature[..open].trim();
+    if name.is_empty() {
+        return Err(invalid());
+    }
+    let inputs = split_params(params)
+        .into_iter()
+        .map(|param| {
+            let (kind, name) = match param.rfind([' ', ')']) {
+                Some(index) if param.as_bytes()[index] == b' ' => {
+                    (&param[..index], param[index + 1..].to_string())
+                }
+                _ => (param, String::new()),
+            };
+            Ok(Param {
+                name,
+                kind: Reader::read(kind.trim()).map_err(|_| invalid())?,
+                internal_type: None,
+            })
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+    #[allow(deprecated)]
+    Ok(Function {
+        name: name.to_string(),
+        inputs,
+        outputs: vec![],
+        constant: None,
+        state_mutability: StateMutability::NonPayable,
+    })
+}
+
+fn format_token(token: &Token) -> Value {
+    match token {
+        Token::Address(address) => json!(format!("0x{}", hex::encode(address.as_bytes()))),
+        Token::Bytes(bytes) | Token::FixedBytes(bytes) => {
+            json!(format!("0x{}", hex::encode(bytes)))
+        }
+        Token::Uint(value) => json!(value.to_string()),
+        Token::Int(value) if value.bit(255) => {
+            json!(format!("-{}", (!*value).overflowing_add(U256::one()).0))
+        }
+        Token::Int(value) => json!(value.to_string()),
+        Token::Bool(value) => json!(value),
+        Token::String(value) => json!(value),
+        Token::FixedArray(tokens) | Token::Array(tokens) | Token::Tuple(tokens) => {
+            Value::Array(tokens.iter().map(format_token).collect())
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct DecodedArgument {
+    pub name: String,
+    pub kind: ParamType,
+    pub value: Token,
+}
+
+/// A call bundled inside a multicall, `target` is `None` when it calls the same contract.
+#[derive(Clone, Debug)]
+pub struct InnerCall {
+    pub target: Option<H160>,
+    pub data: Vec<u8>,
+    pub decoded: Option<DecodedCall>,
+}
+
+#[derive(Clone, Debug)]
+pub struct DecodedCall {
+    function: Function,
+    arguments: Vec<DecodedArgument>,
+    calls: Vec<InnerCall>,
+}
+
+impl DecodedCall {
+    pub fn get_name(&self) -> String {
+        self.function.name.clone()
+    }
+
+    pub fn get_signature(&self) -> String {
+        self.function.signature()
+    }
+
+    pub fn get_selector(&self) -> [u8; 4] {
+        self.function.short_signature()
+    }
+
+    pub fn get_arguments(&self) -> Vec<DecodedArgument> {
+        self.arguments.clone()
+    }
+
+    pub fn get_calls(&self) -> Vec<InnerCall> {
+        self.calls.clone()
+    }
+
+    pub fn to_json(&self) -> Value {
+        let mut result = json!({
+            "selector": format!("0x{}", hex::encode(self.get_selector())),
+            "function": self.get_name(),
+            "signature": self.get_signature(),
+            "arguments": self.arguments.iter().map(|argument| json!({
+                "name": argument.name,
+                "type": argument.kind.to_string(),
+                "value": format_token(&argument.value),
+            })).collect::<Vec<_>>(),
+        });
+        if !self.calls.is_empty() {
+            result["calls"] = self
+                .calls
+                .iter()
+                .map(|call| {
+                    let mut inner = match &call.decoded {
+                        Some(decoded) => decoded.to_json(),
+                        None => json!({"data": format!("0x{}", hex::encode(&call.data))}),
+                    };
+                    if let Some(target) = call.target {
+                        inner["target"] = json!(format!("0x{}", hex::encode(target.as_bytes())));
+                    }
+                    inner
+                })
+                .collect();
+        }
+        result
+    }
+}
+
+/// Function ABIs indexed by selector.
+#[derive(Clone, Debug, Default)]
+pub struct AbiRegistry {
+    functions: BTreeMap<[u8; 4], Vec<Function>>,
+}
+
+impl AbiRegistry {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// ERC-20, ERC-721, ERC-1155, Permit/Permit2, WETH and multicall functions.
+    pub fn builtin() -> Self {
+        let mut registry = AbiRegistry::new();
+        for signature in ERC20_ABI
+            .iter()
+            .chain(ERC721_ABI.iter())
+            .chain(ERC1155_ABI.iter())
+            .chain(PERMIT_ABI.iter())
+            .chain(WETH_ABI.iter())
+            .chain(MULTICALL_ABI.iter())
+        {
+            // The built-in signatures are constants, covered by tests.
+            registry.register_function(parse_function_signature(signature).unwrap());
+        }
+        registry
+    }
+
+    /// Functions with a signature already known are ignored, so built-ins keep precedence.
+    pub fn register_function(&mut self, function: Function) {
+        let functions = self
+            .functions
+            .entry(function.short_signature())
+            .or_default();
+        if !functions
+            .iter()
+            .any(|known| known.signature() == function.signature())
+        {
+            functions.push(function);
+        }
+    }
+
+    /// Registers either a JSON ABI or a single human-readable signature, returning the
+    /// number of functions found.
+    pub fn register_abi(&mut self, abi: &str) -> Result<usize, Error> {
+        let abi = abi.trim();
+        if !abi.starts_with('[') {
+            self.register_function(parse_function_signature(abi)?);
+            return Ok(1);
+        }
+        let contract = Contract::load(abi.as_bytes())?;
+        let mut count = 0;
+        for function in contract.functions() {
+            self.register_function(function.clone());
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    pub fn decode(&self, calldata: &[u8]) -> Result<DecodedCall, Error> {
+        self.decode_nested(calldata, 0)
+    }
+
+    fn decode_nested(&self, calldata: &[u8], depth: usize) -> Result<DecodedCall, Error> {
+        if calldata.len() < 4 {
+            return Err(format_err!("calldata is too short"));
+        }
+        let selector: [u8; 4] = calldata[..4].try_into()?;
+        let candidates = self
+            .functions
+            .get(&selector)
+            .ok_or_else(|| format_err!("unknown function selector 0x{}", hex::encode(selector)))?;
+        let (function, tokens) = candidates
+            .iter()
+            .find_map(|function| {
+                let tokens = function.decode_input(&calldata[4..]).ok()?;
+                // Reject candidates that leave bytes unread or read beyond the calldata.
+                (function.encode_input(&tokens).ok()?.len() == calldata.len())
+                    .then_some((function, tokens))
+            })
+            .ok_or_else(|| format_err!("calldata does not match 0x{}", hex::encode(selector)))?;
+        let arguments = function
+            .inputs
+            .iter()
+            .zip(tokens)
+            .map(|(param, value)| DecodedArgument {
+                name: param.name.clone(),
+                kind: param.kind.clone(),
+                value,
+            })
+            .collect::<Vec<_>>();
+        let calls = self.decode_inner_calls(function, &arguments, depth);
+        Ok(DecodedCall {
+            function: function.clone(),
+            arguments,
+            calls,
+        })
+    }
+
+    fn decode_inner_calls(
+        &self,
+        function: &Function,
+        arguments: &[DecodedArgument],
+        depth: usize,
+    ) -> Vec<InnerCall> {
+        if !MULTICALL_ABI
+            .iter()
+            .any(|signature| signature.starts_with(&format!("{}(", function.name)))
+        {
+            return vec![];
+        }
+        // Calls nested deeper than the limit are kept undecoded.
+        let inner_call = |target: Option<H160>, data: &Vec<u8>| InnerCall {
+            target,
+            data: data.clone(),
+            decoded: match depth < MAX_CALL_DEPTH {
+                true => self.decode_nested(data, depth + 1).ok(),
+                false => None,
+            },
+        };
+        let mut calls = vec![];
+        for argument in arguments {
+            let Token::Array(items) = &argument.value else {
+                continue;
+            };
+            for item in items {
+                match item {
+                    Token::Bytes(data) => calls.push(inner_call(None, data)),
+                    Token::Tuple(fields) => {
+                        if let (Some(Token::Address(target)), Some(Token::Bytes(data))) =
+                            (fields.first(), fields.last())
+                        {
+                            calls.push(inner_call(Some(*target), data));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        calls
+    }
+}
+
+fn registry() -> &'static Mutex<AbiRegistry> {
+    static REGISTRY: OnceLock<Mutex<AbiRegistry>> = OnceLock::new();
+    REGISTRY.get_or_init(|| Mutex::new(AbiRegistry::builtin()))
+}
+
+/// Extracts the target and calldata of an `eth-sign-request` or `evm-sign-request`.
+pub fn request_calldata(ur_type: &str, cbor: Vec<u8>) -> Result<(Option<H160>, Vec<u8>), Error> {
+    let transaction = if ur_type == ETH_SIGN_REQUEST.get_type() {
+        let request = EthSignRequest::try_from(cbor).map_err(|e| format_err!(e.to_string()))?;
+        EthTransaction::from_sign_request(&request)?
+    } else if ur_type == EVM_SIGN_REQUEST.get_type() {
+        let request = EvmSignRequest::try_from(cbor).map_err(|e| format_err!(e.to_string()))?;
+        let SignDataType::Arbitrary = request.get_data_type() else {
+            return Err(format_err!("data type is not a transaction"));
+        };
+        let sign_data = request.get_sign_data();
+        // An EIP-2718 envelope starts with its type byte, a legacy RLP list with >= 0xc0.
+        let data_type = match sign_data.first() {
+            Some(byte) if *byte <= 0x7f => DataType::TypedTransaction,
+            _ => DataType::Transaction,
+        };
+        EthTransaction::decode(&data_type, &sign_data)?
+    } else {
+        return Err(format_err!("type not match"));
+    };
+    Ok((transaction.get_to(), transaction.get_data()))
+}
+
+export! {
+    @Java_com_keystone_sdk_KeystoneNativeSDK_registerETHAbi
+    fn register_eth_abi(abi: &str) -> String {
+        let mut registry = match registry().lock() {
+            Ok(registry) => registry,
+            Err(_) => return json!({"error": "abi registry is unavailable"}).to_string(),
+        };
+        match registry.register_abi(abi) {
+            Ok(count) => json!({"registered": count}).to_string(),
+            Err(e) => json!({"error": e.to_string()}).to_string(),
+        }
+    }
+
+    @Java_com_keystone_sdk_KeystoneNativeSDK_decodeETHCalldata
+    fn decode_eth_calldata(ur_type: &str, cbor_hex: &str) -> String {
+        let decode = || -> Result<Value, Error> {
+            let (to, calldata) = request_calldata(ur_type, hex::decode(cbor_hex)?)?;
+            let registry = registry()
+                .lock()
+                .map_err(|_| format_err!("abi registry is unavailable"))?;
+            let mut result = registry.decode(&calldata)?.to_json();
+            result["to"] = json!(to.map(|to| format!("0x{}", hex::encode(to.as_bytes()))));
+            Ok(result)
+        };
+        match decode() {
+            Ok(result) => result.to_string(),
+            Err(e) => json!({"error": e.to_string()}).to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rlp::RlpStream;
+    use ur_registry::crypto_key_path::CryptoKeyPath;
+
+    const TOKEN: &str = "a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48";
+    const RECIPIENT: &str = "d8da6bf26964af9d7eed9e03e53415d37aa96045";
+
+    fn transfer_calldata() -> Vec<u8> {
+        hex::decode(format!(
+            "a9059cbb000000000000000000000000{RECIPIENT}00000000000000000000000000000000000000000000000000000000000f4240"
+        ))
+        .unwrap()
+    }
+
+    fn eth_sign_request(calldata: &[u8]) -> String {
+        let mut stream = RlpStream::new_list(9);
+        stream
+            .append(&1u64)
+            .append(&1u64)
+            .append(&2u64)
+            .append(&3u64)
+            .append(&60000u64)
+            .append(&hex::decode(TOKEN).unwrap())
+            .append(&0u64)
+            .append(&calldata.to_vec())
+            .begin_list(0);
+        let mut sign_data = vec![0x02];
+        sign_data.extend_from_slice(&stream.out());
+        let path = CryptoKeyPath::from_path("m/44'/60'/0'/0/0".to_string(), None).unwrap();
+        let request = EthSignRequest::new(
+            None,
+            sign_data,
+            DataType::TypedTransaction,
+            None,
+            path,
+            None,
+            None,
+        );
+        let cbor: Vec<u8> = request.try_into().unwrap();
+        hex::encode(cbor)
+    }
+
+    #[test]
+    fn test_builtin_selectors() {
+        let registry = AbiRegistry::builtin();
+        let selectors = [
+            ("a9059cbb", "transfer(address,uint256)"),
+            ("095ea7b3", "approve(address,uint256)"),
+            ("23b872dd", "transferFrom(address,address,uint256)"),
+            ("42842e0e", "safeTransferFrom(address,address,uint256)"),
+            (
+                "f242432a",
+                "safeTransferFrom(address,address,uint256,uint256,bytes)",
+            ),
+            (
+                "2eb2c2d6",
+                "safeBatchTransferFrom(address,address,uint256[],uint256[],bytes)",
+            ),
+            (
+                "d505accf",
+                "permit(address,address,uint256,uint256,uint8,bytes32,bytes32)",
+            ),
+            (
+                "2b67b570",
+                "permit(address,((address,uint160,uint48,uint48),address,uint256),bytes)",
+            ),
+            ("87517c45", "approve(address,address,uint160,uint48)"),
+            ("d0e30db0", "deposit()"),
+            ("2e1a7d4d", "withdraw(uint256)"),
+            ("ac9650d8", "multicall(bytes[])"),
+            ("5ae401dc", "multicall(uint256,bytes[])"),
+            ("82ad56cb", "aggregate3((address,bool,bytes)[])"),
+        ];
+        for (selector, signature) in selectors {
+            let functions = registry
+                .functions
+                .get(&<[u8; 4]>::try_from(hex::decode(selector).unwrap()).unwrap())
+                .unwrap();
+            assert!(
+                functions.iter().any(|f| f.signature() == signature),
+                "{signature}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_decode_eth_sign_request() {
+        let result: Value = serde_json::from_str(&decode_eth_calldata(
+            "eth-sign-request",
+            &eth_sign_request(&transfer_calldata()),
+        ))
+        .unwrap();
+        assert_eq!(
+            json!({
+                "selector": "0xa9059cbb",
+                "function": "transfer",
+                "signature": "transfer(address,uint256)",
+                "arguments": [
+                    {"name": "to", "type": "address", "value": format!("0x{RECIPIENT}")},
+                    {"name": "amount", "type": "uint256", "value": "1000000"},
+                ],
+                "to": format!("0x{TOKEN}"),
+            }),
+            result
+        );
+        assert_eq!(
+            "{\"error\":\"unknown function selector 0x12345678\"}",
+            decode_eth_calldata(
+                "eth-sign-request",
+                &eth_sign_request(&[0x12, 0x34, 0x56, 0x78])
+            )
+        );
+    }
+
+    #[test]
+    fn test_decode_multicall() {
+        let registry = AbiRegistry::builtin();
+        let multicall =
+            parse_function_signature("aggregate3((address,bool,bytes)[] calls)").unwrap();
+        let calldata = multicall
+            .encode_input(&[Token::Array(vec![
+                Token::Tuple(vec![
+                    Token::Address(H160::from_slice(&hex::decode(TOKEN).unwrap())),
+                    Token::Bool(false),
+                    Token::Bytes(transfer_calldata()),
+                ]),
+                Token::Tuple(vec![
+                    Token::Address(H160::zero()),
+                    Token::Bool(true),
+                    Token::Bytes(vec![0xde, 0xad, 0xbe, 0xef]),
+                ]),
+            ])])
+            .unwrap();
+        let decoded = registry.decode(&calldata).unwrap();
+        assert_eq!("aggregate3", decoded.get_name());
+        let calls = decoded.get_calls();
+        assert_eq!(2, calls.len());
+        assert_eq!("transfer", calls[0].decoded.as_ref().unwrap().get_name());
+        assert!(calls[1].decoded.is_none());
+        assert_eq!(json!("0xdeadbeef"), decoded.to_json()["calls"][1]["data"]);
+
+        let multicall = parse_function_signature("multicall(bytes[] data)").unwrap();
+        let mut calldata = transfer_calldata();
+        for _ in 0..10 {
+            calldata = multicall
+                .encode_input(&[Token::Array(vec![Token::Bytes(calldata)])])
+                .unwrap();
+        }
+        let mut decoded = registry.decode(&calldata).unwrap();
+        for _ in 0..MAX_CALL_DEPTH {
+            decoded = decoded.get_calls()[0].decoded.clone().unwrap();
+            assert_eq!("multicall", decoded.get_name());
+        }
+        assert!(decoded.get_calls()[0].decoded.is_none());
+    }
+
+    #[test]
+    fn test_register_custom_abi() {
+        let mut registry = AbiRegistry::builtin();
+        let abi = r#"[{"type":"function","name":"stake","stateMutability":"nonpayable","inputs":[{"name":"amount","type":"uint256"},{"name":"lockDays","type":"int16"}],"outputs":[]}]"#;
+        assert_eq!(1, registry.register_abi(abi).unwrap());
+        let stake = parse_function_signature("stake(uint256,int16)").unwrap();
+        let calldata = stake
+            .encode_input(&[Token::Uint(U256::from(5)), Token::Int(U256::MAX - 6)])
+            .unwrap();
+        let decoded = registry.decode(&calldata).unwrap().to_json();
+        assert_eq!(json!("lockDays"), decoded["arguments"][1]["name"]);
+        assert_eq!(json!("-7"), decoded["arguments"][1]["value"]);
+
+        assert_eq!(
+            1,
+            registry
+                .register_abi("claim(address account, uint256 amount)")
+                .unwrap()
+        );
+        assert!(registry.register_abi("claim(").is_err());
+        assert!(registry.decode(&[0xa9, 0x05, 0x9c]).is_err());
+    }
+}
@@ -0,0 +1,364 @@
+use anyhow::{format_err, Error};
+use bitcoin::secp256k1::ecdsa::{RecoverableSignature, RecoveryId};
+use bitcoin::secp256k1::{Message, Secp256k1};
+use serde_json::json;
+use ur_registry::ethereum::eth_sign_request::{DataType, EthSignRequest};
+use ur_registry::ethereum::eth_signature::EthSignature;
+use ur_registry::registry_types::ETH_SIGNATURE;
+
+use crate::ethereum::eth_transaction::EthTransaction;
+use crate::ethereum::eth_typed_data::{keccak256, TypedData};
+use crate::export;
+use crate::util_internal::string_helper::remove_prefix_0x;
+
+/// `keccak256("\x19Ethereum Signed Message:\n" ‖ len ‖ message)`.
+pub fn personal_message_hash(message: &[u8]) -> [u8; 32] {
+    let mut data = format!("\x19Ethereum Signed Message:\n{}", message.len()).into_bytes();
+    data.extend_from_slice(message);
+    keccak256(&data)
+}
+
+/// The digest the device signs for the request, chosen by its data type.
+pub fn signing_digest(request: &EthSignRequest) -> Result<[u8; 32], Error> {
+    let sign_data = request.get_sign_data();
+    match request.get_data_type() {
+        DataType::Transaction | DataType::TypedTransaction => {
+            EthTransaction::from_sign_request(request)?;
+            Ok(keccak256(&sign_data))
+        }
+        DataType::TypedData => TypedData::from_sign_request(request)?.digest(),
+        DataType::PersonalMessage => Ok(personal_message_hash(&sign_data)),
+    }
+}
+
+/// EIP-55 mixed-case encoding of an address.
+pub fn to_checksum_address(address: &[u8; 20]) -> String {
+    let lower = hex::encode(address);
+    let hash = keccak256(lower.as_bytes());
+    let checksummed: String = lower
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            let nibble = (hash[i / 2] >> (if i % 2 == 0 { 4 } else { 0 })) & 0x0f;
+            if nibble >= 8 {
+                c.to_ascii_uppercase()
+            } else {
+                c
+            }
+        })
+        .collect();
+    format!("0x{checksummed}")
+}
+
+/// The request address may hold either the 20 raw bytes or the hex text of the address.
+fn request_address(address: &[u8]) -> Result<[u8; 20], Error> {
+    let bytes = match address.len() {
+        20 => address.to_vec(),
+        _ => hex::decode(remove_prefix_0x(core::str::from_utf8(address)?))?,
+    };
+    bytes
+        .try_into()
+        .map_err(|_| format_err!("request address is invalid"))
+}
+
+/// Maps the `v` value that follows `r ‖ s` to a recovery id. Legacy transactions carry an
+/// EIP-155 `v`, which takes more than one byte for large chain ids; some signers only
+/// return its lowest byte, which still determines the parity once the chain id is known.
+/// That lowest byte may itself be 0, 1, 27 or 28, so it is resolved against the chain id
+/// before `v` is read as a plain recovery id.
+pub fn recovery_id_from_v(v: &[u8], chain_id: Option<u64>) -> Result<u8, Error> {
+    if v.is_empty() || v.len() > 16 {
+        return Err(format_err!("signature is invalid"));
+    }
+    let value = v.iter().fold(0u128, |acc, byte| (acc << 8) | *byte as u128);
+    if let Some(chain_id) = chain_id {
+        let base = chain_id as u128 * 2 + 35;
+        if value == base || value == base + 1 {
+            return Ok((value - base) as u8);
+        }
+        if let [byte] = v {
+            let parity = byte.wrapping_sub(base as u8);
+            if parity < 2 {
+                return Ok(parity);
+            }
+        }
+    }
+    match value {
+        0 | 1 => Ok(value as u8),
+        27 | 28 => Ok((value - 27) as u8),
+        _ if chain_id.is_some() => Err(format_err!("signature v does not match chain_id")),
+        value if value >= 35 => Ok(((value - 35) % 2) as u8),
+        _ => Err(format_err!("signature v is invalid")),
+    }
+}
+
+/// Recovers the address that produced `signature` for `request`.
+pub fn recover_eth_signer(
+    request: &EthSignRequest,
+    signature: &EthSignature,
+) -> Result<[u8; 20], Error> {
+    let signature_bytes = signature.get_signature();
+    if signature_bytes.len() < 65 {
+        return Err(format_err!("signature is invalid"));
+    }
+    // Only legacy transactions encode the chain id in `v`.
+    let chain_id = match request.get_data_type() {
+        DataType::Transaction => request
+            .get_chain_id()
+            .map(u64::try_from)
+            .transpose()
+            .map_err(|_| format_err!("chain_id is invalid"))?,
+        _ => None,
+    };
+    let digest = Message::from_slice(&signing_digest(request)?)?;
+    let recovery_id = recovery_id_from_v(&signature_bytes[64..], chain_id)?;
+    let recovery_id = RecoveryId::from_i32(recovery_id as i32)?;
+    let recoverable = RecoverableSignature::from_compact(&signature_bytes[..64], recovery_id)?;
+    let public_key = Secp256k1::verification_only().recover_ecdsa(&digest, &recoverable)?;
+    let hash = keccak256(&public_key.serialize_uncompressed()[1..]);
+    let mut address = [0u8; 20];
+    address.copy_from_slice(&hash[12..]);
+    Ok(address)
+}
+
+/// Checks that the signature answers the request and was made by the request address,
+/// returning the recovered signer.
+pub fn verify_eth_signature_for_request(
+    request: &EthSignRequest,
+    signature: &EthSignature,
+) -> Result<[u8; 20], Error> {
+    if let Some(request_id) = request.get_request_id() {
+        if signature.get_request_id() != Some(request_id) {
+            return Err(format_err!("request_id mismatch"));
+        }
+    }
+    let signer = recover_eth_signer(request, signature)?;
+    if let Some(address) = request.get_address() {
+        if request_address(&address)? != signer {
+            return Err(format_err!(
+                "signer {} does not match request address",
+                to_checksum_address(&signer)
+            ));
+        }
+    }
+    Ok(signer)
+}
+
+export! {
+    @Java_com_keystone_sdk_KeystoneNativeSDK_verifyETHSignature
+    fn verify_eth_signature(sign_request_cbor_hex: &str, ur_type: &str, cbor_hex: &str) -> String {
+        if ETH_SIGNATURE.get_type() != ur_type {
+            return json!({"error": "type not match"}).to_string();
+        }
+
+        let verify = || -> Result<[u8; 20], Error> {
+            let request = EthSignRequest::try_from(hex::decode(sign_request_cbor_hex)?)
+                .map_err(|e| format_err!(e.to_string()))?;
+            let signature = EthSignature::try_from(hex::decode(cbor_hex)?)
+                .map_err(|e| format_err!(e.to_string()))?;
+            verify_eth_signature_for_request(&request, &signature)
+        };
+        match verify() {
+            Ok(signer) => json!({
+                "verified": true,
+                "signer": to_checksum_address(&signer),
+            }).to_string(),
+            Err(e) => json!({"error": e.to_string()}).to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PRIVATE_KEY: &str = "4646464646464646464646464646464646464646464646464646464646464646";
+    const ADDRESS: &str = "0x9d8A62f656a8d1615C1294fd71e9CFb3E4855A4F";
+    const REQUEST_ID: &str = "9b1deb4d3b7d4bad9bdd2b0d7b3dcb6d";
+
+    fn sign(digest: [u8; 32]) -> (i32, Vec<u8>) {
+        let secp = Secp256k1::new();
+        let secret_key =
+            bitcoin::secp256k1::SecretKey::from_slice(&hex::decode(PRIVATE_KEY).unwrap()).unwrap();
+        let (recovery_id, compact) = secp
+            .sign_ecdsa_recoverable(&Message::from_slice(&digest).unwrap(), &secret_key)
+            .serialize_compact();
+        (recovery_id.to_i32(), compact.to_vec())
+    }
+
+    fn sign_request(
+        data_type: DataType,
+        sign_data: &[u8],
+        chain_id: Option<i128>,
+    ) -> EthSignRequest {
+        let path = ur_registry::crypto_key_path::CryptoKeyPath::from_path(
+            "m/44'/60'/0'/0/0".to_string(),
+            None,
+        )
+        .unwrap();
+        EthSignRequest::new(
+            Some(hex::decode(REQUEST_ID).unwrap()),
+            sign_data.to_vec(),
+            data_type,
+            chain_id,
+            path,
+            Some(ADDRESS.as_bytes().to_vec()),
+            None,
+        )
+    }
+
+    fn to_hex<T: TryInto<Vec<u8>>>(item: T) -> String
+    where
+        T::Error: core::fmt::Debug,
+    {
+        hex::encode(item.try_into().unwrap())
+    }
+
+    #[test]
+    fn test_verify_personal_message() {
+        let request = sign_request(DataType::PersonalMessage, b"hello", None);
+        let (recovery_id, mut signature) = sign(personal_message_hash(b"hello"));
+        signature.push(27 + recovery_id as u8);
+        let eth_signature = EthSignature::new(
+            Some(hex::decode(REQUEST_ID).unwrap()),
+            signature.clone(),
+            None,
+        );
+        assert_eq!(
+            format!("{{\"signer\":\"{ADDRESS}\",\"verified\":true}}"),
+            verify_eth_signature(
+                &to_hex(request.clone()),
+                "eth-signature",
+                &to_hex(eth_signature)
+            )
+        );
+
+        let wrong_id = EthSignature::new(Some(vec![0u8; 16]), signature.clone(), None);
+        assert_eq!(
+            "request_id mismatch",
+            verify_eth_signature_for_request(&request, &wrong_id)
+                .unwrap_err()
+                .to_string()
+        );
+
+        let other = sign_request(DataType::PersonalMessage, b"hello!", None);
+        let eth_signature =
+            EthSignature::new(Some(hex::decode(REQUEST_ID).unwrap()), signature, None);
+        assert!(verify_eth_signature_for_request(&other, &eth_signature)
+            .unwrap_err()
+            .to_string()
+            .ends_with("does not match request address"));
+    }
+
+    #[test]
+    fn test_verify_transactions() {
+        let legacy = hex::decode("ec098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a764000080018080").unwrap();
+        let request = sign_request(DataType::Transaction, &legacy, Some(1));
+        let (recovery_id, mut signature) = sign(keccak256(&legacy));
+        signature.push(37 + recovery_id as u8);
+        let eth_signature =
+            EthSignature::new(Some(hex::decode(REQUEST_ID).unwrap()), signature, None);
+        assert_eq!(
+            ADDRESS,
+            to_checksum_address(
+                &verify_eth_signature_for_request(&request, &eth_signature).unwrap()
+            )
+        );
+
+        let typed = hex::decode("02ef0109843b9aca008504a817c800825208943535353535353535353535353535353535353535880de0b6b3a764000080c0").unwrap();
+        let request = sign_request(DataType::TypedTransaction, &typed, Some(1));
+        let (recovery_id, mut signature) = sign(keccak256(&typed));
+        signature.push(recovery_id as u8);
+        let eth_signature = EthSignature::new(
+            Some(hex::decode(REQUEST_ID).unwrap()),
+            signature.clone(),
+            None,
+        );
+        assert!(verify_eth_signature_for_request(&request, &eth_signature).is_ok());
+
+        let request = sign_request(DataType::Transaction, &legacy, Some(5));
+        signature[64] = 37 + recovery_id as u8;
+        let eth_signature =
+            EthSignature::new(Some(hex::decode(REQUEST_ID).unwrap()), signature, None);
+        assert_eq!(
+            "chain_id mismatch, request: 5, transaction: 1",
+            verify_eth_signature_for_request(&request, &eth_signature)
+                .unwrap_err()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn test_recovery_id_from_v() {
+        assert_eq!(1, recovery_id_from_v(&[38], Some(1)).unwrap());
+        assert_eq!(0, recovery_id_from_v(&[1, 1], Some(111)).unwrap());
+        assert_eq!(1, recovery_id_from_v(&[28], None).unwrap());
+        // chain_id 110 gives v = 255 + parity, whose lowest byte is 0xff or 0x00.
+        assert_eq!(0, recovery_id_from_v(&[0xff], Some(110)).unwrap());
+        assert_eq!(1, recovery_id_from_v(&[0x00], Some(110)).unwrap());
+        // chain_id 111 gives v = 257 + parity, whose lowest byte is 0x01 or 0x02.
+        assert_eq!(0, recovery_id_from_v(&[0x01], Some(111)).unwrap());
+        assert_eq!(1, recovery_id_from_v(&[0x02], Some(111)).unwrap());
+        assert_eq!(
+            "signature v does not match chain_id",
+            recovery_id_from_v(&[0x05], Some(111))
+                .unwrap_err()
+                .to_string()
+        );
+        // An oversized `v` is rejected rather than overflowing.
+        assert_eq!(
+            "signature v does not match chain_id",
+            recovery_id_from_v(&[0xff; 16], Some(1))
+                .unwrap_err()
+                .to_string()
+        );
+        assert_eq!(
+            "signature v does not match chain_id",
+            recovery_id_from_v(&[0xff; 16], Some(u64::MAX))
+                .unwrap_err()
+                .to_string()
+        );
+        assert_eq!(
+            "signature is invalid",
+            recovery_id_from_v(&[0xff; 17], Some(1))
+                .unwrap_err()
+                .to_string()
+        );
+
+        for chain_id in [110u64, 111] {
+            let mut stream = rlp::RlpStream::new_list(9);
+            stream
+                .append(&9u64)
+                .append(&20_000_000_000u64)
+                .append(&21000u64)
+                .append(&hex::decode("3535353535353535353535353535353535353535").unwrap())
+                .append(&1_000_000_000_000_000_000u64)
+                .append(&Vec::<u8>::new())
+                .append(&chain_id)
+                .append(&0u8)
+                .append(&0u8);
+            let legacy = stream.out().to_vec();
+            let request = sign_request(DataType::Transaction, &legacy, Some(chain_id as i128));
+            let (recovery_id, mut signature) = sign(keccak256(&legacy));
+            signature.push(((chain_id * 2 + 35 + recovery_id as u64) & 0xff) as u8);
+            let eth_signature =
+                EthSignature::new(Some(hex::decode(REQUEST_ID).unwrap()), signature, None);
+            assert_eq!(
+                ADDRESS,
+                to_checksum_address(&recover_eth_signer(&request, &eth_signature).unwrap())
+            );
+        }
+
+        let legacy = hex::decode("ec098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a764000080018080").unwrap();
+        let request = sign_request(DataType::Transaction, &legacy, Some(-1));
+        let (_, mut signature) = sign(keccak256(&legacy));
+        signature.push(27);
+        let eth_signature = EthSignature::new(None, signature, None);
+        assert_eq!(
+            "chain_id is invalid",
+            recover_eth_signer(&request, &eth_signature)
+                .unwrap_err()
+                .to_string()
+        );
+    }
+}
@@ -0,0 +1,252 @@
+use anyhow::{format_err, Error};
+use ethabi::ethereum_types::U256;
+use rlp::{Rlp, RlpStream};
+use serde_json::json;
+use ur_registry::ethereum::eth_sign_request::{DataType, EthSignRequest};
+use ur_registry::ethereum::eth_signature::EthSignature;
+use ur_registry::registry_types::ETH_SIGNATURE;
+
+use crate::ethereum::eth_signature_verification::{
+    recovery_id_from_v, verify_eth_signature_for_request,
+};
+use crate::ethereum::eth_transaction::EthTransaction;
+use crate::ethereum::eth_typed_data::keccak256;
+use crate::export;
+
+/// A signed transaction ready for `eth_sendRawTransaction`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SignedEthTransaction {
+    raw_transaction: Vec<u8>,
+    transaction_hash: [u8; 32],
+}
+
+impl SignedEthTransaction {
+    /// Appends the signature of `signature` to the unsigned transaction of `request`.
+    ///
+    /// Legacy transactions get `v = 27 + parity`, or `chain_id * 2 + 35 + parity` when
+    /// EIP-155 protected; typed transactions get the bare y-parity.
+    pub fn assemble(request: &EthSignRequest, signature: &EthSignature) -> Result<Self, Error> {
+        verify_eth_signature_for_request(request, signature)?;
+        let transaction = EthTransaction::from_sign_request(request)?;
+        let sign_data = request.get_sign_data();
+        let signature_bytes = signature.get_signature();
+        let r = U256::from_big_endian(&signature_bytes[..32]);
+        let s = U256::from_big_endian(&signature_bytes[32..64]);
+        let chain_id = transaction.get_chain_id();
+
+        let raw_transaction = match request.get_data_type() {
+            DataType::Transaction => {
+                let parity = recovery_id_from_v(&signature_bytes[64..], chain_id)?;
+                let v = match chain_id {
+                    Some(chain_id) => U256::from(chain_id) * 2 + 35 + parity,
+                    None => U256::from(27 + parity),
+                };
+                let unsigned = Rlp::new(&sign_data);
+                let mut stream = RlpStream::new_list(9);
+                for item in unsigned.iter().take(6) {
+                    stream.append_raw(item.as_raw(), 1);
+                }
+                stream.append(&v).append(&r).append(&s);
+                stream.out().to_vec()
+            }
+            DataType::TypedTransaction => {
+                let parity = recovery_id_from_v(&signature_bytes[64..], None)?;
+                let unsigned = Rlp::new(&sign_data[1..]);
+                let count = unsigned.item_count()?;
+                let mut stream = RlpStream::new_list(count + 3);
+                for item in unsigned.iter() {
+                    stream.append_raw(item.as_raw(), 1);
+                }
+                stream.append(&parity).append(&r).append(&s);
+                let mut raw_transaction = vec![sign_data[0]];
+                raw_transaction.extend_from_slice(&stream.out());
+                raw_transaction
+            }
+            _ => return Err(format_err!("data type is not a transaction")),
+        };
+        let transaction_hash = keccak256(&raw_transaction);
+        Ok(SignedEthTransaction {
+            raw_transaction,
+            transaction_hash,
+        })
+    }
+
+    pub fn get_raw_transaction(&self) -> Vec<u8> {
+        self.raw_transaction.clone()
+    }
+
+    pub fn get_transaction_hash(&self) -> [u8; 32] {
+        self.transaction_hash
+    }
+}
+
+export! {
+    @Java_com_keystone_sdk_KeystoneNativeSDK_assembleETHSignedTransaction
+    fn assemble_eth_signed_transaction(
+        sign_request_cbor_hex: &str,
+        ur_type: &str,
+        cbor_hex: &str
+    ) -> String {
+        if ETH_SIGNATURE.get_type() != ur_type {
+            return json!({"error": "type not match"}).to_string();
+        }
+
+        let assemble = || -> Result<SignedEthTransaction, Error> {
+            let request = EthSignRequest::try_from(hex::decode(sign_request_cbor_hex)?)
+                .map_err(|e| format_err!(e.to_string()))?;
+            let signature = EthSignature::try_from(hex::decode(cbor_hex)?)
+                .map_err(|e| format_err!(e.to_string()))?;
+            SignedEthTransaction::assemble(&request, &signature)
+        };
+        match assemble() {
+            Ok(signed) => json!({
+                "raw_transaction": format!("0x{}", hex::encode(signed.get_raw_transaction())),
+                "transaction_hash": format!("0x{}", hex::encode(signed.get_transaction_hash())),
+            }).to_string(),
+            Err(e) => json!({"error": e.to_string()}).to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitcoin::secp256k1::{Message, Secp256k1, SecretKey};
+    use ur_registry::crypto_key_path::CryptoKeyPath;
+
+    const PRIVATE_KEY: &str = "4646464646464646464646464646464646464646464646464646464646464646";
+
+    fn sign(digest: [u8; 32]) -> (u8, Vec<u8>) {
+        let secret_key = SecretKey::from_slice(&hex::decode(PRIVATE_KEY).unwrap()).unwrap();
+        let (recovery_id, compact) = Secp256k1::new()
+            .sign_ecdsa_recoverable(&Message::from_slice(&digest).unwrap(), &secret_key)
+            .serialize_compact();
+        (recovery_id.to_i32() as u8, compact.to_vec())
+    }
+
+    fn sign_request(data_type: DataType, sign_data: &[u8], chain_id: i128) -> EthSignRequest {
+        let path = CryptoKeyPath::from_path("m/44'/60'/0'/0/0".to_string(), None).unwrap();
+        EthSignRequest::new(
+            None,
+            sign_data.to_vec(),
+            data_type,
+            Some(chain_id),
+            path,
+            Some(b"0x9d8A62f656a8d1615C1294fd71e9CFb3E4855A4F".to_vec()),
+            None,
+        )
+    }
+
+    #[test]
+    fn test_assemble_eip155_transaction() {
+        // The example transaction from EIP-155.
+        let unsigned = hex::decode("ec098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a764000080018080").unwrap();
+        let request = sign_request(DataType::Transaction, &unsigned, 1);
+        let (parity, mut signature) = sign(keccak256(&unsigned));
+        signature.push(37 + parity);
+        let signature = EthSignature::new(None, signature, None);
+
+        let request_cbor: Vec<u8> = request.try_into().unwrap();
+        let signature_cbor: Vec<u8> = signature.try_into().unwrap();
+        assert_eq!(
+            "{\"raw_transaction\":\"0xf86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a76400008025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83\",\"transaction_hash\":\"0x33469b22e9f636356c4160a87eb19df52b7412e8eac32a4a55ffe88ea8350788\"}",
+            assemble_eth_signed_transaction(
+                &hex::encode(request_cbor),
+                "eth-signature",
+                &hex::encode(signature_cbor)
+            )
+        );
+    }
+
+    #[test]
+    fn test_assemble_large_chain_id() {
+        let chain_id: u64 = 1313161554;
+        let mut stream = RlpStream::new_list(9);
+        stream
+            .append(&0u64)
+            .append(&1u64)
+            .append(&21000u64)
+            .append(&hex::decode("3535353535353535353535353535353535353535").unwrap())
+            .append(&0u64)
+            .append_empty_data()
+            .append(&chain_id)
+            .append_empty_data()
+            .append_empty_data();
+        let unsigned = stream.out().to_vec();
+        let request = sign_request(DataType::Transaction, &unsigned, chain_id as i128);
+        let (parity, mut signature) = sign(keccak256(&unsigned));
+        // Only the lowest byte of the EIP-155 `v`.
+        signature.push(((chain_id * 2 + 35 + parity as u64) & 0xff) as u8);
+        let signed =
+            SignedEthTransaction::assemble(&request, &EthSignature::new(None, signature, None))
+                .unwrap();
+        let raw = signed.get_raw_transaction();
+        let v: U256 = Rlp::new(&raw).val_at(6).unwrap();
+        assert_eq!(U256::from(chain_id * 2 + 35 + parity as u64), v);
+        assert_eq!(keccak256(&raw), signed.get_transaction_hash());
+    }
+
+    #[test]
+    fn test_assemble_chain_id_with_ambiguous_v_byte() {
+        // The lowest byte of `v` is 0xff or 0x00 on chain 110, 0x01 or 0x02 on chain 111.
+        for chain_id in [110u64, 111] {
+            let mut parities = vec![];
+            for nonce in 0u64.. {
+                let mut stream = RlpStream::new_list(9);
+                stream
+                    .append(&nonce)
+                    .append(&1u64)
+                    .append(&21000u64)
+                    .append(&hex::decode("3535353535353535353535353535353535353535").unwrap())
+                    .append(&0u64)
+                    .append_empty_data()
+                    .append(&chain_id)
+                    .append_empty_data()
+                    .append_empty_data();
+                let unsigned = stream.out().to_vec();
+                let request = sign_request(DataType::Transaction, &unsigned, chain_id as i128);
+                let (parity, mut signature) = sign(keccak256(&unsigned));
+                if parities.contains(&parity) {
+                    continue;
+                }
+                parities.push(parity);
+                signature.push(((chain_id * 2 + 35 + parity as u64) & 0xff) as u8);
+                let signed = SignedEthTransaction::assemble(
+                    &request,
+                    &EthSignature::new(None, signature, None),
+                )
+                .unwrap();
+                let v: U256 = Rlp::new(&signed.get_raw_transaction()).val_at(6).unwrap();
+                assert_eq!(U256::from(chain_id * 2 + 35 + parity as u64), v);
+                if parities.len() == 2 {
+                    break;
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_assemble_typed_transaction() {
+        let unsigned = hex::decode("02ef0109843b9aca008504a817c800825208943535353535353535353535353535353535353535880de0b6b3a764000080c0").unwrap();
+        let request = sign_request(DataType::TypedTransaction, &unsigned, 1);
+        let (parity, mut signature) = sign(keccak256(&unsigned));
+        signature.push(27 + parity);
+        let signed =
+            SignedEthTransaction::assemble(&request, &EthSignature::new(None, signature, None))
+                .unwrap();
+        let raw = signed.get_raw_transaction();
+        assert_eq!(0x02, raw[0]);
+        let rlp = Rlp::new(&raw[1..]);
+        assert_eq!(12, rlp.item_count().unwrap());
+        assert_eq!(parity, rlp.val_at::<u8>(9).unwrap());
+
+        let request = sign_request(DataType::PersonalMessage, b"hello", 1);
+        let (parity, mut signature) = sign([0u8; 32]);
+        signature.push(parity);
+        assert!(SignedEthTransaction::assemble(
+            &request,
+            &EthSignature::new(None, signature, None)
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,562 @@
+use anyhow::{format_err, Error};
+use ethabi::ethereum_types::{H160, H256, U256};
+use rlp::Rlp;
+use serde_json::{json, Value};
+use ur_registry::ethereum::eth_sign_request::{DataType, EthSignRequest};
+use ur_registry::registry_types::ETH_SIGN_REQUEST;
+
+use crate::export;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EthTransactionType {
+    Legacy,
+    AccessList,
+    DynamicFee,
+    Blob,
+    SetCode,
+}
+
+impl EthTransactionType {
+    pub fn from_type_byte(byte: u8) -> Result<Self, Error> {
+        match byte {
+            0x01 => Ok(EthTransactionType::AccessList),
+            0x02 => Ok(EthTransactionType::DynamicFee),
+            0x03 => Ok(EthTransactionType::Blob),
+            0x04 => Ok(EthTransactionType::SetCode),
+            x => Err(format_err!("unsupported transaction type {x:#04x}")),
+        }
+    }
+
+    pub fn get_type_byte(&self) -> Option<u8> {
+        match self {
+            EthTransactionType::Legacy => None,
+            EthTransactionType::AccessList => Some(0x01),
+            EthTransactionType::DynamicFee => Some(0x02),
+            EthTransactionType::Blob => Some(0x03),
+            EthTransactionType::SetCode => Some(0x04),
+        }
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            EthTransactionType::Legacy => "legacy",
+            EthTransactionType::AccessList => "eip2930",
+            EthTransactionType::DynamicFee => "eip1559",
+            EthTransactionType::Blob => "eip4844",
+            EthTransactionType::SetCode => "eip7702",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AccessListItem {
+    pub address: H160,
+    pub storage_keys: Vec<H256>,
+}
+
+/// A signed EIP-7702 authorization tuple.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Authorization {
+    pub chain_id: U256,
+    pub address: H160,
+    pub nonce: u64,
+    pub y_parity: u8,
+    pub r: U256,
+    pub s: U256,
+}
+
+/// The unsigned payload carried in `EthSignRequest.sign_data` for transaction data types.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EthTransaction {
+    tx_type: EthTransactionType,
+    chain_id: Option<u64>,
+    nonce: u64,
+    gas_price: Option<U256>,
+    max_priority_fee_per_gas: Option<U256>,
+    max_fee_per_gas: Option<U256>,
+    gas_limit: U256,
+    to: Option<H160>,
+    value: U256,
+    data: Vec<u8>,
+    access_list: Vec<AccessListItem>,
+    max_fee_per_blob_gas: Option<U256>,
+    blob_versioned_hashes: Vec<H256>,
+    authorization_list: Vec<Authorization>,
+}
+
+fn decode_to(rlp: &Rlp) -> Result<Option<H160>, Error> {
+    if rlp.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(rlp.as_val()?))
+}
+
+fn decode_access_list(rlp: &Rlp) -> Result<Vec<AccessListItem>, Error> {
+    rlp.iter()
+        .map(|item| {
+            if item.item_count()? != 2 {
+                return Err(format_err!("invalid access list item"));
+            }
+            Ok(AccessListItem {
+                address: item.val_at(0)?,
+                storage_keys: item.list_at(1)?,
+            })
+        })
+        .collect()
+}
+
+fn decode_authorization_list(rlp: &Rlp) -> Result<Vec<Authorization>, Error> {
+    rlp.iter()
+        .map(|item| {
+            if item.item_count()? != 6 {
+                return Err(format_err!("invalid authorization"));
+            }
+            Ok(Authorization {
+                chain_id: item.val_at(0)?,
+                address: item.val_at(1)?,
+                nonce: item.val_at(2)?,
+                y_parity: item.val_at(3)?,
+                r: item.val_at(4)?,
+                s: item.val_at(5)?,
+            })
+        })
+        .collect()
+}
+
+fn format_h160(address: &H160) -> String {
+    format!("0x{}", hex::encode(address.as_bytes()))
+}
+
+fn format_h256(hash: &H256) -> String {
+    format!("0x{}", hex::encode(hash.as_bytes()))
+}
+
+impl EthTransaction {
+    /// Decodes `sign_data` according to the request data type. `Transaction` carries an
+    /// RLP list (EIP-155 or pre-EIP-155), `TypedTransaction` carries an EIP-2718 envelope.
+    pub fn decode(data_type: &DataType, sign_data: &[u8]) -> Result<Self, Error> {
+        match data_type {
+            DataType::Transaction => Self::decode_legacy(sign_data),
+            DataType::TypedTransaction => {
+                let (type_byte, payload) = sign_data
+                    .split_first()
+                    .ok_or(format_err!("empty typed transaction"))?;
+                Self::decode_typed(EthTransactionType::from_type_byte(*type_byte)?, payload)
+            }
+            _ => Err(format_err!("data type is not a transaction")),
+        }
+    }
+
+    /// Decodes the request payload and checks that the transaction chain id matches the
+    /// `chain_id` of the request when both are present.
+    pub fn from_sign_request(request: &EthSignRequest) -> Result<Self, Error> {
+        let transaction = Self::decode(&request.get_data_type(), &request.get_sign_data())?;
+        if let (Some(expected), Some(actual)) = (request.get_chain_id(), transaction.chain_id) {
+            if expected != i128::from(actual) {
+                return Err(format_err!(
+                    "chain_id mismatch, request: {expected}, transaction: {actual}"
+                ));
+            }
+        }
+        Ok(transaction)
+    }
+
+    fn decode_legacy(bytes: &[u8]) -> Result<Self, Error> {
+        let rlp = Rlp::new(bytes);
+        let count = rlp.item_count()?;
+        if count != 6 && count != 9 {
+            return Err(format_err!("invalid legacy transaction"));
+        }
+        if rlp.as_raw().len() != bytes.len() {
+            return Err(format_err!("unexpected trailing bytes"));
+        }
+        let chain_id = match count {
+            9 => Some(rlp.val_at::<u64>(6)?).filter(|chain_id| *chain_id != 0),
+            _ => None,
+        };
+        Ok(EthTransaction {
+            tx_type: EthTransactionType::Legacy,
+            chain_id,
+            nonce: rlp.val_at(0)?,
+            gas_price: Some(rlp.val_at(1)?),
+            max_priority_fee_per_gas: None,
+            max_fee_per_gas: None,
+            gas_limit: rlp.val_at(2)?,
+            to: decode_to(&rlp.at(3)?)?,
+            value: rlp.val_at(4)?,
+            data: rlp.val_at(5)?,
+            access_list: vec![],
+            max_fee_per_blob_gas: None,
+            blob_versioned_hashes: vec![],
+            authorization_list: vec![],
+        })
+    }
+
+    fn decode_typed(tx_type: EthTransactionType, payload: &[u8]) -> Result<Self, Error> {
+        let rlp = Rlp::new(payload);
+        let expected = match tx_type {
+            EthTransactionType::AccessList => 8,
+            EthTransactionType::DynamicFee => 9,
+            EthTransactionType::Blob => 11,
+            EthTransactionType::SetCode => 10,
+            EthTransactionType::Legacy => {
+                return Err(format_err!("legacy transaction is not typed"))
+            }
+        };
+        if rlp.item_count()? != expected {
+            return Err(format_err!("invalid {} transaction", tx_type.get_name()));
+        }
+        if rlp.as_raw().len() != payload.len() {
+            return Err(format_err!("unexpected trailing bytes"));
+        }
+        let mut transaction = EthTransaction {
+            tx_type,
+            chain_id: Some(rlp.val_at(0)?),
+            nonce: rlp.val_at(1)?,
+            gas_price: None,
+            max_priority_fee_per_gas: None,
+            max_fee_per_gas: None,
+            gas_limit: U256::zero(),
+            to: None,
+            value: U256::zero(),
+            data: vec![],
+            access_list: vec![],
+            max_fee_per_blob_gas: None,
+            blob_versioned_hashes: vec![],
+            authorization_list: vec![],
+        };
+        // EIP-2930 has a single gas price, the others replace it with two 1559 fee fields.
+        let offset = match tx_type {
+            EthTransactionType::AccessList => {
+                transaction.gas_price = Some(rlp.val_at(2)?);
+                3
+            }
+            _ => {
+                transaction.max_priority_fee_per_gas = Some(rlp.val_at(2)?);
+                transaction.max_fee_per_gas = Some(rlp.val_at(3)?);
+                4
+            }
+        };
+        transaction.gas_limit = rlp.val_at(offset)?;
+        transaction.to = decode_to(&rlp.at(offset + 1)?)?;
+        transaction.value = rlp.val_at(offset + 2)?;
+        transaction.data = rlp.val_at(offset + 3)?;
+        transaction.access_list = decode_access_list(&rlp.at(offset + 4)?)?;
+        match tx_type {
+            EthTransactionType::Blob => {
+                transaction.max_fee_per_blob_gas = Some(rlp.val_at(offset + 5)?);
+                transaction.blob_versioned_hashes = rlp.list_at(offset + 6)?;
+                if transaction.to.is_none() {
+                    return Err(format_err!("blob transaction must have a recipient"));
+                }
+            }
+            EthTransactionType::SetCode => {
+                transaction.authorization_list = decode_authorization_list(&rlp.at(offset + 5)?)?;
+                if transaction.to.is_none() {
+                    return Err(format_err!("set code transaction must have a recipient"));
+                }
+            }
+            _ => {}
+        }
+        Ok(transaction)
+    }
+
+    pub fn get_tx_type(&self) -> EthTransactionType {
+        self.tx_type
+    }
+
+    pub fn get_chain_id(&self) -> Option<u64> {
+        self.chain_id
+    }
+
+    pub fn get_nonce(&self) -> u64 {
+        self.nonce
+    }
+
+    pub fn get_gas_price(&self) -> Option<U256> {
+        self.gas_price
+    }
+
+    pub fn get_max_priority_fee_per_gas(&self) -> Option<U256> {
+        self.max_priority_fee_per_gas
+    }
+
+    pub fn get_max_fee_per_gas(&self) -> Option<U256> {
+        self.max_fee_per_gas
+    }
+
+    pub fn get_gas_limit(&self) -> U256 {
+        self.gas_limit
+    }
+
+    pub fn get_to(&self) -> Option<H160> {
+        self.to
+    }
+
+    pub fn get_value(&self) -> U256 {
+        self.value
+    }
+
+    pub fn get_data(&self) -> Vec<u8> {
+        self.data.clone()
+    }
+
+    pub fn get_access_list(&self) -> Vec<AccessListItem> {
+        self.access_list.clone()
+    }
+
+    pub fn get_max_fee_per_blob_gas(&self) -> Option<U256> {
+        self.max_fee_per_blob_gas
+    }
+
+    pub fn get_blob_versioned_hashes(&self) -> Vec<H256> {
+        self.blob_versioned_hashes.clone()
+    }
+
+    pub fn get_authorization_list(&self) -> Vec<Authorization> {
+        self.authorization_list.clone()
+    }
+
+    pub fn to_json(&self) -> Value {
+        let mut result = json!({
+            "type": self.tx_type.get_name(),
+            "chain_id": self.chain_id,
+            "nonce": self.nonce,
+            "gas_limit": self.gas_limit.to_string(),
+            "to": self.to.as_ref().map(format_h160),
+            "value": self.value.to_string(),
+            "data": format!("0x{}", hex::encode(&self.data)),
+        });
+        if let Some(gas_price) = self.gas_price {
+            result["gas_price"] = json!(gas_price.to_string());
+        }
+        if let Some(fee) = self.max_priority_fee_per_gas {
+            result["max_priority_fee_per_gas"] = json!(fee.to_string());
+        }
+        if let Some(fee) = self.max_fee_per_gas {
+            result["max_fee_per_gas"] = json!(fee.to_string());
+        }
+        if self.tx_type != EthTransactionType::Legacy {
+            result["access_list"] = self
+                .access_list
+                .iter()
+                .map(|item| {
+                    json!({
+                        "address": format_h160(&item.address),
+                        "storage_keys": item.storage_keys.iter().map(format_h256).collect::<Vec<_>>(),
+                    })
+                })
+                .collect();
+        }
+        if let Some(fee) = self.max_fee_per_blob_gas {
+            result["max_fee_per_blob_gas"] = json!(fee.to_string());
+            result["blob_versioned_hashes"] = json!(self
+                .blob_versioned_hashes
+                .iter()
+                .map(format_h256)
+                .collect::<Vec<_>>());
+        }
+        if self.tx_type == EthTransactionType::SetCode {
+            result["authorization_list"] = self
+                .authorization_list
+                .iter()
+                .map(|authorization| {
+                    json!({
+                        "chain_id": authorization.chain_id.to_string(),
+                        "address": format_h160(&authorization.address),
+                        "nonce": authorization.nonce,
+                        "y_parity": authorization.y_parity,
+                        "r": format!("{:#x}", authorization.r),
+                        "s": format!("{:#x}", authorization.s),
+                    })
+                })
+                .collect();
+        }
+        result
+    }
+}
+
+export! {
+    @Java_com_keystone_sdk_KeystoneNativeSDK_decodeETHSignRequest
+    fn decode_eth_sign_request(ur_type: &str, cbor_hex: &str) -> String {
+        if ETH_SIGN_REQUEST.get_type() != ur_type {
+            return json!({"error": "type not match"}).to_string();
+        }
+
+        let decode = || -> Result<Value, Error> {
+            let cbor = hex::decode(cbor_hex)?;
+            let request = EthSignRequest::try_from(cbor).map_err(|e| format_err!(e.to_string()))?;
+            Ok(EthTransaction::from_sign_request(&request)?.to_json())
+        };
+        match decode() {
+            Ok(transaction) => transaction.to_string(),
+            Err(e) => json!({"error": e.to_string()}).to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rlp::RlpStream;
+    use ur_registry::crypto_key_path::CryptoKeyPath;
+
+    fn sign_request(data_type: DataType, sign_data: Vec<u8>, chain_id: Option<i128>) -> String {
+        let path = CryptoKeyPath::from_path("m/44'/60'/0'/0/0".to_string(), None).unwrap();
+        let request = EthSignRequest::new(None, sign_data, data_type, chain_id, path, None, None);
+        let cbor: Vec<u8> = request.try_into().unwrap();
+        hex::encode(cbor)
+    }
+
+    #[test]
+    fn test_decode_legacy_transaction() {
+        let sign_data = hex::decode("f849808609184e72a00082271094000000000000000000000000000000000000000080a47f7465737432000000000000000000000000000000000000000000000000000000600057808080").unwrap();
+        let transaction = EthTransaction::decode(&DataType::Transaction, &sign_data).unwrap();
+        assert_eq!(EthTransactionType::Legacy, transaction.get_tx_type());
+        assert_eq!(None, transaction.get_chain_id());
+        assert_eq!(
+            Some(U256::from(0x09184e72a000u64)),
+            transaction.get_gas_price()
+        );
+        assert_eq!(U256::from(10000), transaction.get_gas_limit());
+        assert_eq!(Some(H160::zero()), transaction.get_to());
+        assert_eq!(36, transaction.get_data().len());
+
+        let mut stream = RlpStream::new_list(9);
+        stream
+            .append(&9u64)
+            .append(&U256::from(20_000_000_000u64))
+            .append(&21000u64)
+            .append(&H160::from_slice(
+                &hex::decode("3535353535353535353535353535353535353535").unwrap(),
+            ))
+            .append(&U256::from(1_000_000_000_000_000_000u64))
+            .append_empty_data()
+            .append(&1u64)
+            .append_empty_data()
+            .append_empty_data();
+        let cbor = sign_request(DataType::Transaction, stream.out().to_vec(), Some(1));
+        assert_eq!(
+            "{\"chain_id\":1,\"data\":\"0x\",\"gas_limit\":\"21000\",\"gas_price\":\"20000000000\",\"nonce\":9,\"to\":\"0x3535353535353535353535353535353535353535\",\"type\":\"legacy\",\"value\":\"1000000000000000000\"}",
+            decode_eth_sign_request("eth-sign-request", &cbor)
+        );
+    }
+
+    #[test]
+    fn test_decode_typed_transactions() {
+        let to =
+            H160::from_slice(&hex::decode("d8da6bf26964af9d7eed9e03e53415d37aa96045").unwrap());
+        let storage_key = H256::from_low_u64_be(1);
+
+        let mut stream = RlpStream::new_list(9);
+        stream
+            .append(&137u64)
+            .append(&3u64)
+            .append(&U256::from(30_000_000_000u64))
+            .append(&U256::from(60_000_000_000u64))
+            .append(&50000u64)
+            .append(&to)
+            .append(&0u64)
+            .append(&hex::decode("a9059cbb").unwrap());
+        stream
+            .begin_list(1)
+            .begin_list(2)
+            .append(&to)
+            .append_list(&[storage_key]);
+        let mut sign_data = vec![0x02];
+        sign_data.extend_from_slice(&stream.out());
+        let transaction = EthTransaction::decode(&DataType::TypedTransaction, &sign_data).unwrap();
+        assert_eq!(EthTransactionType::DynamicFee, transaction.get_tx_type());
+        assert_eq!(Some(137), transaction.get_chain_id());
+        assert_eq!(3, transaction.get_nonce());
+        assert_eq!(
+            Some(U256::from(60_000_000_000u64)),
+            transaction.get_max_fee_per_gas()
+        );
+        assert_eq!(
+            vec![storage_key],
+            transaction.get_access_list()[0].storage_keys
+        );
+
+        let cbor = sign_request(DataType::TypedTransaction, sign_data.clone(), Some(1));
+        assert_eq!(
+            "{\"error\":\"chain_id mismatch, request: 1, transaction: 137\"}",
+            decode_eth_sign_request("eth-sign-request", &cbor)
+        );
+
+        let mut stream = RlpStream::new_list(11);
+        stream
+            .append(&1u64)
+            .append(&0u64)
+            .append(&1u64)
+            .append(&2u64)
+            .append(&21000u64)
+            .append(&to)
+            .append(&0u64)
+            .append_empty_data()
+            .append_list::<H160, H160>(&[])
+            .append(&3u64)
+            .append_list(&[H256::from_low_u64_be(0x0100)]);
+        let mut sign_data = vec![0x03];
+        sign_data.extend_from_slice(&stream.out());
+        let transaction = EthTransaction::decode(&DataType::TypedTransaction, &sign_data).unwrap();
+        assert_eq!(EthTransactionType::Blob, transaction.get_tx_type());
+        assert_eq!(Some(U256::from(3)), transaction.get_max_fee_per_blob_gas());
+        assert_eq!(1, transaction.get_blob_versioned_hashes().len());
+
+        let mut stream = RlpStream::new_list(10);
+        stream
+            .append(&1u64)
+            .append(&0u64)
+            .append(&1u64)
+            .append(&2u64)
+            .append(&60000u64)
+            .append(&to)
+            .append(&0u64)
+            .append_empty_data()
+            .append_list::<H160, H160>(&[]);
+        stream
+            .begin_list(1)
+            .begin_list(6)
+            .append(&1u64)
+            .append(&to)
+            .append(&7u64)
+            .append(&1u8)
+            .append(&U256::from(11))
+            .append(&U256::from(12));
+        let mut sign_data = vec![0x04];
+        sign_data.extend_from_slice(&stream.out());
+        let cbor = sign_request(DataType::TypedTransaction, sign_data, Some(1));
+        let result: Value =
+            serde_json::from_str(&decode_eth_sign_request("eth-sign-request", &cbor)).unwrap();
+        assert_eq!("eip7702", result["type"]);
+        assert_eq!(
+            json!([{
+                "chain_id": "1",
+                "address": "0xd8da6bf26964af9d7eed9e03e53415d37aa96045",
+                "nonce": 7,
+                "y_parity": 1,
+                "r": "0xb",
+                "s": "0xc",
+            }]),
+            result["authorization_list"]
+        );
+    }
+
+    #[test]
+    fn test_decode_invalid_transaction() {
+        assert!(EthTransaction::decode(&DataType::TypedTransaction, &[0x05, 0xc0]).is_err());
+        assert!(EthTransaction::decode(&DataType::Transaction, &[0xc0]).is_err());
+        assert!(EthTransaction::decode(&DataType::PersonalMessage, &[0xc0]).is_err());
+        assert_eq!(
+            "legacy transaction is not typed",
+            EthTransaction::decode_typed(EthTransactionType::Legacy, &[0xc0])
+                .unwrap_err()
+                .to_string()
+        );
+        assert_eq!(
+            "{\"error\":\"type not match\"}",
+            decode_eth_sign_request("eth-signature", "")
+        );
+    }
+}
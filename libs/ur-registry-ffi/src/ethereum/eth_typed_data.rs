@@ -0,0 +1,580 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::{format_err, Error};
+use ethabi::ethereum_types::U256;
+use serde_json::{json, Map, Value};
+use sha3::{Digest, Keccak256};
+use ur_registry::ethereum::eth_sign_request::{DataType, EthSignRequest};
+
+use crate::export;
+use crate::util_internal::string_helper::remove_prefix_0x;
+
+const DOMAIN_TYPE: &str = "EIP712Domain";
+
+pub fn keccak256(data: &[u8]) -> [u8; 32] {
+    Keccak256::digest(data).into()
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TypedField {
+    pub name: String,
+    pub field_type: String,
+}
+
+/// EIP-712 typed data as sent by `eth_signTypedData_v4`.
+#[derive(Clone, Debug)]
+pub struct TypedData {
+    types: BTreeMap<String, Vec<TypedField>>,
+    primary_type: String,
+    domain: Map<String, Value>,
+    message: Map<String, Value>,
+}
+
+fn is_atomic_type(field_type: &str) -> bool {
+    match field_type {
+        "address" | "bool" | "string" | "bytes" => true,
+        _ => {
+            let sized = |prefix: &str, valid: &dyn Fn(usize) -> bool| {
+                field_type
+                    .strip_prefix(prefix)
+                    .and_then(|size| size.parse::<usize>().ok())
+                    .is_some_and(valid)
+            };
+            sized("bytes", &|size| (1..=32).contains(&size))
+                || sized("uint", &|size| size % 8 == 0 && (8..=256).contains(&size))
+                || sized("int", &|size| size % 8 == 0 && (8..=256).contains(&size))
+        }
+    }
+}
+
+/// Splits `Type[2][]` into `Type[2]` and the outer array length.
+fn split_array_type(field_type: &str) -> Option<(&str, Option<usize>)> {
+    let inner = field_type.strip_suffix(']')?;
+    let start = inner.rfind('[')?;
+    let length = &inner[start + 1..];
+    if length.is_empty() {
+        return Some((&inner[..start], None));
+    }
+    length
+        .parse()
+        .ok()
+        .map(|length| (&inner[..start], Some(length)))
+}
+
+fn parse_integer(value: &Value, field_type: &str) -> Result<[u8; 32], Error> {
+    let signed = field_type.starts_with("int");
+    let bits: usize = field_type
+        .trim_start_matches("uint")
+        .trim_start_matches("int")
+        .parse()?;
+    let (negative, magnitude) = match value {
+        Value::Number(number) => {
+            let text = number.to_string();
+            match text.strip_prefix('-') {
+                Some(text) => (true, U256::from_dec_str(text)?),
+                None => (false, U256::from_dec_str(&text)?),
+            }
+        }
+        Value::String(text) => {
+            let (negative, text) = match text.strip_prefix('-') {
+                Some(text) => (true, text),
+                None => (false, text.as_str()),
+            };
+            let magnitude = match text.strip_prefix("0x") {
+                Some(hex) => U256::from_str_radix(hex, 16)?,
+                None => U256::from_dec_str(text)?,
+            };
+            (negative, magnitude)
+        }
+        _ => return Err(format_err!("invalid {field_type} value: {value}")),
+    };
+    let out_of_range = || format_err!("{field_type} value out of range: {value}");
+    if negative && !signed {
+        return Err(out_of_range());
+    }
+    let limit = if signed { bits - 1 } else { bits };
+    let max = if limit == 256 {
+        U256::MAX
+    } else {
+        (U256::one() << limit) - 1
+    };
+    let encoded = if negative {
+        if magnitude > max + 1 {
+            return Err(out_of_range());
+        }
+        (!magnitude).overflowing_add(U256::one()).0
+    } else {
+        if magnitude > max {
+            return Err(out_of_range());
+        }
+        magnitude
+    };
+    let mut result = [0u8; 32];
+    encoded.to_big_endian(&mut result);
+    Ok(result)
+}
+
+fn parse_hex(value: &Value, field_type: &str) -> Result<Vec<u8>, Error> {
+    let text = value
+        .as_str()
+        .ok_or(format_err!("invalid {field_type} value: {value}"))?;
+    hex::decode(remove_prefix_0x(text))
+        .map_err(|_| format_err!("invalid {field_type} value: {value}"))
+}
+
+impl TypedData {
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        let value: Value = serde_json::from_str(json)?;
+        let object = value
+            .as_object()
+            .ok_or(format_err!("typed data must be an object"))?;
+        let mut types = BTreeMap::new();
+        let raw_types = object
+            .get("types")
+            .and_then(Value::as_object)
+            .ok_or(format_err!("missing types"))?;
+        for (name, fields) in raw_types {
+            let fields = fields
+                .as_array()
+                .ok_or(format_err!("type {name} must be an array"))?
+                .iter()
+                .map(|field| {
+                    let field_name = field.get("name").and_then(Value::as_str);
+                    let field_type = field.get("type").and_then(Value::as_str);
+                    match (field_name, field_type) {
+                        (Some(field_name), Some(field_type)) => Ok(TypedField {
+                            name: field_name.to_string(),
+                            field_type: field_type.to_string(),
+                        }),
+                        _ => Err(format_err!("invalid field in type {name}")),
+                    }
+                })
+                .collect::<Result<Vec<_>, Error>>()?;
+            types.insert(name.clone(), fields);
+        }
+        let primary_type = object
+            .get("primaryType")
+            .and_then(Value::as_str)
+            .ok_or(format_err!("missing primaryType"))?
+            .to_string();
+        let domain = object
+            .get("domain")
+            .and_then(Value::as_object)
+            .ok_or(format_err!("missing domain"))?
+            .clone();
+        let message = match object.get("message") {
+            Some(Value::Object(message)) => message.clone(),
+            None if primary_type == DOMAIN_TYPE => Map::new(),
+            _ => return Err(format_err!("missing message")),
+        };
+        let typed_data = TypedData {
+            types,
+            primary_type,
+            domain,
+            message,
+        };
+        typed_data.validate()?;
+        Ok(typed_data)
+    }
+
+    pub fn from_sign_request(request: &EthSignRequest) -> Result<Self, Error> {
+        if request.get_data_type() != DataType::TypedData {
+            return Err(format_err!("data type is not typed data"));
+        }
+        let typed_data = Self::from_json(core::str::from_utf8(&request.get_sign_data())?)?;
+        if let (Some(expected), Some(chain_id)) =
+            (request.get_chain_id(), typed_data.domain.get("chainId"))
+        {
+            let actual = U256::from_big_endian(&parse_integer(chain_id, "uint256")?);
+            if U256::from(expected as u128) != actual || expected < 0 {
+                return Err(format_err!(
+                    "chain_id mismatch, request: {expected}, typed data: {actual}"
+                ));
+            }
+        }
+        Ok(typed_data)
+    }
+
+    pub fn get_primary_type(&self) -> String {
+        self.primary_type.clone()
+    }
+
+    pub fn get_domain(&self) -> Map<String, Value> {
+        self.domain.clone()
+    }
+
+    pub fn get_message(&self) -> Map<String, Value> {
+        self.message.clone()
+    }
+
+    /// Checks that every referenced type is defined and that domain and message values
+    /// can be encoded.
+    pub fn validate(&self) -> Result<(), Error> {
+        if !self.types.contains_key(DOMAIN_TYPE) {
+            return Err(format_err!("missing {DOMAIN_TYPE} type"));
+        }
+        if !self.types.contains_key(&self.primary_type) {
+            return Err(format_err!(
+                "primaryType {} is not defined",
+                self.primary_type
+            ));
+        }
+        for (name, fields) in &self.types {
+            let mut names = BTreeSet::new();
+            for field in fields {
+                if !names.insert(&field.name) {
+                    return Err(format_err!("duplicate field {} in type {name}", field.name));
+                }
+                let mut base = field.field_type.as_str();
+                while let Some((inner, _)) = split_array_type(base) {
+                    base = inner;
+                }
+                if !is_atomic_type(base) && !self.types.contains_key(base) {
+                    return Err(format_err!("type {base} is not defined"));
+                }
+            }
+        }
+        self.domain_separator()?;
+        self.message_hash()?;
+        Ok(())
+    }
+
+    fn collect_dependencies(&self, type_name: &str, found: &mut BTreeSet<String>) {
+        if found.contains(type_name) {
+            return;
+        }
+        if let Some(fields) = self.types.get(type_name) {
+            found.insert(type_name.to_string());
+            for field in fields {
+                let base = field.field_type.split('[').next().unwrap_or_default();
+                self.collect_dependencies(base, found);
+            }
+        }
+    }
+
+    pub fn encode_type(&self, type_name: &str) -> Result<String, Error> {
+        let mut dependencies = BTreeSet::new();
+        self.collect_dependencies(type_name, &mut dependencies);
+        dependencies.remove(type_name);
+        let mut result = String::new();
+        for name in core::iter::once(type_name).chain(dependencies.iter().map(String::as_str)) {
+            let fields = self
+                .types
+                .get(name)
+                .ok_or(format_err!("type {name} is not defined"))?;
+            let fields = fields
+                .iter()
+                .map(|field| format!("{} {}", field.field_type, field.name))
+                .collect::<Vec<_>>()
+                .join(",");
+            result.push_str(&format!("{name}({fields})"));
+        }
+        Ok(result)
+    }
+
+    pub fn type_hash(&self, type_name: &str) -> Result<[u8; 32], Error> {
+        Ok(keccak256(self.encode_type(type_name)?.as_bytes()))
+    }
+
+    fn encode_value(&self, field_type: &str, value: &Value) -> Result<[u8; 32], Error> {
+        if let Some((inner, length)) = split_array_type(field_type) {
+            let items = value
+                .as_array()
+                .ok_or(format_err!("{field_type} value must be an array"))?;
+            if length.is_some_and(|length| length != items.len()) {
+                return Err(format_err!("{field_type} value has wrong length"));
+            }
+            let mut encoded = vec![];
+            for item in items {
+                encoded.extend_from_slice(&self.encode_value(inner, item)?);
+            }
+            return Ok(keccak256(&encoded));
+        }
+        if self.types.contains_key(field_type) {
+            let object = value
+                .as_object()
+                .ok_or(format_err!("{field_type} value must be an object"))?;
+            return self.hash_struct(field_type, object);
+        }
+        let mut result = [0u8; 32];
+        match field_type {
+            "string" => {
+                let text = value
+                    .as_str()
+                    .ok_or(format_err!("invalid string value: {value}"))?;
+                result = keccak256(text.as_bytes());
+            }
+            "bytes" => result = keccak256(&parse_hex(value, field_type)?),
+            "bool" => {
+                let flag = value
+                    .as_bool()
+                    .ok_or(format_err!("invalid bool value: {value}"))?;
+                result[31] = flag as u8;
+            }
+            "address" => {
+                let address = parse_hex(value, field_type)?;
+                if address.len() != 20 {
+                    return Err(format_err!("invalid address value: {value}"));
+                }
+                result[12..].copy_from_slice(&address);
+            }
+            _ if field_type.starts_with("bytes") => {
+                let size: usize = field_type["bytes".len()..].parse()?;
+                let bytes = parse_hex(value, field_type)?;
+                if bytes.len() != size {
+                    return Err(format_err!("invalid {field_type} value: {value}"));
+                }
+                result[..size].copy_from_slice(&bytes);
+            }
+            _ => result = parse_integer(value, field_type)?,
+        }
+        Ok(result)
+    }
+
+    pub fn hash_struct(
+        &self,
+        type_name: &str,
+        data: &Map<String, Value>,
+    ) -> Result<[u8; 32], Error> {
+        let fields = self
+            .types
+            .get(type_name)
+            .ok_or(format_err!("type {type_name} is not defined"))?;
+        let mut encoded = self.type_hash(type_name)?.to_vec();
+        for field in fields {
+            let value = data
+                .get(&field.name)
+                .ok_or(format_err!("missing field {} in {type_name}", field.name))?;
+            encoded.extend_from_slice(&self.encode_value(&field.field_type, value)?);
+        }
+        Ok(keccak256(&encoded))
+    }
+
+    pub fn domain_separator(&self) -> Result<[u8; 32], Error> {
+        self.hash_struct(DOMAIN_TYPE, &self.domain)
+    }
+
+    /// `None` when the primary type is the domain itself.
+    pub fn message_hash(&self) -> Result<Option<[u8; 32]>, Error> {
+        if self.primary_type == DOMAIN_TYPE {
+            return Ok(None);
+        }
+        self.hash_struct(&self.primary_type, &self.message)
+            .map(Some)
+    }
+
+    /// `keccak256("\x19\x01" ‖ domainSeparator ‖ hashStruct(message))`.
+    pub fn digest(&self) -> Result<[u8; 32], Error> {
+        let mut encoded = vec![0x19, 0x01];
+        encoded.extend_from_slice(&self.domain_separator()?);
+        if let Some(message_hash) = self.message_hash()? {
+            encoded.extend_from_slice(&message_hash);
+        }
+        Ok(keccak256(&encoded))
+    }
+
+    fn value_tree(&self, name: &str, field_type: &str, value: &Value) -> Value {
+        if let Some((inner, _)) = split_array_type(field_type) {
+            let children = value
+                .as_array()
+                .map(|items| {
+                    items
+                        .iter()
+                        .enumerate()
+                        .map(|(index, item)| self.value_tree(&index.to_string(), inner, item))
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default();
+            return json!({"name": name, "type": field_type, "children": children});
+        }
+        if let Some(fields) = self.types.get(field_type) {
+            let children = fields
+                .iter()
+                .map(|field| {
+                    let value = value.get(&field.name).cloned().unwrap_or(Value::Null);
+                    self.value_tree(&field.name, &field.field_type, &value)
+                })
+                .collect::<Vec<_>>();
+            return json!({"name": name, "type": field_type, "children": children});
+        }
+        let value = match value {
+            Value::String(text) => text.clone(),
+            other => other.to_string(),
+        };
+        json!({"name": name, "type": field_type, "value": value})
+    }
+
+    /// A tree of `{name, type, value}` leaves and `{name, type, children}` nodes following
+    /// the declared field order, for display.
+    pub fn to_tree(&self) -> Value {
+        let domain = Value::Object(self.domain.clone());
+        let message = Value::Object(self.message.clone());
+        json!({
+            "domain": self.value_tree("domain", DOMAIN_TYPE, &domain),
+            "message": self.value_tree("message", &self.primary_type, &message),
+        })
+    }
+}
+
+export! {
+    @Java_com_keystone_sdk_KeystoneNativeSDK_parseETHTypedData
+    fn parse_eth_typed_data(typed_data: &str) -> String {
+        let parse = || -> Result<Value, Error> {
+            let typed_data = TypedData::from_json(typed_data)?;
+            let tree = typed_data.to_tree();
+            Ok(json!({
+                "primary_type": typed_data.get_primary_type(),
+                "domain_separator": hex::encode(typed_data.domain_separator()?),
+                "message_hash": typed_data.message_hash()?.map(hex::encode),
+                "digest": hex::encode(typed_data.digest()?),
+                "domain": tree["domain"],
+                "message": tree["message"],
+            }))
+        };
+        match parse() {
+            Ok(result) => result.to_string(),
+            Err(e) => json!({"error": e.to_string()}).to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ur_registry::crypto_key_path::CryptoKeyPath;
+
+    const MAIL: &str = r#"{
+        "types": {
+            "EIP712Domain": [
+                {"name": "name", "type": "string"},
+                {"name": "version", "type": "string"},
+                {"name": "chainId", "type": "uint256"},
+                {"name": "verifyingContract", "type": "address"}
+            ],
+            "Person": [
+                {"name": "name", "type": "string"},
+                {"name": "wallet", "type": "address"}
+            ],
+            "Mail": [
+                {"name": "from", "type": "Person"},
+                {"name": "to", "type": "Person"},
+                {"name": "contents", "type": "string"}
+            ]
+        },
+        "primaryType": "Mail",
+        "domain": {
+            "name": "Ether Mail",
+            "version": "1",
+            "chainId": 1,
+            "verifyingContract": "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"
+        },
+        "message": {
+            "from": {"name": "Cow", "wallet": "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826"},
+            "to": {"name": "Bob", "wallet": "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB"},
+            "contents": "Hello, Bob!"
+        }
+    }"#;
+
+    #[test]
+    fn test_mail_hashes() {
+        let typed_data = TypedData::from_json(MAIL).unwrap();
+        assert_eq!(
+            "Mail(Person from,Person to,string contents)Person(string name,address wallet)",
+            typed_data.encode_type("Mail").unwrap()
+        );
+        assert_eq!(
+            "f2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f",
+            hex::encode(typed_data.domain_separator().unwrap())
+        );
+        assert_eq!(
+            "c52c0ee5d84264471806290a3f2c4cecfc5490626bf912d01f240d7a274b371e",
+            hex::encode(typed_data.message_hash().unwrap().unwrap())
+        );
+        assert_eq!(
+            "be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2",
+            hex::encode(typed_data.digest().unwrap())
+        );
+
+        let result: Value = serde_json::from_str(&parse_eth_typed_data(MAIL)).unwrap();
+        assert_eq!(
+            "be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2",
+            result["digest"]
+        );
+        assert_eq!(
+            json!({"name": "contents", "type": "string", "value": "Hello, Bob!"}),
+            result["message"]["children"][2]
+        );
+        assert_eq!(
+            json!({"name": "chainId", "type": "uint256", "value": "1"}),
+            result["domain"]["children"][2]
+        );
+    }
+
+    #[test]
+    fn test_arrays_and_integers() {
+        let typed_data = r#"{
+            "types": {
+                "EIP712Domain": [{"name": "chainId", "type": "uint256"}],
+                "Batch": [
+                    {"name": "amounts", "type": "int64[2]"},
+                    {"name": "tags", "type": "bytes4[]"},
+                    {"name": "ok", "type": "bool"}
+                ]
+            },
+            "primaryType": "Batch",
+            "domain": {"chainId": "0x89"},
+            "message": {"amounts": [-1, "200"], "tags": ["0xa9059cbb"], "ok": true}
+        }"#;
+        let typed_data = TypedData::from_json(typed_data).unwrap();
+        let mut expected = vec![0xffu8; 32];
+        expected.extend_from_slice(&[0u8; 31]);
+        expected.push(200);
+        assert_eq!(
+            keccak256(&expected),
+            typed_data
+                .encode_value("int64[2]", &json!([-1, "200"]))
+                .unwrap()
+        );
+        assert!(typed_data.encode_value("int8", &json!(-129)).is_err());
+        assert!(typed_data.encode_value("uint8", &json!(256)).is_err());
+        assert!(typed_data.encode_value("uint256", &json!(-1)).is_err());
+        assert!(typed_data.encode_value("int64[2]", &json!([1])).is_err());
+
+        let path = CryptoKeyPath::from_path("m/44'/60'/0'/0/0".to_string(), None).unwrap();
+        let request = EthSignRequest::new(
+            None,
+            MAIL.as_bytes().to_vec(),
+            DataType::TypedData,
+            Some(137),
+            path,
+            None,
+            None,
+        );
+        assert_eq!(
+            "chain_id mismatch, request: 137, typed data: 1",
+            TypedData::from_sign_request(&request)
+                .unwrap_err()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn test_invalid_typed_data() {
+        let missing_type = MAIL.replace("\"type\": \"Person\"}", "\"type\": \"Human\"}");
+        assert_eq!(
+            "{\"error\":\"type Human is not defined\"}",
+            parse_eth_typed_data(&missing_type)
+        );
+        let missing_field = MAIL.replace("\"contents\": \"Hello, Bob!\"", "\"content\": \"\"");
+        assert_eq!(
+            "{\"error\":\"missing field contents in Mail\"}",
+            parse_eth_typed_data(&missing_field)
+        );
+        let bad_address = MAIL.replace("0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC", "0x1234");
+        assert_eq!(
+            "{\"error\":\"invalid address value: \\\"0x1234\\\"\"}",
+            parse_eth_typed_data(&bad_address)
+        );
+        assert!(TypedData::from_json("[]").is_err());
+    }
+}
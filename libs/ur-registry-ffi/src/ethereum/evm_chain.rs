@@ -0,0 +1,74 @@
+use serde_json::json;
+
+use crate::export;
+pub use crate::util_internal::chain::{find_evm_chain, EvmChain, EVM_CHAINS};
+use crate::util_internal::string_helper::remove_prefix_0x;
+
+fn parse_chain_id(chain_id: &str) -> Option<u64> {
+    if chain_id.starts_with("0x") {
+        u64::from_str_radix(remove_prefix_0x(chain_id), 16).ok()
+    } else {
+        chain_id.parse::<u64>().ok()
+    }
+}
+
+export! {
+    @Java_com_keystone_sdk_KeystoneNativeSDK_getEVMChain
+    fn get_evm_chain(chain_id: &str) -> String {
+        let chain_id = match parse_chain_id(chain_id) {
+            Some(v) => v,
+            None => return json!({"error": "chain id is invalid"}).to_string(),
+        };
+        match find_evm_chain(chain_id) {
+            Some(chain) => json!({
+                "chain_id": chain.chain_id,
+                "name": chain.name,
+                "short_name": chain.short_name,
+                "caip2_id": chain.caip2_id(),
+                "native_currency": {
+                    "name": chain.currency_name,
+                    "symbol": chain.currency_symbol,
+                    "decimals": chain.decimals,
+                },
+            }).to_string(),
+            None => json!({"error": "chain is not supported"}).to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_evm_chain() {
+        let expect_result = "{\"caip2_id\":\"eip155:137\",\"chain_id\":137,\"name\":\"Polygon Mainnet\",\"native_currency\":{\"decimals\":18,\"name\":\"POL\",\"symbol\":\"POL\"},\"short_name\":\"pol\"}";
+        assert_eq!(expect_result, get_evm_chain("137"));
+        assert_eq!(expect_result, get_evm_chain("0x89"));
+
+        let chain = find_evm_chain(11297108109).unwrap();
+        assert_eq!("Palm", chain.name);
+        assert_eq!("eip155:11297108109", chain.caip2_id());
+    }
+
+    #[test]
+    fn test_get_evm_chain_error() {
+        assert_eq!(
+            "{\"error\":\"chain id is invalid\"}",
+            get_evm_chain("ethereum")
+        );
+        assert_eq!(
+            "{\"error\":\"chain is not supported\"}",
+            get_evm_chain("999999999")
+        );
+    }
+
+    #[test]
+    fn test_evm_chain_ids_are_unique() {
+        for (index, chain) in EVM_CHAINS.iter().enumerate() {
+            assert!(EVM_CHAINS[index + 1..]
+                .iter()
+                .all(|other| other.chain_id != chain.chain_id));
+        }
+    }
+}
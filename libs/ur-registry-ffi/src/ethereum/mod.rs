@@ -1,2 +1,10 @@
+pub mod eth_batch_sign_request;
+pub mod eth_batch_signature;
+pub mod eth_calldata;
 pub mod eth_sign_request;
 pub mod eth_signature;
+pub mod eth_signature_verification;
+pub mod eth_signed_transaction;
+pub mod eth_transaction;
+pub mod eth_typed_data;
+pub mod evm_chain;
@@ -0,0 +1,96 @@
+use serde_json::json;
+use ur_registry::iota::iota_sign_hash_request::IotaSignHashRequest;
+use ur_registry::registry_types::IOTA_SIGN_HASH_REQUEST;
+use uuid::Uuid;
+
+use crate::export;
+use crate::iota::iota_sign_request::parse_accounts;
+use crate::util_internal::string_helper::remove_prefix_0x;
+
+export! {
+    @Java_com_keystone_sdk_KeystoneNativeSDK_generateIotaSignHashRequest
+    fn generate_iota_sign_hash_request(
+        request_id: &str,
+        message_hash: &str,
+        accounts: &str,
+        origin: &str
+    ) -> String {
+        let request_id = match Uuid::parse_str(request_id) {
+            Ok(v) => Some(v.as_bytes().to_vec()),
+            Err(_) => return json!({"error": "uuid is invalid"}).to_string(),
+        };
+        let (derivation_paths, addresses) = match parse_accounts(accounts) {
+            Ok(v) => v,
+            Err(e) => return json!({"error": e}).to_string(),
+        };
+        let message_hash = match hex::decode(remove_prefix_0x(message_hash)) {
+            Ok(v) if !v.is_empty() => hex::encode(v),
+            _ => return json!({"error": "message hash is invalid"}).to_string(),
+        };
+        let origin = if origin.is_empty() { None } else { Some(origin.to_string()) };
+
+        let cbor_bytes: Vec<u8> = match IotaSignHashRequest::new(
+            request_id,
+            message_hash,
+            derivation_paths,
+            addresses,
+            origin,
+        ).try_into() {
+            Ok(v) => v,
+            Err(_) => return json!({"error": "sign data is invalid"}).to_string(),
+        };
+        json!({
+            "type": IOTA_SIGN_HASH_REQUEST.get_type(),
+            "cbor": hex::encode(cbor_bytes),
+        }).to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_iota_sign_hash_request() {
+        let request_id = "9b1deb4d-3b7d-4bad-9bdd-2b0d7b3dcb6d";
+        let message_hash = "0x9A43AC0A324D8A6BC151D61A8BA4D6A3206E91157F44CAEECFF39436C0B86C65";
+        let accounts = r#"
+            [
+                {
+                    "path": "m/44'/4218'/0'/0'/0'",
+                    "xfp": "78230804",
+                    "address": "0xebe623e33b7307f1350f8934beb3fb16baef0fc1b3f1b92868eec39440938869"
+                }
+            ]
+            "#;
+        let origin = "IOTA Wallet";
+        let expect_result = "{\"cbor\":\"a501d825509b1deb4d3b7d4bad9bdd2b0d7b3dcb6d027840396134336163306133323464386136626331353164363161386261346436613332303665393131353766343463616565636666333934333663306238366336350381d90130a2018a182cf519107af500f500f500f5021a7823080404815820ebe623e33b7307f1350f8934beb3fb16baef0fc1b3f1b92868eec39440938869056b494f54412057616c6c6574\",\"type\":\"iota-sign-hash-request\"}";
+
+        assert_eq!(
+            expect_result,
+            generate_iota_sign_hash_request(request_id, message_hash, accounts, origin)
+        );
+    }
+
+    #[test]
+    fn test_generate_iota_sign_hash_request_err_message_hash() {
+        let request_id = "9b1deb4d-3b7d-4bad-9bdd-2b0d7b3dcb6d";
+        let message_hash = "9a43ac0a324d8a6bc151d61a8ba4d6a3206e91157f44caeecff39436c0b86c6";
+        let accounts = r#"
+            [
+                {
+                    "path": "m/44'/4218'/0'/0'/0'",
+                    "xfp": "78230804"
+                }
+            ]
+            "#;
+        let origin = "IOTA Wallet";
+
+        let expect_result = "{\"error\":\"message hash is invalid\"}";
+
+        assert_eq!(
+            expect_result,
+            generate_iota_sign_hash_request(request_id, message_hash, accounts, origin)
+        );
+    }
+}
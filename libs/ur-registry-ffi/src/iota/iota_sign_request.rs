@@ -0,0 +1,159 @@
+use serde::Deserialize;
+use serde_json::json;
+use ur_registry::crypto_key_path::CryptoKeyPath;
+use ur_registry::iota::iota_sign_request::IotaSignRequest;
+use ur_registry::registry_types::IOTA_SIGN_REQUEST;
+use uuid::Uuid;
+
+use crate::export;
+use crate::util_internal::string_helper::remove_prefix_0x;
+
+#[derive(Deserialize)]
+struct Account {
+    path: String,
+    xfp: String,
+    address: Option<String>,
+}
+
+type ParsedAccounts = (Vec<CryptoKeyPath>, Option<Vec<Vec<u8>>>);
+
+/// Parses the `accounts` JSON shared by the IOTA requests into derivation paths and,
+/// when every account carries one, their addresses.
+pub(crate) fn parse_accounts(accounts: &str) -> Result<ParsedAccounts, String> {
+    let accounts = serde_json::from_str::<Vec<Account>>(accounts)
+        .ok()
+        .filter(|accounts| !accounts.is_empty())
+        .ok_or("accounts is invalid")?;
+    let mut derivation_paths = vec![];
+    let mut addresses = vec![];
+    for account in accounts {
+        if let Some(address) = account.address {
+            addresses.push(
+                hex::decode(remove_prefix_0x(&address)).map_err(|_| "accounts is invalid")?,
+            );
+        }
+        let xfp: [u8; 4] = hex::decode(&account.xfp)
+            .ok()
+            .and_then(|xfp| xfp.try_into().ok())
+            .ok_or("accounts is invalid")?;
+        derivation_paths.push(
+            CryptoKeyPath::from_path(account.path, Some(xfp)).map_err(|_| "accounts is invalid")?,
+        );
+    }
+    if !addresses.is_empty() && addresses.len() != derivation_paths.len() {
+        return Err("account and path count must match".to_string());
+    }
+    let addresses = (!addresses.is_empty()).then_some(addresses);
+    Ok((derivation_paths, addresses))
+}
+
+export! {
+    @Java_com_keystone_sdk_KeystoneNativeSDK_generateIotaSignRequest
+    fn generate_iota_sign_request(
+        request_id: &str,
+        intent_message: &str,
+        accounts: &str,
+        origin: &str
+    ) -> String {
+        let request_id = match Uuid::parse_str(request_id) {
+            Ok(v) => Some(v.as_bytes().to_vec()),
+            Err(_) => return json!({"error": "uuid is invalid"}).to_string(),
+        };
+        let (derivation_paths, addresses) = match parse_accounts(accounts) {
+            Ok(v) => v,
+            Err(e) => return json!({"error": e}).to_string(),
+        };
+        let intent_message = match hex::decode(remove_prefix_0x(intent_message)) {
+            Ok(v) => v,
+            Err(_) => return json!({"error": "intent message is invalid"}).to_string(),
+        };
+        let origin = if origin.is_empty() { None } else { Some(origin.to_string()) };
+
+        let cbor_bytes: Vec<u8> = match IotaSignRequest::new(
+            request_id,
+            intent_message,
+            derivation_paths,
+            addresses,
+            origin,
+        ).try_into() {
+            Ok(v) => v,
+            Err(_) => return json!({"error": "sign data is invalid"}).to_string(),
+        };
+        json!({
+            "type": IOTA_SIGN_REQUEST.get_type(),
+            "cbor": hex::encode(cbor_bytes),
+        }).to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_iota_sign_request() {
+        let request_id = "9b1deb4d-3b7d-4bad-9bdd-2b0d7b3dcb6d";
+        let intent_message = "00000000000200201ff915a5e9e32fdbe0135535b6c69a00a9809aaf7f7c0275d3239ca79db20d6400081027000000000000020200010101000101020000010000ebe623e33b7307f1350f8934beb3fb16baef0fc1b3f1b92868eec3944093886901a2e3e42930675d9571a467eb5d4b22553c93ccb84e9097972e02c490b4e7a22ab73200000000000020176c4727433105da34209f04ac3f22e192a2573d7948cb2fabde7d13a7f4f149ebe623e33b7307f1350f8934beb3fb16baef0fc1b3f1b92868eec39440938869e803000000000000640000000000000000";
+        let accounts = r#"
+            [
+                {
+                    "path": "m/44'/4218'/0'/0'/0'",
+                    "xfp": "78230804",
+                    "address": "0xebe623e33b7307f1350f8934beb3fb16baef0fc1b3f1b92868eec39440938869"
+                }
+            ]
+            "#;
+        let origin = "IOTA Wallet";
+        let expect_result = "{\"cbor\":\"a501d825509b1deb4d3b7d4bad9bdd2b0d7b3dcb6d0258dc00000000000200201ff915a5e9e32fdbe0135535b6c69a00a9809aaf7f7c0275d3239ca79db20d6400081027000000000000020200010101000101020000010000ebe623e33b7307f1350f8934beb3fb16baef0fc1b3f1b92868eec3944093886901a2e3e42930675d9571a467eb5d4b22553c93ccb84e9097972e02c490b4e7a22ab73200000000000020176c4727433105da34209f04ac3f22e192a2573d7948cb2fabde7d13a7f4f149ebe623e33b7307f1350f8934beb3fb16baef0fc1b3f1b92868eec39440938869e8030000000000006400000000000000000381d90130a2018a182cf519107af500f500f500f5021a7823080404815820ebe623e33b7307f1350f8934beb3fb16baef0fc1b3f1b92868eec39440938869056b494f54412057616c6c6574\",\"type\":\"iota-sign-request\"}";
+
+        assert_eq!(
+            expect_result,
+            generate_iota_sign_request(request_id, intent_message, accounts, origin)
+        );
+    }
+
+    #[test]
+    fn test_generate_iota_sign_request_account_error() {
+        let request_id = "9b1deb4d-3b7d-4bad-9bdd-2b0d7b3dcb6d";
+        let intent_message = "0000000000020020";
+        let accounts = r#"
+            [
+                {
+                    "path": "",
+                    "xfp": "78230804",
+                    "address": "0xebe623e33b7307f1350f8934beb3fb16baef0fc1b3f1b92868eec39440938869"
+                }
+            ]
+            "#;
+        let origin = "IOTA Wallet";
+
+        let expect_result = "{\"error\":\"accounts is invalid\"}";
+
+        assert_eq!(
+            expect_result,
+            generate_iota_sign_request(request_id, intent_message, accounts, origin)
+        );
+    }
+
+    #[test]
+    fn test_generate_iota_sign_request_err_sign_data() {
+        let request_id = "9b1deb4d-3b7d-4bad-9bdd-2b0d7b3dcb6d";
+        let intent_message = "8e53e7b10656816de70824e3016fc1a277e";
+        let accounts = r#"
+            [
+                {
+                    "path": "m/44'/4218'/0'/0'/0'",
+                    "xfp": "78230804"
+                }
+            ]
+            "#;
+        let origin = "IOTA Wallet";
+
+        let expect_result = "{\"error\":\"intent message is invalid\"}";
+
+        assert_eq!(
+            expect_result,
+            generate_iota_sign_request(request_id, intent_message, accounts, origin)
+        );
+    }
+}
@@ -0,0 +1,3 @@
+pub mod iota_sign_hash_request;
+pub mod iota_sign_request;
+pub mod iota_signature;
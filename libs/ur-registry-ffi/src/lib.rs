@@ -6,6 +6,7 @@ pub mod cosmos;
 pub mod ethereum;
 pub mod evm;
 mod export;
+pub mod iota;
 pub mod keystone;
 pub mod near;
 pub mod solana;
@@ -1,2 +1,4 @@
+pub mod near_message;
 pub mod near_sign_request;
 pub mod near_signature;
+pub mod near_transaction;
@@ -0,0 +1,374 @@
+use anyhow::{format_err, Error};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use ur_registry::near::near_sign_request::NearSignRequest;
+use ur_registry::near::near_signature::NearSignature;
+use ur_registry::registry_types::NEAR_SIGNATURE;
+
+use crate::export;
+use crate::near::near_transaction::PublicKey;
+use crate::util_internal::borsh::{write_string, BorshReader};
+use crate::util_internal::ed25519::verify_ed25519;
+use crate::util_internal::string_helper::remove_prefix_0x;
+
+/// `2^31 + 413`, the Borsh `u32` tag prepended to NEP-413 payloads.
+pub const NEP413_TAG: u32 = (1 << 31) + 413;
+
+/// A NEP-413 `signMessage` payload.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Nep413Payload {
+    message: String,
+    nonce: [u8; 32],
+    recipient: String,
+    callback_url: Option<String>,
+}
+
+impl Nep413Payload {
+    pub fn new(
+        message: String,
+        nonce: [u8; 32],
+        recipient: String,
+        callback_url: Option<String>,
+    ) -> Result<Self, Error> {
+        if recipient.is_empty() {
+            return Err(format_err!("recipient is empty"));
+        }
+        Ok(Nep413Payload {
+            message,
+            nonce,
+            recipient,
+            callback_url,
+        })
+    }
+
+    /// Serializes the tagged payload that the wallet hashes and signs.
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut result = NEP413_TAG.to_le_bytes().to_vec();
+        write_string(&mut result, &self.message);
+        result.extend_from_slice(&self.nonce);
+        write_string(&mut result, &self.recipient);
+        match &self.callback_url {
+            Some(callback_url) => {
+                result.push(1);
+                write_string(&mut result, callback_url);
+            }
+            None => result.push(0),
+        }
+        result
+    }
+
+    pub fn deserialize(bytes: &[u8]) -> Result<Self, Error> {
+        let mut reader = BorshReader::new(bytes);
+        if reader.u32()? != NEP413_TAG {
+            return Err(format_err!("invalid nep-413 tag"));
+        }
+        let payload = Nep413Payload {
+            message: reader.string()?,
+            nonce: reader.fixed()?,
+            recipient: reader.string()?,
+            callback_url: reader.option(|r| r.string())?,
+        };
+        reader.finish()?;
+        Ok(payload)
+    }
+
+    pub fn hash(&self) -> [u8; 32] {
+        Sha256::digest(self.serialize()).into()
+    }
+
+    pub fn verify(&self, public_key: &[u8; 32], signature: &[u8]) -> bool {
+        verify_ed25519(public_key, &self.hash(), signature)
+    }
+
+    pub fn get_message(&self) -> String {
+        self.message.clone()
+    }
+
+    pub fn get_nonce(&self) -> [u8; 32] {
+        self.nonce
+    }
+
+    pub fn get_recipient(&self) -> String {
+        self.recipient.clone()
+    }
+
+    pub fn get_callback_url(&self) -> Option<String> {
+        self.callback_url.clone()
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "message": self.message,
+            "nonce": hex::encode(self.nonce),
+            "recipient": self.recipient,
+            "callback_url": self.callback_url,
+        })
+    }
+}
+
+/// Resolves the signing key from `ed25519:<base58>` or hex, falling back to the key of an
+/// implicit request account.
+fn resolve_public_key(public_key: &str, request: &NearSignRequest) -> Result<[u8; 32], Error> {
+    let key = match public_key.is_empty() {
+        false => public_key.to_string(),
+        true => request
+            .get_account()
+            .and_then(|account| String::from_utf8(account).ok())
+            .filter(|account| account.len() == 64)
+            .ok_or(format_err!("public key is required"))?,
+    };
+    let bytes = match key.parse::<PublicKey>() {
+        Ok(PublicKey::Ed25519(bytes)) => bytes,
+        Ok(PublicKey::Secp256k1(_)) => return Err(format_err!("public key is not ed25519")),
+        Err(_) => hex::decode(remove_prefix_0x(&key))
+            .ok()
+            .and_then(|v| v.try_into().ok())
+            .ok_or(format_err!("public key is invalid"))?,
+    };
+    Ok(bytes)
+}
+
+/// Verifies every NEP-413 payload of a request against the matching `NearSignature` entry.
+pub fn verify_near_message_signature_for_request(
+    request: &NearSignRequest,
+    signature: &NearSignature,
+    public_key: &[u8; 32],
+) -> Result<Vec<Nep413Payload>, Error> {
+    if let Some(request_id) = request.get_request_id() {
+        if signature.get_request_id() != Some(request_id) {
+            return Err(format_err!("request id mismatch"));
+        }
+    }
+    let sign_data = request.get_sign_data();
+    let signatures = signature.get_signature();
+    if sign_data.len() != signatures.len() {
+        return Err(format_err!("signature count mismatch"));
+    }
+    sign_data
+        .iter()
+        .zip(signatures.iter())
+        .map(|(data, signature)| {
+            let payload = Nep413Payload::deserialize(data)?;
+            match payload.verify(public_key, signature) {
+                true => Ok(payload),
+                false => Err(format_err!("signature does not match the public key")),
+            }
+        })
+        .collect()
+}
+
+export! {
+    @Java_com_keystone_sdk_KeystoneNativeSDK_generateNearNep413Payload
+    fn generate_near_nep413_payload(
+        message: &str,
+        nonce: &str,
+        recipient: &str,
+        callback_url: &str
+    ) -> String {
+        let nonce: [u8; 32] = match hex::decode(remove_prefix_0x(nonce))
+            .ok()
+            .and_then(|v| v.try_into().ok())
+        {
+            Some(v) => v,
+            None => return json!({"error": "nonce is invalid"}).to_string(),
+        };
+        let callback_url = if callback_url.is_empty() { None } else { Some(callback_url.to_string()) };
+
+        match Nep413Payload::new(message.to_string(), nonce, recipient.to_string(), callback_url) {
+            Ok(payload) => json!({
+                "sign_data": hex::encode(payload.serialize()),
+                "hash": hex::encode(payload.hash()),
+            }).to_string(),
+            Err(e) => json!({"error": e.to_string()}).to_string(),
+        }
+    }
+
+    @Java_com_keystone_sdk_KeystoneNativeSDK_verifyNearMessageSignature
+    fn verify_near_message_signature(
+        sign_request_cbor_hex: &str,
+        public_key: &str,
+        ur_type: &str,
+        cbor_hex: &str
+    ) -> String {
+        if NEAR_SIGNATURE.get_type() != ur_type {
+            return json!({"error": "type not match"}).to_string();
+        }
+
+        let verify = || -> Result<([u8; 32], Vec<Nep413Payload>), Error> {
+            let request = NearSignRequest::try_from(hex::decode(sign_request_cbor_hex)?)
+                .map_err(|e| format_err!(e.to_string()))?;
+            let signature = NearSignature::try_from(hex::decode(cbor_hex)?)
+                .map_err(|e| format_err!(e.to_string()))?;
+            let public_key = resolve_public_key(public_key, &request)?;
+            let payloads = verify_near_message_signature_for_request(&request, &signature, &public_key)?;
+            Ok((public_key, payloads))
+        };
+        match verify() {
+            Ok((public_key, payloads)) => json!({
+                "public_key": PublicKey::Ed25519(public_key).to_string(),
+                "messages": payloads.iter().map(Nep413Payload::to_json).collect::<Vec<_>>(),
+                "verified": true,
+            }).to_string(),
+            Err(e) => json!({"error": e.to_string()}).to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util_internal::ed25519::sign_ed25519;
+    use ur_registry::crypto_key_path::CryptoKeyPath;
+
+    const SEED: [u8; 32] = [5u8; 32];
+
+    fn payload() -> Nep413Payload {
+        Nep413Payload::new(
+            "Hello NEAR".to_string(),
+            [9u8; 32],
+            "example.near".to_string(),
+            None,
+        )
+        .unwrap()
+    }
+
+    fn sign_request(sign_data: Vec<u8>, account: Option<String>) -> String {
+        let path = CryptoKeyPath::from_path("m/44'/397'/0'".to_string(), None).unwrap();
+        let request = NearSignRequest::new(
+            Some(vec![1u8; 16]),
+            vec![sign_data],
+            path,
+            account.map(String::into_bytes),
+            None,
+        );
+        let cbor: Vec<u8> = request.try_into().unwrap();
+        hex::encode(cbor)
+    }
+
+    fn signature(request_id: Vec<u8>, signature: Vec<u8>) -> String {
+        let cbor: Vec<u8> = NearSignature::new(Some(request_id), vec![signature])
+            .try_into()
+            .unwrap();
+        hex::encode(cbor)
+    }
+
+    #[test]
+    fn test_nep413_payload() {
+        let serialized = payload().serialize();
+        assert_eq!("9d010080", hex::encode(&serialized[..4]));
+        assert_eq!(payload(), Nep413Payload::deserialize(&serialized).unwrap());
+
+        let with_callback = Nep413Payload::new(
+            "Hello NEAR".to_string(),
+            [9u8; 32],
+            "example.near".to_string(),
+            Some("https://example.com".to_string()),
+        )
+        .unwrap();
+        assert_eq!(
+            with_callback,
+            Nep413Payload::deserialize(&with_callback.serialize()).unwrap()
+        );
+
+        let result: Value = serde_json::from_str(&generate_near_nep413_payload(
+            "Hello NEAR",
+            &"09".repeat(32),
+            "example.near",
+            "",
+        ))
+        .unwrap();
+        assert_eq!(hex::encode(&serialized), result["sign_data"]);
+        assert_eq!(hex::encode(payload().hash()), result["hash"]);
+        assert_eq!(
+            "{\"error\":\"nonce is invalid\"}",
+            generate_near_nep413_payload("Hello NEAR", "09", "example.near", "")
+        );
+    }
+
+    #[test]
+    fn test_verify_near_message_signature() {
+        let (public_key, sig) = sign_ed25519(&SEED, &payload().hash());
+        let request = sign_request(payload().serialize(), Some(hex::encode(public_key)));
+
+        let result: Value = serde_json::from_str(&verify_near_message_signature(
+            &request,
+            "",
+            "near-signature",
+            &signature(vec![1u8; 16], sig.to_vec()),
+        ))
+        .unwrap();
+        assert_eq!(true, result["verified"]);
+        assert_eq!(
+            format!("ed25519:{}", bs58::encode(public_key).into_string()),
+            result["public_key"]
+        );
+        assert_eq!("example.near", result["messages"][0]["recipient"]);
+
+        let named = sign_request(payload().serialize(), Some("alice.near".to_string()));
+        assert_eq!(
+            "{\"error\":\"public key is required\"}",
+            verify_near_message_signature(
+                &named,
+                "",
+                "near-signature",
+                &signature(vec![1u8; 16], sig.to_vec())
+            )
+        );
+        let public_key = PublicKey::Ed25519(public_key).to_string();
+        assert!(verify_near_message_signature(
+            &named,
+            &public_key,
+            "near-signature",
+            &signature(vec![1u8; 16], sig.to_vec())
+        )
+        .contains("\"verified\":true"));
+    }
+
+    #[test]
+    fn test_verify_near_message_signature_error() {
+        let (public_key, sig) = sign_ed25519(&SEED, &payload().hash());
+        let request = sign_request(payload().serialize(), Some(hex::encode(public_key)));
+        assert_eq!(
+            "{\"error\":\"request id mismatch\"}",
+            verify_near_message_signature(
+                &request,
+                "",
+                "near-signature",
+                &signature(vec![2u8; 16], sig.to_vec())
+            )
+        );
+        let cbor: Vec<u8> = NearSignature::new(None, vec![sig.to_vec()])
+            .try_into()
+            .unwrap();
+        assert_eq!(
+            "{\"error\":\"request id mismatch\"}",
+            verify_near_message_signature(&request, "", "near-signature", &hex::encode(cbor))
+        );
+        let mut tampered = sig;
+        tampered[0] ^= 1;
+        assert_eq!(
+            "{\"error\":\"signature does not match the public key\"}",
+            verify_near_message_signature(
+                &request,
+                "",
+                "near-signature",
+                &signature(vec![1u8; 16], tampered.to_vec())
+            )
+        );
+        assert_eq!(
+            "{\"error\":\"type not match\"}",
+            verify_near_message_signature(
+                &request,
+                "",
+                "near-sign-request",
+                &signature(vec![1u8; 16], sig.to_vec())
+            )
+        );
+        assert_eq!(
+            "invalid nep-413 tag",
+            Nep413Payload::deserialize(&[0, 0, 0, 0])
+                .unwrap_err()
+                .to_string()
+        );
+    }
+}
@@ -0,0 +1,599 @@
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{format_err, Error};
+use serde_json::{json, Value};
+use ur_registry::near::near_sign_request::NearSignRequest;
+use ur_registry::registry_types::NEAR_SIGN_REQUEST;
+
+use crate::export;
+use crate::near::near_message::{Nep413Payload, NEP413_TAG};
+use crate::util_internal::borsh::BorshReader;
+
+/// `2^30 + 366`, the Borsh `u32` tag prepended to NEP-366 delegate actions.
+pub const NEP366_TAG: u32 = (1 << 30) + 366;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PublicKey {
+    Ed25519([u8; 32]),
+    Secp256k1([u8; 64]),
+}
+
+impl PublicKey {
+    fn decode(reader: &mut BorshReader) -> Result<Self, Error> {
+        match reader.u8()? {
+            0 => Ok(PublicKey::Ed25519(reader.fixed()?)),
+            1 => Ok(PublicKey::Secp256k1(reader.fixed()?)),
+            x => Err(format_err!("unsupported key type {x}")),
+        }
+    }
+}
+
+impl fmt::Display for PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PublicKey::Ed25519(key) => write!(f, "ed25519:{}", bs58::encode(key).into_string()),
+            PublicKey::Secp256k1(key) => {
+                write!(f, "secp256k1:{}", bs58::encode(key).into_string())
+            }
+        }
+    }
+}
+
+impl FromStr for PublicKey {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (key_type, key) = value
+            .split_once(':')
+            .ok_or(format_err!("public key is invalid"))?;
+        let bytes = bs58::decode(key)
+            .into_vec()
+            .map_err(|_| format_err!("public key is invalid"))?;
+        let key = match key_type {
+            "ed25519" => bytes.try_into().ok().map(PublicKey::Ed25519),
+            "secp256k1" => bytes.try_into().ok().map(PublicKey::Secp256k1),
+            _ => None,
+        };
+        key.ok_or(format_err!("public key is invalid"))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Signature {
+    Ed25519([u8; 64]),
+    Secp256k1([u8; 65]),
+}
+
+impl Signature {
+    fn decode(reader: &mut BorshReader) -> Result<Self, Error> {
+        match reader.u8()? {
+            0 => Ok(Signature::Ed25519(reader.fixed()?)),
+            1 => Ok(Signature::Secp256k1(reader.fixed()?)),
+            x => Err(format_err!("unsupported signature type {x}")),
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        match self {
+            Signature::Ed25519(signature) => {
+                json!(format!("ed25519:{}", bs58::encode(signature).into_string()))
+            }
+            Signature::Secp256k1(signature) => json!(format!(
+                "secp256k1:{}",
+                bs58::encode(signature).into_string()
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum AccessKeyPermission {
+    FunctionCall {
+        allowance: Option<u128>,
+        receiver_id: String,
+        method_names: Vec<String>,
+    },
+    FullAccess,
+}
+
+impl AccessKeyPermission {
+    fn decode(reader: &mut BorshReader) -> Result<Self, Error> {
+        match reader.u8()? {
+            0 => Ok(AccessKeyPermission::FunctionCall {
+                allowance: reader.option(|r| r.u128())?,
+                receiver_id: reader.string()?,
+                method_names: reader.vec(|r| r.string())?,
+            }),
+            1 => Ok(AccessKeyPermission::FullAccess),
+            x => Err(format_err!("unsupported access key permission {x}")),
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        match self {
+            AccessKeyPermission::FunctionCall {
+                allowance,
+                receiver_id,
+                method_names,
+            } => json!({
+                "function_call": {
+                    "allowance": allowance.map(|a| a.to_string()),
+                    "receiver_id": receiver_id,
+                    "method_names": method_names,
+                }
+            }),
+            AccessKeyPermission::FullAccess => json!("full_access"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Action {
+    CreateAccount,
+    DeployContract(Vec<u8>),
+    FunctionCall {
+        method_name: String,
+        args: Vec<u8>,
+        gas: u64,
+        deposit: u128,
+    },
+    Transfer(u128),
+    Stake {
+        stake: u128,
+        public_key: PublicKey,
+    },
+    AddKey {
+        public_key: PublicKey,
+        nonce: u64,
+        permission: AccessKeyPermission,
+    },
+    DeleteKey(PublicKey),
+    DeleteAccount(String),
+    Delegate(Box<DelegateAction>, Signature),
+}
+
+impl Action {
+    /// Decodes an action; delegate actions may not nest another delegate action.
+    fn decode(reader: &mut BorshReader, allow_delegate: bool) -> Result<Self, Error> {
+        match reader.u8()? {
+            0 => Ok(Action::CreateAccount),
+            1 => Ok(Action::DeployContract(reader.bytes()?)),
+            2 => Ok(Action::FunctionCall {
+                method_name: reader.string()?,
+                args: reader.bytes()?,
+                gas: reader.u64()?,
+                deposit: reader.u128()?,
+            }),
+            3 => Ok(Action::Transfer(reader.u128()?)),
+            4 => Ok(Action::Stake {
+                stake: reader.u128()?,
+                public_key: PublicKey::decode(reader)?,
+            }),
+            5 => Ok(Action::AddKey {
+                public_key: PublicKey::decode(reader)?,
+                nonce: reader.u64()?,
+                permission: AccessKeyPermission::decode(reader)?,
+            }),
+            6 => Ok(Action::DeleteKey(PublicKey::decode(reader)?)),
+            7 => Ok(Action::DeleteAccount(reader.string()?)),
+            8 if allow_delegate => Ok(Action::Delegate(
+                Box::new(DelegateAction::decode(reader)?),
+                Signature::decode(reader)?,
+            )),
+            x => Err(format_err!("unsupported action {x}")),
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        match self {
+            Action::CreateAccount => json!({"type": "create_account"}),
+            Action::DeployContract(code) => json!({
+                "type": "deploy_contract",
+                "code_length": code.len(),
+            }),
+            Action::FunctionCall {
+                method_name,
+                args,
+                gas,
+                deposit,
+            } => json!({
+                "type": "function_call",
+                "method_name": method_name,
+                "args": serde_json::from_slice::<Value>(args)
+                    .unwrap_or_else(|_| json!(format!("0x{}", hex::encode(args)))),
+                "gas": gas.to_string(),
+                "deposit": deposit.to_string(),
+            }),
+            Action::Transfer(deposit) => json!({
+                "type": "transfer",
+                "deposit": deposit.to_string(),
+            }),
+            Action::Stake { stake, public_key } => json!({
+                "type": "stake",
+                "stake": stake.to_string(),
+                "public_key": public_key.to_string(),
+            }),
+            Action::AddKey {
+                public_key,
+                nonce,
+                permission,
+            } => json!({
+                "type": "add_key",
+                "public_key": public_key.to_string(),
+                "access_key": {
+                    "nonce": nonce.to_string(),
+                    "permission": permission.to_json(),
+                },
+            }),
+            Action::DeleteKey(public_key) => json!({
+                "type": "delete_key",
+                "public_key": public_key.to_string(),
+            }),
+            Action::DeleteAccount(beneficiary_id) => json!({
+                "type": "delete_account",
+                "beneficiary_id": beneficiary_id,
+            }),
+            Action::Delegate(delegate_action, signature) => json!({
+                "type": "delegate",
+                "delegate_action": delegate_action.to_json(),
+                "signature": signature.to_json(),
+            }),
+        }
+    }
+}
+
+/// A NEP-366 meta transaction relayed on behalf of `sender_id`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DelegateAction {
+    pub sender_id: String,
+    pub receiver_id: String,
+    pub actions: Vec<Action>,
+    pub nonce: u64,
+    pub max_block_height: u64,
+    pub public_key: PublicKey,
+}
+
+impl DelegateAction {
+    fn decode(reader: &mut BorshReader) -> Result<Self, Error> {
+        Ok(DelegateAction {
+            sender_id: reader.string()?,
+            receiver_id: reader.string()?,
+            actions: reader.vec(|r| Action::decode(r, false))?,
+            nonce: reader.u64()?,
+            max_block_height: reader.u64()?,
+            public_key: PublicKey::decode(reader)?,
+        })
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "sender_id": self.sender_id,
+            "receiver_id": self.receiver_id,
+            "actions": self.actions.iter().map(Action::to_json).collect::<Vec<_>>(),
+            "nonce": self.nonce.to_string(),
+            "max_block_height": self.max_block_height.to_string(),
+            "public_key": self.public_key.to_string(),
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct NearTransaction {
+    pub signer_id: String,
+    pub public_key: PublicKey,
+    pub nonce: u64,
+    pub receiver_id: String,
+    pub block_hash: [u8; 32],
+    pub actions: Vec<Action>,
+}
+
+impl NearTransaction {
+    fn decode(reader: &mut BorshReader) -> Result<Self, Error> {
+        Ok(NearTransaction {
+            signer_id: reader.string()?,
+            public_key: PublicKey::decode(reader)?,
+            nonce: reader.u64()?,
+            receiver_id: reader.string()?,
+            block_hash: reader.fixed()?,
+            actions: reader.vec(|r| Action::decode(r, true))?,
+        })
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "signer_id": self.signer_id,
+            "public_key": self.public_key.to_string(),
+            "nonce": self.nonce.to_string(),
+            "receiver_id": self.receiver_id,
+            "block_hash": bs58::encode(self.block_hash).into_string(),
+            "actions": self.actions.iter().map(Action::to_json).collect::<Vec<_>>(),
+        })
+    }
+}
+
+/// One entry of `NearSignRequest` sign data, told apart by its leading Borsh tag.
+#[derive(Clone, Debug, PartialEq)]
+pub enum NearSignData {
+    Transaction(NearTransaction),
+    DelegateAction(DelegateAction),
+    Message(Nep413Payload),
+}
+
+impl NearSignData {
+    pub fn decode(bytes: &[u8]) -> Result<Self, Error> {
+        let tag = bytes
+            .get(..4)
+            .map(|tag| u32::from_le_bytes(tag.try_into().unwrap_or_default()));
+        if tag == Some(NEP413_TAG) {
+            return Ok(NearSignData::Message(Nep413Payload::deserialize(bytes)?));
+        }
+        let mut reader = BorshReader::new(bytes);
+        let result = match tag == Some(NEP366_TAG) {
+            true => {
+                reader.u32()?;
+                NearSignData::DelegateAction(DelegateAction::decode(&mut reader)?)
+            }
+            false => NearSignData::Transaction(NearTransaction::decode(&mut reader)?),
+        };
+        reader.finish()?;
+        Ok(result)
+    }
+
+    /// The account that authorizes this entry, if it names one.
+    pub fn get_signer_id(&self) -> Option<String> {
+        match self {
+            NearSignData::Transaction(transaction) => Some(transaction.signer_id.clone()),
+            NearSignData::DelegateAction(delegate_action) => {
+                Some(delegate_action.sender_id.clone())
+            }
+            NearSignData::Message(_) => None,
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        let (data_type, mut result) = match self {
+            NearSignData::Transaction(transaction) => ("transaction", transaction.to_json()),
+            NearSignData::DelegateAction(delegate_action) => {
+                ("delegate_action", delegate_action.to_json())
+            }
+            NearSignData::Message(payload) => ("nep413_message", payload.to_json()),
+        };
+        result["type"] = json!(data_type);
+        result
+    }
+}
+
+/// Decodes every sign data entry and checks that the request account, when present,
+/// is the signer of each transaction and delegate action.
+pub fn decode_near_sign_data(request: &NearSignRequest) -> Result<Vec<NearSignData>, Error> {
+    let account = match request.get_account() {
+        Some(account) => {
+            Some(String::from_utf8(account).map_err(|_| format_err!("account is invalid"))?)
+        }
+        None => None,
+    };
+    request
+        .get_sign_data()
+        .iter()
+        .map(|data| {
+            let sign_data = NearSignData::decode(data)?;
+            if let (Some(account), Some(signer_id)) = (&account, sign_data.get_signer_id()) {
+                if *account != signer_id {
+                    return Err(format_err!("request account does not match the signer"));
+                }
+            }
+            Ok(sign_data)
+        })
+        .collect()
+}
+
+export! {
+    @Java_com_keystone_sdk_KeystoneNativeSDK_decodeNearSignRequest
+    fn decode_near_sign_request(ur_type: &str, cbor_hex: &str) -> String {
+        if NEAR_SIGN_REQUEST.get_type() != ur_type {
+            return json!({"error": "type not match"}).to_string();
+        }
+
+        let decode = || -> Result<Vec<NearSignData>, Error> {
+            let cbor = hex::decode(cbor_hex)?;
+            let request = NearSignRequest::try_from(cbor).map_err(|e| format_err!(e.to_string()))?;
+            decode_near_sign_data(&request)
+        };
+        match decode() {
+            Ok(sign_data) => json!({
+                "sign_data": sign_data.iter().map(NearSignData::to_json).collect::<Vec<_>>(),
+            }).to_string(),
+            Err(e) => json!({"error": e.to_string()}).to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util_internal::borsh::write_string;
+    use ur_registry::crypto_key_path::CryptoKeyPath;
+
+    const TRANSFER: &str = "4000000039666363303732306130313664336331653834396438366231366437313339653034336566633438616464316337386633396333643266303065653938633037009FCC0720A016D3C1E849D86B16D7139E043EFC48ADD1C78F39C3D2F00EE98C07823E0CA1957100004000000039666363303732306130313664336331653834396438366231366437313339653034336566633438616464316337386633396333643266303065653938633037F0787E1CB1C22A1C63C24A37E4C6C656DD3CB049E6B7C17F75D01F0859EFB7D80100000003000000A1EDCCCE1BC2D3000000000000";
+    const SIGNER: &str = "9fcc0720a016d3c1e849d86b16d7139e043efc48add1c78f39c3d2f00ee98c07";
+
+    fn sign_request(sign_data: Vec<Vec<u8>>, account: Option<&str>) -> String {
+        let path = CryptoKeyPath::from_path("m/44'/397'/0'".to_string(), None).unwrap();
+        let request = NearSignRequest::new(
+            None,
+            sign_data,
+            path,
+            account.map(|a| a.as_bytes().to_vec()),
+            None,
+        );
+        let cbor: Vec<u8> = request.try_into().unwrap();
+        hex::encode(cbor)
+    }
+
+    fn ed25519_key(output: &mut Vec<u8>, byte: u8) {
+        output.push(0);
+        output.extend_from_slice(&[byte; 32]);
+    }
+
+    #[test]
+    fn test_decode_near_transfer() {
+        let result: Value = serde_json::from_str(&decode_near_sign_request(
+            "near-sign-request",
+            &sign_request(vec![hex::decode(TRANSFER).unwrap()], Some(SIGNER)),
+        ))
+        .unwrap();
+        let transaction = &result["sign_data"][0];
+        assert_eq!("transaction", transaction["type"]);
+        assert_eq!(SIGNER, transaction["signer_id"]);
+        assert_eq!(SIGNER, transaction["receiver_id"]);
+        assert_eq!("124887466000002", transaction["nonce"]);
+        assert_eq!(
+            "ed25519:BknGVsConk1ZKeJNoeCnt3BbJpbZ6SfT7gK8ztC4BHDp",
+            transaction["public_key"]
+        );
+        assert_eq!(
+            json!([{"type": "transfer", "deposit": "1000000000000000000000000"}]),
+            transaction["actions"]
+        );
+    }
+
+    #[test]
+    fn test_decode_near_actions() {
+        let mut data = vec![];
+        write_string(&mut data, "alice.near");
+        ed25519_key(&mut data, 1);
+        data.extend_from_slice(&5u64.to_le_bytes());
+        write_string(&mut data, "wrap.near");
+        data.extend_from_slice(&[2u8; 32]);
+        data.extend_from_slice(&4u32.to_le_bytes());
+        // ft_transfer with JSON args
+        data.push(2);
+        write_string(&mut data, "ft_transfer");
+        write_string(&mut data, r#"{"receiver_id":"bob.near","amount":"100"}"#);
+        data.extend_from_slice(&30_000_000_000_000u64.to_le_bytes());
+        data.extend_from_slice(&1u128.to_le_bytes());
+        // AddKey with a function call access key
+        data.push(5);
+        ed25519_key(&mut data, 3);
+        data.extend_from_slice(&0u64.to_le_bytes());
+        data.extend_from_slice(&[0, 1]);
+        data.extend_from_slice(&250_000_000_000_000_000_000_000u128.to_le_bytes());
+        write_string(&mut data, "app.near");
+        data.extend_from_slice(&1u32.to_le_bytes());
+        write_string(&mut data, "vote");
+        // DeleteKey and DeleteAccount
+        data.push(6);
+        ed25519_key(&mut data, 3);
+        data.push(7);
+        write_string(&mut data, "bob.near");
+
+        let result: Value = serde_json::from_str(&decode_near_sign_request(
+            "near-sign-request",
+            &sign_request(vec![data], Some("alice.near")),
+        ))
+        .unwrap();
+        let actions = &result["sign_data"][0]["actions"];
+        assert_eq!(
+            json!({
+                "type": "function_call",
+                "method_name": "ft_transfer",
+                "args": {"receiver_id": "bob.near", "amount": "100"},
+                "gas": "30000000000000",
+                "deposit": "1",
+            }),
+            actions[0]
+        );
+        assert_eq!(
+            json!({
+                "function_call": {
+                    "allowance": "250000000000000000000000",
+                    "receiver_id": "app.near",
+                    "method_names": ["vote"],
+                }
+            }),
+            actions[1]["access_key"]["permission"]
+        );
+        assert_eq!("delete_key", actions[2]["type"]);
+        assert_eq!("bob.near", actions[3]["beneficiary_id"]);
+    }
+
+    #[test]
+    fn test_decode_near_delegate_action() {
+        let mut delegate_action = vec![];
+        write_string(&mut delegate_action, "alice.near");
+        write_string(&mut delegate_action, "bob.near");
+        delegate_action.extend_from_slice(&2u32.to_le_bytes());
+        delegate_action.push(0);
+        delegate_action.push(4);
+        delegate_action.extend_from_slice(&7u128.to_le_bytes());
+        ed25519_key(&mut delegate_action, 4);
+        delegate_action.extend_from_slice(&9u64.to_le_bytes());
+        delegate_action.extend_from_slice(&1000u64.to_le_bytes());
+        ed25519_key(&mut delegate_action, 1);
+
+        let sign_data = [NEP366_TAG.to_le_bytes().as_slice(), &delegate_action].concat();
+        let result = NearSignData::decode(&sign_data).unwrap().to_json();
+        assert_eq!("delegate_action", result["type"]);
+        assert_eq!("alice.near", result["sender_id"]);
+        assert_eq!("1000", result["max_block_height"]);
+        assert_eq!(json!({"type": "create_account"}), result["actions"][0]);
+        assert_eq!("7", result["actions"][1]["stake"]);
+
+        // A relayer transaction carrying the signed delegate action.
+        let mut transaction = vec![];
+        write_string(&mut transaction, "relayer.near");
+        ed25519_key(&mut transaction, 5);
+        transaction.extend_from_slice(&1u64.to_le_bytes());
+        write_string(&mut transaction, "alice.near");
+        transaction.extend_from_slice(&[0u8; 32]);
+        transaction.extend_from_slice(&1u32.to_le_bytes());
+        transaction.push(8);
+        transaction.extend_from_slice(&delegate_action);
+        transaction.push(0);
+        transaction.extend_from_slice(&[6u8; 64]);
+        let result = NearSignData::decode(&transaction).unwrap().to_json();
+        assert_eq!("delegate", result["actions"][0]["type"]);
+        assert_eq!(
+            "bob.near",
+            result["actions"][0]["delegate_action"]["receiver_id"]
+        );
+
+        // Delegate actions may not nest another delegate action.
+        let mut nested = sign_data.clone();
+        let position = 4 + 4 + 10 + 4 + 8 + 4;
+        nested[position] = 8;
+        assert_eq!(
+            "unsupported action 8",
+            NearSignData::decode(&nested).unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn test_decode_near_sign_request_error() {
+        let transfer = hex::decode(TRANSFER).unwrap();
+        assert_eq!(
+            "{\"error\":\"request account does not match the signer\"}",
+            decode_near_sign_request(
+                "near-sign-request",
+                &sign_request(vec![transfer.clone()], Some("alice.near"))
+            )
+        );
+        assert_eq!(
+            "{\"error\":\"type not match\"}",
+            decode_near_sign_request(
+                "near-signature",
+                &sign_request(vec![transfer.clone()], None)
+            )
+        );
+        assert_eq!(
+            "unexpected trailing bytes",
+            NearSignData::decode(&[transfer.as_slice(), &[0]].concat())
+                .unwrap_err()
+                .to_string()
+        );
+        let key = PublicKey::Ed25519([1u8; 32]);
+        assert_eq!(key, key.to_string().parse().unwrap());
+        assert!("ed25519:0OIl".parse::<PublicKey>().is_err());
+    }
+}
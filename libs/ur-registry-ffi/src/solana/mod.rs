@@ -1,2 +1,4 @@
+pub mod sol_message;
+pub mod sol_offchain_message;
 pub mod sol_sign_request;
 pub mod sol_signature;
@@ -0,0 +1,1041 @@
+use anyhow::{format_err, Error};
+use serde_json::{json, Map, Value};
+use ur_registry::registry_types::SOL_SIGN_REQUEST;
+use ur_registry::solana::sol_sign_request::{SignType, SolSignRequest};
+
+use crate::export;
+
+pub const SYSTEM_PROGRAM_ID: &str = "11111111111111111111111111111111";
+pub const TOKEN_PROGRAM_ID: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+pub const TOKEN_2022_PROGRAM_ID: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
+pub const ASSOCIATED_TOKEN_PROGRAM_ID: &str = "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL";
+pub const COMPUTE_BUDGET_PROGRAM_ID: &str = "ComputeBudget111111111111111111111111111111";
+pub const STAKE_PROGRAM_ID: &str = "Stake11111111111111111111111111111111111111";
+pub const MEMO_PROGRAM_ID: &str = "MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr";
+
+const VERSION_PREFIX_MASK: u8 = 0x80;
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Reader { bytes }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        if self.bytes.len() < len {
+            return Err(format_err!("unexpected end of data"));
+        }
+        let (head, tail) = self.bytes.split_at(len);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    fn u8(&mut self) -> Result<u8, Error> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, Error> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into()?))
+    }
+
+    fn u64(&mut self) -> Result<u64, Error> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into()?))
+    }
+
+    fn i64(&mut self) -> Result<i64, Error> {
+        Ok(i64::from_le_bytes(self.take(8)?.try_into()?))
+    }
+
+    fn pubkey(&mut self) -> Result<[u8; 32], Error> {
+        Ok(self.take(32)?.try_into()?)
+    }
+
+    /// Reads the compact-u16 length prefix used by Solana's short vectors.
+    fn short_vec_len(&mut self) -> Result<usize, Error> {
+        let mut len = 0usize;
+        for index in 0..3 {
+            let byte = self.u8()?;
+            len |= ((byte & 0x7f) as usize) << (index * 7);
+            if byte & 0x80 == 0 {
+                return Ok(len);
+            }
+        }
+        Err(format_err!("invalid compact-u16 length"))
+    }
+
+    fn short_vec_bytes(&mut self) -> Result<Vec<u8>, Error> {
+        let len = self.short_vec_len()?;
+        Ok(self.take(len)?.to_vec())
+    }
+
+    /// Reads a bincode `String`, prefixed by a u64 length.
+    fn string(&mut self) -> Result<String, Error> {
+        let len = usize::try_from(self.u64()?)?;
+        Ok(String::from_utf8(self.take(len)?.to_vec())?)
+    }
+
+    /// Reads an SPL Token `COption<Pubkey>`, a one byte tag followed by the key when set.
+    fn optional_pubkey(&mut self) -> Result<Option<[u8; 32]>, Error> {
+        match self.u8()? {
+            0 => Ok(None),
+            1 => Ok(Some(self.pubkey()?)),
+            _ => Err(format_err!("invalid optional pubkey")),
+        }
+    }
+}
+
+fn encode_pubkey(key: &[u8]) -> String {
+    bs58::encode(key).into_string()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SolMessageVersion {
+    Legacy,
+    V0,
+}
+
+impl SolMessageVersion {
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            SolMessageVersion::Legacy => "legacy",
+            SolMessageVersion::V0 => "v0",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MessageHeader {
+    pub num_required_signatures: u8,
+    pub num_readonly_signed_accounts: u8,
+    pub num_readonly_unsigned_accounts: u8,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CompiledInstruction {
+    pub program_id_index: u8,
+    pub accounts: Vec<u8>,
+    pub data: Vec<u8>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AddressTableLookup {
+    pub account_key: [u8; 32],
+    pub writable_indexes: Vec<u8>,
+    pub readonly_indexes: Vec<u8>,
+}
+
+/// An account referenced by an instruction. v0 messages may load accounts from address
+/// lookup tables, whose contents are not part of the message.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SolAccount {
+    Static([u8; 32]),
+    Lookup { table: [u8; 32], index: u8 },
+}
+
+impl SolAccount {
+    pub fn to_json(&self) -> Value {
+        match self {
+            SolAccount::Static(key) => json!(encode_pubkey(key)),
+            SolAccount::Lookup { table, index } => json!({
+                "lookup_table": encode_pubkey(table),
+                "index": index,
+            }),
+        }
+    }
+}
+
+/// An instruction decoded against one of the known programs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParsedInstruction {
+    pub program: &'static str,
+    pub program_id: Value,
+    pub name: String,
+    pub info: Map<String, Value>,
+}
+
+impl ParsedInstruction {
+    pub fn to_json(&self) -> Value {
+        json!({
+            "program": self.program,
+            "program_id": self.program_id,
+            "type": self.name,
+            "info": self.info,
+        })
+    }
+}
+
+/// The serialized message carried in `SolSignRequest.sign_data` for transactions.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SolMessage {
+    version: SolMessageVersion,
+    header: MessageHeader,
+    account_keys: Vec<[u8; 32]>,
+    recent_blockhash: [u8; 32],
+    instructions: Vec<CompiledInstruction>,
+    address_table_lookups: Vec<AddressTableLookup>,
+}
+
+impl SolMessage {
+    /// Decodes a legacy message, or a versioned message when the first byte has the
+    /// version prefix bit set.
+    pub fn decode(bytes: &[u8]) -> Result<Self, Error> {
+        let mut reader = Reader::new(bytes);
+        let first = *bytes.first().ok_or(format_err!("empty message"))?;
+        let version = if first & VERSION_PREFIX_MASK != 0 {
+            reader.u8()?;
+            match first & !VERSION_PREFIX_MASK {
+                0 => SolMessageVersion::V0,
+                x => return Err(format_err!("unsupported message version {x}")),
+            }
+        } else {
+            SolMessageVersion::Legacy
+        };
+        let header = MessageHeader {
+            num_required_signatures: reader.u8()?,
+            num_readonly_signed_accounts: reader.u8()?,
+            num_readonly_unsigned_accounts: reader.u8()?,
+        };
+        let account_keys = (0..reader.short_vec_len()?)
+            .map(|_| reader.pubkey())
+            .collect::<Result<Vec<_>, _>>()?;
+        let recent_blockhash = reader.pubkey()?;
+        let instructions = (0..reader.short_vec_len()?)
+            .map(|_| {
+                Ok(CompiledInstruction {
+                    program_id_index: reader.u8()?,
+                    accounts: reader.short_vec_bytes()?,
+                    data: reader.short_vec_bytes()?,
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        let address_table_lookups = match version {
+            SolMessageVersion::Legacy => vec![],
+            SolMessageVersion::V0 => (0..reader.short_vec_len()?)
+                .map(|_| {
+                    Ok(AddressTableLookup {
+                        account_key: reader.pubkey()?,
+                        writable_indexes: reader.short_vec_bytes()?,
+                        readonly_indexes: reader.short_vec_bytes()?,
+                    })
+                })
+                .collect::<Result<Vec<_>, Error>>()?,
+        };
+        if !reader.is_empty() {
+            return Err(format_err!("unexpected trailing bytes"));
+        }
+
+        let message = SolMessage {
+            version,
+            header,
+            account_keys,
+            recent_blockhash,
+            instructions,
+            address_table_lookups,
+        };
+        message.validate()?;
+        Ok(message)
+    }
+
+    /// Decodes the request payload and checks that the request address, when present,
+    /// is one of the message signers.
+    pub fn from_sign_request(request: &SolSignRequest) -> Result<Self, Error> {
+        if request.get_sign_type() != SignType::Transaction {
+            return Err(format_err!("sign type is not a transaction"));
+        }
+        let message = Self::decode(&request.get_sign_data())?;
+        if let Some(address) = request.get_address() {
+            let address = match address.len() {
+                32 => address,
+                _ => bs58::decode(&address)
+                    .into_vec()
+                    .map_err(|_| format_err!("address is invalid"))?,
+            };
+            let signers = &message.account_keys[..message.header.num_required_signatures as usize];
+            if !signers.iter().any(|signer| signer[..] == address[..]) {
+                return Err(format_err!("address is not a signer of the message"));
+            }
+        }
+        Ok(message)
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        let header = &self.header;
+        let static_count = self.account_keys.len();
+        if header.num_required_signatures == 0
+            || header.num_required_signatures as usize > static_count
+            || header.num_readonly_signed_accounts >= header.num_required_signatures
+            || header.num_readonly_unsigned_accounts as usize
+                > static_count - header.num_required_signatures as usize
+        {
+            return Err(format_err!("invalid message header"));
+        }
+        let total = self.get_accounts().len();
+        for instruction in &self.instructions {
+            let program_id_index = instruction.program_id_index as usize;
+            // Program ids cannot be loaded from lookup tables.
+            if program_id_index == 0 || program_id_index >= static_count {
+                return Err(format_err!("invalid program id index"));
+            }
+            if instruction
+                .accounts
+                .iter()
+                .any(|index| *index as usize >= total)
+            {
+                return Err(format_err!("invalid account index"));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn get_version(&self) -> SolMessageVersion {
+        self.version
+    }
+
+    pub fn get_header(&self) -> MessageHeader {
+        self.header
+    }
+
+    pub fn get_account_keys(&self) -> Vec<[u8; 32]> {
+        self.account_keys.clone()
+    }
+
+    pub fn get_recent_blockhash(&self) -> [u8; 32] {
+        self.recent_blockhash
+    }
+
+    pub fn get_instructions(&self) -> Vec<CompiledInstruction> {
+        self.instructions.clone()
+    }
+
+    pub fn get_address_table_lookups(&self) -> Vec<AddressTableLookup> {
+        self.address_table_lookups.clone()
+    }
+
+    pub fn get_fee_payer(&self) -> [u8; 32] {
+        self.account_keys[0]
+    }
+
+    /// All accounts in message order: the static keys, then the writable and finally the
+    /// readonly addresses loaded from lookup tables.
+    pub fn get_accounts(&self) -> Vec<SolAccount> {
+        let mut accounts: Vec<SolAccount> = self
+            .account_keys
+            .iter()
+            .map(|key| SolAccount::Static(*key))
+            .collect();
+        let lookups = |writable: bool| {
+            self.address_table_lookups.iter().flat_map(move |lookup| {
+                let indexes = match writable {
+                    true => &lookup.writable_indexes,
+                    false => &lookup.readonly_indexes,
+                };
+                indexes.iter().map(|index| SolAccount::Lookup {
+                    table: lookup.account_key,
+                    index: *index,
+                })
+            })
+        };
+        accounts.extend(lookups(true));
+        accounts.extend(lookups(false));
+        accounts
+    }
+
+    pub fn parse_instructions(&self) -> Result<Vec<ParsedInstruction>, Error> {
+        let accounts = self.get_accounts();
+        self.instructions
+            .iter()
+            .map(|instruction| {
+                let program_id = self.account_keys[instruction.program_id_index as usize];
+                let instruction_accounts: Vec<Value> = instruction
+                    .accounts
+                    .iter()
+                    .map(|index| accounts[*index as usize].to_json())
+                    .collect();
+                parse_instruction(&program_id, &instruction_accounts, &instruction.data)
+            })
+            .collect()
+    }
+
+    pub fn to_json(&self) -> Result<Value, Error> {
+        let instructions = self
+            .parse_instructions()?
+            .iter()
+            .map(ParsedInstruction::to_json)
+            .collect::<Vec<_>>();
+        let mut result = json!({
+            "version": self.version.get_name(),
+            "header": {
+                "num_required_signatures": self.header.num_required_signatures,
+                "num_readonly_signed_accounts": self.header.num_readonly_signed_accounts,
+                "num_readonly_unsigned_accounts": self.header.num_readonly_unsigned_accounts,
+            },
+            "fee_payer": encode_pubkey(&self.get_fee_payer()),
+            "account_keys": self.account_keys.iter().map(|key| encode_pubkey(key)).collect::<Vec<_>>(),
+            "recent_blockhash": encode_pubkey(&self.recent_blockhash),
+            "instructions": instructions,
+        });
+        if self.version == SolMessageVersion::V0 {
+            result["address_table_lookups"] = self
+                .address_table_lookups
+                .iter()
+                .map(|lookup| {
+                    json!({
+                        "account_key": encode_pubkey(&lookup.account_key),
+                        "writable_indexes": lookup.writable_indexes,
+                        "readonly_indexes": lookup.readonly_indexes,
+                    })
+                })
+                .collect();
+        }
+        Ok(result)
+    }
+}
+
+struct InstructionBuilder<'a> {
+    accounts: &'a [Value],
+    info: Map<String, Value>,
+}
+
+impl InstructionBuilder<'_> {
+    fn accounts(mut self, names: &[&str]) -> Result<Self, Error> {
+        if self.accounts.len() < names.len() {
+            return Err(format_err!("missing instruction accounts"));
+        }
+        for (name, account) in names.iter().zip(self.accounts) {
+            self.info.insert(name.to_string(), account.clone());
+        }
+        Ok(self)
+    }
+
+    fn field(mut self, name: &str, value: Value) -> Self {
+        self.info.insert(name.to_string(), value);
+        self
+    }
+}
+
+fn parse_instruction(
+    program_id: &[u8; 32],
+    accounts: &[Value],
+    data: &[u8],
+) -> Result<ParsedInstruction, Error> {
+    let program_id = encode_pubkey(program_id);
+    let builder = InstructionBuilder {
+        accounts,
+        info: Map::new(),
+    };
+    let mut reader = Reader::new(data);
+    let (program, name, builder) = match program_id.as_str() {
+        SYSTEM_PROGRAM_ID => {
+            let (name, builder) = parse_system_instruction(&mut reader, builder)?;
+            ("system", name, builder)
+        }
+        TOKEN_PROGRAM_ID => {
+            let (name, builder) = parse_token_instruction(&mut reader, builder)?;
+            ("spl-token", name, builder)
+        }
+        TOKEN_2022_PROGRAM_ID => {
+            let (name, builder) = parse_token_instruction(&mut reader, builder)?;
+            ("spl-token-2022", name, builder)
+        }
+        ASSOCIATED_TOKEN_PROGRAM_ID => {
+            let names = [
+                "source",
+                "account",
+                "wallet",
+                "mint",
+                "system_program",
+                "token_program",
+            ];
+            let name = match reader.is_empty() {
+                true => "create",
+                false => match reader.u8()? {
+                    0 => "create",
+                    1 => "create_idempotent",
+                    2 => "recover_nested",
+                    _ => "unknown",
+                },
+            };
+            let builder = match name {
+                "create" | "create_idempotent" => builder.accounts(&names)?,
+                _ => builder,
+            };
+            ("spl-associated-token-account", name, builder)
+        }
+        COMPUTE_BUDGET_PROGRAM_ID => {
+            let (name, builder) = match reader.u8()? {
+                0 => (
+                    "request_units",
+                    builder
+                        .field("units", json!(reader.u32()?))
+                        .field("additional_fee", json!(reader.u32()?)),
+                ),
+                1 => (
+                    "request_heap_frame",
+                    builder.field("bytes", json!(reader.u32()?)),
+                ),
+                2 => (
+                    "set_compute_unit_limit",
+                    builder.field("units", json!(reader.u32()?)),
+                ),
+                3 => (
+                    "set_compute_unit_price",
+                    builder.field("micro_lamports", json!(reader.u64()?.to_string())),
+                ),
+                4 => (
+                    "set_loaded_accounts_data_size_limit",
+                    builder.field("bytes", json!(reader.u32()?)),
+                ),
+                _ => ("unknown", builder),
+            };
+            ("compute-budget", name, builder)
+        }
+        STAKE_PROGRAM_ID => {
+            let (name, builder) = parse_stake_instruction(&mut reader, builder)?;
+            ("stake", name, builder)
+        }
+        MEMO_PROGRAM_ID => {
+            let memo = String::from_utf8(data.to_vec()).map_err(|_| format_err!("invalid memo"))?;
+            return Ok(ParsedInstruction {
+                program: "spl-memo",
+                program_id: json!(program_id),
+                name: "memo".to_string(),
+                info: builder.field("memo", json!(memo)).info,
+            });
+        }
+        _ => {
+            return Ok(ParsedInstruction {
+                program: "unknown",
+                program_id: json!(program_id),
+                name: "unknown".to_string(),
+                info: builder
+                    .field("accounts", json!(accounts))
+                    .field("data", json!(hex::encode(data)))
+                    .info,
+            });
+        }
+    };
+    let builder = match name {
+        "unknown" => builder.field("data", json!(hex::encode(data))),
+        _ => builder,
+    };
+    Ok(ParsedInstruction {
+        program,
+        program_id: json!(program_id),
+        name: name.to_string(),
+        info: builder.info,
+    })
+}
+
+fn parse_system_instruction<'a>(
+    reader: &mut Reader,
+    builder: InstructionBuilder<'a>,
+) -> Result<(&'static str, InstructionBuilder<'a>), Error> {
+    let result = match reader.u32()? {
+        0 => (
+            "create_account",
+            builder
+                .accounts(&["source", "new_account"])?
+                .field("lamports", json!(reader.u64()?.to_string()))
+                .field("space", json!(reader.u64()?))
+                .field("owner", json!(encode_pubkey(&reader.pubkey()?))),
+        ),
+        1 => (
+            "assign",
+            builder
+                .accounts(&["account"])?
+                .field("owner", json!(encode_pubkey(&reader.pubkey()?))),
+        ),
+        2 => (
+            "transfer",
+            builder
+                .accounts(&["source", "destination"])?
+                .field("lamports", json!(reader.u64()?.to_string())),
+        ),
+        3 => (
+            "create_account_with_seed",
+            builder
+                .accounts(&["source", "new_account"])?
+                .field("base", json!(encode_pubkey(&reader.pubkey()?)))
+                .field("seed", json!(reader.string()?))
+                .field("lamports", json!(reader.u64()?.to_string()))
+                .field("space", json!(reader.u64()?))
+                .field("owner", json!(encode_pubkey(&reader.pubkey()?))),
+        ),
+        4 => (
+            "advance_nonce",
+            builder.accounts(&[
+                "nonce_account",
+                "recent_blockhashes_sysvar",
+                "nonce_authority",
+            ])?,
+        ),
+        5 => (
+            "withdraw_from_nonce",
+            builder
+                .accounts(&[
+                    "nonce_account",
+                    "destination",
+                    "recent_blockhashes_sysvar",
+                    "rent_sysvar",
+                    "nonce_authority",
+                ])?
+                .field("lamports", json!(reader.u64()?.to_string())),
+        ),
+        6 => (
+            "initialize_nonce",
+            builder
+                .accounts(&["nonce_account", "recent_blockhashes_sysvar", "rent_sysvar"])?
+                .field("nonce_authority", json!(encode_pubkey(&reader.pubkey()?))),
+        ),
+        7 => (
+            "authorize_nonce",
+            builder
+                .accounts(&["nonce_account", "nonce_authority"])?
+                .field("new_authorized", json!(encode_pubkey(&reader.pubkey()?))),
+        ),
+        8 => (
+            "allocate",
+            builder
+                .accounts(&["account"])?
+                .field("space", json!(reader.u64()?)),
+        ),
+        9 => (
+            "allocate_with_seed",
+            builder
+                .accounts(&["account"])?
+                .field("base", json!(encode_pubkey(&reader.pubkey()?)))
+                .field("seed", json!(reader.string()?))
+                .field("space", json!(reader.u64()?))
+                .field("owner", json!(encode_pubkey(&reader.pubkey()?))),
+        ),
+        10 => (
+            "assign_with_seed",
+            builder
+                .accounts(&["account"])?
+                .field("base", json!(encode_pubkey(&reader.pubkey()?)))
+                .field("seed", json!(reader.string()?))
+                .field("owner", json!(encode_pubkey(&reader.pubkey()?))),
+        ),
+        11 => (
+            "transfer_with_seed",
+            builder
+                .accounts(&["source", "source_base", "destination"])?
+                .field("lamports", json!(reader.u64()?.to_string()))
+                .field("source_seed", json!(reader.string()?))
+                .field("source_owner", json!(encode_pubkey(&reader.pubkey()?))),
+        ),
+        12 => ("upgrade_nonce", builder.accounts(&["nonce_account"])?),
+        _ => ("unknown", builder),
+    };
+    Ok(result)
+}
+
+fn parse_token_instruction<'a>(
+    reader: &mut Reader,
+    builder: InstructionBuilder<'a>,
+) -> Result<(&'static str, InstructionBuilder<'a>), Error> {
+    let optional_pubkey = |key: Option<[u8; 32]>| json!(key.map(|key| encode_pubkey(&key)));
+    let result = match reader.u8()? {
+        x @ (0 | 20) => {
+            let (name, accounts): (_, &[&str]) = match x {
+                0 => ("initialize_mint", &["mint", "rent_sysvar"]),
+                _ => ("initialize_mint2", &["mint"]),
+            };
+            (
+                name,
+                builder
+                    .accounts(accounts)?
+                    .field("decimals", json!(reader.u8()?))
+                    .field("mint_authority", json!(encode_pubkey(&reader.pubkey()?)))
+                    .field(
+                        "freeze_authority",
+                        optional_pubkey(reader.optional_pubkey()?),
+                    ),
+            )
+        }
+        1 => (
+            "initialize_account",
+            builder.accounts(&["account", "mint", "owner", "rent_sysvar"])?,
+        ),
+        2 => (
+            "initialize_multisig",
+            builder
+                .accounts(&["multisig"])?
+                .field("m", json!(reader.u8()?)),
+        ),
+        3 => (
+            "transfer",
+            builder
+                .accounts(&["source", "destination", "authority"])?
+                .field("amount", json!(reader.u64()?.to_string())),
+        ),
+        4 => (
+            "approve",
+            builder
+                .accounts(&["source", "delegate", "owner"])?
+                .field("amount", json!(reader.u64()?.to_string())),
+        ),
+        5 => ("revoke", builder.accounts(&["source", "owner"])?),
+        6 => {
+            let authority_type = match reader.u8()? {
+                0 => "mint_tokens",
+                1 => "freeze_account",
+                2 => "account_owner",
+                3 => "close_account",
+                _ => "unknown",
+            };
+            (
+                "set_authority",
+                builder
+                    .accounts(&["account", "authority"])?
+                    .field("authority_type", json!(authority_type))
+                    .field("new_authority", optional_pubkey(reader.optional_pubkey()?)),
+            )
+        }
+        7 => (
+            "mint_to",
+            builder
+                .accounts(&["mint", "account", "mint_authority"])?
+                .field("amount", json!(reader.u64()?.to_string())),
+        ),
+        8 => (
+            "burn",
+            builder
+                .accounts(&["account", "mint", "authority"])?
+                .field("amount", json!(reader.u64()?.to_string())),
+        ),
+        9 => (
+            "close_account",
+            builder.accounts(&["account", "destination", "owner"])?,
+        ),
+        10 => (
+            "freeze_account",
+            builder.accounts(&["account", "mint", "freeze_authority"])?,
+        ),
+        11 => (
+            "thaw_account",
+            builder.accounts(&["account", "mint", "freeze_authority"])?,
+        ),
+        12 => (
+            "transfer_checked",
+            builder
+                .accounts(&["source", "mint", "destination", "authority"])?
+                .field("amount", json!(reader.u64()?.to_string()))
+                .field("decimals", json!(reader.u8()?)),
+        ),
+        13 => (
+            "approve_checked",
+            builder
+                .accounts(&["source", "mint", "delegate", "owner"])?
+                .field("amount", json!(reader.u64()?.to_string()))
+                .field("decimals", json!(reader.u8()?)),
+        ),
+        14 => (
+            "mint_to_checked",
+            builder
+                .accounts(&["mint", "account", "mint_authority"])?
+                .field("amount", json!(reader.u64()?.to_string()))
+                .field("decimals", json!(reader.u8()?)),
+        ),
+        15 => (
+            "burn_checked",
+            builder
+                .accounts(&["account", "mint", "authority"])?
+                .field("amount", json!(reader.u64()?.to_string()))
+                .field("decimals", json!(reader.u8()?)),
+        ),
+        16 => (
+            "initialize_account2",
+            builder
+                .accounts(&["account", "mint", "rent_sysvar"])?
+                .field("owner", json!(encode_pubkey(&reader.pubkey()?))),
+        ),
+        17 => ("sync_native", builder.accounts(&["account"])?),
+        18 => (
+            "initialize_account3",
+            builder
+                .accounts(&["account", "mint"])?
+                .field("owner", json!(encode_pubkey(&reader.pubkey()?))),
+        ),
+        19 => (
+            "initialize_multisig2",
+            builder
+                .accounts(&["multisig"])?
+                .field("m", json!(reader.u8()?)),
+        ),
+        _ => ("unknown", builder),
+    };
+    Ok(result)
+}
+
+fn parse_stake_instruction<'a>(
+    reader: &mut Reader,
+    builder: InstructionBuilder<'a>,
+) -> Result<(&'static str, InstructionBuilder<'a>), Error> {
+    let result = match reader.u32()? {
+        0 => (
+            "initialize",
+            builder
+                .accounts(&["stake_account", "rent_sysvar"])?
+                .field("staker", json!(encode_pubkey(&reader.pubkey()?)))
+                .field("withdrawer", json!(encode_pubkey(&reader.pubkey()?)))
+                .field("lockup_unix_timestamp", json!(reader.i64()?))
+                .field("lockup_epoch", json!(reader.u64()?))
+                .field("lockup_custodian", json!(encode_pubkey(&reader.pubkey()?))),
+        ),
+        1 => {
+            let new_authority = encode_pubkey(&reader.pubkey()?);
+            let authority_type = match reader.u32()? {
+                0 => "staker",
+                1 => "withdrawer",
+                _ => return Err(format_err!("invalid stake authority type")),
+            };
+            (
+                "authorize",
+                builder
+                    .accounts(&["stake_account", "clock_sysvar", "authority"])?
+                    .field("new_authority", json!(new_authority))
+                    .field("authority_type", json!(authority_type)),
+            )
+        }
+        2 => (
+            "delegate",
+            builder.accounts(&[
+                "stake_account",
+                "vote_account",
+                "clock_sysvar",
+                "stake_history_sysvar",
+                "stake_config",
+                "stake_authority",
+            ])?,
+        ),
+        3 => (
+            "split",
+            builder
+                .accounts(&["stake_account", "new_split_account", "stake_authority"])?
+                .field("lamports", json!(reader.u64()?.to_string())),
+        ),
+        4 => (
+            "withdraw",
+            builder
+                .accounts(&[
+                    "stake_account",
+                    "destination",
+                    "clock_sysvar",
+                    "stake_history_sysvar",
+                    "withdraw_authority",
+                ])?
+                .field("lamports", json!(reader.u64()?.to_string())),
+        ),
+        5 => (
+            "deactivate",
+            builder.accounts(&["stake_account", "clock_sysvar", "stake_authority"])?,
+        ),
+        7 => (
+            "merge",
+            builder.accounts(&[
+                "destination",
+                "source",
+                "clock_sysvar",
+                "stake_history_sysvar",
+                "stake_authority",
+            ])?,
+        ),
+        16 => (
+            "move_stake",
+            builder
+                .accounts(&["source", "destination", "stake_authority"])?
+                .field("lamports", json!(reader.u64()?.to_string())),
+        ),
+        17 => (
+            "move_lamports",
+            builder
+                .accounts(&["source", "destination", "stake_authority"])?
+                .field("lamports", json!(reader.u64()?.to_string())),
+        ),
+        _ => ("unknown", builder),
+    };
+    Ok(result)
+}
+
+export! {
+    @Java_com_keystone_sdk_KeystoneNativeSDK_decodeSolSignRequest
+    fn decode_sol_sign_request(ur_type: &str, cbor_hex: &str) -> String {
+        if SOL_SIGN_REQUEST.get_type() != ur_type {
+            return json!({"error": "type not match"}).to_string();
+        }
+
+        let decode = || -> Result<Value, Error> {
+            let cbor = hex::decode(cbor_hex)?;
+            let request = SolSignRequest::try_from(cbor).map_err(|e| format_err!(e.to_string()))?;
+            SolMessage::from_sign_request(&request)?.to_json()
+        };
+        match decode() {
+            Ok(message) => message.to_string(),
+            Err(e) => json!({"error": e.to_string()}).to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ur_registry::crypto_key_path::CryptoKeyPath;
+
+    const TRANSFER_MESSAGE: &str = "01000103c8d842a2f17fd7aab608ce2ea535a6e958dffa20caf669b347b911c4171965530f957620b228bae2b94c82ddd4c093983a67365555b737ec7ddc1117e61c72e0000000000000000000000000000000000000000000000000000000000000000010295cc2f1f39f3604718496ea00676d6a72ec66ad09d926e3ece34f565f18d201020200010c0200000000e1f50500000000";
+
+    fn short_vec(bytes: &[u8]) -> Vec<u8> {
+        let mut result = vec![bytes.len() as u8];
+        result.extend_from_slice(bytes);
+        result
+    }
+
+    fn decode_key(key: &str) -> [u8; 32] {
+        bs58::decode(key).into_vec().unwrap().try_into().unwrap()
+    }
+
+    fn sign_request(sign_data: Vec<u8>, address: Option<Vec<u8>>) -> String {
+        let path = CryptoKeyPath::from_path("m/44'/501'/0'/0'".to_string(), None).unwrap();
+        let request =
+            SolSignRequest::new(None, sign_data, path, address, None, SignType::Transaction);
+        let cbor: Vec<u8> = request.try_into().unwrap();
+        hex::encode(cbor)
+    }
+
+    #[test]
+    fn test_decode_legacy_transfer() {
+        let message = SolMessage::decode(&hex::decode(TRANSFER_MESSAGE).unwrap()).unwrap();
+        assert_eq!(SolMessageVersion::Legacy, message.get_version());
+        assert_eq!(3, message.get_account_keys().len());
+        let instructions = message.parse_instructions().unwrap();
+        assert_eq!(1, instructions.len());
+        assert_eq!(
+            json!({
+                "program": "system",
+                "program_id": SYSTEM_PROGRAM_ID,
+                "type": "transfer",
+                "info": {
+                    "source": "EX1oURpiPWWYUjVSK9KQR2qyqTBaR1EGfRNxkTsNk57Y",
+                    "destination": "23qJPvgvCBGJFhPmemqcksVCtrLDKyXJh5ZstjfCuu9q",
+                    "lamports": "100000000",
+                },
+            }),
+            instructions[0].to_json()
+        );
+
+        let fee_payer = encode_pubkey(&message.get_fee_payer());
+        let result = decode_sol_sign_request(
+            "sol-sign-request",
+            &sign_request(
+                hex::decode(TRANSFER_MESSAGE).unwrap(),
+                Some(fee_payer.as_bytes().to_vec()),
+            ),
+        );
+        let result: Value = serde_json::from_str(&result).unwrap();
+        assert_eq!("legacy", result["version"]);
+        assert_eq!(fee_payer, result["fee_payer"]);
+        assert!(result.get("address_table_lookups").is_none());
+    }
+
+    #[test]
+    fn test_decode_v0_message() {
+        let payer = [1u8; 32];
+        let destination = [2u8; 32];
+        let table = [3u8; 32];
+        let mut message = vec![0x80, 1, 0, 3];
+        message.push(5);
+        for key in [
+            payer,
+            destination,
+            decode_key(COMPUTE_BUDGET_PROGRAM_ID),
+            decode_key(TOKEN_PROGRAM_ID),
+            decode_key(MEMO_PROGRAM_ID),
+        ] {
+            message.extend_from_slice(&key);
+        }
+        message.extend_from_slice(&[9u8; 32]);
+        message.push(3);
+        // SetComputeUnitPrice(5000)
+        message.push(2);
+        message.extend(short_vec(&[]));
+        message.extend(short_vec(&[3, 136, 19, 0, 0, 0, 0, 0, 0]));
+        // TransferChecked(1000000, 6) with the mint loaded from the lookup table.
+        message.push(3);
+        message.extend(short_vec(&[1, 6, 5, 0]));
+        message.extend(short_vec(&[12, 64, 66, 15, 0, 0, 0, 0, 0, 6]));
+        message.push(4);
+        message.extend(short_vec(&[]));
+        message.extend(short_vec(b"hello"));
+        message.push(1);
+        message.extend_from_slice(&table);
+        message.extend(short_vec(&[7]));
+        message.extend(short_vec(&[8]));
+
+        let decoded = SolMessage::decode(&message).unwrap();
+        assert_eq!(SolMessageVersion::V0, decoded.get_version());
+        assert_eq!(7, decoded.get_accounts().len());
+        assert_eq!(
+            SolAccount::Lookup { table, index: 8 },
+            decoded.get_accounts()[6]
+        );
+
+        let result = decoded.to_json().unwrap();
+        let instructions = &result["instructions"];
+        assert_eq!("set_compute_unit_price", instructions[0]["type"]);
+        assert_eq!("5000", instructions[0]["info"]["micro_lamports"]);
+        assert_eq!("spl-token", instructions[1]["program"]);
+        assert_eq!("transfer_checked", instructions[1]["type"]);
+        assert_eq!("1000000", instructions[1]["info"]["amount"]);
+        assert_eq!(6, instructions[1]["info"]["decimals"]);
+        assert_eq!(
+            json!({"lookup_table": encode_pubkey(&table), "index": 8}),
+            instructions[1]["info"]["mint"]
+        );
+        assert_eq!(
+            json!({"lookup_table": encode_pubkey(&table), "index": 7}),
+            instructions[1]["info"]["destination"]
+        );
+        assert_eq!("hello", instructions[2]["info"]["memo"]);
+        assert_eq!(
+            json!([7]),
+            result["address_table_lookups"][0]["writable_indexes"]
+        );
+    }
+
+    #[test]
+    fn test_decode_sol_sign_request_error() {
+        let message = hex::decode(TRANSFER_MESSAGE).unwrap();
+        assert_eq!(
+            "{\"error\":\"type not match\"}",
+            decode_sol_sign_request("eth-sign-request", &sign_request(message.clone(), None))
+        );
+        assert_eq!(
+            "{\"error\":\"address is not a signer of the message\"}",
+            decode_sol_sign_request(
+                "sol-sign-request",
+                &sign_request(message.clone(), Some(vec![1u8; 32]))
+            )
+        );
+        assert_eq!(
+            "{\"error\":\"unexpected end of data\"}",
+            decode_sol_sign_request(
+                "sol-sign-request",
+                &sign_request(message[..message.len() - 1].to_vec(), None)
+            )
+        );
+        let mut invalid_index = message.clone();
+        let len = invalid_index.len();
+        invalid_index[len - 15] = 3;
+        assert_eq!(
+            "{\"error\":\"invalid account index\"}",
+            decode_sol_sign_request("sol-sign-request", &sign_request(invalid_index, None))
+        );
+    }
+}
@@ -0,0 +1,434 @@
+use anyhow::{format_err, Error};
+use serde_json::json;
+use ur_registry::registry_types::SOL_SIGNATURE;
+use ur_registry::solana::sol_sign_request::{SignType, SolSignRequest};
+use ur_registry::solana::sol_signature::SolSignature;
+
+use crate::export;
+use crate::util_internal::ed25519::verify_ed25519;
+use crate::util_internal::string_helper::remove_prefix_0x;
+
+pub const SIGNING_DOMAIN: &[u8; 16] = b"\xffsolana offchain";
+pub const HEADER_VERSION: u8 = 0;
+/// Formats 0 and 1 must fit, preamble included, in a transaction packet so that
+/// hardware wallets can display them.
+pub const MAX_LEN_LIMITED: usize = 1232;
+pub const MAX_LEN_EXTENDED: usize = u16::MAX as usize;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OffchainMessageFormat {
+    RestrictedAscii,
+    LimitedUtf8,
+    ExtendedUtf8,
+}
+
+impl OffchainMessageFormat {
+    pub fn from_u8(format: u8) -> Result<Self, Error> {
+        match format {
+            0 => Ok(OffchainMessageFormat::RestrictedAscii),
+            1 => Ok(OffchainMessageFormat::LimitedUtf8),
+            2 => Ok(OffchainMessageFormat::ExtendedUtf8),
+            x => Err(format_err!("invalid message format {x}")),
+        }
+    }
+
+    pub fn get_value(&self) -> u8 {
+        match self {
+            OffchainMessageFormat::RestrictedAscii => 0,
+            OffchainMessageFormat::LimitedUtf8 => 1,
+            OffchainMessageFormat::ExtendedUtf8 => 2,
+        }
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            OffchainMessageFormat::RestrictedAscii => "restricted_ascii",
+            OffchainMessageFormat::LimitedUtf8 => "limited_utf8",
+            OffchainMessageFormat::ExtendedUtf8 => "extended_utf8",
+        }
+    }
+}
+
+fn is_restricted_ascii(message: &[u8]) -> bool {
+    message.iter().all(|byte| (0x20..=0x7e).contains(byte))
+}
+
+/// A message following the Solana off-chain message signing standard, header version 0.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OffchainMessage {
+    application_domain: [u8; 32],
+    format: OffchainMessageFormat,
+    signers: Vec<[u8; 32]>,
+    message: Vec<u8>,
+}
+
+impl OffchainMessage {
+    /// Builds a message with the most restrictive format that can hold `message`.
+    pub fn new(
+        application_domain: [u8; 32],
+        signers: Vec<[u8; 32]>,
+        message: Vec<u8>,
+    ) -> Result<Self, Error> {
+        let preamble_len = Self::preamble_len(signers.len());
+        let format =
+            if is_restricted_ascii(&message) && preamble_len + message.len() <= MAX_LEN_LIMITED {
+                OffchainMessageFormat::RestrictedAscii
+            } else if preamble_len + message.len() <= MAX_LEN_LIMITED {
+                OffchainMessageFormat::LimitedUtf8
+            } else {
+                OffchainMessageFormat::ExtendedUtf8
+            };
+        let message = OffchainMessage {
+            application_domain,
+            format,
+            signers,
+            message,
+        };
+        message.validate()?;
+        Ok(message)
+    }
+
+    fn preamble_len(signer_count: usize) -> usize {
+        SIGNING_DOMAIN.len() + 1 + 32 + 1 + 1 + 32 * signer_count + 2
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        if self.signers.is_empty() || self.signers.len() > u8::MAX as usize {
+            return Err(format_err!("invalid signer count"));
+        }
+        if self.message.is_empty() {
+            return Err(format_err!("message is empty"));
+        }
+        let total_len = Self::preamble_len(self.signers.len()) + self.message.len();
+        match self.format {
+            OffchainMessageFormat::RestrictedAscii if !is_restricted_ascii(&self.message) => {
+                Err(format_err!("message is not restricted ascii"))
+            }
+            OffchainMessageFormat::LimitedUtf8 | OffchainMessageFormat::ExtendedUtf8
+                if std::str::from_utf8(&self.message).is_err() =>
+            {
+                Err(format_err!("message is not utf-8"))
+            }
+            OffchainMessageFormat::RestrictedAscii | OffchainMessageFormat::LimitedUtf8
+                if total_len > MAX_LEN_LIMITED =>
+            {
+                Err(format_err!("message is too long"))
+            }
+            _ if self.message.len() > MAX_LEN_EXTENDED => Err(format_err!("message is too long")),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut result = Vec::with_capacity(Self::preamble_len(self.signers.len()));
+        result.extend_from_slice(SIGNING_DOMAIN);
+        result.push(HEADER_VERSION);
+        result.extend_from_slice(&self.application_domain);
+        result.push(self.format.get_value());
+        result.push(self.signers.len() as u8);
+        for signer in &self.signers {
+            result.extend_from_slice(signer);
+        }
+        result.extend_from_slice(&(self.message.len() as u16).to_le_bytes());
+        result.extend_from_slice(&self.message);
+        result
+    }
+
+    pub fn deserialize(bytes: &[u8]) -> Result<Self, Error> {
+        let rest = bytes
+            .strip_prefix(SIGNING_DOMAIN)
+            .ok_or(format_err!("invalid signing domain"))?;
+        let take = |rest: &mut &[u8], len: usize| -> Result<Vec<u8>, Error> {
+            if rest.len() < len {
+                return Err(format_err!("unexpected end of data"));
+            }
+            let (head, tail) = rest.split_at(len);
+            *rest = tail;
+            Ok(head.to_vec())
+        };
+        let mut rest = rest;
+        let version = take(&mut rest, 1)?[0];
+        if version != HEADER_VERSION {
+            return Err(format_err!("unsupported header version {version}"));
+        }
+        let application_domain = take(&mut rest, 32)?.try_into().unwrap_or_default();
+        let format = OffchainMessageFormat::from_u8(take(&mut rest, 1)?[0])?;
+        let signer_count = take(&mut rest, 1)?[0] as usize;
+        let signers = (0..signer_count)
+            .map(|_| Ok(take(&mut rest, 32)?.try_into().unwrap_or_default()))
+            .collect::<Result<Vec<[u8; 32]>, Error>>()?;
+        let len = u16::from_le_bytes(take(&mut rest, 2)?.try_into().unwrap_or_default());
+        let message = take(&mut rest, len as usize)?;
+        if !rest.is_empty() {
+            return Err(format_err!("unexpected trailing bytes"));
+        }
+
+        let message = OffchainMessage {
+            application_domain,
+            format,
+            signers,
+            message,
+        };
+        message.validate()?;
+        Ok(message)
+    }
+
+    /// Decodes the payload of a `Message` request and checks that the request address,
+    /// when present, is one of the message signers.
+    pub fn from_sign_request(request: &SolSignRequest) -> Result<Self, Error> {
+        if request.get_sign_type() != SignType::Message {
+            return Err(format_err!("sign type is not a message"));
+        }
+        let message = Self::deserialize(&request.get_sign_data())?;
+        if let Some(address) = request.get_address() {
+            let address = match address.len() {
+                32 => address,
+                _ => bs58::decode(&address)
+                    .into_vec()
+                    .map_err(|_| format_err!("address is invalid"))?,
+            };
+            if !message
+                .signers
+                .iter()
+                .any(|signer| signer[..] == address[..])
+            {
+                return Err(format_err!("address is not a signer of the message"));
+            }
+        }
+        Ok(message)
+    }
+
+    /// Returns the signer whose key verifies `signature` over the serialized message.
+    pub fn verify(&self, signature: &[u8]) -> Result<[u8; 32], Error> {
+        let payload = self.serialize();
+        self.signers
+            .iter()
+            .find(|signer| verify_ed25519(signer, &payload, signature))
+            .copied()
+            .ok_or(format_err!("signature does not match any signer"))
+    }
+
+    pub fn get_application_domain(&self) -> [u8; 32] {
+        self.application_domain
+    }
+
+    pub fn get_format(&self) -> OffchainMessageFormat {
+        self.format
+    }
+
+    pub fn get_signers(&self) -> Vec<[u8; 32]> {
+        self.signers.clone()
+    }
+
+    pub fn get_message(&self) -> Vec<u8> {
+        self.message.clone()
+    }
+}
+
+/// Verifies a `SolSignature` returned for an off-chain message request and returns the signer.
+pub fn verify_sol_offchain_signature_for_request(
+    request: &SolSignRequest,
+    signature: &SolSignature,
+) -> Result<[u8; 32], Error> {
+    if let Some(request_id) = request.get_request_id() {
+        if signature.get_request_id() != Some(request_id) {
+            return Err(format_err!("request id mismatch"));
+        }
+    }
+    OffchainMessage::from_sign_request(request)?.verify(&signature.get_signature())
+}
+
+export! {
+    @Java_com_keystone_sdk_KeystoneNativeSDK_generateSolOffchainMessage
+    fn generate_sol_offchain_message(
+        application_domain: &str,
+        signers: &str,
+        message: &str
+    ) -> String {
+        let application_domain: [u8; 32] = match application_domain.is_empty() {
+            true => [0u8; 32],
+            false => match hex::decode(remove_prefix_0x(application_domain))
+                .ok()
+                .and_then(|v| v.try_into().ok())
+            {
+                Some(v) => v,
+                None => return json!({"error": "application domain is invalid"}).to_string(),
+            },
+        };
+        let signers = match serde_json::from_str::<Vec<String>>(signers) {
+            Ok(v) => v,
+            Err(_) => return json!({"error": "signers is invalid"}).to_string(),
+        };
+        let signers = match signers
+            .iter()
+            .map(|signer| bs58::decode(signer).into_vec().ok().and_then(|v| v.try_into().ok()))
+            .collect::<Option<Vec<[u8; 32]>>>()
+        {
+            Some(v) => v,
+            None => return json!({"error": "signers is invalid"}).to_string(),
+        };
+
+        match OffchainMessage::new(application_domain, signers, message.as_bytes().to_vec()) {
+            Ok(offchain_message) => json!({
+                "format": offchain_message.get_format().get_name(),
+                "sign_data": hex::encode(offchain_message.serialize()),
+            }).to_string(),
+            Err(e) => json!({"error": e.to_string()}).to_string(),
+        }
+    }
+
+    @Java_com_keystone_sdk_KeystoneNativeSDK_verifySolOffchainMessageSignature
+    fn verify_sol_offchain_message_signature(
+        sign_request_cbor_hex: &str,
+        ur_type: &str,
+        cbor_hex: &str
+    ) -> String {
+        if SOL_SIGNATURE.get_type() != ur_type {
+            return json!({"error": "type not match"}).to_string();
+        }
+
+        let verify = || -> Result<[u8; 32], Error> {
+            let request = SolSignRequest::try_from(hex::decode(sign_request_cbor_hex)?)
+                .map_err(|e| format_err!(e.to_string()))?;
+            let signature = SolSignature::try_from(hex::decode(cbor_hex)?)
+                .map_err(|e| format_err!(e.to_string()))?;
+            verify_sol_offchain_signature_for_request(&request, &signature)
+        };
+        match verify() {
+            Ok(signer) => json!({
+                "signer": bs58::encode(signer).into_string(),
+                "verified": true,
+            }).to_string(),
+            Err(e) => json!({"error": e.to_string()}).to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util_internal::ed25519::sign_ed25519;
+    use ur_registry::crypto_key_path::CryptoKeyPath;
+
+    const SEED: [u8; 32] = [7u8; 32];
+
+    fn sign_request(sign_data: Vec<u8>, address: Option<Vec<u8>>) -> SolSignRequest {
+        let path = CryptoKeyPath::from_path("m/44'/501'/0'/0'".to_string(), None).unwrap();
+        SolSignRequest::new(
+            Some(vec![1u8; 16]),
+            sign_data,
+            path,
+            address,
+            None,
+            SignType::Message,
+        )
+    }
+
+    #[test]
+    fn test_build_offchain_message() {
+        let signer = [1u8; 32];
+        let message = OffchainMessage::new([0u8; 32], vec![signer], b"Hello".to_vec()).unwrap();
+        assert_eq!(OffchainMessageFormat::RestrictedAscii, message.get_format());
+        let serialized = message.serialize();
+        assert_eq!(
+            format!(
+                "ff736f6c616e61206f6666636861696e00{}0001{}050048656c6c6f",
+                "00".repeat(32),
+                "01".repeat(32)
+            ),
+            hex::encode(&serialized)
+        );
+        assert_eq!(message, OffchainMessage::deserialize(&serialized).unwrap());
+
+        let utf8 = OffchainMessage::new([0u8; 32], vec![signer], "héllo".as_bytes().to_vec());
+        assert_eq!(
+            OffchainMessageFormat::LimitedUtf8,
+            utf8.unwrap().get_format()
+        );
+        let long = OffchainMessage::new([0u8; 32], vec![signer], vec![b'a'; 2000]);
+        assert_eq!(
+            OffchainMessageFormat::ExtendedUtf8,
+            long.unwrap().get_format()
+        );
+
+        assert_eq!(
+            "message is not utf-8",
+            OffchainMessage::new([0u8; 32], vec![signer], vec![0xff])
+                .unwrap_err()
+                .to_string()
+        );
+        let mut invalid = serialized.clone();
+        let last = invalid.len() - 1;
+        invalid[last] = 0x07;
+        assert_eq!(
+            "message is not restricted ascii",
+            OffchainMessage::deserialize(&invalid)
+                .unwrap_err()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn test_verify_offchain_message_signature() {
+        let (public_key, _) = sign_ed25519(&SEED, b"");
+        let address = bs58::encode(public_key).into_string();
+        let result: serde_json::Value = serde_json::from_str(&generate_sol_offchain_message(
+            "",
+            &json!([address]).to_string(),
+            "Sign in to example.com",
+        ))
+        .unwrap();
+        assert_eq!("restricted_ascii", result["format"]);
+        let sign_data = hex::decode(result["sign_data"].as_str().unwrap()).unwrap();
+
+        let (_, signature) = sign_ed25519(&SEED, &sign_data);
+        let request = sign_request(sign_data.clone(), Some(address.as_bytes().to_vec()));
+        let request_cbor: Vec<u8> = request.clone().try_into().unwrap();
+        let signature_cbor: Vec<u8> = SolSignature::new(Some(vec![1u8; 16]), signature.to_vec())
+            .try_into()
+            .unwrap();
+        assert_eq!(
+            format!("{{\"signer\":\"{address}\",\"verified\":true}}"),
+            verify_sol_offchain_message_signature(
+                &hex::encode(&request_cbor),
+                "sol-signature",
+                &hex::encode(&signature_cbor)
+            )
+        );
+
+        let (_, other_signature) = sign_ed25519(&[8u8; 32], &sign_data);
+        let signature_cbor: Vec<u8> =
+            SolSignature::new(Some(vec![1u8; 16]), other_signature.to_vec())
+                .try_into()
+                .unwrap();
+        assert_eq!(
+            "{\"error\":\"signature does not match any signer\"}",
+            verify_sol_offchain_message_signature(
+                &hex::encode(&request_cbor),
+                "sol-signature",
+                &hex::encode(&signature_cbor)
+            )
+        );
+
+        assert_eq!(
+            "request id mismatch",
+            verify_sol_offchain_signature_for_request(
+                &request,
+                &SolSignature::new(None, signature.to_vec())
+            )
+            .unwrap_err()
+            .to_string()
+        );
+
+        let request = sign_request(sign_data, Some(vec![2u8; 32]));
+        assert_eq!(
+            "address is not a signer of the message",
+            verify_sol_offchain_signature_for_request(
+                &request,
+                &SolSignature::new(Some(vec![1u8; 16]), signature.to_vec())
+            )
+            .unwrap_err()
+            .to_string()
+        );
+    }
+}
@@ -1,2 +1,4 @@
+pub mod stellar_address;
 pub mod stellar_sign_request;
 pub mod stellar_signature;
+pub mod stellar_transaction;
@@ -0,0 +1,128 @@
+use anyhow::{format_err, Error};
+
+use crate::util_internal::checksum::crc16_xmodem;
+
+pub const ACCOUNT_VERSION: u8 = 6 << 3;
+pub const MUXED_ACCOUNT_VERSION: u8 = 12 << 3;
+pub const CONTRACT_VERSION: u8 = 2 << 3;
+pub const LIQUIDITY_POOL_VERSION: u8 = 11 << 3;
+pub const CLAIMABLE_BALANCE_VERSION: u8 = 1 << 3;
+
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+fn base32_encode(data: &[u8]) -> String {
+    let mut result = String::new();
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for byte in data {
+        buffer = (buffer << 8) | u32::from(*byte);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            result.push(BASE32_ALPHABET[(buffer >> bits) as usize & 31] as char);
+        }
+    }
+    if bits > 0 {
+        result.push(BASE32_ALPHABET[(buffer << (5 - bits)) as usize & 31] as char);
+    }
+    result
+}
+
+fn base32_decode(data: &str) -> Option<Vec<u8>> {
+    let mut result = vec![];
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for c in data.bytes() {
+        let value = BASE32_ALPHABET.iter().position(|x| *x == c)? as u32;
+        buffer = (buffer << 5) | value;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            result.push((buffer >> bits) as u8);
+        }
+    }
+    // Leftover bits must be zero so that every payload has a single encoding.
+    match buffer & ((1 << bits) - 1) {
+        0 => Some(result),
+        _ => None,
+    }
+}
+
+/// Encodes a strkey: base32 of the version byte, the payload and a CRC-16 checksum.
+pub fn encode_strkey(version: u8, payload: &[u8]) -> String {
+    let mut bytes = vec![version];
+    bytes.extend_from_slice(payload);
+    bytes.extend_from_slice(&crc16_xmodem(&bytes).to_le_bytes());
+    base32_encode(&bytes)
+}
+
+/// Decodes a strkey into its version byte and payload.
+pub fn decode_strkey(strkey: &str) -> Result<(u8, Vec<u8>), Error> {
+    let bytes = base32_decode(strkey)
+        .filter(|bytes| bytes.len() > 3 && base32_encode(bytes) == strkey)
+        .ok_or(format_err!("address is invalid"))?;
+    let (data, checksum) = bytes.split_at(bytes.len() - 2);
+    if crc16_xmodem(data).to_le_bytes() != checksum {
+        return Err(format_err!("address is invalid"));
+    }
+    Ok((data[0], data[1..].to_vec()))
+}
+
+/// Decodes a `G...` account address into its Ed25519 public key.
+pub fn decode_account_id(address: &str) -> Result<[u8; 32], Error> {
+    match decode_strkey(address)? {
+        (ACCOUNT_VERSION, payload) => Ok(payload
+            .try_into()
+            .map_err(|_| format_err!("address is invalid"))?),
+        _ => Err(format_err!("address is not an account")),
+    }
+}
+
+pub fn encode_account_id(public_key: &[u8; 32]) -> String {
+    encode_strkey(ACCOUNT_VERSION, public_key)
+}
+
+/// Encodes a muxed account as `M...`, or as its `G...` account without an id.
+pub fn encode_muxed_account(public_key: &[u8; 32], id: Option<u64>) -> String {
+    match id {
+        Some(id) => {
+            let mut payload = public_key.to_vec();
+            payload.extend_from_slice(&id.to_be_bytes());
+            encode_strkey(MUXED_ACCOUNT_VERSION, &payload)
+        }
+        None => encode_account_id(public_key),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADDRESS: &str = "GA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJVSGZ";
+    const PUBLIC_KEY: &str = "3f0c34bf93ad0d9971d04ccc90f705511c838aad9734a4a2fb0d7a03fc7fe89a";
+
+    #[test]
+    fn test_strkey() {
+        let public_key: [u8; 32] = hex::decode(PUBLIC_KEY).unwrap().try_into().unwrap();
+        assert_eq!(public_key, decode_account_id(ADDRESS).unwrap());
+        assert_eq!(ADDRESS, encode_account_id(&public_key));
+
+        let muxed = encode_muxed_account(&public_key, Some(1234));
+        assert!(muxed.starts_with('M'));
+        let (version, payload) = decode_strkey(&muxed).unwrap();
+        assert_eq!(MUXED_ACCOUNT_VERSION, version);
+        assert_eq!(1234u64.to_be_bytes(), payload[32..]);
+        assert_eq!(ADDRESS, encode_muxed_account(&public_key, None));
+
+        assert_eq!(
+            "address is invalid",
+            decode_account_id("GA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJVSGA")
+                .unwrap_err()
+                .to_string()
+        );
+        assert_eq!(
+            "address is not an account",
+            decode_account_id(&muxed).unwrap_err().to_string()
+        );
+    }
+}
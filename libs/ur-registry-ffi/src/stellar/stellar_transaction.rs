@@ -0,0 +1,1460 @@
+use std::ops::Range;
+
+use anyhow::{format_err, Error};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use ur_registry::registry_types::{STELLAR_SIGNATURE, STELLAR_SIGN_REQUEST};
+use ur_registry::stellar::stellar_sign_request::{SignType, StellarSignRequest};
+use ur_registry::stellar::stellar_signature::StellarSignature;
+
+use crate::export;
+use crate::stellar::stellar_address::{
+    decode_account_id, encode_account_id, encode_muxed_account, encode_strkey,
+    CLAIMABLE_BALANCE_VERSION, CONTRACT_VERSION, LIQUIDITY_POOL_VERSION,
+};
+use crate::util_internal::ed25519::verify_ed25519;
+use crate::util_internal::string_helper::remove_prefix_0x;
+use crate::util_internal::xdr::XdrReader;
+
+pub const PUBLIC_NETWORK_PASSPHRASE: &str = "Public Global Stellar Network ; September 2015";
+pub const TESTNET_NETWORK_PASSPHRASE: &str = "Test SDF Network ; September 2015";
+pub const FUTURENET_NETWORK_PASSPHRASE: &str = "Test SDF Future Network ; October 2022";
+
+const ENVELOPE_TYPE_TX_V0: u32 = 0;
+const ENVELOPE_TYPE_TX: u32 = 2;
+const ENVELOPE_TYPE_TX_FEE_BUMP: u32 = 5;
+const KEY_TYPE_ED25519: u32 = 0;
+const KEY_TYPE_MUXED_ED25519: u32 = 0x100;
+const MAX_SIGNATURES: usize = 20;
+const MAX_OPERATIONS: usize = 100;
+const MAX_DEPTH: usize = 32;
+
+/// Resolves `public`, `testnet` and `futurenet`; anything else is taken as the passphrase.
+pub fn network_passphrase(network: &str) -> &str {
+    match network {
+        "public" | "mainnet" => PUBLIC_NETWORK_PASSPHRASE,
+        "testnet" => TESTNET_NETWORK_PASSPHRASE,
+        "futurenet" => FUTURENET_NETWORK_PASSPHRASE,
+        passphrase => passphrase,
+    }
+}
+
+pub fn network_id(network: &str) -> [u8; 32] {
+    Sha256::digest(network_passphrase(network)).into()
+}
+
+fn read_account_id(reader: &mut XdrReader) -> Result<[u8; 32], Error> {
+    match reader.u32()? {
+        KEY_TYPE_ED25519 => reader.fixed(),
+        x => Err(format_err!("invalid public key type {x}")),
+    }
+}
+
+/// A source or destination account, optionally multiplexed with an id.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MuxedAccount {
+    ed25519: [u8; 32],
+    id: Option<u64>,
+}
+
+impl MuxedAccount {
+    fn decode(reader: &mut XdrReader) -> Result<Self, Error> {
+        match reader.u32()? {
+            KEY_TYPE_ED25519 => Ok(MuxedAccount {
+                ed25519: reader.fixed()?,
+                id: None,
+            }),
+            KEY_TYPE_MUXED_ED25519 => {
+                let id = reader.u64()?;
+                Ok(MuxedAccount {
+                    ed25519: reader.fixed()?,
+                    id: Some(id),
+                })
+            }
+            x => Err(format_err!("invalid muxed account type {x}")),
+        }
+    }
+
+    pub fn get_ed25519(&self) -> [u8; 32] {
+        self.ed25519
+    }
+
+    pub fn to_address(&self) -> String {
+        encode_muxed_account(&self.ed25519, self.id)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Asset {
+    Native,
+    Credit {
+        code: String,
+        issuer: [u8; 32],
+    },
+    LiquidityPool {
+        asset_a: Box<Asset>,
+        asset_b: Box<Asset>,
+        fee: i32,
+    },
+}
+
+impl Asset {
+    fn decode_credit(reader: &mut XdrReader, len: usize) -> Result<Self, Error> {
+        let code = reader.opaque(len)?;
+        let code = String::from_utf8_lossy(code)
+            .trim_end_matches('\0')
+            .to_string();
+        Ok(Asset::Credit {
+            code,
+            issuer: read_account_id(reader)?,
+        })
+    }
+
+    fn decode(reader: &mut XdrReader) -> Result<Self, Error> {
+        match reader.u32()? {
+            0 => Ok(Asset::Native),
+            1 => Asset::decode_credit(reader, 4),
+            2 => Asset::decode_credit(reader, 12),
+            x => Err(format_err!("invalid asset type {x}")),
+        }
+    }
+
+    /// Decodes a `ChangeTrustAsset`, which may also name a liquidity pool.
+    fn decode_change_trust(reader: &mut XdrReader) -> Result<Self, Error> {
+        match reader.u32()? {
+            0 => Ok(Asset::Native),
+            1 => Asset::decode_credit(reader, 4),
+            2 => Asset::decode_credit(reader, 12),
+            3 => match reader.u32()? {
+                0 => Ok(Asset::LiquidityPool {
+                    asset_a: Box::new(Asset::decode(reader)?),
+                    asset_b: Box::new(Asset::decode(reader)?),
+                    fee: reader.i32()?,
+                }),
+                x => Err(format_err!("invalid liquidity pool type {x}")),
+            },
+            x => Err(format_err!("invalid asset type {x}")),
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        match self {
+            Asset::Native => json!("native"),
+            Asset::Credit { code, issuer } => {
+                json!(format!("{}:{}", code, encode_account_id(issuer)))
+            }
+            Asset::LiquidityPool {
+                asset_a,
+                asset_b,
+                fee,
+            } => json!({
+                "asset_a": asset_a.to_json(),
+                "asset_b": asset_b.to_json(),
+                "fee": fee,
+            }),
+        }
+    }
+}
+
+fn read_sc_address(reader: &mut XdrReader) -> Result<String, Error> {
+    match reader.u32()? {
+        0 => Ok(encode_account_id(&read_account_id(reader)?)),
+        1 => Ok(encode_strkey(CONTRACT_VERSION, &reader.fixed::<32>()?)),
+        2 => {
+            let id = reader.u64()?;
+            Ok(encode_muxed_account(&reader.fixed()?, Some(id)))
+        }
+        3 => match reader.u32()? {
+            0 => {
+                let mut payload = vec![0];
+                payload.extend_from_slice(&reader.fixed::<32>()?);
+                Ok(encode_strkey(CLAIMABLE_BALANCE_VERSION, &payload))
+            }
+            x => Err(format_err!("invalid claimable balance type {x}")),
+        },
+        4 => Ok(encode_strkey(
+            LIQUIDITY_POOL_VERSION,
+            &reader.fixed::<32>()?,
+        )),
+        x => Err(format_err!("invalid sc address type {x}")),
+    }
+}
+
+fn read_sc_map(reader: &mut XdrReader, depth: usize) -> Result<Value, Error> {
+    let entries = reader.vec(usize::MAX, |r| {
+        Ok(json!({
+            "key": read_sc_val(r, depth + 1)?,
+            "value": read_sc_val(r, depth + 1)?,
+        }))
+    })?;
+    Ok(Value::Array(entries))
+}
+
+fn read_contract_executable(reader: &mut XdrReader) -> Result<Value, Error> {
+    match reader.u32()? {
+        0 => Ok(json!({"wasm": hex::encode(reader.fixed::<32>()?)})),
+        1 => Ok(json!("stellar_asset")),
+        x => Err(format_err!("invalid contract executable type {x}")),
+    }
+}
+
+/// Reads an `SCVal` into the `{"<type>": <value>}` JSON form used by Stellar tooling.
+fn read_sc_val(reader: &mut XdrReader, depth: usize) -> Result<Value, Error> {
+    if depth > MAX_DEPTH {
+        return Err(format_err!("sc value is nested too deeply"));
+    }
+    let value = match reader.u32()? {
+        0 => json!({"bool": reader.bool()?}),
+        1 => json!("void"),
+        2 => json!({"error": {"type": reader.u32()?, "code": reader.u32()?}}),
+        3 => json!({"u32": reader.u32()?}),
+        4 => json!({"i32": reader.i32()?}),
+        5 => json!({"u64": reader.u64()?.to_string()}),
+        6 => json!({"i64": reader.i64()?.to_string()}),
+        7 => json!({"timepoint": reader.u64()?.to_string()}),
+        8 => json!({"duration": reader.u64()?.to_string()}),
+        9 => {
+            let value = (u128::from(reader.u64()?) << 64) | u128::from(reader.u64()?);
+            json!({"u128": value.to_string()})
+        }
+        10 => {
+            let value = (i128::from(reader.i64()?) << 64) | i128::from(reader.u64()?);
+            json!({"i128": value.to_string()})
+        }
+        11 => json!({"u256": hex::encode(reader.fixed::<32>()?)}),
+        12 => json!({"i256": hex::encode(reader.fixed::<32>()?)}),
+        13 => json!({"bytes": hex::encode(reader.var_opaque(usize::MAX)?)}),
+        14 => json!({"string": reader.string(usize::MAX)?}),
+        15 => json!({"symbol": reader.string(32)?}),
+        16 => {
+            let items = reader.option(|r| r.vec(usize::MAX, |r| read_sc_val(r, depth + 1)))?;
+            json!({"vec": items})
+        }
+        17 => json!({"map": reader.option(|r| read_sc_map(r, depth))?}),
+        18 => json!({"address": read_sc_address(reader)?}),
+        19 => json!({"contract_instance": {
+            "executable": read_contract_executable(reader)?,
+            "storage": reader.option(|r| read_sc_map(r, depth))?,
+        }}),
+        20 => json!("ledger_key_contract_instance"),
+        21 => json!({"ledger_key_nonce": reader.i64()?.to_string()}),
+        x => return Err(format_err!("invalid sc value type {x}")),
+    };
+    Ok(value)
+}
+
+fn read_ledger_key(reader: &mut XdrReader) -> Result<(), Error> {
+    match reader.u32()? {
+        0 => {
+            read_account_id(reader)?;
+        }
+        1 => {
+            read_account_id(reader)?;
+            match reader.u32()? {
+                0 => {}
+                1 => {
+                    reader.opaque(4)?;
+                    read_account_id(reader)?;
+                }
+                2 => {
+                    reader.opaque(12)?;
+                    read_account_id(reader)?;
+                }
+                3 => {
+                    reader.fixed::<32>()?;
+                }
+                x => return Err(format_err!("invalid asset type {x}")),
+            }
+        }
+        2 => {
+            read_account_id(reader)?;
+            reader.i64()?;
+        }
+        3 => {
+            read_account_id(reader)?;
+            reader.string(64)?;
+        }
+        4 => {
+            reader.u32()?;
+            reader.fixed::<32>()?;
+        }
+        5 | 7 | 9 => {
+            reader.fixed::<32>()?;
+        }
+        6 => {
+            read_sc_address(reader)?;
+            read_sc_val(reader, 0)?;
+            reader.u32()?;
+        }
+        8 => {
+            reader.u32()?;
+        }
+        x => return Err(format_err!("invalid ledger key type {x}")),
+    }
+    Ok(())
+}
+
+/// Reads `SorobanTransactionData`, returning the resource fee.
+fn read_soroban_data(reader: &mut XdrReader) -> Result<i64, Error> {
+    match reader.u32()? {
+        0 => {}
+        1 => {
+            reader.vec(usize::MAX, |r| r.u32())?;
+        }
+        x => return Err(format_err!("invalid soroban data extension {x}")),
+    }
+    reader.vec(usize::MAX, read_ledger_key)?;
+    reader.vec(usize::MAX, read_ledger_key)?;
+    reader.u32()?;
+    reader.u32()?;
+    reader.u32()?;
+    reader.i64()
+}
+
+fn read_extension_point(reader: &mut XdrReader) -> Result<(), Error> {
+    match reader.u32()? {
+        0 => Ok(()),
+        x => Err(format_err!("invalid extension point {x}")),
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum HostFunction {
+    InvokeContract {
+        contract: String,
+        function_name: String,
+        args: Vec<Value>,
+    },
+    CreateContract {
+        executable: Value,
+        args: Vec<Value>,
+    },
+    UploadContractWasm(Vec<u8>),
+}
+
+impl HostFunction {
+    fn decode_invoke_contract(reader: &mut XdrReader) -> Result<Self, Error> {
+        Ok(HostFunction::InvokeContract {
+            contract: read_sc_address(reader)?,
+            function_name: reader.string(32)?,
+            args: reader.vec(usize::MAX, |r| read_sc_val(r, 0))?,
+        })
+    }
+
+    fn decode_create_contract(reader: &mut XdrReader, with_args: bool) -> Result<Self, Error> {
+        match reader.u32()? {
+            0 => {
+                read_sc_address(reader)?;
+                reader.fixed::<32>()?;
+            }
+            1 => {
+                Asset::decode(reader)?;
+            }
+            x => return Err(format_err!("invalid contract id preimage type {x}")),
+        }
+        let executable = read_contract_executable(reader)?;
+        let args = match with_args {
+            true => reader.vec(usize::MAX, |r| read_sc_val(r, 0))?,
+            false => vec![],
+        };
+        Ok(HostFunction::CreateContract { executable, args })
+    }
+
+    fn decode(reader: &mut XdrReader) -> Result<Self, Error> {
+        match reader.u32()? {
+            0 => HostFunction::decode_invoke_contract(reader),
+            1 => HostFunction::decode_create_contract(reader, false),
+            2 => Ok(HostFunction::UploadContractWasm(
+                reader.var_opaque(usize::MAX)?.to_vec(),
+            )),
+            3 => HostFunction::decode_create_contract(reader, true),
+            x => Err(format_err!("invalid host function type {x}")),
+        }
+    }
+
+    /// Decodes a `SorobanAuthorizedFunction`, which shares the layout of host functions.
+    fn decode_authorized(reader: &mut XdrReader) -> Result<Self, Error> {
+        match reader.u32()? {
+            0 => HostFunction::decode_invoke_contract(reader),
+            1 => HostFunction::decode_create_contract(reader, false),
+            2 => HostFunction::decode_create_contract(reader, true),
+            x => Err(format_err!("invalid authorized function type {x}")),
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        match self {
+            HostFunction::InvokeContract {
+                contract,
+                function_name,
+                args,
+            } => json!({
+                "type": "invoke_contract",
+                "contract": contract,
+                "function_name": function_name,
+                "args": args,
+            }),
+            HostFunction::CreateContract { executable, args } => json!({
+                "type": "create_contract",
+                "executable": executable,
+                "args": args,
+            }),
+            HostFunction::UploadContractWasm(wasm) => json!({
+                "type": "upload_contract_wasm",
+                "size": wasm.len(),
+                "hash": hex::encode(Sha256::digest(wasm)),
+            }),
+        }
+    }
+}
+
+/// Reads a `SorobanAuthorizedInvocation` tree, flattening it into the authorized functions.
+fn read_authorized_invocation(
+    reader: &mut XdrReader,
+    depth: usize,
+    functions: &mut Vec<HostFunction>,
+) -> Result<(), Error> {
+    if depth > MAX_DEPTH {
+        return Err(format_err!("authorized invocation is nested too deeply"));
+    }
+    functions.push(HostFunction::decode_authorized(reader)?);
+    reader.vec(usize::MAX, |r| {
+        read_authorized_invocation(r, depth + 1, functions)
+    })?;
+    Ok(())
+}
+
+/// A `SorobanAuthorizationEntry`; `address` is `None` for source account credentials.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AuthorizationEntry {
+    address: Option<String>,
+    functions: Vec<HostFunction>,
+}
+
+impl AuthorizationEntry {
+    fn decode(reader: &mut XdrReader) -> Result<Self, Error> {
+        let address = match reader.u32()? {
+            0 => None,
+            1 => {
+                let address = read_sc_address(reader)?;
+                reader.i64()?;
+                reader.u32()?;
+                read_sc_val(reader, 0)?;
+                Some(address)
+            }
+            x => return Err(format_err!("invalid soroban credentials type {x}")),
+        };
+        let mut functions = vec![];
+        read_authorized_invocation(reader, 0, &mut functions)?;
+        Ok(AuthorizationEntry { address, functions })
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "address": self.address,
+            "functions": self.functions.iter().map(HostFunction::to_json).collect::<Vec<_>>(),
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum OperationBody {
+    CreateAccount {
+        destination: [u8; 32],
+        starting_balance: i64,
+    },
+    Payment {
+        destination: MuxedAccount,
+        asset: Asset,
+        amount: i64,
+    },
+    PathPaymentStrictReceive {
+        send_asset: Asset,
+        send_max: i64,
+        destination: MuxedAccount,
+        dest_asset: Asset,
+        dest_amount: i64,
+        path: Vec<Asset>,
+    },
+    PathPaymentStrictSend {
+        send_asset: Asset,
+        send_amount: i64,
+        destination: MuxedAccount,
+        dest_asset: Asset,
+        dest_min: i64,
+        path: Vec<Asset>,
+    },
+    ChangeTrust {
+        line: Asset,
+        limit: i64,
+    },
+    AccountMerge {
+        destination: MuxedAccount,
+    },
+    ManageData {
+        name: String,
+        value: Option<Vec<u8>>,
+    },
+    BumpSequence {
+        bump_to: i64,
+    },
+    InvokeHostFunction {
+        function: HostFunction,
+        auth: Vec<AuthorizationEntry>,
+    },
+    ExtendFootprintTtl {
+        extend_to: u32,
+    },
+    RestoreFootprint,
+}
+
+impl OperationBody {
+    fn decode(reader: &mut XdrReader) -> Result<Self, Error> {
+        let body = match reader.u32()? {
+            0 => OperationBody::CreateAccount {
+                destination: read_account_id(reader)?,
+                starting_balance: reader.i64()?,
+            },
+            1 => OperationBody::Payment {
+                destination: MuxedAccount::decode(reader)?,
+                asset: Asset::decode(reader)?,
+                amount: reader.i64()?,
+            },
+            2 => OperationBody::PathPaymentStrictReceive {
+                send_asset: Asset::decode(reader)?,
+                send_max: reader.i64()?,
+                destination: MuxedAccount::decode(reader)?,
+                dest_asset: Asset::decode(reader)?,
+                dest_amount: reader.i64()?,
+                path: reader.vec(5, Asset::decode)?,
+            },
+            6 => OperationBody::ChangeTrust {
+                line: Asset::decode_change_trust(reader)?,
+                limit: reader.i64()?,
+            },
+            8 => OperationBody::AccountMerge {
+                destination: MuxedAccount::decode(reader)?,
+            },
+            10 => OperationBody::ManageData {
+                name: reader.string(64)?,
+                value: reader.option(|r| Ok(r.var_opaque(64)?.to_vec()))?,
+            },
+            11 => OperationBody::BumpSequence {
+                bump_to: reader.i64()?,
+            },
+            13 => OperationBody::PathPaymentStrictSend {
+                send_asset: Asset::decode(reader)?,
+                send_amount: reader.i64()?,
+                destination: MuxedAccount::decode(reader)?,
+                dest_asset: Asset::decode(reader)?,
+                dest_min: reader.i64()?,
+                path: reader.vec(5, Asset::decode)?,
+            },
+            24 => OperationBody::InvokeHostFunction {
+                function: HostFunction::decode(reader)?,
+                auth: reader.vec(usize::MAX, AuthorizationEntry::decode)?,
+            },
+            25 => {
+                read_extension_point(reader)?;
+                OperationBody::ExtendFootprintTtl {
+                    extend_to: reader.u32()?,
+                }
+            }
+            26 => {
+                read_extension_point(reader)?;
+                OperationBody::RestoreFootprint
+            }
+            x => return Err(format_err!("operation type {x} is not supported")),
+        };
+        Ok(body)
+    }
+
+    pub fn to_json(&self) -> Value {
+        match self {
+            OperationBody::CreateAccount {
+                destination,
+                starting_balance,
+            } => json!({
+                "type": "create_account",
+                "destination": encode_account_id(destination),
+                "starting_balance": starting_balance.to_string(),
+            }),
+            OperationBody::Payment {
+                destination,
+                asset,
+                amount,
+            } => json!({
+                "type": "payment",
+                "destination": destination.to_address(),
+                "asset": asset.to_json(),
+                "amount": amount.to_string(),
+            }),
+            OperationBody::PathPaymentStrictReceive {
+                send_asset,
+                send_max,
+                destination,
+                dest_asset,
+                dest_amount,
+                path,
+            } => json!({
+                "type": "path_payment_strict_receive",
+                "send_asset": send_asset.to_json(),
+                "send_max": send_max.to_string(),
+                "destination": destination.to_address(),
+                "dest_asset": dest_asset.to_json(),
+                "dest_amount": dest_amount.to_string(),
+                "path": path.iter().map(Asset::to_json).collect::<Vec<_>>(),
+            }),
+            OperationBody::PathPaymentStrictSend {
+                send_asset,
+                send_amount,
+                destination,
+                dest_asset,
+                dest_min,
+                path,
+            } => json!({
+                "type": "path_payment_strict_send",
+                "send_asset": send_asset.to_json(),
+                "send_amount": send_amount.to_string(),
+                "destination": destination.to_address(),
+                "dest_asset": dest_asset.to_json(),
+                "dest_min": dest_min.to_string(),
+                "path": path.iter().map(Asset::to_json).collect::<Vec<_>>(),
+            }),
+            OperationBody::ChangeTrust { line, limit } => json!({
+                "type": "change_trust",
+                "line": line.to_json(),
+                "limit": limit.to_string(),
+            }),
+            OperationBody::AccountMerge { destination } => json!({
+                "type": "account_merge",
+                "destination": destination.to_address(),
+            }),
+            OperationBody::ManageData { name, value } => json!({
+                "type": "manage_data",
+                "name": name,
+                "value": value.as_ref().map(hex::encode),
+            }),
+            OperationBody::BumpSequence { bump_to } => json!({
+                "type": "bump_sequence",
+                "bump_to": bump_to.to_string(),
+            }),
+            OperationBody::InvokeHostFunction { function, auth } => json!({
+                "type": "invoke_host_function",
+                "function": function.to_json(),
+                "auth": auth.iter().map(AuthorizationEntry::to_json).collect::<Vec<_>>(),
+            }),
+            OperationBody::ExtendFootprintTtl { extend_to } => json!({
+                "type": "extend_footprint_ttl",
+                "extend_to": extend_to,
+            }),
+            OperationBody::RestoreFootprint => json!({
+                "type": "restore_footprint",
+            }),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Operation {
+    source: Option<MuxedAccount>,
+    body: OperationBody,
+}
+
+impl Operation {
+    fn decode(reader: &mut XdrReader) -> Result<Self, Error> {
+        Ok(Operation {
+            source: reader.option(MuxedAccount::decode)?,
+            body: OperationBody::decode(reader)?,
+        })
+    }
+
+    pub fn get_body(&self) -> &OperationBody {
+        &self.body
+    }
+
+    pub fn to_json(&self) -> Value {
+        let mut value = self.body.to_json();
+        value["source"] = json!(self.source.map(|source| source.to_address()));
+        value
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Memo {
+    None,
+    Text(String),
+    Id(u64),
+    Hash([u8; 32]),
+    Return([u8; 32]),
+}
+
+impl Memo {
+    fn decode(reader: &mut XdrReader) -> Result<Self, Error> {
+        match reader.u32()? {
+            0 => Ok(Memo::None),
+            1 => Ok(Memo::Text(reader.string(28)?)),
+            2 => Ok(Memo::Id(reader.u64()?)),
+            3 => Ok(Memo::Hash(reader.fixed()?)),
+            4 => Ok(Memo::Return(reader.fixed()?)),
+            x => Err(format_err!("invalid memo type {x}")),
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        match self {
+            Memo::None => Value::Null,
+            Memo::Text(text) => json!({"type": "text", "value": text}),
+            Memo::Id(id) => json!({"type": "id", "value": id.to_string()}),
+            Memo::Hash(hash) => json!({"type": "hash", "value": hex::encode(hash)}),
+            Memo::Return(hash) => json!({"type": "return", "value": hex::encode(hash)}),
+        }
+    }
+}
+
+fn read_time_bounds(reader: &mut XdrReader) -> Result<(u64, u64), Error> {
+    Ok((reader.u64()?, reader.u64()?))
+}
+
+/// Reads `Preconditions`, keeping only the time bounds.
+fn read_preconditions(reader: &mut XdrReader) -> Result<Option<(u64, u64)>, Error> {
+    match reader.u32()? {
+        0 => Ok(None),
+        1 => Ok(Some(read_time_bounds(reader)?)),
+        2 => {
+            let time_bounds = reader.option(read_time_bounds)?;
+            reader.option(|r| Ok((r.u32()?, r.u32()?)))?;
+            reader.option(|r| r.i64())?;
+            reader.u64()?;
+            reader.u32()?;
+            reader.vec(2, |r| {
+                match r.u32()? {
+                    0..=2 => {
+                        r.fixed::<32>()?;
+                    }
+                    3 => {
+                        r.fixed::<32>()?;
+                        r.var_opaque(64)?;
+                    }
+                    x => return Err(format_err!("invalid signer key type {x}")),
+                }
+                Ok(())
+            })?;
+            Ok(time_bounds)
+        }
+        x => Err(format_err!("invalid preconditions type {x}")),
+    }
+}
+
+/// A `Transaction`, or a `TransactionV0` whose source is a plain Ed25519 key.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StellarTransaction {
+    source: MuxedAccount,
+    fee: u32,
+    sequence: i64,
+    time_bounds: Option<(u64, u64)>,
+    memo: Memo,
+    operations: Vec<Operation>,
+    soroban_resource_fee: Option<i64>,
+}
+
+impl StellarTransaction {
+    fn decode(reader: &mut XdrReader, v0: bool) -> Result<Self, Error> {
+        let source = match v0 {
+            true => MuxedAccount {
+                ed25519: reader.fixed()?,
+                id: None,
+            },
+            false => MuxedAccount::decode(reader)?,
+        };
+        let fee = reader.u32()?;
+        let sequence = reader.i64()?;
+        let time_bounds = match v0 {
+            true => reader.option(read_time_bounds)?,
+            false => read_preconditions(reader)?,
+        };
+        let memo = Memo::decode(reader)?;
+        let operations = reader.vec(MAX_OPERATIONS, Operation::decode)?;
+        let soroban_resource_fee = match reader.u32()? {
+            0 => None,
+            1 if !v0 => Some(read_soroban_data(reader)?),
+            x => return Err(format_err!("invalid transaction extension {x}")),
+        };
+        Ok(StellarTransaction {
+            source,
+            fee,
+            sequence,
+            time_bounds,
+            memo,
+            operations,
+            soroban_resource_fee,
+        })
+    }
+
+    pub fn get_source(&self) -> MuxedAccount {
+        self.source
+    }
+
+    pub fn get_operations(&self) -> &[Operation] {
+        &self.operations
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "source": self.source.to_address(),
+            "fee": self.fee,
+            "sequence": self.sequence.to_string(),
+            "time_bounds": self.time_bounds.map(|(min, max)| json!({
+                "min_time": min.to_string(),
+                "max_time": max.to_string(),
+            })),
+            "memo": self.memo.to_json(),
+            "operations": self.operations.iter().map(Operation::to_json).collect::<Vec<_>>(),
+            "soroban_resource_fee": self.soroban_resource_fee.map(|fee| fee.to_string()),
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecoratedSignature {
+    hint: [u8; 4],
+    signature: Vec<u8>,
+}
+
+impl DecoratedSignature {
+    fn decode(reader: &mut XdrReader) -> Result<Self, Error> {
+        Ok(DecoratedSignature {
+            hint: reader.fixed()?,
+            signature: reader.var_opaque(64)?.to_vec(),
+        })
+    }
+
+    /// Hints a signature with the last four bytes of the signing key.
+    pub fn new(public_key: &[u8; 32], signature: Vec<u8>) -> Self {
+        DecoratedSignature {
+            hint: public_key[28..].try_into().unwrap_or_default(),
+            signature,
+        }
+    }
+
+    fn serialize(&self) -> Vec<u8> {
+        let mut result = self.hint.to_vec();
+        result.extend_from_slice(&(self.signature.len() as u32).to_be_bytes());
+        result.extend_from_slice(&self.signature);
+        result.resize(result.len() + (4 - self.signature.len() % 4) % 4, 0);
+        result
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "hint": hex::encode(self.hint),
+            "signature": hex::encode(&self.signature),
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EnvelopeType {
+    TransactionV0,
+    Transaction,
+    FeeBump,
+}
+
+/// A decoded `TransactionEnvelope`. For fee bumps, `transaction` is the inner transaction.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StellarEnvelope {
+    envelope_type: EnvelopeType,
+    bytes: Vec<u8>,
+    /// The bytes of the transaction the outer signatures sign.
+    signed_range: Range<usize>,
+    transaction: StellarTransaction,
+    fee_bump: Option<(MuxedAccount, i64)>,
+    signatures: Vec<DecoratedSignature>,
+}
+
+impl StellarEnvelope {
+    pub fn decode(bytes: &[u8]) -> Result<Self, Error> {
+        let mut reader = XdrReader::new(bytes);
+        let envelope_type = reader.u32()?;
+        let start = reader.position();
+        let (envelope_type, transaction, fee_bump) = match envelope_type {
+            ENVELOPE_TYPE_TX_V0 => (
+                EnvelopeType::TransactionV0,
+                StellarTransaction::decode(&mut reader, true)?,
+                None,
+            ),
+            ENVELOPE_TYPE_TX => (
+                EnvelopeType::Transaction,
+                StellarTransaction::decode(&mut reader, false)?,
+                None,
+            ),
+            ENVELOPE_TYPE_TX_FEE_BUMP => {
+                let fee_source = MuxedAccount::decode(&mut reader)?;
+                let fee = reader.i64()?;
+                if reader.u32()? != ENVELOPE_TYPE_TX {
+                    return Err(format_err!("fee bump inner transaction is invalid"));
+                }
+                let transaction = StellarTransaction::decode(&mut reader, false)?;
+                reader.vec(MAX_SIGNATURES, DecoratedSignature::decode)?;
+                read_extension_point(&mut reader)?;
+                (EnvelopeType::FeeBump, transaction, Some((fee_source, fee)))
+            }
+            x => return Err(format_err!("invalid envelope type {x}")),
+        };
+        let signed_range = start..reader.position();
+        let signatures = reader.vec(MAX_SIGNATURES, DecoratedSignature::decode)?;
+        reader.finish()?;
+        Ok(StellarEnvelope {
+            envelope_type,
+            bytes: bytes.to_vec(),
+            signed_range,
+            transaction,
+            fee_bump,
+            signatures,
+        })
+    }
+
+    pub fn get_envelope_type(&self) -> EnvelopeType {
+        self.envelope_type
+    }
+
+    pub fn get_transaction(&self) -> &StellarTransaction {
+        &self.transaction
+    }
+
+    pub fn get_signatures(&self) -> &[DecoratedSignature] {
+        &self.signatures
+    }
+
+    /// The account whose signature the outer envelope carries: the fee source of a fee bump,
+    /// or the transaction source.
+    pub fn get_signer(&self) -> MuxedAccount {
+        match self.fee_bump {
+            Some((fee_source, _)) => fee_source,
+            None => self.transaction.source,
+        }
+    }
+
+    /// The `TransactionSignaturePayload` for a network. V0 transactions are signed as
+    /// `Transaction`s, which they become by prefixing the Ed25519 key type.
+    pub fn signature_payload(&self, network: &str) -> Vec<u8> {
+        let mut result = network_id(network).to_vec();
+        match self.envelope_type {
+            EnvelopeType::TransactionV0 => {
+                result.extend_from_slice(&ENVELOPE_TYPE_TX.to_be_bytes());
+                result.extend_from_slice(&KEY_TYPE_ED25519.to_be_bytes());
+            }
+            EnvelopeType::Transaction => {
+                result.extend_from_slice(&ENVELOPE_TYPE_TX.to_be_bytes());
+            }
+            EnvelopeType::FeeBump => {
+                result.extend_from_slice(&ENVELOPE_TYPE_TX_FEE_BUMP.to_be_bytes());
+            }
+        }
+        result.extend_from_slice(&self.bytes[self.signed_range.clone()]);
+        result
+    }
+
+    /// The transaction hash, which is what the account key signs.
+    pub fn hash(&self, network: &str) -> [u8; 32] {
+        Sha256::digest(self.signature_payload(network)).into()
+    }
+
+    /// Serializes the envelope with `signature` appended to its signatures.
+    pub fn add_signature(&self, signature: &DecoratedSignature) -> Result<Vec<u8>, Error> {
+        if self.signatures.len() >= MAX_SIGNATURES {
+            return Err(format_err!("envelope has too many signatures"));
+        }
+        let offset = self.signed_range.end;
+        let mut result = self.bytes[..offset].to_vec();
+        result.extend_from_slice(&(self.signatures.len() as u32 + 1).to_be_bytes());
+        result.extend_from_slice(&self.bytes[offset + 4..]);
+        result.extend(signature.serialize());
+        Ok(result)
+    }
+
+    pub fn to_json(&self) -> Value {
+        let envelope_type = match self.envelope_type {
+            EnvelopeType::TransactionV0 => "transaction_v0",
+            EnvelopeType::Transaction => "transaction",
+            EnvelopeType::FeeBump => "fee_bump",
+        };
+        json!({
+            "envelope_type": envelope_type,
+            "transaction": self.transaction.to_json(),
+            "fee_bump": self.fee_bump.map(|(fee_source, fee)| json!({
+                "fee_source": fee_source.to_address(),
+                "fee": fee.to_string(),
+            })),
+            "signatures": self.signatures.iter().map(DecoratedSignature::to_json).collect::<Vec<_>>(),
+        })
+    }
+}
+
+/// Resolves the signing key from a `G...` address or hex, falling back to the request address.
+fn resolve_public_key(public_key: &str, request: &StellarSignRequest) -> Result<[u8; 32], Error> {
+    let key = match public_key.is_empty() {
+        false => public_key.to_string(),
+        true => request
+            .get_address()
+            .and_then(|address| String::from_utf8(address).ok())
+            .ok_or(format_err!("public key is required"))?,
+    };
+    match decode_account_id(&key) {
+        Ok(bytes) => Ok(bytes),
+        Err(_) => hex::decode(remove_prefix_0x(&key))
+            .ok()
+            .and_then(|v| v.try_into().ok())
+            .ok_or(format_err!("public key is invalid")),
+    }
+}
+
+fn decode_transaction_request(request: &StellarSignRequest) -> Result<StellarEnvelope, Error> {
+    match request.get_sign_type() {
+        SignType::Transaction => StellarEnvelope::decode(&request.get_sign_data()),
+        _ => Err(format_err!("sign type is not a transaction")),
+    }
+}
+
+export! {
+    @Java_com_keystone_sdk_KeystoneNativeSDK_decodeStellarSignRequest
+    fn decode_stellar_sign_request(ur_type: &str, cbor_hex: &str, network: &str) -> String {
+        if STELLAR_SIGN_REQUEST.get_type() != ur_type {
+            return json!({"error": "type not match"}).to_string();
+        }
+
+        let decode = || -> Result<Value, Error> {
+            let cbor = hex::decode(cbor_hex)?;
+            let request = StellarSignRequest::try_from(cbor).map_err(|e| format_err!(e.to_string()))?;
+            let envelope = decode_transaction_request(&request)?;
+            let mut value = envelope.to_json();
+            if !network.is_empty() {
+                value["network_passphrase"] = json!(network_passphrase(network));
+                value["hash"] = json!(hex::encode(envelope.hash(network)));
+            }
+            Ok(value)
+        };
+        match decode() {
+            Ok(value) => value.to_string(),
+            Err(e) => json!({"error": e.to_string()}).to_string(),
+        }
+    }
+
+    @Java_com_keystone_sdk_KeystoneNativeSDK_getStellarTransactionHash
+    fn get_stellar_transaction_hash(envelope_hex: &str, network: &str) -> String {
+        if network.is_empty() {
+            return json!({"error": "network passphrase is required"}).to_string();
+        }
+        let hash = || -> Result<StellarEnvelope, Error> {
+            StellarEnvelope::decode(&hex::decode(remove_prefix_0x(envelope_hex))?)
+        };
+        match hash() {
+            Ok(envelope) => json!({
+                "signature_payload": hex::encode(envelope.signature_payload(network)),
+                "hash": hex::encode(envelope.hash(network)),
+            }).to_string(),
+            Err(e) => json!({"error": e.to_string()}).to_string(),
+        }
+    }
+
+    @Java_com_keystone_sdk_KeystoneNativeSDK_attachStellarSignature
+    fn attach_stellar_signature(
+        sign_request_cbor_hex: &str,
+        public_key: &str,
+        network: &str,
+        ur_type: &str,
+        cbor_hex: &str
+    ) -> String {
+        if STELLAR_SIGNATURE.get_type() != ur_type {
+            return json!({"error": "type not match"}).to_string();
+        }
+        if network.is_empty() {
+            return json!({"error": "network passphrase is required"}).to_string();
+        }
+
+        let attach = || -> Result<(StellarEnvelope, Vec<u8>), Error> {
+            let request = StellarSignRequest::try_from(hex::decode(sign_request_cbor_hex)?)
+                .map_err(|e| format_err!(e.to_string()))?;
+            let signature = StellarSignature::try_from(hex::decode(cbor_hex)?)
+                .map_err(|e| format_err!(e.to_string()))?;
+            if let Some(request_id) = request.get_request_id() {
+                if signature.get_request_id() != Some(request_id) {
+                    return Err(format_err!("request id mismatch"));
+                }
+            }
+            let envelope = decode_transaction_request(&request)?;
+            let public_key = resolve_public_key(public_key, &request)?;
+            let signature = signature.get_signature();
+            if !verify_ed25519(&public_key, &envelope.hash(network), &signature) {
+                return Err(format_err!("signature does not match the public key"));
+            }
+            let signed = envelope.add_signature(&DecoratedSignature::new(&public_key, signature))?;
+            Ok((envelope, signed))
+        };
+        match attach() {
+            Ok((envelope, signed)) => json!({
+                "hash": hex::encode(envelope.hash(network)),
+                "envelope": STANDARD.encode(&signed),
+                "envelope_hex": hex::encode(&signed),
+                "signatures": envelope.get_signatures().len() + 1,
+            }).to_string(),
+            Err(e) => json!({"error": e.to_string()}).to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util_internal::ed25519::sign_ed25519;
+    use ur_registry::crypto_key_path::CryptoKeyPath;
+
+    const SEED: [u8; 32] = [3u8; 32];
+
+    fn u32(value: u32) -> Vec<u8> {
+        value.to_be_bytes().to_vec()
+    }
+
+    fn i64(value: i64) -> Vec<u8> {
+        value.to_be_bytes().to_vec()
+    }
+
+    fn muxed(key: &[u8; 32]) -> Vec<u8> {
+        [u32(KEY_TYPE_ED25519), key.to_vec()].concat()
+    }
+
+    fn credit_asset(code: &[u8; 4], issuer: &[u8; 32]) -> Vec<u8> {
+        [u32(1), code.to_vec(), muxed(issuer)].concat()
+    }
+
+    fn sc_symbol(symbol: &str) -> Vec<u8> {
+        let mut bytes = [
+            u32(15),
+            u32(symbol.len() as u32),
+            symbol.as_bytes().to_vec(),
+        ]
+        .concat();
+        bytes.resize(bytes.len() + (4 - symbol.len() % 4) % 4, 0);
+        bytes
+    }
+
+    /// A payment, a USDC trustline and a token `transfer` invocation from `source`.
+    fn transaction(source: &[u8; 32], v0: bool) -> Vec<u8> {
+        let issuer = [0x11; 32];
+        let contract = [0x22; 32];
+        let mut bytes = match v0 {
+            true => source.to_vec(),
+            false => muxed(source),
+        };
+        bytes.extend(u32(300));
+        bytes.extend(i64(42));
+        // Optional time bounds in v0, `PRECOND_TIME` in v1: the encodings coincide.
+        bytes.extend(u32(1));
+        bytes.extend(i64(0));
+        bytes.extend(i64(1700000000));
+        bytes.extend([u32(1), u32(5), b"hello\0\0\0".to_vec()].concat());
+        bytes.extend(u32(match v0 {
+            true => 2,
+            false => 3,
+        }));
+        // payment of 10 XLM
+        bytes.extend([u32(0), u32(1), muxed(&[0x33; 32]), u32(0), i64(100_000_000)].concat());
+        // change trust to USDC
+        bytes.extend(
+            [
+                u32(0),
+                u32(6),
+                credit_asset(b"USDC", &issuer),
+                i64(i64::MAX),
+            ]
+            .concat(),
+        );
+        if !v0 {
+            // transfer(from, to, 1000) with source account authorization
+            bytes.extend(
+                [
+                    u32(0),
+                    u32(24),
+                    u32(0),
+                    u32(1),
+                    contract.to_vec(),
+                    sc_symbol("transfer")[4..].to_vec(),
+                    u32(3),
+                    u32(18),
+                    u32(0),
+                    muxed(source),
+                    u32(18),
+                    u32(1),
+                    contract.to_vec(),
+                    u32(10),
+                    i64(0),
+                    i64(1000),
+                    u32(1),
+                    u32(0),
+                    u32(0),
+                    u32(1),
+                    contract.to_vec(),
+                    sc_symbol("transfer")[4..].to_vec(),
+                    u32(0),
+                    u32(0),
+                ]
+                .concat(),
+            );
+        }
+        bytes.extend(u32(0));
+        bytes
+    }
+
+    fn tx_envelope(source: &[u8; 32]) -> Vec<u8> {
+        [u32(ENVELOPE_TYPE_TX), transaction(source, false), u32(0)].concat()
+    }
+
+    fn sign_request(sign_data: Vec<u8>, address: Option<String>, sign_type: SignType) -> String {
+        let path = CryptoKeyPath::from_path("m/44'/148'/0'".to_string(), None).unwrap();
+        let request = StellarSignRequest::new(
+            Some(vec![1u8; 16]),
+            sign_data,
+            path,
+            address.map(String::into_bytes),
+            None,
+            sign_type,
+        );
+        let cbor: Vec<u8> = request.try_into().unwrap();
+        hex::encode(cbor)
+    }
+
+    fn signature(request_id: Vec<u8>, signature: Vec<u8>) -> String {
+        let cbor: Vec<u8> = StellarSignature::new(Some(request_id), signature)
+            .try_into()
+            .unwrap();
+        hex::encode(cbor)
+    }
+
+    #[test]
+    fn test_decode_envelope() {
+        let source = [0x44; 32];
+        let envelope = StellarEnvelope::decode(&tx_envelope(&source)).unwrap();
+        assert_eq!(EnvelopeType::Transaction, envelope.get_envelope_type());
+        let operations = envelope.get_transaction().get_operations();
+        assert_eq!(3, operations.len());
+        assert_eq!(
+            &OperationBody::Payment {
+                destination: MuxedAccount {
+                    ed25519: [0x33; 32],
+                    id: None
+                },
+                asset: Asset::Native,
+                amount: 100_000_000,
+            },
+            operations[0].get_body()
+        );
+
+        let json = envelope.to_json();
+        let transaction = &json["transaction"];
+        assert_eq!(encode_account_id(&source), transaction["source"]);
+        assert_eq!("hello", transaction["memo"]["value"]);
+        assert_eq!("1700000000", transaction["time_bounds"]["max_time"]);
+        assert_eq!(
+            format!("USDC:{}", encode_account_id(&[0x11; 32])),
+            transaction["operations"][1]["line"]
+        );
+        let function = &transaction["operations"][2]["function"];
+        assert_eq!("transfer", function["function_name"]);
+        assert_eq!(
+            encode_strkey(CONTRACT_VERSION, &[0x22; 32]),
+            function["contract"]
+        );
+        assert_eq!("1000", function["args"][2]["i128"]);
+        assert_eq!(
+            Value::Null,
+            transaction["operations"][2]["auth"][0]["address"]
+        );
+    }
+
+    #[test]
+    fn test_signature_payload() {
+        let source = [0x44; 32];
+        let bytes = tx_envelope(&source);
+        let envelope = StellarEnvelope::decode(&bytes).unwrap();
+        let payload = envelope.signature_payload("testnet");
+        assert_eq!(
+            "cee0302d59844d32bdca915c8203dd44b33fbb7edc19051ea37abedf28ecd472",
+            hex::encode(&payload[..32])
+        );
+        assert_eq!(&bytes[..bytes.len() - 4], &payload[32..]);
+        assert_ne!(envelope.hash("public"), envelope.hash("testnet"));
+
+        // A v0 envelope is signed as the equivalent v1 transaction.
+        let v0 = [u32(ENVELOPE_TYPE_TX_V0), transaction(&source, true), u32(0)].concat();
+        let v0 = StellarEnvelope::decode(&v0).unwrap();
+        let v1 = [
+            u32(ENVELOPE_TYPE_TX),
+            u32(KEY_TYPE_ED25519),
+            transaction(&source, true),
+        ]
+        .concat();
+        assert_eq!(
+            hex::encode(Sha256::digest(
+                [network_id("testnet").to_vec(), v1].concat()
+            )),
+            hex::encode(v0.hash("testnet"))
+        );
+
+        // A fee bump is signed over the fee bump transaction, inner envelope included.
+        let fee_bump_body = [muxed(&[0x55; 32]), i64(1000), tx_envelope(&source), u32(0)].concat();
+        let fee_bump = [
+            u32(ENVELOPE_TYPE_TX_FEE_BUMP),
+            fee_bump_body.clone(),
+            u32(0),
+        ]
+        .concat();
+        let fee_bump = StellarEnvelope::decode(&fee_bump).unwrap();
+        assert_eq!([0x55; 32], fee_bump.get_signer().get_ed25519());
+        assert_eq!(
+            [
+                network_id("testnet").to_vec(),
+                u32(ENVELOPE_TYPE_TX_FEE_BUMP),
+                fee_bump_body
+            ]
+            .concat(),
+            fee_bump.signature_payload("testnet")
+        );
+    }
+
+    #[test]
+    fn test_attach_stellar_signature() {
+        let (public_key, _) = sign_ed25519(&SEED, &[]);
+        let bytes = tx_envelope(&public_key);
+        let hash = StellarEnvelope::decode(&bytes).unwrap().hash("testnet");
+        let (_, sig) = sign_ed25519(&SEED, &hash);
+        let request = sign_request(
+            bytes.clone(),
+            Some(encode_account_id(&public_key)),
+            SignType::Transaction,
+        );
+
+        let result: Value = serde_json::from_str(&attach_stellar_signature(
+            &request,
+            "",
+            "testnet",
+            "stellar-signature",
+            &signature(vec![1u8; 16], sig.to_vec()),
+        ))
+        .unwrap();
+        assert_eq!(hex::encode(hash), result["hash"]);
+        let signed = hex::decode(result["envelope_hex"].as_str().unwrap()).unwrap();
+        assert_eq!(
+            STANDARD.encode(&signed),
+            result["envelope"].as_str().unwrap()
+        );
+        let signed = StellarEnvelope::decode(&signed).unwrap();
+        assert_eq!(
+            &[DecoratedSignature::new(&public_key, sig.to_vec())],
+            signed.get_signatures()
+        );
+        assert_eq!(hash, signed.hash("testnet"));
+
+        let result: Value = serde_json::from_str(&decode_stellar_sign_request(
+            "stellar-sign-request",
+            &request,
+            "testnet",
+        ))
+        .unwrap();
+        assert_eq!(hex::encode(hash), result["hash"]);
+        assert_eq!(TESTNET_NETWORK_PASSPHRASE, result["network_passphrase"]);
+
+        let result: Value = serde_json::from_str(&get_stellar_transaction_hash(
+            &hex::encode(&bytes),
+            TESTNET_NETWORK_PASSPHRASE,
+        ))
+        .unwrap();
+        assert_eq!(hex::encode(hash), result["hash"]);
+    }
+
+    #[test]
+    fn test_attach_stellar_signature_error() {
+        let (public_key, _) = sign_ed25519(&SEED, &[]);
+        let bytes = tx_envelope(&public_key);
+        let hash = StellarEnvelope::decode(&bytes).unwrap().hash("testnet");
+        let (_, raw_sig) = sign_ed25519(&SEED, &hash);
+        let request = sign_request(bytes.clone(), None, SignType::Transaction);
+        let sig = signature(vec![1u8; 16], raw_sig.to_vec());
+
+        assert_eq!(
+            "{\"error\":\"public key is required\"}",
+            attach_stellar_signature(&request, "", "testnet", "stellar-signature", &sig)
+        );
+        assert_eq!(
+            "{\"error\":\"signature does not match the public key\"}",
+            attach_stellar_signature(
+                &request,
+                &hex::encode(public_key),
+                "public",
+                "stellar-signature",
+                &sig
+            )
+        );
+        assert_eq!(
+            "{\"error\":\"network passphrase is required\"}",
+            attach_stellar_signature(&request, "", "", "stellar-signature", &sig)
+        );
+        let public_key_hex = hex::encode(public_key);
+        assert_eq!(
+            "{\"error\":\"request id mismatch\"}",
+            attach_stellar_signature(
+                &request,
+                &public_key_hex,
+                "testnet",
+                "stellar-signature",
+                &signature(vec![2u8; 16], raw_sig.to_vec())
+            )
+        );
+        let cbor: Vec<u8> = StellarSignature::new(None, raw_sig.to_vec())
+            .try_into()
+            .unwrap();
+        assert_eq!(
+            "{\"error\":\"request id mismatch\"}",
+            attach_stellar_signature(
+                &request,
+                &public_key_hex,
+                "testnet",
+                "stellar-signature",
+                &hex::encode(cbor)
+            )
+        );
+        assert_eq!(
+            "{\"error\":\"type not match\"}",
+            attach_stellar_signature(&request, "", "testnet", "stellar-sign-request", &sig)
+        );
+        assert_eq!(
+            "{\"error\":\"sign type is not a transaction\"}",
+            decode_stellar_sign_request(
+                "stellar-sign-request",
+                &sign_request(hash.to_vec(), None, SignType::TransactionHash),
+                ""
+            )
+        );
+        assert_eq!(
+            "{\"error\":\"operation type 3 is not supported\"}",
+            get_stellar_transaction_hash(
+                &hex::encode(
+                    [
+                        u32(ENVELOPE_TYPE_TX),
+                        muxed(&public_key),
+                        u32(100),
+                        i64(1),
+                        u32(0),
+                        u32(0),
+                        u32(1),
+                        u32(0),
+                        u32(3)
+                    ]
+                    .concat()
+                ),
+                "testnet"
+            )
+        );
+    }
+}
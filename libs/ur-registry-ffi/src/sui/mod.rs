@@ -1,2 +1,3 @@
 pub mod sui_sign_request;
 pub mod sui_signature;
+pub mod sui_transaction;
@@ -0,0 +1,670 @@
+use anyhow::{format_err, Error};
+use serde_json::{json, Value};
+use ur_registry::registry_types::SUI_SIGN_REQUEST;
+use ur_registry::sui::sui_sign_request::SuiSignRequest;
+
+use crate::export;
+use crate::util_internal::bcs::BcsReader;
+pub use crate::util_internal::move_types::TypeTag;
+use crate::util_internal::move_types::{format_address, format_short_address};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum IntentScope {
+    TransactionData,
+    TransactionEffects,
+    CheckpointSummary,
+    PersonalMessage,
+}
+
+impl IntentScope {
+    pub fn from_u8(scope: u8) -> Result<Self, Error> {
+        match scope {
+            0 => Ok(IntentScope::TransactionData),
+            1 => Ok(IntentScope::TransactionEffects),
+            2 => Ok(IntentScope::CheckpointSummary),
+            3 => Ok(IntentScope::PersonalMessage),
+            x => Err(format_err!("unsupported intent scope {x}")),
+        }
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            IntentScope::TransactionData => "transaction_data",
+            IntentScope::TransactionEffects => "transaction_effects",
+            IntentScope::CheckpointSummary => "checkpoint_summary",
+            IntentScope::PersonalMessage => "personal_message",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Intent {
+    pub scope: IntentScope,
+    pub version: u8,
+    pub app_id: u8,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ObjectRef {
+    pub object_id: [u8; 32],
+    pub version: u64,
+    pub digest: Vec<u8>,
+}
+
+impl ObjectRef {
+    fn decode(reader: &mut BcsReader) -> Result<Self, Error> {
+        let object_id = reader.fixed()?;
+        let version = reader.u64()?;
+        let digest = reader.bytes()?;
+        if digest.len() != 32 {
+            return Err(format_err!("invalid object digest"));
+        }
+        Ok(ObjectRef {
+            object_id,
+            version,
+            digest,
+        })
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "object_id": format_address(&self.object_id),
+            "version": self.version.to_string(),
+            "digest": bs58::encode(&self.digest).into_string(),
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum CallArg {
+    Pure(Vec<u8>),
+    ImmOrOwnedObject(ObjectRef),
+    SharedObject {
+        object_id: [u8; 32],
+        initial_shared_version: u64,
+        mutable: bool,
+    },
+    Receiving(ObjectRef),
+}
+
+impl CallArg {
+    fn decode(reader: &mut BcsReader) -> Result<Self, Error> {
+        match reader.uleb128()? {
+            0 => Ok(CallArg::Pure(reader.bytes()?)),
+            1 => match reader.uleb128()? {
+                0 => Ok(CallArg::ImmOrOwnedObject(ObjectRef::decode(reader)?)),
+                1 => Ok(CallArg::SharedObject {
+                    object_id: reader.fixed()?,
+                    initial_shared_version: reader.u64()?,
+                    mutable: reader.bool()?,
+                }),
+                2 => Ok(CallArg::Receiving(ObjectRef::decode(reader)?)),
+                x => Err(format_err!("unsupported object argument {x}")),
+            },
+            x => Err(format_err!("unsupported call argument {x}")),
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        match self {
+            CallArg::Pure(bytes) => json!({
+                "type": "pure",
+                "value": format!("0x{}", hex::encode(bytes)),
+            }),
+            CallArg::ImmOrOwnedObject(object) => {
+                let mut result = object.to_json();
+                result["type"] = json!("imm_or_owned_object");
+                result
+            }
+            CallArg::SharedObject {
+                object_id,
+                initial_shared_version,
+                mutable,
+            } => json!({
+                "type": "shared_object",
+                "object_id": format_address(object_id),
+                "initial_shared_version": initial_shared_version.to_string(),
+                "mutable": mutable,
+            }),
+            CallArg::Receiving(object) => {
+                let mut result = object.to_json();
+                result["type"] = json!("receiving");
+                result
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Argument {
+    GasCoin,
+    Input(u16),
+    Result(u16),
+    NestedResult(u16, u16),
+}
+
+impl Argument {
+    fn decode(reader: &mut BcsReader) -> Result<Self, Error> {
+        match reader.uleb128()? {
+            0 => Ok(Argument::GasCoin),
+            1 => Ok(Argument::Input(reader.u16()?)),
+            2 => Ok(Argument::Result(reader.u16()?)),
+            3 => Ok(Argument::NestedResult(reader.u16()?, reader.u16()?)),
+            x => Err(format_err!("unsupported argument {x}")),
+        }
+    }
+
+    fn to_json(self) -> Value {
+        match self {
+            Argument::GasCoin => json!("GasCoin"),
+            Argument::Input(index) => json!({"Input": index}),
+            Argument::Result(index) => json!({"Result": index}),
+            Argument::NestedResult(index, nested) => json!({"NestedResult": [index, nested]}),
+        }
+    }
+}
+
+fn arguments_to_json(arguments: &[Argument]) -> Value {
+    arguments
+        .iter()
+        .map(|argument| argument.to_json())
+        .collect()
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MoveCall {
+    pub package: [u8; 32],
+    pub module: String,
+    pub function: String,
+    pub type_arguments: Vec<TypeTag>,
+    pub arguments: Vec<Argument>,
+}
+
+impl MoveCall {
+    pub fn get_target(&self) -> String {
+        format!(
+            "{}::{}::{}",
+            format_short_address(&self.package),
+            self.module,
+            self.function
+        )
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Command {
+    MoveCall(MoveCall),
+    TransferObjects(Vec<Argument>, Argument),
+    SplitCoins(Argument, Vec<Argument>),
+    MergeCoins(Argument, Vec<Argument>),
+    Publish(Vec<Vec<u8>>, Vec<[u8; 32]>),
+    MakeMoveVec(Option<TypeTag>, Vec<Argument>),
+    Upgrade(Vec<Vec<u8>>, Vec<[u8; 32]>, [u8; 32], Argument),
+}
+
+impl Command {
+    fn decode(reader: &mut BcsReader) -> Result<Self, Error> {
+        match reader.uleb128()? {
+            0 => Ok(Command::MoveCall(MoveCall {
+                package: reader.fixed()?,
+                module: reader.string()?,
+                function: reader.string()?,
+                type_arguments: reader.vec(TypeTag::decode)?,
+                arguments: reader.vec(Argument::decode)?,
+            })),
+            1 => Ok(Command::TransferObjects(
+                reader.vec(Argument::decode)?,
+                Argument::decode(reader)?,
+            )),
+            2 => Ok(Command::SplitCoins(
+                Argument::decode(reader)?,
+                reader.vec(Argument::decode)?,
+            )),
+            3 => Ok(Command::MergeCoins(
+                Argument::decode(reader)?,
+                reader.vec(Argument::decode)?,
+            )),
+            4 => Ok(Command::Publish(
+                reader.vec(|r| r.bytes())?,
+                reader.vec(|r| r.fixed())?,
+            )),
+            5 => Ok(Command::MakeMoveVec(
+                reader.option(TypeTag::decode)?,
+                reader.vec(Argument::decode)?,
+            )),
+            6 => Ok(Command::Upgrade(
+                reader.vec(|r| r.bytes())?,
+                reader.vec(|r| r.fixed())?,
+                reader.fixed()?,
+                Argument::decode(reader)?,
+            )),
+            x => Err(format_err!("unsupported command {x}")),
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        match self {
+            Command::MoveCall(call) => json!({
+                "MoveCall": {
+                    "package": format_address(&call.package),
+                    "module": call.module,
+                    "function": call.function,
+                    "type_arguments": call.type_arguments.iter().map(|t| t.to_string()).collect::<Vec<_>>(),
+                    "arguments": arguments_to_json(&call.arguments),
+                }
+            }),
+            Command::TransferObjects(objects, recipient) => json!({
+                "TransferObjects": [arguments_to_json(objects), recipient.to_json()]
+            }),
+            Command::SplitCoins(coin, amounts) => json!({
+                "SplitCoins": [coin.to_json(), arguments_to_json(amounts)]
+            }),
+            Command::MergeCoins(coin, sources) => json!({
+                "MergeCoins": [coin.to_json(), arguments_to_json(sources)]
+            }),
+            Command::Publish(modules, dependencies) => json!({
+                "Publish": {
+                    "modules": modules.len(),
+                    "dependencies": dependencies.iter().map(format_address).collect::<Vec<_>>(),
+                }
+            }),
+            Command::MakeMoveVec(type_tag, elements) => json!({
+                "MakeMoveVec": [type_tag.as_ref().map(|t| t.to_string()), arguments_to_json(elements)]
+            }),
+            Command::Upgrade(modules, dependencies, package, ticket) => json!({
+                "Upgrade": {
+                    "modules": modules.len(),
+                    "dependencies": dependencies.iter().map(format_address).collect::<Vec<_>>(),
+                    "package": format_address(package),
+                    "ticket": ticket.to_json(),
+                }
+            }),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct GasData {
+    pub payment: Vec<ObjectRef>,
+    pub owner: [u8; 32],
+    pub price: u64,
+    pub budget: u64,
+}
+
+/// A `TransactionData::V1` carrying a programmable transaction.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SuiTransaction {
+    intent: Intent,
+    inputs: Vec<CallArg>,
+    commands: Vec<Command>,
+    sender: [u8; 32],
+    gas_data: GasData,
+    expiration: Option<u64>,
+}
+
+impl SuiTransaction {
+    /// Decodes a BCS `IntentMessage<TransactionData>`.
+    pub fn decode(intent_message: &[u8]) -> Result<Self, Error> {
+        let mut reader = BcsReader::new(intent_message);
+        let intent = Intent {
+            scope: IntentScope::from_u8(reader.u8()?)?,
+            version: reader.u8()?,
+            app_id: reader.u8()?,
+        };
+        if intent.scope != IntentScope::TransactionData {
+            return Err(format_err!("intent is not a transaction"));
+        }
+        if reader.uleb128()? != 0 {
+            return Err(format_err!("unsupported transaction data version"));
+        }
+        if reader.uleb128()? != 0 {
+            return Err(format_err!("transaction is not programmable"));
+        }
+        let inputs = reader.vec(CallArg::decode)?;
+        let commands = reader.vec(Command::decode)?;
+        let sender = reader.fixed()?;
+        let gas_data = GasData {
+            payment: reader.vec(ObjectRef::decode)?,
+            owner: reader.fixed()?,
+            price: reader.u64()?,
+            budget: reader.u64()?,
+        };
+        let expiration = match reader.uleb128()? {
+            0 => None,
+            1 => Some(reader.u64()?),
+            x => return Err(format_err!("unsupported transaction expiration {x}")),
+        };
+        reader.finish()?;
+
+        let transaction = SuiTransaction {
+            intent,
+            inputs,
+            commands,
+            sender,
+            gas_data,
+            expiration,
+        };
+        transaction.validate()?;
+        Ok(transaction)
+    }
+
+    /// Decodes the request intent message and checks that the request addresses, when
+    /// present, are the transaction sender.
+    pub fn from_sign_request(request: &SuiSignRequest) -> Result<Self, Error> {
+        let transaction = Self::decode(&request.get_intent_message())?;
+        if let Some(addresses) = request.get_addresses() {
+            if addresses
+                .iter()
+                .any(|address| address[..] != transaction.sender[..])
+            {
+                return Err(format_err!("request address does not match the sender"));
+            }
+        }
+        Ok(transaction)
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        for (index, command) in self.commands.iter().enumerate() {
+            let arguments: Vec<&Argument> = match command {
+                Command::MoveCall(call) => call.arguments.iter().collect(),
+                Command::TransferObjects(objects, recipient) => {
+                    objects.iter().chain([recipient]).collect()
+                }
+                Command::SplitCoins(coin, others) | Command::MergeCoins(coin, others) => {
+                    [coin].into_iter().chain(others).collect()
+                }
+                Command::MakeMoveVec(_, elements) => elements.iter().collect(),
+                Command::Upgrade(_, _, _, ticket) => vec![ticket],
+                Command::Publish(_, _) => vec![],
+            };
+            for argument in arguments {
+                let valid = match argument {
+                    Argument::GasCoin => true,
+                    Argument::Input(input) => (*input as usize) < self.inputs.len(),
+                    Argument::Result(result) | Argument::NestedResult(result, _) => {
+                        (*result as usize) < index
+                    }
+                };
+                if !valid {
+                    return Err(format_err!("invalid argument in command {index}"));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn get_intent(&self) -> Intent {
+        self.intent
+    }
+
+    pub fn get_inputs(&self) -> Vec<CallArg> {
+        self.inputs.clone()
+    }
+
+    pub fn get_commands(&self) -> Vec<Command> {
+        self.commands.clone()
+    }
+
+    pub fn get_sender(&self) -> [u8; 32] {
+        self.sender
+    }
+
+    pub fn get_gas_data(&self) -> GasData {
+        self.gas_data.clone()
+    }
+
+    pub fn get_expiration(&self) -> Option<u64> {
+        self.expiration
+    }
+
+    fn pure_input(&self, argument: &Argument) -> Option<&[u8]> {
+        match argument {
+            Argument::Input(index) => match self.inputs.get(*index as usize) {
+                Some(CallArg::Pure(bytes)) => Some(bytes),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Describes the commands, resolving pure amounts and recipients where possible.
+    pub fn summary(&self) -> Vec<Value> {
+        self.commands
+            .iter()
+            .map(|command| match command {
+                Command::SplitCoins(coin, amounts) => json!({
+                    "action": "split_coins",
+                    "coin": coin.to_json(),
+                    "amounts": amounts.iter().map(|amount| {
+                        match self.pure_input(amount).and_then(|bytes| <[u8; 8]>::try_from(bytes).ok()) {
+                            Some(bytes) => json!(u64::from_le_bytes(bytes).to_string()),
+                            None => amount.to_json(),
+                        }
+                    }).collect::<Vec<_>>(),
+                }),
+                Command::MergeCoins(coin, sources) => json!({
+                    "action": "merge_coins",
+                    "coin": coin.to_json(),
+                    "sources": arguments_to_json(sources),
+                }),
+                Command::TransferObjects(objects, recipient) => json!({
+                    "action": "transfer_objects",
+                    "objects": arguments_to_json(objects),
+                    "recipient": match self.pure_input(recipient).and_then(|bytes| <[u8; 32]>::try_from(bytes).ok()) {
+                        Some(address) => json!(format_address(&address)),
+                        None => recipient.to_json(),
+                    },
+                }),
+                Command::MoveCall(call) => json!({
+                    "action": "move_call",
+                    "target": call.get_target(),
+                    "type_arguments": call.type_arguments.iter().map(|t| t.to_string()).collect::<Vec<_>>(),
+                }),
+                Command::Publish(_, _) => json!({"action": "publish"}),
+                Command::MakeMoveVec(_, _) => json!({"action": "make_move_vec"}),
+                Command::Upgrade(_, _, package, _) => json!({
+                    "action": "upgrade",
+                    "package": format_address(package),
+                }),
+            })
+            .collect()
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "intent": {
+                "scope": self.intent.scope.get_name(),
+                "version": self.intent.version,
+                "app_id": self.intent.app_id,
+            },
+            "sender": format_address(&self.sender),
+            "inputs": self.inputs.iter().map(CallArg::to_json).collect::<Vec<_>>(),
+            "commands": self.commands.iter().map(Command::to_json).collect::<Vec<_>>(),
+            "gas_data": {
+                "payment": self.gas_data.payment.iter().map(ObjectRef::to_json).collect::<Vec<_>>(),
+                "owner": format_address(&self.gas_data.owner),
+                "price": self.gas_data.price.to_string(),
+                "budget": self.gas_data.budget.to_string(),
+            },
+            "expiration": self.expiration.map(|epoch| json!({"epoch": epoch.to_string()})),
+            "summary": self.summary(),
+        })
+    }
+}
+
+export! {
+    @Java_com_keystone_sdk_KeystoneNativeSDK_decodeSuiSignRequest
+    fn decode_sui_sign_request(ur_type: &str, cbor_hex: &str) -> String {
+        if SUI_SIGN_REQUEST.get_type() != ur_type {
+            return json!({"error": "type not match"}).to_string();
+        }
+
+        let decode = || -> Result<Value, Error> {
+            let cbor = hex::decode(cbor_hex)?;
+            let request = SuiSignRequest::try_from(cbor).map_err(|e| format_err!(e.to_string()))?;
+            Ok(SuiTransaction::from_sign_request(&request)?.to_json())
+        };
+        match decode() {
+            Ok(transaction) => transaction.to_string(),
+            Err(e) => json!({"error": e.to_string()}).to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ur_registry::crypto_key_path::CryptoKeyPath;
+
+    const INTENT_MESSAGE: &str = "00000000000200201ff915a5e9e32fdbe0135535b6c69a00a9809aaf7f7c0275d3239ca79db20d6400081027000000000000020200010101000101020000010000ebe623e33b7307f1350f8934beb3fb16baef0fc1b3f1b92868eec3944093886901a2e3e42930675d9571a467eb5d4b22553c93ccb84e9097972e02c490b4e7a22ab73200000000000020176c4727433105da34209f04ac3f22e192a2573d7948cb2fabde7d13a7f4f149ebe623e33b7307f1350f8934beb3fb16baef0fc1b3f1b92868eec39440938869e803000000000000640000000000000000";
+    const SENDER: &str = "ebe623e33b7307f1350f8934beb3fb16baef0fc1b3f1b92868eec39440938869";
+
+    fn sign_request(intent_message: &[u8], address: &str) -> String {
+        let path = CryptoKeyPath::from_path("m/44'/784'/0'/0'/0'".to_string(), None).unwrap();
+        let request = SuiSignRequest::new(
+            None,
+            intent_message.to_vec(),
+            vec![path],
+            Some(vec![hex::decode(address).unwrap()]),
+            None,
+        );
+        let cbor: Vec<u8> = request.try_into().unwrap();
+        hex::encode(cbor)
+    }
+
+    #[test]
+    fn test_decode_sui_sign_request() {
+        let intent_message = hex::decode(INTENT_MESSAGE).unwrap();
+        let transaction = SuiTransaction::decode(&intent_message).unwrap();
+        assert_eq!(IntentScope::TransactionData, transaction.get_intent().scope);
+        assert_eq!(2, transaction.get_inputs().len());
+        assert_eq!(
+            vec![
+                Command::SplitCoins(Argument::GasCoin, vec![Argument::Input(1)]),
+                Command::TransferObjects(vec![Argument::Result(0)], Argument::Input(0)),
+            ],
+            transaction.get_commands()
+        );
+        assert_eq!(1000, transaction.get_gas_data().price);
+        assert_eq!(100, transaction.get_gas_data().budget);
+
+        let result: Value = serde_json::from_str(&decode_sui_sign_request(
+            "sui-sign-request",
+            &sign_request(&intent_message, SENDER),
+        ))
+        .unwrap();
+        assert_eq!(format!("0x{SENDER}"), result["sender"]);
+        assert_eq!(
+            json!([
+                {"action": "split_coins", "coin": "GasCoin", "amounts": ["10000"]},
+                {
+                    "action": "transfer_objects",
+                    "objects": [{"Result": 0}],
+                    "recipient": "0x1ff915a5e9e32fdbe0135535b6c69a00a9809aaf7f7c0275d3239ca79db20d64",
+                },
+            ]),
+            result["summary"]
+        );
+        assert_eq!(
+            "0xa2e3e42930675d9571a467eb5d4b22553c93ccb84e9097972e02c490b4e7a22a",
+            result["gas_data"]["payment"][0]["object_id"]
+        );
+    }
+
+    #[test]
+    fn test_decode_move_call() {
+        let mut intent_message = vec![0, 0, 0, 0, 0];
+        // One shared object input.
+        intent_message.extend_from_slice(&[1, 1, 1]);
+        intent_message.extend_from_slice(&[6u8; 32]);
+        intent_message.extend_from_slice(&5u64.to_le_bytes());
+        intent_message.push(1);
+        // 0x2::coin::join<0x2::sui::SUI>(Input(0), GasCoin)
+        intent_message.extend_from_slice(&[1, 0]);
+        let mut framework = [0u8; 32];
+        framework[31] = 2;
+        intent_message.extend_from_slice(&framework);
+        intent_message.extend_from_slice(b"\x04coin\x04join\x01\x07");
+        intent_message.extend_from_slice(&framework);
+        intent_message.extend_from_slice(b"\x03sui\x03SUI\x00\x02\x01\x00\x00\x00");
+        intent_message.extend_from_slice(&[9u8; 32]);
+        intent_message.push(0);
+        intent_message.extend_from_slice(&[9u8; 32]);
+        intent_message.extend_from_slice(&1000u64.to_le_bytes());
+        intent_message.extend_from_slice(&5000000u64.to_le_bytes());
+        intent_message.push(1);
+        intent_message.extend_from_slice(&42u64.to_le_bytes());
+
+        let transaction = SuiTransaction::decode(&intent_message).unwrap();
+        let result = transaction.to_json();
+        assert_eq!("shared_object", result["inputs"][0]["type"]);
+        assert_eq!("5", result["inputs"][0]["initial_shared_version"]);
+        assert_eq!(
+            json!({
+                "MoveCall": {
+                    "package": format_address(&framework),
+                    "module": "coin",
+                    "function": "join",
+                    "type_arguments": ["0x2::sui::SUI"],
+                    "arguments": [{"Input": 0}, "GasCoin"],
+                }
+            }),
+            result["commands"][0]
+        );
+        assert_eq!("0x2::coin::join", result["summary"][0]["target"]);
+        assert_eq!("42", result["expiration"]["epoch"]);
+    }
+
+    #[test]
+    fn test_decode_sui_sign_request_error() {
+        let intent_message = hex::decode(INTENT_MESSAGE).unwrap();
+        assert_eq!(
+            "{\"error\":\"request address does not match the sender\"}",
+            decode_sui_sign_request(
+                "sui-sign-request",
+                &sign_request(&intent_message, &"11".repeat(32))
+            )
+        );
+        assert_eq!(
+            "{\"error\":\"type not match\"}",
+            decode_sui_sign_request("sui-signature", &sign_request(&intent_message, SENDER))
+        );
+
+        let mut personal_message = intent_message.clone();
+        personal_message[0] = 3;
+        assert_eq!(
+            "intent is not a transaction",
+            SuiTransaction::decode(&personal_message)
+                .unwrap_err()
+                .to_string()
+        );
+
+        // A move call whose type argument is a deeply nested vector.
+        let mut nested_message = vec![0, 0, 0, 0, 0, 0, 1, 0];
+        nested_message.extend_from_slice(&[2u8; 32]);
+        nested_message.extend_from_slice(b"\x04coin\x04join\x01");
+        nested_message.extend_from_slice(&vec![6u8; 500_000]);
+        assert_eq!(
+            "{\"error\":\"type tag is nested too deeply\"}",
+            decode_sui_sign_request("sui-sign-request", &sign_request(&nested_message, SENDER))
+        );
+
+        // TransferObjects referencing the result of a later command.
+        let mut invalid = intent_message.clone();
+        let position = intent_message
+            .windows(8)
+            .position(|window| window == [1, 1, 2, 0, 0, 1, 0, 0])
+            .unwrap();
+        invalid[position + 3] = 1;
+        assert_eq!(
+            "invalid argument in command 1",
+            SuiTransaction::decode(&invalid).unwrap_err().to_string()
+        );
+        assert_eq!(
+            "unexpected trailing bytes",
+            SuiTransaction::decode(&[intent_message.as_slice(), &[0]].concat())
+                .unwrap_err()
+                .to_string()
+        );
+    }
+}
@@ -1,5 +1,5 @@
 use crate::export;
-use crate::util_internal::account_helper::gen_extra_data;
+use crate::util_internal::account_helper::{gen_chain_info, gen_extra_data};
 use crate::util_internal::chain::map_coin_type;
 use anyhow::format_err;
 use anyhow::Error;
@@ -20,6 +20,15 @@ pub struct AccountExtra {
     pub okx: OkxExtra,
 }
 
+/// The EVM chain an account defaults to, from the chain registry.
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+pub struct ChainInfo {
+    pub chain_id: u64,
+    pub name: String,
+    pub caip2_id: String,
+    pub currency_symbol: String,
+}
+
 #[derive(Default, Clone, Debug, Serialize, Deserialize)]
 pub struct Account {
     chain: String,
@@ -33,6 +42,8 @@ pub struct Account {
     #[serde(skip_serializing_if = "Option::is_none")]
     xfp: Option<String>,
     extra: AccountExtra,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    chain_info: Option<ChainInfo>,
 }
 
 impl core::convert::From<&CryptoHDKey> for Account {
@@ -78,6 +89,7 @@ impl core::convert::From<&CryptoHDKey> for Account {
             note: value.get_note(),
             xfp,
             extra: gen_extra_data(coin_type),
+            chain_info: gen_chain_info(coin_type),
         }
     }
 }
@@ -108,7 +120,7 @@ mod tests {
     #[test]
     fn test_parse_crypto_hd_key() {
         let hd_key_cbor = "a902f403582102cc6d7834204653ff10e0047a2395343cc6df081e76c88d5eee83f346f0b21cb7045820712a9187e5c60c573a5acce855445376e1b74c240e417fe8cb2a8fdfd78d2d9d05d90131a201183c020006d90130a30186182cf5183cf500f5021af23f9fd2030307d90130a2018400f480f40300081a483c932809684b657973746f6e650a706163636f756e742e7374616e64617264";
-        let expect_result = r#"{"chain":"ETH","chain_code":"712a9187e5c60c573a5acce855445376e1b74c240e417fe8cb2a8fdfd78d2d9d","extended_public_key":"xpub6CBZfsQuZgVnvTcScAAXSxtX5jdMHtX5LdRuygnTScMBbKyjsxznd8XMEqDntdY1jigmjunwRwHsQs3xusYQBVFbvLdN4YLzH8caLSSiAoV","extra":{"okx":{"chain_id":1}},"name":"Keystone","note":"account.standard","path":"m/44'/60'/0'","public_key":"02cc6d7834204653ff10e0047a2395343cc6df081e76c88d5eee83f346f0b21cb7","xfp":"f23f9fd2"}"#;
+        let expect_result = r#"{"chain":"ETH","chain_code":"712a9187e5c60c573a5acce855445376e1b74c240e417fe8cb2a8fdfd78d2d9d","chain_info":{"caip2_id":"eip155:1","chain_id":1,"currency_symbol":"ETH","name":"Ethereum Mainnet"},"extended_public_key":"xpub6CBZfsQuZgVnvTcScAAXSxtX5jdMHtX5LdRuygnTScMBbKyjsxznd8XMEqDntdY1jigmjunwRwHsQs3xusYQBVFbvLdN4YLzH8caLSSiAoV","extra":{"okx":{"chain_id":1}},"name":"Keystone","note":"account.standard","path":"m/44'/60'/0'","public_key":"02cc6d7834204653ff10e0047a2395343cc6df081e76c88d5eee83f346f0b21cb7","xfp":"f23f9fd2"}"#;
 
         assert_eq!(
             expect_result,
@@ -1,2 +1,6 @@
+pub mod ton_address;
+pub mod ton_cell;
+pub mod ton_proof;
 pub mod ton_sign_request;
 pub mod ton_signature;
+pub mod ton_transaction;
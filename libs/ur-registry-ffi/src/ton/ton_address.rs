@@ -0,0 +1,120 @@
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{format_err, Error};
+use base64::engine::general_purpose::{STANDARD, URL_SAFE};
+use base64::Engine;
+
+use crate::util_internal::checksum::crc16_xmodem;
+
+const BOUNCEABLE_TAG: u8 = 0x11;
+const NON_BOUNCEABLE_TAG: u8 = 0x51;
+const TESTNET_FLAG: u8 = 0x80;
+
+/// A standard TON address: a workchain and the 256-bit account id.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TonAddress {
+    workchain: i8,
+    hash: [u8; 32],
+}
+
+impl TonAddress {
+    pub fn new(workchain: i8, hash: [u8; 32]) -> Self {
+        TonAddress { workchain, hash }
+    }
+
+    pub fn get_workchain(&self) -> i8 {
+        self.workchain
+    }
+
+    pub fn get_hash(&self) -> [u8; 32] {
+        self.hash
+    }
+
+    /// Formats the raw `<workchain>:<hex>` form.
+    pub fn to_raw(&self) -> String {
+        format!("{}:{}", self.workchain, hex::encode(self.hash))
+    }
+
+    /// Formats the url-safe base64 form used by wallets.
+    pub fn to_friendly(&self, bounceable: bool, testnet: bool) -> String {
+        let mut tag = match bounceable {
+            true => BOUNCEABLE_TAG,
+            false => NON_BOUNCEABLE_TAG,
+        };
+        if testnet {
+            tag |= TESTNET_FLAG;
+        }
+        let mut bytes = vec![tag, self.workchain as u8];
+        bytes.extend_from_slice(&self.hash);
+        bytes.extend_from_slice(&crc16_xmodem(&bytes).to_be_bytes());
+        URL_SAFE.encode(bytes)
+    }
+
+    fn from_friendly(address: &str) -> Result<Self, Error> {
+        let bytes = URL_SAFE
+            .decode(address)
+            .or_else(|_| STANDARD.decode(address))
+            .map_err(|_| format_err!("address is invalid"))?;
+        if bytes.len() != 36 || crc16_xmodem(&bytes[..34]).to_be_bytes() != bytes[34..] {
+            return Err(format_err!("address is invalid"));
+        }
+        match bytes[0] & !TESTNET_FLAG {
+            BOUNCEABLE_TAG | NON_BOUNCEABLE_TAG => Ok(TonAddress {
+                workchain: bytes[1] as i8,
+                hash: bytes[2..34].try_into()?,
+            }),
+            _ => Err(format_err!("address is invalid")),
+        }
+    }
+}
+
+impl fmt::Display for TonAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_raw())
+    }
+}
+
+impl FromStr for TonAddress {
+    type Err = Error;
+
+    /// Parses either the raw or the user-friendly form.
+    fn from_str(address: &str) -> Result<Self, Self::Err> {
+        match address.split_once(':') {
+            Some((workchain, hash)) => Ok(TonAddress {
+                workchain: workchain
+                    .parse()
+                    .map_err(|_| format_err!("address is invalid"))?,
+                hash: hex::decode(hash)
+                    .ok()
+                    .and_then(|v| v.try_into().ok())
+                    .ok_or(format_err!("address is invalid"))?,
+            }),
+            None => TonAddress::from_friendly(address),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ton_address() {
+        let address: TonAddress = "UQC1IywyQwixSOU8pezOZDC9rv2xCV4CGJzOWH6RX8BTsGJx"
+            .parse()
+            .unwrap();
+        assert_eq!(0, address.get_workchain());
+        assert_eq!(
+            "UQC1IywyQwixSOU8pezOZDC9rv2xCV4CGJzOWH6RX8BTsGJx",
+            address.to_friendly(false, false)
+        );
+        assert_eq!(address, address.to_raw().parse().unwrap());
+        assert_eq!(address, address.to_friendly(true, true).parse().unwrap());
+
+        assert!("UQC1IywyQwixSOU8pezOZDC9rv2xCV4CGJzOWH6RX8BTsGJy"
+            .parse::<TonAddress>()
+            .is_err());
+        assert!("0:1234".parse::<TonAddress>().is_err());
+    }
+}
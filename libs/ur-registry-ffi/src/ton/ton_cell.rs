@@ -0,0 +1,542 @@
+use std::rc::Rc;
+
+use anyhow::{format_err, Error};
+use sha2::{Digest, Sha256};
+
+use crate::ton::ton_address::TonAddress;
+
+const BOC_MAGIC: [u8; 4] = [0xb5, 0xee, 0x9c, 0x72];
+const MAX_BITS: usize = 1023;
+const MAX_REFS: usize = 4;
+/// The maximum depth of a cell tree; deeper trees are rejected by the network.
+const MAX_DEPTH: u16 = 1024;
+
+/// CRC-32C, the optional checksum at the end of a bag of cells.
+fn crc32c(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in data {
+        crc ^= u32::from(*byte);
+        for _ in 0..8 {
+            crc = match crc & 1 {
+                0 => crc >> 1,
+                _ => (crc >> 1) ^ 0x82f6_3b78,
+            };
+        }
+    }
+    !crc
+}
+
+/// An ordinary TON cell. The hash and depth are computed once, when the cell is built.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Cell {
+    data: Vec<u8>,
+    bit_len: usize,
+    refs: Vec<Rc<Cell>>,
+    depth: u16,
+    hash: [u8; 32],
+}
+
+impl Cell {
+    /// Builds a cell from `bit_len` bits of `data`; bits past `bit_len` are ignored.
+    pub fn new(mut data: Vec<u8>, bit_len: usize, refs: Vec<Rc<Cell>>) -> Result<Self, Error> {
+        if bit_len > MAX_BITS || refs.len() > MAX_REFS || data.len() * 8 < bit_len {
+            return Err(format_err!("cell is invalid"));
+        }
+        data.truncate(bit_len.div_ceil(8));
+        if bit_len % 8 != 0 {
+            let last = data.len() - 1;
+            data[last] &= 0xff << (8 - bit_len % 8);
+        }
+        let depth = match refs.iter().map(|cell| cell.depth).max() {
+            Some(depth) => depth + 1,
+            None => 0,
+        };
+        if depth > MAX_DEPTH {
+            return Err(format_err!("cell is nested too deeply"));
+        }
+        let mut cell = Cell {
+            data,
+            bit_len,
+            refs,
+            depth,
+            hash: [0; 32],
+        };
+        let mut hasher = Sha256::new();
+        hasher.update(cell.descriptors());
+        hasher.update(cell.padded_data());
+        for child in &cell.refs {
+            hasher.update(child.depth.to_be_bytes());
+        }
+        for child in &cell.refs {
+            hasher.update(child.hash);
+        }
+        cell.hash = hasher.finalize().into();
+        Ok(cell)
+    }
+
+    pub fn get_bit_len(&self) -> usize {
+        self.bit_len
+    }
+
+    pub fn get_refs(&self) -> &[Rc<Cell>] {
+        &self.refs
+    }
+
+    /// The representation hash, which wallets sign.
+    pub fn hash(&self) -> [u8; 32] {
+        self.hash
+    }
+
+    pub fn parse(&self) -> CellSlice<'_> {
+        CellSlice {
+            cell: self,
+            bit: 0,
+            reference: 0,
+        }
+    }
+
+    fn descriptors(&self) -> [u8; 2] {
+        [
+            self.refs.len() as u8,
+            (self.bit_len / 8 + self.bit_len.div_ceil(8)) as u8,
+        ]
+    }
+
+    /// The data with the completion tag appended when the last byte is partial.
+    fn padded_data(&self) -> Vec<u8> {
+        let mut data = self.data.clone();
+        if self.bit_len % 8 != 0 {
+            let last = data.len() - 1;
+            data[last] |= 0x80 >> (self.bit_len % 8);
+        }
+        data
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        if self.bytes.len() < len {
+            return Err(format_err!("unexpected end of boc data"));
+        }
+        let (head, tail) = self.bytes.split_at(len);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    fn uint(&mut self, len: usize) -> Result<usize, Error> {
+        Ok(self
+            .take(len)?
+            .iter()
+            .fold(0, |acc, byte| (acc << 8) | usize::from(*byte)))
+    }
+}
+
+/// Deserializes a bag of cells, returning its root cells.
+pub fn parse_boc(bytes: &[u8]) -> Result<Vec<Rc<Cell>>, Error> {
+    let mut reader = Reader { bytes };
+    if reader.take(4)? != BOC_MAGIC {
+        return Err(format_err!("boc magic is invalid"));
+    }
+    let flags = reader.uint(1)?;
+    let has_index = flags & 0x80 != 0;
+    let has_crc32c = flags & 0x40 != 0;
+    let size = flags & 0x07;
+    let offset_size = reader.uint(1)?;
+    if !(1..=4).contains(&size) || !(1..=8).contains(&offset_size) {
+        return Err(format_err!("boc header is invalid"));
+    }
+    let cells_num = reader.uint(size)?;
+    let roots_num = reader.uint(size)?;
+    if reader.uint(size)? != 0 {
+        return Err(format_err!("absent cells are not supported"));
+    }
+    let data_size = reader.uint(offset_size)?;
+    if cells_num > data_size / 2 || roots_num > cells_num {
+        return Err(format_err!("boc header is invalid"));
+    }
+    let roots = (0..roots_num)
+        .map(|_| reader.uint(size))
+        .collect::<Result<Vec<_>, _>>()?;
+    if has_index {
+        reader.take(cells_num * offset_size)?;
+    }
+    let mut data = Reader {
+        bytes: reader.take(data_size)?,
+    };
+    if has_crc32c {
+        let checksum = reader.take(4)?;
+        if crc32c(&bytes[..bytes.len() - 4]).to_le_bytes() != checksum {
+            return Err(format_err!("boc checksum is invalid"));
+        }
+    }
+    if !reader.bytes.is_empty() {
+        return Err(format_err!("unexpected trailing boc data"));
+    }
+
+    let mut raw_cells = Vec::with_capacity(cells_num);
+    for _ in 0..cells_num {
+        let d1 = data.uint(1)?;
+        let d2 = data.uint(1)?;
+        if d1 & 0xf8 != 0 {
+            return Err(format_err!("exotic cells are not supported"));
+        }
+        let mut bytes = data.take(d2.div_ceil(2))?.to_vec();
+        let mut bit_len = d2 / 2 * 8;
+        if d2 % 2 == 1 {
+            let last = bytes.last().copied().unwrap_or(0);
+            if last == 0 {
+                return Err(format_err!("cell padding is invalid"));
+            }
+            bit_len += 7 - last.trailing_zeros() as usize;
+        }
+        let refs = (0..d1 & 0x07)
+            .map(|_| data.uint(size))
+            .collect::<Result<Vec<_>, _>>()?;
+        bytes.truncate(bit_len.div_ceil(8));
+        raw_cells.push((bytes, bit_len, refs));
+    }
+    if !data.bytes.is_empty() {
+        return Err(format_err!("unexpected trailing cell data"));
+    }
+
+    let mut cells: Vec<Option<Rc<Cell>>> = vec![None; cells_num];
+    for (index, (bytes, bit_len, refs)) in raw_cells.into_iter().enumerate().rev() {
+        let refs = refs
+            .into_iter()
+            .map(|child| match child > index {
+                true => cells.get(child).cloned().flatten(),
+                false => None,
+            })
+            .collect::<Option<Vec<_>>>()
+            .ok_or(format_err!("cell reference is invalid"))?;
+        cells[index] = Some(Rc::new(Cell::new(bytes, bit_len, refs)?));
+    }
+    roots
+        .into_iter()
+        .map(|root| {
+            cells
+                .get(root)
+                .cloned()
+                .flatten()
+                .ok_or(format_err!("root cell is invalid"))
+        })
+        .collect()
+}
+
+/// Parses a bag of cells that holds exactly one root.
+pub fn parse_boc_root(bytes: &[u8]) -> Result<Rc<Cell>, Error> {
+    let mut roots = parse_boc(bytes)?;
+    match roots.len() {
+        1 => Ok(roots.remove(0)),
+        _ => Err(format_err!("boc must have a single root")),
+    }
+}
+
+/// A cursor over the bits and references of a cell.
+#[derive(Clone, Debug)]
+pub struct CellSlice<'a> {
+    cell: &'a Cell,
+    bit: usize,
+    reference: usize,
+}
+
+impl<'a> CellSlice<'a> {
+    pub fn remaining_bits(&self) -> usize {
+        self.cell.bit_len - self.bit
+    }
+
+    pub fn remaining_refs(&self) -> usize {
+        self.cell.refs.len() - self.reference
+    }
+
+    pub fn bit(&mut self) -> Result<bool, Error> {
+        if self.remaining_bits() == 0 {
+            return Err(format_err!("unexpected end of cell data"));
+        }
+        let value = self.cell.data[self.bit / 8] & (0x80 >> (self.bit % 8)) != 0;
+        self.bit += 1;
+        Ok(value)
+    }
+
+    /// Reads an unsigned big-endian integer of up to 128 bits.
+    pub fn uint(&mut self, bits: usize) -> Result<u128, Error> {
+        if bits > 128 || self.remaining_bits() < bits {
+            return Err(format_err!("unexpected end of cell data"));
+        }
+        (0..bits).try_fold(0u128, |acc, _| Ok((acc << 1) | u128::from(self.bit()?)))
+    }
+
+    pub fn u8(&mut self) -> Result<u8, Error> {
+        Ok(self.uint(8)? as u8)
+    }
+
+    pub fn u32(&mut self) -> Result<u32, Error> {
+        Ok(self.uint(32)? as u32)
+    }
+
+    pub fn u64(&mut self) -> Result<u64, Error> {
+        Ok(self.uint(64)? as u64)
+    }
+
+    pub fn bytes(&mut self, len: usize) -> Result<Vec<u8>, Error> {
+        (0..len).map(|_| self.u8()).collect()
+    }
+
+    pub fn skip(&mut self, bits: usize) -> Result<(), Error> {
+        if self.remaining_bits() < bits {
+            return Err(format_err!("unexpected end of cell data"));
+        }
+        self.bit += bits;
+        Ok(())
+    }
+
+    /// Reads a `VarUInteger 16`, the encoding of coin amounts.
+    pub fn coins(&mut self) -> Result<u128, Error> {
+        let len = self.uint(4)? as usize;
+        self.uint(len * 8)
+    }
+
+    pub fn reference(&mut self) -> Result<&'a Cell, Error> {
+        let cell = self
+            .cell
+            .refs
+            .get(self.reference)
+            .ok_or(format_err!("unexpected end of cell references"))?;
+        self.reference += 1;
+        Ok(cell)
+    }
+
+    /// Reads a `Maybe ^Cell`.
+    pub fn maybe_reference(&mut self) -> Result<Option<&'a Cell>, Error> {
+        match self.bit()? {
+            true => Ok(Some(self.reference()?)),
+            false => Ok(None),
+        }
+    }
+
+    /// Reads an `Either X ^X`, returning a slice over the inline or referenced value.
+    pub fn either(&mut self) -> Result<CellSlice<'a>, Error> {
+        match self.bit()? {
+            true => Ok(self.reference()?.parse()),
+            false => {
+                let inline = self.clone();
+                self.bit = self.cell.bit_len;
+                self.reference = self.cell.refs.len();
+                Ok(inline)
+            }
+        }
+    }
+
+    /// Reads a `MsgAddress`; `None` stands for `addr_none` and external addresses.
+    pub fn address(&mut self) -> Result<Option<TonAddress>, Error> {
+        match self.uint(2)? {
+            0 => Ok(None),
+            1 => {
+                let len = self.uint(9)? as usize;
+                self.skip(len)?;
+                Ok(None)
+            }
+            2 => {
+                if self.bit()? {
+                    return Err(format_err!("anycast addresses are not supported"));
+                }
+                let workchain = self.u8()? as i8;
+                let hash = self.bytes(32)?.try_into().unwrap_or_default();
+                Ok(Some(TonAddress::new(workchain, hash)))
+            }
+            _ => Err(format_err!("variable addresses are not supported")),
+        }
+    }
+
+    /// Reads the remaining bytes of a snake-encoded string, following the first reference of
+    /// every cell in the chain.
+    pub fn snake_string(&mut self) -> Result<String, Error> {
+        let mut bytes = vec![];
+        let mut slice = self.clone();
+        loop {
+            if slice.remaining_bits() % 8 != 0 {
+                return Err(format_err!("snake data is not byte aligned"));
+            }
+            bytes.extend(slice.bytes(slice.remaining_bits() / 8)?);
+            match slice.remaining_refs() {
+                0 => break,
+                _ => slice = slice.reference()?.parse(),
+            }
+        }
+        self.bit = self.cell.bit_len;
+        self.reference = self.cell.refs.len();
+        String::from_utf8(bytes).map_err(|_| format_err!("string is not utf-8"))
+    }
+
+    /// Copies the next `bits` bits and all remaining references into a new cell.
+    pub fn to_cell(&self, bits: usize) -> Result<Cell, Error> {
+        let mut slice = self.clone();
+        let mut data = vec![0u8; bits.div_ceil(8)];
+        for index in 0..bits {
+            if slice.bit()? {
+                data[index / 8] |= 0x80 >> (index % 8);
+            }
+        }
+        Cell::new(data, bits, self.cell.refs[self.reference..].to_vec())
+    }
+}
+
+/// Serializes a single-root bag of cells with a CRC-32C checksum.
+#[cfg(test)]
+pub fn serialize_boc(root: &Rc<Cell>) -> Vec<u8> {
+    fn collect(cell: &Rc<Cell>, cells: &mut Vec<Rc<Cell>>) {
+        if cells.iter().any(|known| Rc::ptr_eq(known, cell)) {
+            return;
+        }
+        cells.push(cell.clone());
+        for child in &cell.refs {
+            collect(child, cells);
+        }
+    }
+    let mut cells = vec![];
+    collect(root, &mut cells);
+    let mut data = vec![];
+    for cell in &cells {
+        data.extend_from_slice(&cell.descriptors());
+        data.extend(cell.padded_data());
+        for child in &cell.refs {
+            data.push(cells.iter().position(|c| Rc::ptr_eq(c, child)).unwrap() as u8);
+        }
+    }
+    let mut result = BOC_MAGIC.to_vec();
+    let offset_size: usize = if data.len() < 0x100 { 1 } else { 2 };
+    result.extend_from_slice(&[0x41, offset_size as u8, cells.len() as u8, 1, 0]);
+    result.extend_from_slice(&data.len().to_be_bytes()[8 - offset_size..]);
+    result.push(0);
+    result.extend(data);
+    result.extend_from_slice(&crc32c(&result).to_le_bytes());
+    result
+}
+
+/// Appends bits to a cell under construction, for building test messages.
+#[cfg(test)]
+#[derive(Default)]
+pub struct CellBuilder {
+    bits: Vec<bool>,
+    refs: Vec<Rc<Cell>>,
+}
+
+#[cfg(test)]
+impl CellBuilder {
+    pub fn uint(mut self, value: u128, bits: usize) -> Self {
+        self.bits
+            .extend((0..bits).rev().map(|index| (value >> index) & 1 == 1));
+        self
+    }
+
+    pub fn bytes(self, bytes: &[u8]) -> Self {
+        bytes
+            .iter()
+            .fold(self, |builder, byte| builder.uint(u128::from(*byte), 8))
+    }
+
+    pub fn coins(self, value: u128) -> Self {
+        let len = (128 - value.leading_zeros() as usize).div_ceil(8);
+        self.uint(len as u128, 4).uint(value, len * 8)
+    }
+
+    pub fn address(self, address: Option<&TonAddress>) -> Self {
+        match address {
+            Some(address) => self
+                .uint(0b100, 3)
+                .uint(address.get_workchain() as u8 as u128, 8)
+                .bytes(&address.get_hash()),
+            None => self.uint(0, 2),
+        }
+    }
+
+    pub fn reference(mut self, cell: Rc<Cell>) -> Self {
+        self.refs.push(cell);
+        self
+    }
+
+    pub fn build(self) -> Rc<Cell> {
+        let mut data = vec![0u8; self.bits.len().div_ceil(8)];
+        for (index, bit) in self.bits.iter().enumerate() {
+            if *bit {
+                data[index / 8] |= 0x80 >> (index % 8);
+            }
+        }
+        Rc::new(Cell::new(data, self.bits.len(), self.refs).unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_boc() {
+        let boc = hex::decode("b5ee9c7241010201004700011c29a9a317663b3ea500000008000301006842002b16732f1c05fdb4e8d3a78fd10dddef3f6067f311be539313b8a44a504d4da2a1dcd65000000000000000000000000000007072e06f").unwrap();
+        let root = parse_boc_root(&boc).unwrap();
+        assert_eq!(112, root.get_bit_len());
+        assert_eq!(1, root.get_refs().len());
+        let mut slice = root.parse();
+        assert_eq!(698983191, slice.u32().unwrap());
+        assert_eq!(serialize_boc(&root), boc);
+
+        let mut tampered = boc.clone();
+        tampered[20] ^= 1;
+        assert_eq!(
+            "boc checksum is invalid",
+            parse_boc(&tampered).unwrap_err().to_string()
+        );
+        assert!(parse_boc(&boc[..boc.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn test_parse_deep_boc() {
+        // A chain of cells where each cell references the next one, with 3-byte indexes.
+        let chain = |cells_num: u32| {
+            let index = |i: u32| i.to_be_bytes()[1..].to_vec();
+            let mut data = vec![];
+            for i in 1..cells_num {
+                data.extend_from_slice(&[1, 0]);
+                data.extend(index(i));
+            }
+            data.extend_from_slice(&[0, 0]);
+            let mut boc = BOC_MAGIC.to_vec();
+            boc.extend_from_slice(&[0x03, 4]);
+            boc.extend(index(cells_num));
+            boc.extend(index(1));
+            boc.extend(index(0));
+            boc.extend_from_slice(&(data.len() as u32).to_be_bytes());
+            boc.extend(index(0));
+            boc.extend(data);
+            boc
+        };
+        assert!(parse_boc_root(&chain(1025)).is_ok());
+        assert_eq!(
+            "cell is nested too deeply",
+            parse_boc_root(&chain(1026)).unwrap_err().to_string()
+        );
+        assert_eq!(
+            "cell is nested too deeply",
+            parse_boc_root(&chain(70_000)).unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn test_cell_hash() {
+        // The hash of an empty cell.
+        assert_eq!(
+            "96a296d224f285c67bee93c30f8a309157f0daa35dc5b87e410b78630a09cfc7",
+            hex::encode(CellBuilder::default().build().hash())
+        );
+        let cell = CellBuilder::default().uint(0b101, 3).build();
+        let boc = serialize_boc(&cell);
+        let parsed = parse_boc_root(&boc).unwrap();
+        assert_eq!(3, parsed.get_bit_len());
+        assert_eq!(cell.hash(), parsed.hash());
+        assert_eq!(cell.hash(), parsed.parse().to_cell(3).unwrap().hash());
+    }
+}
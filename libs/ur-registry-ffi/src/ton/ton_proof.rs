@@ -0,0 +1,331 @@
+use anyhow::{format_err, Error};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use ur_registry::registry_types::TON_SIGNATURE;
+use ur_registry::ton::ton_sign_request::{DataType, TonSignRequest};
+use ur_registry::ton::ton_signature::TonSignature;
+
+use crate::export;
+use crate::ton::ton_address::TonAddress;
+use crate::util_internal::ed25519::verify_ed25519;
+use crate::util_internal::string_helper::remove_prefix_0x;
+
+const TON_PROOF_PREFIX: &[u8] = b"ton-proof-item-v2/";
+const TON_CONNECT_PREFIX: &[u8] = b"ton-connect";
+
+/// A TON Connect `ton_proof` item, serialized as the message the wallet signs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TonProof {
+    address: TonAddress,
+    domain: String,
+    timestamp: u64,
+    payload: String,
+}
+
+impl TonProof {
+    pub fn new(
+        address: TonAddress,
+        domain: String,
+        timestamp: u64,
+        payload: String,
+    ) -> Result<Self, Error> {
+        if domain.is_empty() {
+            return Err(format_err!("domain is empty"));
+        }
+        Ok(TonProof {
+            address,
+            domain,
+            timestamp,
+            payload,
+        })
+    }
+
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut result = TON_PROOF_PREFIX.to_vec();
+        result.extend_from_slice(&i32::from(self.address.get_workchain()).to_be_bytes());
+        result.extend_from_slice(&self.address.get_hash());
+        result.extend_from_slice(&(self.domain.len() as u32).to_le_bytes());
+        result.extend_from_slice(self.domain.as_bytes());
+        result.extend_from_slice(&self.timestamp.to_le_bytes());
+        result.extend_from_slice(self.payload.as_bytes());
+        result
+    }
+
+    pub fn deserialize(bytes: &[u8]) -> Result<Self, Error> {
+        let invalid = || format_err!("ton proof is invalid");
+        let bytes = bytes.strip_prefix(TON_PROOF_PREFIX).ok_or_else(invalid)?;
+        if bytes.len() < 4 + 32 + 4 {
+            return Err(invalid());
+        }
+        let workchain = i32::from_be_bytes(bytes[..4].try_into()?);
+        let hash: [u8; 32] = bytes[4..36].try_into()?;
+        let domain_len = u32::from_le_bytes(bytes[36..40].try_into()?) as usize;
+        let rest = &bytes[40..];
+        if domain_len.checked_add(8).is_none_or(|len| rest.len() < len) {
+            return Err(invalid());
+        }
+        let domain = String::from_utf8(rest[..domain_len].to_vec()).map_err(|_| invalid())?;
+        let timestamp = u64::from_le_bytes(rest[domain_len..domain_len + 8].try_into()?);
+        let payload = String::from_utf8(rest[domain_len + 8..].to_vec()).map_err(|_| invalid())?;
+        let workchain = i8::try_from(workchain).map_err(|_| invalid())?;
+        TonProof::new(TonAddress::new(workchain, hash), domain, timestamp, payload)
+    }
+
+    /// `sha256(0xffff ++ "ton-connect" ++ sha256(message))`, the bytes the key signs.
+    pub fn hash(&self) -> [u8; 32] {
+        let message: [u8; 32] = Sha256::digest(self.serialize()).into();
+        Sha256::new()
+            .chain_update([0xff, 0xff])
+            .chain_update(TON_CONNECT_PREFIX)
+            .chain_update(message)
+            .finalize()
+            .into()
+    }
+
+    pub fn verify(&self, public_key: &[u8; 32], signature: &[u8]) -> bool {
+        verify_ed25519(public_key, &self.hash(), signature)
+    }
+
+    pub fn get_address(&self) -> TonAddress {
+        self.address
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "address": self.address.to_raw(),
+            "domain": self.domain,
+            "timestamp": self.timestamp,
+            "payload": self.payload,
+        })
+    }
+
+    /// The `proof` object of a TON Connect `ton_proof` reply.
+    pub fn to_ton_connect_json(&self, signature: &[u8]) -> Value {
+        json!({
+            "timestamp": self.timestamp,
+            "domain": {
+                "lengthBytes": self.domain.len(),
+                "value": self.domain,
+            },
+            "signature": STANDARD.encode(signature),
+            "payload": self.payload,
+        })
+    }
+}
+
+/// Verifies the signature of a `SignProof` request, checking the proof address against the
+/// request address.
+pub fn verify_ton_proof_for_request(
+    request: &TonSignRequest,
+    signature: &TonSignature,
+    public_key: &[u8; 32],
+) -> Result<TonProof, Error> {
+    if let Some(request_id) = request.get_request_id() {
+        if signature.get_request_id() != Some(request_id) {
+            return Err(format_err!("request id mismatch"));
+        }
+    }
+    if request.get_data_type() != DataType::SignProof {
+        return Err(format_err!("data type is not a sign proof"));
+    }
+    let proof = TonProof::deserialize(&request.get_sign_data())?;
+    let address: TonAddress = request.get_address().parse()?;
+    if address != proof.get_address() {
+        return Err(format_err!("request address does not match the proof"));
+    }
+    match proof.verify(public_key, &signature.get_signature()) {
+        true => Ok(proof),
+        false => Err(format_err!("signature does not match the public key")),
+    }
+}
+
+export! {
+    @Java_com_keystone_sdk_KeystoneNativeSDK_generateTonProof
+    fn generate_ton_proof(
+        address: &str,
+        domain: &str,
+        timestamp: i64,
+        payload: &str
+    ) -> String {
+        let generate = || -> Result<TonProof, Error> {
+            let address: TonAddress = address.parse()?;
+            let timestamp = u64::try_from(timestamp).map_err(|_| format_err!("timestamp is invalid"))?;
+            TonProof::new(address, domain.to_string(), timestamp, payload.to_string())
+        };
+        match generate() {
+            Ok(proof) => json!({
+                "sign_data": hex::encode(proof.serialize()),
+                "hash": hex::encode(proof.hash()),
+            }).to_string(),
+            Err(e) => json!({"error": e.to_string()}).to_string(),
+        }
+    }
+
+    @Java_com_keystone_sdk_KeystoneNativeSDK_verifyTonProof
+    fn verify_ton_proof(
+        sign_request_cbor_hex: &str,
+        public_key: &str,
+        ur_type: &str,
+        cbor_hex: &str
+    ) -> String {
+        if TON_SIGNATURE.get_type() != ur_type {
+            return json!({"error": "type not match"}).to_string();
+        }
+
+        let verify = || -> Result<(TonProof, Vec<u8>), Error> {
+            let request = TonSignRequest::try_from(hex::decode(sign_request_cbor_hex)?)
+                .map_err(|e| format_err!(e.to_string()))?;
+            let signature = TonSignature::try_from(hex::decode(cbor_hex)?)
+                .map_err(|e| format_err!(e.to_string()))?;
+            let public_key: [u8; 32] = hex::decode(remove_prefix_0x(public_key))
+                .ok()
+                .and_then(|v| v.try_into().ok())
+                .ok_or(format_err!("public key is invalid"))?;
+            let proof = verify_ton_proof_for_request(&request, &signature, &public_key)?;
+            Ok((proof, signature.get_signature()))
+        };
+        match verify() {
+            Ok((proof, signature)) => json!({
+                "address": proof.get_address().to_raw(),
+                "proof": proof.to_ton_connect_json(&signature),
+                "verified": true,
+            }).to_string(),
+            Err(e) => json!({"error": e.to_string()}).to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util_internal::ed25519::sign_ed25519;
+
+    const SEED: [u8; 32] = [7u8; 32];
+    const WALLET: &str = "UQC1IywyQwixSOU8pezOZDC9rv2xCV4CGJzOWH6RX8BTsGJx";
+
+    fn proof() -> TonProof {
+        TonProof::new(
+            WALLET.parse().unwrap(),
+            "ton.org".to_string(),
+            1700000000,
+            "nonce".to_string(),
+        )
+        .unwrap()
+    }
+
+    fn sign_request(sign_data: Vec<u8>) -> String {
+        let request = TonSignRequest::new(
+            Some(vec![1u8; 16]),
+            sign_data,
+            DataType::SignProof,
+            None,
+            WALLET.to_string(),
+            None,
+        );
+        let cbor: Vec<u8> = request.try_into().unwrap();
+        hex::encode(cbor)
+    }
+
+    fn signature(request_id: Vec<u8>, signature: Vec<u8>) -> String {
+        let cbor: Vec<u8> = TonSignature::new(Some(request_id), signature, None)
+            .try_into()
+            .unwrap();
+        hex::encode(cbor)
+    }
+
+    #[test]
+    fn test_ton_proof() {
+        let serialized = proof().serialize();
+        assert!(serialized.starts_with(b"ton-proof-item-v2/\x00\x00\x00\x00"));
+        assert_eq!(proof(), TonProof::deserialize(&serialized).unwrap());
+
+        let result: Value =
+            serde_json::from_str(&generate_ton_proof(WALLET, "ton.org", 1700000000, "nonce"))
+                .unwrap();
+        assert_eq!(hex::encode(&serialized), result["sign_data"]);
+        assert_eq!(hex::encode(proof().hash()), result["hash"]);
+        assert_eq!(
+            "{\"error\":\"timestamp is invalid\"}",
+            generate_ton_proof(WALLET, "ton.org", -1, "nonce")
+        );
+        assert_eq!(
+            "ton proof is invalid",
+            TonProof::deserialize(&serialized[..40])
+                .unwrap_err()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn test_verify_ton_proof() {
+        let (public_key, sig) = sign_ed25519(&SEED, &proof().hash());
+        let request = sign_request(proof().serialize());
+
+        let result: Value = serde_json::from_str(&verify_ton_proof(
+            &request,
+            &hex::encode(public_key),
+            "ton-signature",
+            &signature(vec![1u8; 16], sig.to_vec()),
+        ))
+        .unwrap();
+        assert_eq!(true, result["verified"]);
+        assert_eq!(7, result["proof"]["domain"]["lengthBytes"]);
+        assert_eq!(STANDARD.encode(sig), result["proof"]["signature"]);
+
+        let mut tampered = sig;
+        tampered[0] ^= 1;
+        assert_eq!(
+            "{\"error\":\"signature does not match the public key\"}",
+            verify_ton_proof(
+                &request,
+                &hex::encode(public_key),
+                "ton-signature",
+                &signature(vec![1u8; 16], tampered.to_vec())
+            )
+        );
+        assert_eq!(
+            "{\"error\":\"request id mismatch\"}",
+            verify_ton_proof(
+                &request,
+                &hex::encode(public_key),
+                "ton-signature",
+                &signature(vec![2u8; 16], sig.to_vec())
+            )
+        );
+        let cbor: Vec<u8> = TonSignature::new(None, sig.to_vec(), None)
+            .try_into()
+            .unwrap();
+        assert_eq!(
+            "{\"error\":\"request id mismatch\"}",
+            verify_ton_proof(
+                &request,
+                &hex::encode(public_key),
+                "ton-signature",
+                &hex::encode(cbor)
+            )
+        );
+        assert_eq!(
+            "{\"error\":\"type not match\"}",
+            verify_ton_proof(&request, "", "ton-sign-request", "")
+        );
+
+        let other = TonProof::new(
+            TonAddress::new(0, [1u8; 32]),
+            "ton.org".to_string(),
+            1700000000,
+            "nonce".to_string(),
+        )
+        .unwrap();
+        assert_eq!(
+            "{\"error\":\"request address does not match the proof\"}",
+            verify_ton_proof(
+                &sign_request(other.serialize()),
+                &hex::encode(public_key),
+                "ton-signature",
+                &signature(vec![1u8; 16], sig.to_vec())
+            )
+        );
+    }
+}
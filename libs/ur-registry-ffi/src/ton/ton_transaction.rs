@@ -0,0 +1,617 @@
+use anyhow::{format_err, Error};
+use serde_json::{json, Value};
+use ur_registry::registry_types::TON_SIGN_REQUEST;
+use ur_registry::ton::ton_sign_request::{DataType, TonSignRequest};
+
+use crate::export;
+use crate::ton::ton_address::TonAddress;
+use crate::ton::ton_cell::{parse_boc_root, Cell, CellSlice};
+use crate::ton::ton_proof::TonProof;
+
+const SIGNATURE_BITS: usize = 512;
+const TEXT_COMMENT_OP: u32 = 0;
+const JETTON_TRANSFER_OP: u32 = 0x0f8a_7ea5;
+const W5_EXTERNAL_OP: u32 = 0x7369_676e;
+const W5_INTERNAL_OP: u32 = 0x7369_6e74;
+const W5_SEND_MSG_ACTION: u32 = 0x0ec3_c86d;
+const W4_SIMPLE_SEND_OP: u8 = 0;
+const MAX_ACTIONS: usize = 255;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WalletVersion {
+    V4,
+    V5,
+}
+
+impl WalletVersion {
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            WalletVersion::V4 => "v4",
+            WalletVersion::V5 => "v5",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct JettonTransfer {
+    query_id: u64,
+    amount: u128,
+    destination: Option<TonAddress>,
+    response_destination: Option<TonAddress>,
+    custom_payload: bool,
+    forward_ton_amount: u128,
+    comment: Option<String>,
+}
+
+impl JettonTransfer {
+    fn decode(slice: &mut CellSlice) -> Result<Self, Error> {
+        let query_id = slice.u64()?;
+        let amount = slice.coins()?;
+        let destination = slice.address()?;
+        let response_destination = slice.address()?;
+        let custom_payload = slice.maybe_reference()?.is_some();
+        let forward_ton_amount = slice.coins()?;
+        let comment = match slice.remaining_bits() {
+            0 => None,
+            _ => match MessageBody::decode(slice.either()?)? {
+                MessageBody::Comment(comment) => Some(comment),
+                _ => None,
+            },
+        };
+        Ok(JettonTransfer {
+            query_id,
+            amount,
+            destination,
+            response_destination,
+            custom_payload,
+            forward_ton_amount,
+            comment,
+        })
+    }
+
+    pub fn get_amount(&self) -> u128 {
+        self.amount
+    }
+
+    pub fn get_destination(&self) -> Option<TonAddress> {
+        self.destination
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "query_id": self.query_id.to_string(),
+            "amount": self.amount.to_string(),
+            "destination": self.destination.map(|a| a.to_friendly(false, false)),
+            "response_destination": self.response_destination.map(|a| a.to_friendly(false, false)),
+            "custom_payload": self.custom_payload,
+            "forward_ton_amount": self.forward_ton_amount.to_string(),
+            "comment": self.comment,
+        })
+    }
+}
+
+/// The body of an internal message, recognized by its 32-bit op code.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MessageBody {
+    Empty,
+    Comment(String),
+    JettonTransfer(JettonTransfer),
+    Unknown(u32),
+}
+
+impl MessageBody {
+    fn decode(mut slice: CellSlice) -> Result<Self, Error> {
+        if slice.remaining_bits() < 32 {
+            return Ok(MessageBody::Empty);
+        }
+        match slice.u32()? {
+            TEXT_COMMENT_OP => Ok(MessageBody::Comment(slice.snake_string()?)),
+            JETTON_TRANSFER_OP => Ok(MessageBody::JettonTransfer(JettonTransfer::decode(
+                &mut slice,
+            )?)),
+            op => Ok(MessageBody::Unknown(op)),
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        match self {
+            MessageBody::Empty => Value::Null,
+            MessageBody::Comment(comment) => json!({
+                "type": "comment",
+                "comment": comment,
+            }),
+            MessageBody::JettonTransfer(transfer) => {
+                let mut value = transfer.to_json();
+                value["type"] = json!("jetton_transfer");
+                value
+            }
+            MessageBody::Unknown(op) => json!({
+                "type": "unknown",
+                "op": format!("0x{op:08x}"),
+            }),
+        }
+    }
+}
+
+/// An internal message sent by the wallet, with its send mode.
+#[derive(Clone, Debug, PartialEq)]
+pub struct InternalMessage {
+    mode: u8,
+    bounce: bool,
+    destination: TonAddress,
+    amount: u128,
+    state_init: bool,
+    body: MessageBody,
+}
+
+/// Skips an inline `StateInit`.
+fn skip_state_init(slice: &mut CellSlice) -> Result<(), Error> {
+    if slice.bit()? {
+        slice.skip(5)?;
+    }
+    if slice.bit()? {
+        slice.skip(2)?;
+    }
+    for _ in 0..3 {
+        slice.maybe_reference()?;
+    }
+    Ok(())
+}
+
+impl InternalMessage {
+    /// Decodes a `MessageRelaxed` cell.
+    fn decode(mode: u8, cell: &Cell) -> Result<Self, Error> {
+        let mut slice = cell.parse();
+        if slice.bit()? {
+            return Err(format_err!("message is not an internal message"));
+        }
+        let _ihr_disabled = slice.bit()?;
+        let bounce = slice.bit()?;
+        let _bounced = slice.bit()?;
+        let _source = slice.address()?;
+        let destination = slice
+            .address()?
+            .ok_or(format_err!("message destination is invalid"))?;
+        let amount = slice.coins()?;
+        let _extra_currencies = slice.maybe_reference()?;
+        let _ihr_fee = slice.coins()?;
+        let _forward_fee = slice.coins()?;
+        let _created_lt = slice.u64()?;
+        let _created_at = slice.u32()?;
+        let state_init = slice.bit()?;
+        if state_init {
+            match slice.bit()? {
+                true => {
+                    slice.reference()?;
+                }
+                false => skip_state_init(&mut slice)?,
+            }
+        }
+        let body = MessageBody::decode(slice.either()?)?;
+        Ok(InternalMessage {
+            mode,
+            bounce,
+            destination,
+            amount,
+            state_init,
+            body,
+        })
+    }
+
+    pub fn get_destination(&self) -> TonAddress {
+        self.destination
+    }
+
+    pub fn get_amount(&self) -> u128 {
+        self.amount
+    }
+
+    pub fn get_body(&self) -> &MessageBody {
+        &self.body
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "mode": self.mode,
+            "bounce": self.bounce,
+            "destination": self.destination.to_friendly(self.bounce, false),
+            "destination_raw": self.destination.to_raw(),
+            "amount": self.amount.to_string(),
+            "state_init": self.state_init,
+            "body": self.body.to_json(),
+        })
+    }
+}
+
+/// The message a wallet v4 or v5 contract verifies, without its signature.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TonTransaction {
+    version: WalletVersion,
+    wallet: Option<TonAddress>,
+    wallet_id: u32,
+    valid_until: u32,
+    seqno: u32,
+    messages: Vec<InternalMessage>,
+    hash: [u8; 32],
+}
+
+/// Walks a v5 `OutList`, whose last action sits in the root cell.
+fn decode_out_list(cell: &Cell) -> Result<Vec<InternalMessage>, Error> {
+    let mut messages = vec![];
+    let mut cell = cell;
+    while cell.get_bit_len() != 0 || !cell.get_refs().is_empty() {
+        if messages.len() == MAX_ACTIONS {
+            return Err(format_err!("too many wallet actions"));
+        }
+        let mut slice = cell.parse();
+        let previous = slice.reference()?;
+        if slice.u32()? != W5_SEND_MSG_ACTION {
+            return Err(format_err!("wallet action is not supported"));
+        }
+        let mode = slice.u8()?;
+        messages.push(InternalMessage::decode(mode, slice.reference()?)?);
+        cell = previous;
+    }
+    messages.reverse();
+    Ok(messages)
+}
+
+impl TonTransaction {
+    /// Decodes a bag of cells holding either the signing message or a signed external message.
+    pub fn decode(boc: &[u8]) -> Result<Self, Error> {
+        let root = parse_boc_root(boc)?;
+        let mut slice = root.parse();
+        if slice.uint(2)? != 0b10 {
+            return TonTransaction::decode_signing_message(&root.parse(), None);
+        }
+        // ext_in_msg_info$10 src:MsgAddressExt dest:MsgAddressInt import_fee:Grams
+        slice.address()?;
+        let wallet = slice
+            .address()?
+            .ok_or(format_err!("wallet address is invalid"))?;
+        slice.coins()?;
+        if slice.bit()? {
+            match slice.bit()? {
+                true => {
+                    slice.reference()?;
+                }
+                false => skip_state_init(&mut slice)?,
+            }
+        }
+        let mut body = slice.either()?;
+        let mut peek = body.clone();
+        if peek.remaining_bits() >= 32 && peek.u32()? == W5_EXTERNAL_OP {
+            let bits = body
+                .remaining_bits()
+                .checked_sub(SIGNATURE_BITS)
+                .ok_or(format_err!("message signature is missing"))?;
+            let unsigned = body.to_cell(bits)?;
+            return TonTransaction::decode_signing_message(&unsigned.parse(), Some(wallet));
+        }
+        body.skip(SIGNATURE_BITS)?;
+        let unsigned = body.to_cell(body.remaining_bits())?;
+        TonTransaction::decode_signing_message(&unsigned.parse(), Some(wallet))
+    }
+
+    fn decode_signing_message(
+        slice: &CellSlice,
+        wallet: Option<TonAddress>,
+    ) -> Result<Self, Error> {
+        let hash = slice.to_cell(slice.remaining_bits())?.hash();
+        let mut slice = slice.clone();
+        let op = slice.clone().u32()?;
+        let version = match op {
+            W5_EXTERNAL_OP | W5_INTERNAL_OP => {
+                slice.u32()?;
+                WalletVersion::V5
+            }
+            _ => WalletVersion::V4,
+        };
+        let wallet_id = slice.u32()?;
+        let valid_until = slice.u32()?;
+        let seqno = slice.u32()?;
+        let messages = match version {
+            WalletVersion::V4 => {
+                let refs = slice.remaining_refs();
+                if slice.remaining_bits() != 8 + refs * 8 {
+                    return Err(format_err!("wallet message is not supported"));
+                }
+                if slice.u8()? != W4_SIMPLE_SEND_OP {
+                    return Err(format_err!("wallet operation is not supported"));
+                }
+                (0..refs)
+                    .map(|_| {
+                        let mode = slice.u8()?;
+                        InternalMessage::decode(mode, slice.reference()?)
+                    })
+                    .collect::<Result<Vec<_>, _>>()?
+            }
+            WalletVersion::V5 => {
+                let messages = match slice.maybe_reference()? {
+                    Some(out_list) => decode_out_list(out_list)?,
+                    None => vec![],
+                };
+                if slice.bit()? {
+                    return Err(format_err!("extended wallet actions are not supported"));
+                }
+                messages
+            }
+        };
+        Ok(TonTransaction {
+            version,
+            wallet,
+            wallet_id,
+            valid_until,
+            seqno,
+            messages,
+            hash,
+        })
+    }
+
+    /// Decodes the transaction of a request, checking the wallet of an external message
+    /// against the request address.
+    pub fn from_sign_request(request: &TonSignRequest) -> Result<Self, Error> {
+        if request.get_data_type() != DataType::Transaction {
+            return Err(format_err!("data type is not a transaction"));
+        }
+        let transaction = TonTransaction::decode(&request.get_sign_data())?;
+        if let Some(wallet) = transaction.wallet {
+            let address: TonAddress = request.get_address().parse()?;
+            if address != wallet {
+                return Err(format_err!("request address does not match the wallet"));
+            }
+        }
+        Ok(transaction)
+    }
+
+    pub fn get_version(&self) -> WalletVersion {
+        self.version
+    }
+
+    pub fn get_seqno(&self) -> u32 {
+        self.seqno
+    }
+
+    pub fn get_messages(&self) -> &[InternalMessage] {
+        &self.messages
+    }
+
+    /// The hash of the signing message, which the wallet key signs.
+    pub fn get_hash(&self) -> [u8; 32] {
+        self.hash
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "wallet_version": self.version.get_name(),
+            "wallet": self.wallet.map(|a| a.to_friendly(true, false)),
+            "wallet_id": self.wallet_id,
+            "valid_until": self.valid_until,
+            "seqno": self.seqno,
+            "messages": self.messages.iter().map(InternalMessage::to_json).collect::<Vec<_>>(),
+            "hash": hex::encode(self.hash),
+        })
+    }
+}
+
+export! {
+    @Java_com_keystone_sdk_KeystoneNativeSDK_decodeTonSignRequest
+    fn decode_ton_sign_request(ur_type: &str, cbor_hex: &str) -> String {
+        if TON_SIGN_REQUEST.get_type() != ur_type {
+            return json!({"error": "type not match"}).to_string();
+        }
+
+        let decode = || -> Result<Value, Error> {
+            let cbor = hex::decode(cbor_hex)?;
+            let request = TonSignRequest::try_from(cbor).map_err(|e| format_err!(e.to_string()))?;
+            match request.get_data_type() {
+                DataType::Transaction => {
+                    let mut value = TonTransaction::from_sign_request(&request)?.to_json();
+                    value["data_type"] = json!("transaction");
+                    Ok(value)
+                }
+                DataType::SignProof => {
+                    let mut value = TonProof::deserialize(&request.get_sign_data())?.to_json();
+                    value["data_type"] = json!("sign_proof");
+                    Ok(value)
+                }
+            }
+        };
+        match decode() {
+            Ok(value) => value.to_string(),
+            Err(e) => json!({"error": e.to_string()}).to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ton::ton_cell::{serialize_boc, CellBuilder};
+    use std::rc::Rc;
+
+    const WALLET: &str = "UQC1IywyQwixSOU8pezOZDC9rv2xCV4CGJzOWH6RX8BTsGJx";
+    const V4_TRANSFER: &str = "b5ee9c7241010201004700011c29a9a317663b3ea500000008000301006842002b16732f1c05fdb4e8d3a78fd10dddef3f6067f311be539313b8a44a504d4da2a1dcd65000000000000000000000000000007072e06f";
+
+    fn address(byte: u8) -> TonAddress {
+        TonAddress::new(0, [byte; 32])
+    }
+
+    fn comment(text: &str) -> Rc<Cell> {
+        CellBuilder::default()
+            .uint(0, 32)
+            .bytes(text.as_bytes())
+            .build()
+    }
+
+    fn internal_message(destination: &TonAddress, amount: u128, body: Rc<Cell>) -> Rc<Cell> {
+        CellBuilder::default()
+            .uint(0b0110, 4)
+            .address(None)
+            .address(Some(destination))
+            .coins(amount)
+            .uint(0, 1 + 4 + 4 + 64 + 32 + 1)
+            .uint(1, 1)
+            .reference(body)
+            .build()
+    }
+
+    fn jetton_transfer() -> Rc<Cell> {
+        CellBuilder::default()
+            .uint(u128::from(JETTON_TRANSFER_OP), 32)
+            .uint(7, 64)
+            .coins(1_500_000)
+            .address(Some(&address(0x33)))
+            .address(Some(&address(0x44)))
+            .uint(0, 1)
+            .coins(1)
+            .uint(1, 1)
+            .reference(comment("gm"))
+            .build()
+    }
+
+    fn v5_signing_message(signature: &[u8]) -> Rc<Cell> {
+        let first = CellBuilder::default()
+            .reference(CellBuilder::default().build())
+            .uint(u128::from(W5_SEND_MSG_ACTION), 32)
+            .uint(3, 8)
+            .reference(internal_message(
+                &address(0x11),
+                1_000_000_000,
+                comment("hello"),
+            ))
+            .build();
+        let second = CellBuilder::default()
+            .reference(first)
+            .uint(u128::from(W5_SEND_MSG_ACTION), 32)
+            .uint(1, 8)
+            .reference(internal_message(
+                &address(0x22),
+                50_000_000,
+                jetton_transfer(),
+            ))
+            .build();
+        CellBuilder::default()
+            .uint(u128::from(W5_EXTERNAL_OP), 32)
+            .uint(2147483409, 32)
+            .uint(1700000000, 32)
+            .uint(5, 32)
+            .uint(1, 1)
+            .reference(second)
+            .uint(0, 1)
+            .bytes(signature)
+            .build()
+    }
+
+    fn sign_request(sign_data: Vec<u8>, data_type: DataType) -> String {
+        let request = TonSignRequest::new(
+            Some(vec![1u8; 16]),
+            sign_data,
+            data_type,
+            None,
+            WALLET.to_string(),
+            None,
+        );
+        let cbor: Vec<u8> = request.try_into().unwrap();
+        hex::encode(cbor)
+    }
+
+    #[test]
+    fn test_decode_v4_transaction() {
+        let transaction = TonTransaction::decode(&hex::decode(V4_TRANSFER).unwrap()).unwrap();
+        assert_eq!(WalletVersion::V4, transaction.get_version());
+        assert_eq!(8, transaction.get_seqno());
+        let message = &transaction.get_messages()[0];
+        assert_eq!(1_000_000_000, message.get_amount());
+        assert_eq!(
+            "UQBWLOZeOAv7adGnTx-iG7vefsDP5iN8pyYncUiUoJqbRdx9",
+            message.get_destination().to_friendly(false, false)
+        );
+        assert_eq!(&MessageBody::Empty, message.get_body());
+
+        let root = parse_boc_root(&hex::decode(V4_TRANSFER).unwrap()).unwrap();
+        assert_eq!(root.hash(), transaction.get_hash());
+    }
+
+    #[test]
+    fn test_decode_v5_transaction() {
+        let root = v5_signing_message(&[]);
+        let transaction = TonTransaction::decode(&serialize_boc(&root)).unwrap();
+        assert_eq!(WalletVersion::V5, transaction.get_version());
+        assert_eq!(root.hash(), transaction.get_hash());
+
+        let messages = transaction.get_messages();
+        assert_eq!(2, messages.len());
+        assert_eq!(address(0x11), messages[0].get_destination());
+        assert_eq!(
+            &MessageBody::Comment("hello".to_string()),
+            messages[0].get_body()
+        );
+        let MessageBody::JettonTransfer(transfer) = messages[1].get_body() else {
+            panic!("expected a jetton transfer");
+        };
+        assert_eq!(1_500_000, transfer.get_amount());
+        assert_eq!(Some(address(0x33)), transfer.get_destination());
+
+        let json = transaction.to_json();
+        assert_eq!("v5", json["wallet_version"]);
+        assert_eq!("jetton_transfer", json["messages"][1]["body"]["type"]);
+        assert_eq!("gm", json["messages"][1]["body"]["comment"]);
+        assert_eq!("1500000", json["messages"][1]["body"]["amount"]);
+    }
+
+    #[test]
+    fn test_decode_external_message() {
+        let wallet: TonAddress = WALLET.parse().unwrap();
+        let unsigned = v5_signing_message(&[]);
+        let body = v5_signing_message(&[0xaa; 64]);
+        let external = CellBuilder::default()
+            .uint(0b10, 2)
+            .address(None)
+            .address(Some(&wallet))
+            .coins(0)
+            .uint(0, 1)
+            .uint(1, 1)
+            .reference(body)
+            .build();
+
+        let result: Value = serde_json::from_str(&decode_ton_sign_request(
+            "ton-sign-request",
+            &sign_request(serialize_boc(&external), DataType::Transaction),
+        ))
+        .unwrap();
+        assert_eq!("transaction", result["data_type"]);
+        assert_eq!(wallet.to_friendly(true, false), result["wallet"]);
+        assert_eq!(hex::encode(unsigned.hash()), result["hash"]);
+        assert_eq!(5, result["seqno"]);
+    }
+
+    #[test]
+    fn test_decode_ton_sign_request_error() {
+        assert_eq!(
+            "{\"error\":\"type not match\"}",
+            decode_ton_sign_request("ton-signature", "a0")
+        );
+        assert_eq!(
+            "{\"error\":\"boc magic is invalid\"}",
+            decode_ton_sign_request(
+                "ton-sign-request",
+                &sign_request(vec![0u8; 8], DataType::Transaction)
+            )
+        );
+        let request = TonSignRequest::new(
+            None,
+            hex::decode(V4_TRANSFER).unwrap(),
+            DataType::SignProof,
+            None,
+            WALLET.to_string(),
+            None,
+        );
+        assert_eq!(
+            "data type is not a transaction",
+            TonTransaction::from_sign_request(&request)
+                .unwrap_err()
+                .to_string()
+        );
+    }
+}
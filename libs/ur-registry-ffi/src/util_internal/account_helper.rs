@@ -1,10 +1,10 @@
-use crate::sync::crypto_hd_key::{AccountExtra, OkxExtra};
+use crate::sync::crypto_hd_key::{AccountExtra, ChainInfo, OkxExtra};
+use crate::util_internal::chain::find_evm_chain_by_coin_type;
 
 fn okx_chain_id_map(coin_type: u32) -> u32 {
-    match coin_type {
-        60 => 1,
-        _ => coin_type,
-    }
+    find_evm_chain_by_coin_type(coin_type)
+        .and_then(|chain| u32::try_from(chain.chain_id).ok())
+        .unwrap_or(coin_type)
 }
 
 pub fn gen_extra_data(coin_type: u32) -> AccountExtra {
@@ -16,6 +16,15 @@ pub fn gen_extra_data(coin_type: u32) -> AccountExtra {
     };
 }
 
+pub fn gen_chain_info(coin_type: u32) -> Option<ChainInfo> {
+    find_evm_chain_by_coin_type(coin_type).map(|chain| ChainInfo {
+        chain_id: chain.chain_id,
+        name: chain.name.to_string(),
+        caip2_id: chain.caip2_id(),
+        currency_symbol: chain.currency_symbol.to_string(),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -35,4 +44,16 @@ mod tests {
 
         assert_eq!(expect_chain_id, gen_extra_data(coin_type).okx.chain_id);
     }
+
+    #[test]
+    fn test_gen_chain_info() {
+        let chain_info = gen_chain_info(61).unwrap();
+        assert_eq!(61, chain_info.chain_id);
+        assert_eq!("Ethereum Classic", chain_info.name);
+        assert_eq!("eip155:61", chain_info.caip2_id);
+        assert_eq!("ETC", chain_info.currency_symbol);
+        assert_eq!("eip155:1", gen_chain_info(60).unwrap().caip2_id);
+        assert!(gen_chain_info(0).is_none());
+        assert!(gen_chain_info(501).is_none());
+    }
 }
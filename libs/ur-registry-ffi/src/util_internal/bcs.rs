@@ -0,0 +1,132 @@
+use anyhow::{format_err, Error};
+
+/// A minimal reader for the Binary Canonical Serialization used by Sui and Aptos.
+pub struct BcsReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> BcsReader<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        BcsReader { bytes }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    pub fn remaining(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    pub fn take(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        if self.bytes.len() < len {
+            return Err(format_err!("unexpected end of data"));
+        }
+        let (head, tail) = self.bytes.split_at(len);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    pub fn u8(&mut self) -> Result<u8, Error> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub fn u16(&mut self) -> Result<u16, Error> {
+        Ok(u16::from_le_bytes(self.take(2)?.try_into()?))
+    }
+
+    pub fn u32(&mut self) -> Result<u32, Error> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into()?))
+    }
+
+    pub fn u64(&mut self) -> Result<u64, Error> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into()?))
+    }
+
+    pub fn u128(&mut self) -> Result<u128, Error> {
+        Ok(u128::from_le_bytes(self.take(16)?.try_into()?))
+    }
+
+    pub fn bool(&mut self) -> Result<bool, Error> {
+        match self.u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            x => Err(format_err!("invalid bool {x}")),
+        }
+    }
+
+    pub fn uleb128(&mut self) -> Result<u32, Error> {
+        let mut value: u64 = 0;
+        for shift in (0..32).step_by(7) {
+            let byte = self.u8()?;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                if shift > 0 && byte == 0 {
+                    return Err(format_err!("non-canonical uleb128"));
+                }
+                return u32::try_from(value).map_err(|_| format_err!("uleb128 overflow"));
+            }
+        }
+        Err(format_err!("uleb128 overflow"))
+    }
+
+    pub fn fixed<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+        Ok(self.take(N)?.try_into()?)
+    }
+
+    pub fn bytes(&mut self) -> Result<Vec<u8>, Error> {
+        let len = self.uleb128()? as usize;
+        Ok(self.take(len)?.to_vec())
+    }
+
+    pub fn string(&mut self) -> Result<String, Error> {
+        Ok(String::from_utf8(self.bytes()?)?)
+    }
+
+    pub fn vec<T>(
+        &mut self,
+        mut item: impl FnMut(&mut Self) -> Result<T, Error>,
+    ) -> Result<Vec<T>, Error> {
+        let len = self.uleb128()?;
+        (0..len).map(|_| item(self)).collect()
+    }
+
+    pub fn option<T>(
+        &mut self,
+        item: impl FnOnce(&mut Self) -> Result<T, Error>,
+    ) -> Result<Option<T>, Error> {
+        match self.u8()? {
+            0 => Ok(None),
+            1 => Ok(Some(item(self)?)),
+            x => Err(format_err!("invalid option tag {x}")),
+        }
+    }
+
+    pub fn finish(&self) -> Result<(), Error> {
+        match self.is_empty() {
+            true => Ok(()),
+            false => Err(format_err!("unexpected trailing bytes")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bcs_reader() {
+        let bytes = hex::decode("800102030000010568656c6c6f01e803").unwrap();
+        let mut reader = BcsReader::new(&bytes);
+        assert_eq!(128, reader.uleb128().unwrap());
+        assert_eq!(vec![3, 0], reader.vec(|r| r.u8()).unwrap());
+        assert_eq!(0, reader.u8().unwrap());
+        assert!(reader.bool().unwrap());
+        assert_eq!("hello", reader.string().unwrap());
+        assert_eq!(Some(1000), reader.option(|r| r.u16()).unwrap());
+        assert!(reader.finish().is_ok());
+
+        assert!(BcsReader::new(&[0x80, 0x00]).uleb128().is_err());
+        assert!(BcsReader::new(&[0x02]).bool().is_err());
+    }
+}
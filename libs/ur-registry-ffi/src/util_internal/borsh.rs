@@ -0,0 +1,104 @@
+use anyhow::{format_err, Error};
+
+use crate::util_internal::bcs::BcsReader;
+
+/// A minimal reader for the Borsh serialization used by NEAR. Fixed-width values share
+/// their little-endian layout with BCS; sequences are prefixed with a `u32` length.
+pub struct BorshReader<'a> {
+    inner: BcsReader<'a>,
+}
+
+impl<'a> BorshReader<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        BorshReader {
+            inner: BcsReader::new(bytes),
+        }
+    }
+
+    pub fn take(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        self.inner.take(len)
+    }
+
+    pub fn u8(&mut self) -> Result<u8, Error> {
+        self.inner.u8()
+    }
+
+    pub fn u32(&mut self) -> Result<u32, Error> {
+        self.inner.u32()
+    }
+
+    pub fn u64(&mut self) -> Result<u64, Error> {
+        self.inner.u64()
+    }
+
+    pub fn u128(&mut self) -> Result<u128, Error> {
+        self.inner.u128()
+    }
+
+    pub fn fixed<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+        self.inner.fixed()
+    }
+
+    pub fn bytes(&mut self) -> Result<Vec<u8>, Error> {
+        let len = self.u32()? as usize;
+        Ok(self.take(len)?.to_vec())
+    }
+
+    pub fn string(&mut self) -> Result<String, Error> {
+        Ok(String::from_utf8(self.bytes()?)?)
+    }
+
+    pub fn vec<T>(
+        &mut self,
+        mut item: impl FnMut(&mut Self) -> Result<T, Error>,
+    ) -> Result<Vec<T>, Error> {
+        let len = self.u32()?;
+        if len as usize > self.inner.remaining().len() {
+            return Err(format_err!("unexpected end of data"));
+        }
+        (0..len).map(|_| item(self)).collect()
+    }
+
+    pub fn option<T>(
+        &mut self,
+        item: impl FnOnce(&mut Self) -> Result<T, Error>,
+    ) -> Result<Option<T>, Error> {
+        match self.u8()? {
+            0 => Ok(None),
+            1 => Ok(Some(item(self)?)),
+            x => Err(format_err!("invalid option tag {x}")),
+        }
+    }
+
+    pub fn finish(&self) -> Result<(), Error> {
+        self.inner.finish()
+    }
+}
+
+/// Writes the Borsh encoding of a string: a `u32` length followed by its bytes.
+pub fn write_string(output: &mut Vec<u8>, value: &str) {
+    output.extend_from_slice(&(value.len() as u32).to_le_bytes());
+    output.extend_from_slice(value.as_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_borsh_reader() {
+        let mut bytes = vec![];
+        write_string(&mut bytes, "near");
+        bytes.extend_from_slice(&hex::decode("02000000070801e803000000000000").unwrap());
+        let mut reader = BorshReader::new(&bytes);
+        assert_eq!("near", reader.string().unwrap());
+        assert_eq!(vec![7, 8], reader.vec(|r| r.u8()).unwrap());
+        assert_eq!(Some(1000), reader.option(|r| r.u64()).unwrap());
+        assert!(reader.finish().is_ok());
+
+        assert!(BorshReader::new(&[0xff, 0xff, 0xff, 0xff])
+            .vec(|r| r.u8())
+            .is_err());
+        assert!(BorshReader::new(&[0x02]).option(|r| r.u8()).is_err());
+    }
+}
@@ -1,3 +1,8 @@
+use std::borrow::Cow;
+use std::sync::{Mutex, OnceLock};
+
+use anyhow::{format_err, Error};
+
 pub fn map_coin_type(coin_type: u32) -> String {
     match coin_type {
         0 => "BTC",
@@ -1053,3 +1058,297 @@ pub fn map_coin_type(coin_type: u32) -> String {
     }
     .to_string()
 }
+
+/// Metadata of an EIP-155 chain, keyed by the `chain_id` carried in `EthSignRequest`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EvmChain {
+    pub chain_id: u64,
+    pub name: &'static str,
+    pub short_name: &'static str,
+    pub currency_name: &'static str,
+    pub currency_symbol: &'static str,
+    pub decimals: u8,
+    /// SLIP-44 coin type whose accounts default to this chain.
+    pub coin_type: Option<u32>,
+}
+
+impl EvmChain {
+    /// The CAIP-2 chain identifier, e.g. `eip155:1`.
+    pub fn caip2_id(&self) -> String {
+        format!("eip155:{}", self.chain_id)
+    }
+}
+
+const fn evm_chain(
+    chain_id: u64,
+    name: &'static str,
+    short_name: &'static str,
+    currency_name: &'static str,
+    currency_symbol: &'static str,
+    coin_type: Option<u32>,
+) -> EvmChain {
+    EvmChain {
+        chain_id,
+        name,
+        short_name,
+        currency_name,
+        currency_symbol,
+        decimals: 18,
+        coin_type,
+    }
+}
+
+pub const EVM_CHAINS: [EvmChain; 31] = [
+    evm_chain(1, "Ethereum Mainnet", "eth", "Ether", "ETH", Some(60)),
+    evm_chain(10, "OP Mainnet", "oeth", "Ether", "ETH", None),
+    evm_chain(25, "Cronos Mainnet", "cro", "Cronos", "CRO", None),
+    evm_chain(
+        56,
+        "BNB Smart Chain Mainnet",
+        "bnb",
+        "BNB Chain Native Token",
+        "BNB",
+        None,
+    ),
+    evm_chain(61, "Ethereum Classic", "etc", "Ether", "ETC", Some(61)),
+    evm_chain(
+        66,
+        "OKXChain Mainnet",
+        "okt",
+        "OKXChain Global Utility Token",
+        "OKT",
+        None,
+    ),
+    evm_chain(
+        97,
+        "BNB Smart Chain Testnet",
+        "bnbt",
+        "BNB Chain Native Token",
+        "tBNB",
+        None,
+    ),
+    evm_chain(100, "Gnosis", "gno", "xDAI", "XDAI", None),
+    evm_chain(
+        128,
+        "Huobi ECO Chain Mainnet",
+        "heco",
+        "Huobi ECO Chain Native Token",
+        "HT",
+        None,
+    ),
+    evm_chain(137, "Polygon Mainnet", "pol", "POL", "POL", None),
+    evm_chain(250, "Fantom Opera", "ftm", "Fantom", "FTM", None),
+    evm_chain(324, "zkSync Mainnet", "zksync", "Ether", "ETH", None),
+    evm_chain(1101, "Polygon zkEVM", "zkevm", "Ether", "ETH", None),
+    evm_chain(1284, "Moonbeam", "mbeam", "Glimmer", "GLMR", None),
+    evm_chain(1285, "Moonriver", "mriver", "Moonriver", "MOVR", None),
+    evm_chain(5000, "Mantle", "mantle", "Mantle", "MNT", None),
+    evm_chain(8217, "Kaia Mainnet", "kaia", "KAIA", "KAIA", None),
+    evm_chain(8453, "Base", "base", "Ether", "ETH", None),
+    evm_chain(17000, "Holesky", "holesky", "Testnet Ether", "ETH", None),
+    evm_chain(42161, "Arbitrum One", "arb1", "Ether", "ETH", None),
+    evm_chain(42170, "Arbitrum Nova", "arb-nova", "Ether", "ETH", None),
+    evm_chain(42220, "Celo Mainnet", "celo", "CELO", "CELO", None),
+    evm_chain(
+        43114,
+        "Avalanche C-Chain",
+        "avax",
+        "Avalanche",
+        "AVAX",
+        None,
+    ),
+    evm_chain(59144, "Linea", "linea", "Linea Ether", "ETH", None),
+    evm_chain(80002, "Amoy", "polygonamoy", "POL", "POL", None),
+    evm_chain(81457, "Blast", "blastmainnet", "Ether", "ETH", None),
+    evm_chain(534352, "Scroll Mainnet", "scr", "Ether", "ETH", None),
+    evm_chain(7777777, "Zora", "zora", "Ether", "ETH", None),
+    evm_chain(11155111, "Sepolia", "sep", "Sepolia Ether", "ETH", None),
+    evm_chain(1313161554, "Aurora Mainnet", "aurora", "Ether", "ETH", None),
+    evm_chain(11297108109, "Palm", "palm", "PALM", "PALM", None),
+];
+
+pub fn find_evm_chain(chain_id: u64) -> Option<&'static EvmChain> {
+    EVM_CHAINS.iter().find(|chain| chain.chain_id == chain_id)
+}
+
+pub fn find_evm_chain_by_coin_type(coin_type: u32) -> Option<&'static EvmChain> {
+    EVM_CHAINS
+        .iter()
+        .find(|chain| chain.coin_type == Some(coin_type))
+}
+
+/// How a Cosmos chain derives account addresses from a secp256k1 public key.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CosmosKeyType {
+    /// `ripemd160(sha256(compressed key))`.
+    Secp256k1,
+    /// Ethermint-style `keccak256(uncompressed key)[12..]`.
+    EthSecp256k1,
+}
+
+impl CosmosKeyType {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "secp256k1" => Some(CosmosKeyType::Secp256k1),
+            "ethsecp256k1" => Some(CosmosKeyType::EthSecp256k1),
+            _ => None,
+        }
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            CosmosKeyType::Secp256k1 => "secp256k1",
+            CosmosKeyType::EthSecp256k1 => "ethsecp256k1",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CosmosChain {
+    pub chain_id: Cow<'static, str>,
+    pub name: Cow<'static, str>,
+    pub hrp: Cow<'static, str>,
+    pub coin_type: u32,
+    pub key_type: CosmosKeyType,
+    /// The EIP-155 chain id of the chain's EVM, used as an `evm-sign-request`
+    /// custom chain identifier.
+    pub evm_chain_id: Option<u64>,
+}
+
+const fn cosmos_chain(
+    chain_id: &'static str,
+    name: &'static str,
+    hrp: &'static str,
+    coin_type: u32,
+) -> CosmosChain {
+    CosmosChain {
+        chain_id: Cow::Borrowed(chain_id),
+        name: Cow::Borrowed(name),
+        hrp: Cow::Borrowed(hrp),
+        coin_type,
+        key_type: CosmosKeyType::Secp256k1,
+        evm_chain_id: None,
+    }
+}
+
+const fn ethermint_chain(
+    chain_id: &'static str,
+    name: &'static str,
+    hrp: &'static str,
+    evm_chain_id: Option<u64>,
+) -> CosmosChain {
+    CosmosChain {
+        chain_id: Cow::Borrowed(chain_id),
+        name: Cow::Borrowed(name),
+        hrp: Cow::Borrowed(hrp),
+        coin_type: 60,
+        key_type: CosmosKeyType::EthSecp256k1,
+        evm_chain_id,
+    }
+}
+
+pub const COSMOS_CHAINS: [CosmosChain; 24] = [
+    cosmos_chain("cosmoshub-4", "Cosmos Hub", "cosmos", 118),
+    cosmos_chain("osmosis-1", "Osmosis", "osmo", 118),
+    cosmos_chain("juno-1", "Juno", "juno", 118),
+    cosmos_chain("akashnet-2", "Akash", "akash", 118),
+    cosmos_chain("secret-4", "Secret Network", "secret", 529),
+    cosmos_chain("crypto-org-chain-mainnet-1", "Cronos POS Chain", "cro", 394),
+    cosmos_chain("kava_2222-10", "Kava", "kava", 459),
+    cosmos_chain("dydx-mainnet-1", "dYdX", "dydx", 118),
+    cosmos_chain("celestia", "Celestia", "celestia", 118),
+    cosmos_chain("stargaze-1", "Stargaze", "stars", 118),
+    cosmos_chain("stride-1", "Stride", "stride", 118),
+    cosmos_chain("axelar-dojo-1", "Axelar", "axelar", 118),
+    cosmos_chain("regen-1", "Regen", "regen", 118),
+    cosmos_chain("phoenix-1", "Terra", "terra", 330),
+    cosmos_chain("neutron-1", "Neutron", "neutron", 118),
+    cosmos_chain("pacific-1", "Sei", "sei", 118),
+    cosmos_chain("cataclysm-1", "Nibiru", "nibi", 118),
+    cosmos_chain("thorchain-1", "THORChain", "thor", 931),
+    cosmos_chain("irishub-1", "IRISnet", "iaa", 118),
+    cosmos_chain("umee-1", "Umee", "umee", 118),
+    ethermint_chain("evmos_9001-2", "Evmos", "evmos", Some(9001)),
+    ethermint_chain("injective-1", "Injective", "inj", None),
+    ethermint_chain("canto_7700-1", "Canto", "canto", Some(7700)),
+    ethermint_chain("dymension_1100-1", "Dymension", "dym", Some(1100)),
+];
+
+/// The built-in Cosmos chains plus any registered at runtime through `register_cosmos_chain`,
+/// so wallets can support new chains without a library release.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CosmosChainRegistry {
+    chains: Vec<CosmosChain>,
+}
+
+impl CosmosChainRegistry {
+    pub fn builtin() -> Self {
+        CosmosChainRegistry {
+            chains: COSMOS_CHAINS.to_vec(),
+        }
+    }
+
+    /// Adds a chain, replacing a registered chain with the same chain id. A chain whose HRP or
+    /// EVM chain id already belongs to another chain is rejected, as lookups would be ambiguous.
+    pub fn register(&mut self, chain: CosmosChain) -> Result<(), Error> {
+        if chain.chain_id.is_empty() {
+            return Err(format_err!("chain id is required"));
+        }
+        let conflict = self.chains.iter().any(|registered| {
+            registered.chain_id != chain.chain_id
+                && (registered.hrp == chain.hrp
+                    || (chain.evm_chain_id.is_some()
+                        && registered.evm_chain_id == chain.evm_chain_id))
+        });
+        if conflict {
+            return Err(format_err!("chain conflicts with a registered chain"));
+        }
+        match self
+            .chains
+            .iter_mut()
+            .find(|registered| registered.chain_id == chain.chain_id)
+        {
+            Some(registered) => *registered = chain,
+            None => self.chains.push(chain),
+        }
+        Ok(())
+    }
+
+    pub fn find(&self, chain_id: &str) -> Option<&CosmosChain> {
+        self.chains.iter().find(|chain| chain.chain_id == chain_id)
+    }
+
+    pub fn find_by_hrp(&self, hrp: &str) -> Option<&CosmosChain> {
+        self.chains.iter().find(|chain| chain.hrp == hrp)
+    }
+
+    pub fn find_by_evm_chain_id(&self, evm_chain_id: u64) -> Option<&CosmosChain> {
+        self.chains
+            .iter()
+            .find(|chain| chain.evm_chain_id == Some(evm_chain_id))
+    }
+}
+
+pub fn cosmos_chain_registry() -> &'static Mutex<CosmosChainRegistry> {
+    static REGISTRY: OnceLock<Mutex<CosmosChainRegistry>> = OnceLock::new();
+    REGISTRY.get_or_init(|| Mutex::new(CosmosChainRegistry::builtin()))
+}
+
+fn find_registered_cosmos_chain(
+    find: impl FnOnce(&CosmosChainRegistry) -> Option<&CosmosChain>,
+) -> Option<CosmosChain> {
+    let registry = cosmos_chain_registry().lock().ok()?;
+    find(&registry).cloned()
+}
+
+pub fn find_cosmos_chain(chain_id: &str) -> Option<CosmosChain> {
+    find_registered_cosmos_chain(|registry| registry.find(chain_id))
+}
+
+pub fn find_cosmos_chain_by_hrp(hrp: &str) -> Option<CosmosChain> {
+    find_registered_cosmos_chain(|registry| registry.find_by_hrp(hrp))
+}
+
+pub fn find_cosmos_chain_by_evm_chain_id(evm_chain_id: u64) -> Option<CosmosChain> {
+    find_registered_cosmos_chain(|registry| registry.find_by_evm_chain_id(evm_chain_id))
+}
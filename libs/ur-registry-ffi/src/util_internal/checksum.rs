@@ -0,0 +1,25 @@
+/// CRC-16/XMODEM, the checksum of TON user-friendly addresses and Stellar strkeys.
+pub fn crc16_xmodem(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0;
+    for byte in data {
+        crc ^= u16::from(*byte) << 8;
+        for _ in 0..8 {
+            crc = match crc & 0x8000 {
+                0 => crc << 1,
+                _ => (crc << 1) ^ 0x1021,
+            };
+        }
+    }
+    crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crc16_xmodem() {
+        assert_eq!(0x31c3, crc16_xmodem(b"123456789"));
+        assert_eq!(0, crc16_xmodem(&[]));
+    }
+}
@@ -0,0 +1,63 @@
+use ed25519_dalek::{Signature, VerifyingKey};
+
+/// Verifies an RFC 8032 Ed25519 signature, rejecting small-order public keys and `R`
+/// points as well as non-canonical encodings.
+pub fn verify_ed25519(public_key: &[u8; 32], message: &[u8], signature: &[u8]) -> bool {
+    let Ok(signature) = Signature::from_slice(signature) else {
+        return false;
+    };
+    let Ok(verifying_key) = VerifyingKey::from_bytes(public_key) else {
+        return false;
+    };
+    verifying_key.verify_strict(message, &signature).is_ok()
+}
+
+/// Signs with an Ed25519 seed, returning the public key and the signature.
+#[cfg(test)]
+pub fn sign_ed25519(seed: &[u8; 32], message: &[u8]) -> ([u8; 32], [u8; 64]) {
+    use ed25519_dalek::{Signer, SigningKey};
+
+    let signing_key = SigningKey::from_bytes(seed);
+    (
+        signing_key.verifying_key().to_bytes(),
+        signing_key.sign(message).to_bytes(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rfc8032_vector() {
+        // RFC 8032, section 7.1, TEST 2.
+        let seed: [u8; 32] =
+            hex::decode("4ccd089b28ff96da9db6c346ec114e0f5b8a319f35aba624da8cf6ed4fb8a6fb")
+                .unwrap()
+                .try_into()
+                .unwrap();
+        let (public_key, signature) = sign_ed25519(&seed, &[0x72]);
+        assert_eq!(
+            "3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c",
+            hex::encode(public_key)
+        );
+        assert_eq!(
+            "92a009a9f0d4cab8720e820b5f642540a2b27b5416503f8fb3762223ebdb69da085ac1e43e15996e458f3613d0f11d8c387b2eaeb4302aeeb00d291612bb0c00",
+            hex::encode(signature)
+        );
+        assert!(verify_ed25519(&public_key, &[0x72], &signature));
+        assert!(!verify_ed25519(&public_key, &[0x73], &signature));
+        assert!(!verify_ed25519(&public_key, &[0x72], &signature[..63]));
+    }
+
+    #[test]
+    fn test_reject_small_order_key() {
+        // The identity point as public key and R with S = 0 satisfies [S]B = R + [k]A for
+        // every message.
+        let mut identity = [0u8; 32];
+        identity[0] = 1;
+        let mut signature = [0u8; 64];
+        signature[0] = 1;
+        assert!(!verify_ed25519(&identity, b"any message", &signature));
+    }
+}
@@ -1,3 +1,10 @@
 pub mod account_helper;
+pub mod bcs;
+pub mod borsh;
 pub mod chain;
+pub mod checksum;
+pub mod ed25519;
+pub mod move_types;
+pub mod protobuf;
 pub mod string_helper;
+pub mod xdr;
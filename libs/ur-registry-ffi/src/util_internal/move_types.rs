@@ -0,0 +1,134 @@
+use anyhow::{format_err, Error};
+
+use crate::util_internal::bcs::BcsReader;
+
+const MAX_DEPTH: usize = 32;
+
+pub fn format_address(address: &[u8; 32]) -> String {
+    format!("0x{}", hex::encode(address))
+}
+
+/// Formats well-known framework addresses the way Move does, e.g. `0x2`.
+pub fn format_short_address(address: &[u8; 32]) -> String {
+    let trimmed = hex::encode(address).trim_start_matches('0').to_string();
+    match trimmed.len() {
+        0 => "0x0".to_string(),
+        x if x <= 2 => format!("0x{trimmed}"),
+        _ => format_address(address),
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TypeTag {
+    Bool,
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+    U256,
+    Address,
+    Signer,
+    Vector(Box<TypeTag>),
+    Struct {
+        address: [u8; 32],
+        module: String,
+        name: String,
+        type_params: Vec<TypeTag>,
+    },
+}
+
+impl TypeTag {
+    pub fn decode(reader: &mut BcsReader) -> Result<Self, Error> {
+        TypeTag::decode_nested(reader, 0)
+    }
+
+    fn decode_nested(reader: &mut BcsReader, depth: usize) -> Result<Self, Error> {
+        if depth > MAX_DEPTH {
+            return Err(format_err!("type tag is nested too deeply"));
+        }
+        match reader.uleb128()? {
+            0 => Ok(TypeTag::Bool),
+            1 => Ok(TypeTag::U8),
+            2 => Ok(TypeTag::U64),
+            3 => Ok(TypeTag::U128),
+            4 => Ok(TypeTag::Address),
+            5 => Ok(TypeTag::Signer),
+            6 => Ok(TypeTag::Vector(Box::new(TypeTag::decode_nested(
+                reader,
+                depth + 1,
+            )?))),
+            7 => Ok(TypeTag::Struct {
+                address: reader.fixed()?,
+                module: reader.string()?,
+                name: reader.string()?,
+                type_params: reader.vec(|r| TypeTag::decode_nested(r, depth + 1))?,
+            }),
+            8 => Ok(TypeTag::U16),
+            9 => Ok(TypeTag::U32),
+            10 => Ok(TypeTag::U256),
+            x => Err(format_err!("unsupported type tag {x}")),
+        }
+    }
+}
+
+impl std::fmt::Display for TypeTag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TypeTag::Bool => write!(f, "bool"),
+            TypeTag::U8 => write!(f, "u8"),
+            TypeTag::U16 => write!(f, "u16"),
+            TypeTag::U32 => write!(f, "u32"),
+            TypeTag::U64 => write!(f, "u64"),
+            TypeTag::U128 => write!(f, "u128"),
+            TypeTag::U256 => write!(f, "u256"),
+            TypeTag::Address => write!(f, "address"),
+            TypeTag::Signer => write!(f, "signer"),
+            TypeTag::Vector(inner) => write!(f, "vector<{inner}>"),
+            TypeTag::Struct {
+                address,
+                module,
+                name,
+                type_params,
+            } => {
+                write!(f, "{}::{module}::{name}", format_short_address(address))?;
+                if !type_params.is_empty() {
+                    let params: Vec<String> = type_params.iter().map(|t| t.to_string()).collect();
+                    write!(f, "<{}>", params.join(", "))?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_type_tag() {
+        let bytes = [6, 6, 1];
+        let tag = TypeTag::decode(&mut BcsReader::new(&bytes)).unwrap();
+        assert_eq!("vector<vector<u8>>", tag.to_string());
+
+        let mut nested = vec![6u8; 500_000];
+        nested.push(1);
+        assert_eq!(
+            "type tag is nested too deeply",
+            TypeTag::decode(&mut BcsReader::new(&nested))
+                .unwrap_err()
+                .to_string()
+        );
+
+        // Struct type parameters count towards the same limit.
+        let mut nested = vec![];
+        for _ in 0..100 {
+            nested.extend_from_slice(&[7]);
+            nested.extend_from_slice(&[0u8; 32]);
+            nested.extend_from_slice(&[1, b'm', 1, b'S', 1]);
+        }
+        nested.push(1);
+        assert!(TypeTag::decode(&mut BcsReader::new(&nested)).is_err());
+    }
+}
@@ -0,0 +1,150 @@
+use anyhow::{format_err, Error};
+
+/// A decoded protobuf field value; groups are not supported.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ProtoValue<'a> {
+    Varint(u64),
+    Fixed64(u64),
+    Bytes(&'a [u8]),
+    Fixed32(u32),
+}
+
+/// The fields of a protobuf message, read schemalessly from its wire format.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ProtoMessage<'a> {
+    fields: Vec<(u32, ProtoValue<'a>)>,
+}
+
+fn read_varint(bytes: &mut &[u8]) -> Result<u64, Error> {
+    let mut value: u64 = 0;
+    for shift in (0..64).step_by(7) {
+        let (&byte, rest) = bytes
+            .split_first()
+            .ok_or(format_err!("unexpected end of protobuf data"))?;
+        *bytes = rest;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(format_err!("protobuf varint overflow"))
+}
+
+fn take<'a>(bytes: &mut &'a [u8], len: usize) -> Result<&'a [u8], Error> {
+    if bytes.len() < len {
+        return Err(format_err!("unexpected end of protobuf data"));
+    }
+    let (head, tail) = bytes.split_at(len);
+    *bytes = tail;
+    Ok(head)
+}
+
+impl<'a> ProtoMessage<'a> {
+    pub fn decode(mut bytes: &'a [u8]) -> Result<Self, Error> {
+        let mut fields = vec![];
+        while !bytes.is_empty() {
+            let key = read_varint(&mut bytes)?;
+            let field = u32::try_from(key >> 3).map_err(|_| format_err!("invalid field number"))?;
+            if field == 0 {
+                return Err(format_err!("invalid field number"));
+            }
+            let value = match key & 7 {
+                0 => ProtoValue::Varint(read_varint(&mut bytes)?),
+                1 => ProtoValue::Fixed64(u64::from_le_bytes(take(&mut bytes, 8)?.try_into()?)),
+                2 => {
+                    let len = read_varint(&mut bytes)? as usize;
+                    ProtoValue::Bytes(take(&mut bytes, len)?)
+                }
+                5 => ProtoValue::Fixed32(u32::from_le_bytes(take(&mut bytes, 4)?.try_into()?)),
+                x => return Err(format_err!("unsupported wire type {x}")),
+            };
+            fields.push((field, value));
+        }
+        Ok(ProtoMessage { fields })
+    }
+
+    fn last(&self, field: u32) -> Option<ProtoValue<'a>> {
+        self.fields
+            .iter()
+            .rev()
+            .find(|(number, _)| *number == field)
+            .map(|(_, value)| *value)
+    }
+
+    pub fn uint64(&self, field: u32) -> Result<u64, Error> {
+        match self.last(field) {
+            None => Ok(0),
+            Some(ProtoValue::Varint(value)) => Ok(value),
+            Some(_) => Err(format_err!("field {field} is not a varint")),
+        }
+    }
+
+    pub fn bytes(&self, field: u32) -> Result<&'a [u8], Error> {
+        match self.last(field) {
+            None => Ok(&[]),
+            Some(ProtoValue::Bytes(value)) => Ok(value),
+            Some(_) => Err(format_err!("field {field} is not length-delimited")),
+        }
+    }
+
+    pub fn string(&self, field: u32) -> Result<String, Error> {
+        String::from_utf8(self.bytes(field)?.to_vec())
+            .map_err(|_| format_err!("field {field} is not a string"))
+    }
+
+    /// Decodes an embedded message, or `None` when the field is absent.
+    pub fn message(&self, field: u32) -> Result<Option<ProtoMessage<'a>>, Error> {
+        match self.last(field) {
+            None => Ok(None),
+            Some(ProtoValue::Bytes(value)) => Ok(Some(ProtoMessage::decode(value)?)),
+            Some(_) => Err(format_err!("field {field} is not length-delimited")),
+        }
+    }
+
+    pub fn repeated_bytes(&self, field: u32) -> Result<Vec<&'a [u8]>, Error> {
+        self.fields
+            .iter()
+            .filter(|(number, _)| *number == field)
+            .map(|(_, value)| match value {
+                ProtoValue::Bytes(bytes) => Ok(*bytes),
+                _ => Err(format_err!("field {field} is not length-delimited")),
+            })
+            .collect()
+    }
+
+    pub fn repeated_messages(&self, field: u32) -> Result<Vec<ProtoMessage<'a>>, Error> {
+        self.repeated_bytes(field)?
+            .into_iter()
+            .map(ProtoMessage::decode)
+            .collect()
+    }
+
+    pub fn has(&self, field: u32) -> bool {
+        self.fields.iter().any(|(number, _)| *number == field)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_proto_message() {
+        // field 1: "abc", field 2: 300, field 3: { field 1: 7 }, field 4: "x", field 4: "y"
+        let bytes = hex::decode("0a0361626310ac021a020807220178220179").unwrap();
+        let message = ProtoMessage::decode(&bytes).unwrap();
+        assert_eq!("abc", message.string(1).unwrap());
+        assert_eq!(300, message.uint64(2).unwrap());
+        assert_eq!(7, message.message(3).unwrap().unwrap().uint64(1).unwrap());
+        assert_eq!(
+            vec![b"x".as_slice(), b"y".as_slice()],
+            message.repeated_bytes(4).unwrap()
+        );
+        assert_eq!(0, message.uint64(5).unwrap());
+        assert_eq!(None, message.message(5).unwrap());
+        assert!(message.uint64(1).is_err());
+
+        assert!(ProtoMessage::decode(&[0x0a, 0x05, 0x61]).is_err());
+        assert!(ProtoMessage::decode(&[0x0b]).is_err());
+    }
+}
@@ -0,0 +1,132 @@
+use anyhow::{format_err, Error};
+
+/// A minimal reader for XDR (RFC 4506): big-endian values padded to four-byte units.
+pub struct XdrReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> XdrReader<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        XdrReader { bytes, position: 0 }
+    }
+
+    /// The number of bytes read so far.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        if self.bytes.len() - self.position < len {
+            return Err(format_err!("unexpected end of xdr data"));
+        }
+        let result = &self.bytes[self.position..self.position + len];
+        self.position += len;
+        Ok(result)
+    }
+
+    pub fn u32(&mut self) -> Result<u32, Error> {
+        Ok(u32::from_be_bytes(self.take(4)?.try_into()?))
+    }
+
+    pub fn i32(&mut self) -> Result<i32, Error> {
+        Ok(i32::from_be_bytes(self.take(4)?.try_into()?))
+    }
+
+    pub fn u64(&mut self) -> Result<u64, Error> {
+        Ok(u64::from_be_bytes(self.take(8)?.try_into()?))
+    }
+
+    pub fn i64(&mut self) -> Result<i64, Error> {
+        Ok(i64::from_be_bytes(self.take(8)?.try_into()?))
+    }
+
+    pub fn bool(&mut self) -> Result<bool, Error> {
+        match self.u32()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            x => Err(format_err!("invalid xdr bool {x}")),
+        }
+    }
+
+    /// Reads fixed-length opaque data and its zero padding.
+    pub fn opaque(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        let result = self.take(len)?;
+        if self.take((4 - len % 4) % 4)?.iter().any(|byte| *byte != 0) {
+            return Err(format_err!("xdr padding is not zero"));
+        }
+        Ok(result)
+    }
+
+    pub fn fixed<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+        Ok(self.opaque(N)?.try_into()?)
+    }
+
+    /// Reads variable-length opaque data of at most `max` bytes.
+    pub fn var_opaque(&mut self, max: usize) -> Result<&'a [u8], Error> {
+        let len = self.u32()? as usize;
+        if len > max {
+            return Err(format_err!("xdr data exceeds {max} bytes"));
+        }
+        self.opaque(len)
+    }
+
+    pub fn string(&mut self, max: usize) -> Result<String, Error> {
+        Ok(String::from_utf8_lossy(self.var_opaque(max)?).into_owned())
+    }
+
+    pub fn option<T>(
+        &mut self,
+        item: impl FnOnce(&mut Self) -> Result<T, Error>,
+    ) -> Result<Option<T>, Error> {
+        match self.bool()? {
+            true => Ok(Some(item(self)?)),
+            false => Ok(None),
+        }
+    }
+
+    pub fn vec<T>(
+        &mut self,
+        max: usize,
+        mut item: impl FnMut(&mut Self) -> Result<T, Error>,
+    ) -> Result<Vec<T>, Error> {
+        let len = self.u32()? as usize;
+        if len > max || len > self.bytes.len() - self.position {
+            return Err(format_err!("xdr array is too long"));
+        }
+        (0..len).map(|_| item(self)).collect()
+    }
+
+    pub fn finish(&self) -> Result<(), Error> {
+        match self.position == self.bytes.len() {
+            true => Ok(()),
+            false => Err(format_err!("unexpected trailing xdr data")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_xdr_reader() {
+        let bytes =
+            hex::decode("0000000100000003616263000000000100000000000000070000000200000005fffffffe")
+                .unwrap();
+        let mut reader = XdrReader::new(&bytes);
+        assert!(reader.bool().unwrap());
+        assert_eq!("abc", reader.string(28).unwrap());
+        assert_eq!(Some(7), reader.option(|r| r.u64()).unwrap());
+        assert_eq!(vec![5, -2], reader.vec(2, |r| r.i32()).unwrap());
+        assert_eq!(36, reader.position());
+        assert!(reader.finish().is_ok());
+
+        assert!(XdrReader::new(&hex::decode("0000000361626301").unwrap())
+            .string(28)
+            .is_err());
+        assert!(XdrReader::new(&hex::decode("00000003").unwrap())
+            .vec(2, |r| r.u32())
+            .is_err());
+    }
+}
@@ -20,14 +20,18 @@ const ORIGIN: u8 = 6;
 pub enum DataType {
     #[default]
     Message = 1,
+    Bip322Simple = 2,
+    Bip322Full = 3,
 }
 
 impl DataType {
     pub fn from_u32(i: u32) -> Result<Self, String> {
         match i {
             1 => Ok(DataType::Message),
+            2 => Ok(DataType::Bip322Simple),
+            3 => Ok(DataType::Bip322Full),
             x => Err(format!(
-                "invalid value for data_type in btc-sign-request, expected (1), received {:?}",
+                "invalid value for data_type in btc-sign-request, expected (1, 2, 3), received {:?}",
                 x
             )),
         }
@@ -188,3 +192,66 @@ impl<'b, C> minicbor::Decode<'b, C> for BtcSignRequest {
         Ok(result)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::bitcoin::btc_sign_request::{BtcSignRequest, DataType};
+    use crate::crypto_key_path::{CryptoKeyPath, PathComponent};
+    use alloc::string::ToString;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    fn build_request(data_type: DataType) -> BtcSignRequest {
+        let path = CryptoKeyPath::new(
+            vec![
+                PathComponent::new(Some(84), true).unwrap(),
+                PathComponent::new(Some(0), true).unwrap(),
+                PathComponent::new(Some(0), true).unwrap(),
+                PathComponent::new(Some(0), false).unwrap(),
+                PathComponent::new(Some(0), false).unwrap(),
+            ],
+            Some([0x73, 0xc5, 0xda, 0x0a]),
+            None,
+        );
+        BtcSignRequest::new(
+            hex::decode("9b1deb4d3b7d4bad9bdd2b0d7b3dcb6d").unwrap(),
+            b"Hello World".to_vec(),
+            data_type,
+            vec![path],
+            Some(vec![
+                "bc1q9vza2e8x573nczrlzms0wvx3gsqjx7vavgkx0l".to_string()
+            ]),
+            Some("keystone".to_string()),
+        )
+    }
+
+    #[test]
+    fn test_bip322_data_type_round_trip() {
+        for (data_type, value) in [(DataType::Bip322Simple, 2), (DataType::Bip322Full, 3)] {
+            let request = build_request(data_type);
+            let cbor: Vec<u8> = request.clone().try_into().unwrap();
+            // key 3 (data type) followed by the unsigned value
+            assert!(cbor.windows(2).any(|w| w == [0x03, value]));
+
+            let decoded = BtcSignRequest::try_from(cbor).unwrap();
+            assert_eq!(value, decoded.get_data_type() as u8);
+            assert_eq!(request.get_request_id(), decoded.get_request_id());
+            assert_eq!(request.get_sign_data(), decoded.get_sign_data());
+            assert_eq!(request.get_addresses(), decoded.get_addresses());
+            assert_eq!(request.get_origin(), decoded.get_origin());
+            assert_eq!(
+                request.get_derivation_paths()[0].get_path(),
+                decoded.get_derivation_paths()[0].get_path()
+            );
+        }
+    }
+
+    #[test]
+    fn test_decode_invalid_data_type() {
+        let request = build_request(DataType::Bip322Full);
+        let mut cbor: Vec<u8> = request.try_into().unwrap();
+        let position = cbor.windows(2).position(|w| w == [0x03, 0x03]).unwrap();
+        cbor[position + 1] = 0x04;
+        assert!(BtcSignRequest::try_from(cbor).is_err());
+    }
+}
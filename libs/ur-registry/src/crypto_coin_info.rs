@@ -11,26 +11,108 @@ use minicbor::{Decoder, Encoder};
 const COIN_TYPE: u8 = 1;
 const NETWORK: u8 = 2;
 
+/// A SLIP-44 coin type. Coins used across this SDK have named variants, any other index is
+/// kept as `Unknown` so that it survives a decode/encode round trip.
 #[derive(Clone, Debug, PartialEq)]
 pub enum CoinType {
-    Bitcoin = 0,
-    Ethereum = 60,
+    Bitcoin,
+    Testnet,
+    Litecoin,
+    Dogecoin,
+    Dash,
+    Ethereum,
+    EthereumClassic,
+    Cosmos,
+    Monero,
+    Zcash,
+    BitcoinCash,
+    Stellar,
+    Tron,
+    Near,
+    Ergo,
+    Arweave,
+    Solana,
+    Ton,
+    Aptos,
+    Sui,
+    Cardano,
+    Iota,
+    Avalanche,
+    Kaspa,
+    Unknown(u32),
 }
 
 impl CoinType {
     pub fn from_u32(i: u32) -> CoinType {
         match i {
             0 => CoinType::Bitcoin,
+            1 => CoinType::Testnet,
+            2 => CoinType::Litecoin,
+            3 => CoinType::Dogecoin,
+            5 => CoinType::Dash,
             60 => CoinType::Ethereum,
-            _ => CoinType::Bitcoin,
+            61 => CoinType::EthereumClassic,
+            118 => CoinType::Cosmos,
+            128 => CoinType::Monero,
+            133 => CoinType::Zcash,
+            145 => CoinType::BitcoinCash,
+            148 => CoinType::Stellar,
+            195 => CoinType::Tron,
+            397 => CoinType::Near,
+            429 => CoinType::Ergo,
+            472 => CoinType::Arweave,
+            501 => CoinType::Solana,
+            607 => CoinType::Ton,
+            637 => CoinType::Aptos,
+            784 => CoinType::Sui,
+            1815 => CoinType::Cardano,
+            4218 => CoinType::Iota,
+            9000 => CoinType::Avalanche,
+            111111 => CoinType::Kaspa,
+            x => CoinType::Unknown(x),
+        }
+    }
+
+    pub fn to_u32(&self) -> u32 {
+        match self {
+            CoinType::Bitcoin => 0,
+            CoinType::Testnet => 1,
+            CoinType::Litecoin => 2,
+            CoinType::Dogecoin => 3,
+            CoinType::Dash => 5,
+            CoinType::Ethereum => 60,
+            CoinType::EthereumClassic => 61,
+            CoinType::Cosmos => 118,
+            CoinType::Monero => 128,
+            CoinType::Zcash => 133,
+            CoinType::BitcoinCash => 145,
+            CoinType::Stellar => 148,
+            CoinType::Tron => 195,
+            CoinType::Near => 397,
+            CoinType::Ergo => 429,
+            CoinType::Arweave => 472,
+            CoinType::Solana => 501,
+            CoinType::Ton => 607,
+            CoinType::Aptos => 637,
+            CoinType::Sui => 784,
+            CoinType::Cardano => 1815,
+            CoinType::Iota => 4218,
+            CoinType::Avalanche => 9000,
+            CoinType::Kaspa => 111111,
+            CoinType::Unknown(x) => *x,
         }
     }
 }
 
+/// The network of a coin. BCR-2020-007 only defines mainnet (0) and testnet (1); regtest and
+/// signet use the next free values and any other value is kept as `Unknown`.
 #[derive(Clone, Debug, PartialEq)]
 pub enum Network {
-    MainNet = 0,
-    TestNet = 1,
+    MainNet,
+    TestNet,
+    Regtest,
+    Signet,
+    Unknown(u32),
 }
 
 impl Network {
@@ -38,9 +120,25 @@ impl Network {
         match i {
             0 => Network::MainNet,
             1 => Network::TestNet,
-            _ => Network::TestNet,
+            2 => Network::Regtest,
+            3 => Network::Signet,
+            x => Network::Unknown(x),
         }
     }
+
+    pub fn to_u32(&self) -> u32 {
+        match self {
+            Network::MainNet => 0,
+            Network::TestNet => 1,
+            Network::Regtest => 2,
+            Network::Signet => 3,
+            Network::Unknown(x) => *x,
+        }
+    }
+
+    pub fn is_test_network(&self) -> bool {
+        matches!(self, Network::TestNet | Network::Regtest | Network::Signet)
+    }
 }
 
 #[derive(Clone, Debug, Default)]
@@ -95,12 +193,12 @@ impl<C> minicbor::Encode<C> for CryptoCoinInfo {
         e.map(size)?;
         if let Some(coin_type) = &self.coin_type {
             e.int(Int::from(COIN_TYPE))?
-                .int(Int::from(coin_type.clone() as u8))?;
+                .int(Int::from(coin_type.to_u32()))?;
         }
 
         if let Some(network) = &self.network {
             e.int(Int::from(NETWORK))?
-                .int(Int::from(network.clone() as u8))?;
+                .int(Int::from(network.to_u32()))?;
         }
         Ok(())
     }
@@ -176,4 +274,33 @@ mod tests {
         assert_eq!(crypto.get_network(), Network::TestNet);
         assert_eq!(crypto.get_coin_type(), CoinType::Bitcoin);
     }
+
+    #[test]
+    fn test_unknown_values_round_trip() {
+        let crypto = CryptoCoinInfo::new(
+            Some(CoinType::from_u32(354)),
+            Some(Network::from_u32(7)),
+        );
+        let bytes: Vec<u8> = crypto.try_into().unwrap();
+        assert_eq!("a2011901620207", hex::encode(&bytes));
+
+        let crypto = CryptoCoinInfo::try_from(bytes).unwrap();
+        assert_eq!(crypto.get_coin_type(), CoinType::Unknown(354));
+        assert_eq!(crypto.get_network(), Network::Unknown(7));
+    }
+
+    #[test]
+    fn test_named_values() {
+        let bytes = Vec::from_hex("a2011a0001b2070203").unwrap();
+        let crypto = CryptoCoinInfo::try_from(bytes.clone()).unwrap();
+        assert_eq!(crypto.get_coin_type(), CoinType::Kaspa);
+        assert_eq!(crypto.get_network(), Network::Signet);
+        assert!(crypto.get_network().is_test_network());
+        let encoded: Vec<u8> = crypto.try_into().unwrap();
+        assert_eq!(bytes, encoded);
+
+        assert_eq!(CoinType::from_u32(145), CoinType::BitcoinCash);
+        assert_eq!(CoinType::Dash.to_u32(), 5);
+        assert_eq!(Network::from_u32(2), Network::Regtest);
+    }
 }
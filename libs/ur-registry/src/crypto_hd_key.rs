@@ -1,16 +1,25 @@
 use crate::cbor::cbor_map;
 use crate::crypto_coin_info::CryptoCoinInfo;
-use crate::crypto_key_path::CryptoKeyPath;
+use crate::crypto_key_path::{CryptoKeyPath, PathComponent};
 use crate::error::{URError, URResult};
 use crate::registry_types::{RegistryType, CRYPTO_HDKEY};
 use crate::traits::{From as FromCbor, RegistryItem, To};
 use crate::types::{Bytes, Fingerprint};
+use alloc::format;
 use alloc::string::{String, ToString};
 use alloc::vec;
 use alloc::vec::Vec;
+use curve25519_dalek::edwards::{CompressedEdwardsY, EdwardsPoint};
+use hmac::{Hmac, Mac};
+use k256::elliptic_curve::group::prime::PrimeCurveAffine;
+use k256::elliptic_curve::sec1::{FromEncodedPoint, ToEncodedPoint};
+use k256::elliptic_curve::PrimeField;
+use k256::{AffinePoint, EncodedPoint, ProjectivePoint};
 use minicbor::data::{Int, Tag};
 use minicbor::encode::Write;
 use minicbor::{Decoder, Encoder};
+use ripemd::Ripemd160;
+use sha2::{Digest, Sha256, Sha512};
 
 const IS_MASTER: u8 = 1;
 const IS_PRIVATE: u8 = 2;
@@ -158,6 +167,142 @@ impl CryptoHDKey {
         self.origin.clone().and_then(|v| v.get_depth())
     }
 
+    fn child_indexes(path: &str) -> Result<Vec<PathComponent>, String> {
+        let components = CryptoKeyPath::from_path(path.to_string(), None)?.get_components();
+        if components
+            .iter()
+            .any(|c| c.is_hardened() || c.get_index().is_none())
+        {
+            return Err(format!(
+                "Public derivation only supports non-hardened indexes: {path}"
+            ));
+        }
+        Ok(components)
+    }
+
+    fn derived_key(
+        &self,
+        components: &[PathComponent],
+        key: Bytes,
+        chain_code: Bytes,
+        parent_fingerprint: Option<Fingerprint>,
+    ) -> CryptoHDKey {
+        let origin = match &self.origin {
+            Some(origin) => {
+                let mut path = origin.get_components();
+                path.extend_from_slice(components);
+                CryptoKeyPath::new(
+                    path,
+                    origin.get_source_fingerprint(),
+                    origin
+                        .get_depth()
+                        .map(|depth| depth + components.len() as u32),
+                )
+            }
+            None => CryptoKeyPath::new(components.to_vec(), None, None),
+        };
+        CryptoHDKey {
+            is_master: Some(false),
+            is_private_key: Some(false),
+            key,
+            chain_code: Some(chain_code),
+            use_info: self.use_info.clone(),
+            origin: Some(origin),
+            children: None,
+            parent_fingerprint,
+            name: self.name.clone(),
+            note: self.note.clone(),
+        }
+    }
+
+    fn public_key_and_chain_code(&self, key_len: usize) -> Result<(Bytes, Bytes), String> {
+        if self.is_private_key() {
+            return Err("Child derivation requires a public key".to_string());
+        }
+        if self.key.len() != key_len {
+            return Err(format!("Invalid public key length: {}", self.key.len()));
+        }
+        match &self.chain_code {
+            Some(chain_code) if chain_code.len() == 32 => {
+                Ok((self.key.clone(), chain_code.clone()))
+            }
+            _ => Err("Child derivation requires a 32 bytes chain code".to_string()),
+        }
+    }
+
+    /// BIP-32 public child derivation (secp256k1) along a non-hardened relative path such
+    /// as `0/1`. The returned key extends this key's origin with `path`.
+    pub fn derive_child(&self, path: &str) -> Result<CryptoHDKey, String> {
+        let components = CryptoHDKey::child_indexes(path)?;
+        let (mut key, mut chain_code) = self.public_key_and_chain_code(33)?;
+        let mut parent_fingerprint = self.parent_fingerprint;
+        for component in components.iter() {
+            let parent = EncodedPoint::from_bytes(&key)
+                .ok()
+                .and_then(|point| {
+                    Option::<AffinePoint>::from(AffinePoint::from_encoded_point(&point))
+                })
+                .ok_or("Invalid secp256k1 public key".to_string())?;
+            let mut mac = Hmac::<Sha512>::new_from_slice(&chain_code)
+                .map_err(|_| "Invalid chain code".to_string())?;
+            mac.update(&key);
+            mac.update(&component.get_canonical_index().unwrap_or(0).to_be_bytes());
+            let i = mac.finalize().into_bytes();
+            let tweak: [u8; 32] = i[..32].try_into().unwrap();
+            let tweak = Option::<k256::Scalar>::from(k256::Scalar::from_repr(tweak.into()))
+                .ok_or("Invalid child key, try the next index".to_string())?;
+            let child =
+                (ProjectivePoint::GENERATOR * tweak + ProjectivePoint::from(parent)).to_affine();
+            if bool::from(child.is_identity()) {
+                return Err("Invalid child key, try the next index".to_string());
+            }
+            let hash = Ripemd160::digest(Sha256::digest(&key));
+            parent_fingerprint = Some(hash[..4].try_into().unwrap());
+            key = child.to_encoded_point(true).as_bytes().to_vec();
+            chain_code = i[32..].to_vec();
+        }
+        Ok(self.derived_key(&components, key, chain_code, parent_fingerprint))
+    }
+
+    /// BIP32-Ed25519 public child derivation (as used by Cardano) along a non-hardened
+    /// relative path. BIP32-Ed25519 has no key fingerprint, so the parent fingerprint is
+    /// left unset.
+    pub fn derive_ed25519_child(&self, path: &str) -> Result<CryptoHDKey, String> {
+        let components = CryptoHDKey::child_indexes(path)?;
+        let (mut key, mut chain_code) = self.public_key_and_chain_code(32)?;
+        for component in components.iter() {
+            let parent = CompressedEdwardsY::from_slice(&key)
+                .ok()
+                .and_then(|point| point.decompress())
+                .ok_or("Invalid ed25519 public key".to_string())?;
+            let index = component.get_canonical_index().unwrap_or(0).to_le_bytes();
+            let hmac = |prefix: u8| -> Result<Vec<u8>, String> {
+                let mut mac = Hmac::<Sha512>::new_from_slice(&chain_code)
+                    .map_err(|_| "Invalid chain code".to_string())?;
+                mac.update(&[prefix]);
+                mac.update(&key);
+                mac.update(&index);
+                Ok(mac.finalize().into_bytes().to_vec())
+            };
+            let z = hmac(0x02)?;
+            let c = hmac(0x03)?;
+            // 8 * ZL, where ZL is the first 28 bytes of Z read as a little endian integer
+            let mut zl8 = [0u8; 32];
+            let mut carry = 0u16;
+            for (i, byte) in z[..28].iter().enumerate() {
+                let value = ((*byte as u16) << 3) + carry;
+                zl8[i] = value as u8;
+                carry = value >> 8;
+            }
+            zl8[28] = carry as u8;
+            let child = parent
+                + EdwardsPoint::mul_base(&curve25519_dalek::Scalar::from_bytes_mod_order(zl8));
+            key = child.compress().to_bytes().to_vec();
+            chain_code = c[32..].to_vec();
+        }
+        Ok(self.derived_key(&components, key, chain_code, None))
+    }
+
     fn get_map_size(&self) -> u64 {
         let mut size = 1;
         if self.is_private_key.is_some() {
@@ -405,6 +550,7 @@ mod tests {
     use crate::crypto_hd_key::CryptoHDKey;
     use crate::crypto_key_path::{CryptoKeyPath, PathComponent};
     use crate::traits::{From as FromCbor, RegistryItem, To};
+    use alloc::string::ToString;
     use alloc::vec;
     use alloc::vec::Vec;
     use hex;
@@ -499,4 +645,89 @@ mod tests {
         );
         assert_eq!("xpub6H8Qkexp9BdSgEwPAnhiEjp7NMXVEZWoAFWwon5mSwbuPZMfSUTpPwAP1Q2q2kYMRgRQ8udBpEj89wburY1vW7AWDuYpByteGogpB6pPprX", hd_key.get_bip32_key());
     }
+
+    fn from_xpub(xpub: &str, origin: CryptoKeyPath) -> CryptoHDKey {
+        let data = bs58::decode(xpub).with_check(None).into_vec().unwrap();
+        CryptoHDKey::new_extended_key(
+            Some(false),
+            data[45..78].to_vec(),
+            Some(data[13..45].to_vec()),
+            None,
+            Some(origin),
+            None,
+            Some(data[5..9].try_into().unwrap()),
+            None,
+            None,
+        )
+    }
+
+    #[test]
+    fn test_derive_child() {
+        // BIP-32 test vector 1
+        let origin =
+            CryptoKeyPath::from_path("m/0'".to_string(), Some([0x34, 0x42, 0x19, 0x3e])).unwrap();
+        let hd_key = from_xpub("xpub68Gmy5EdvgibQVfPdqkBBCHxA5htiqg55crXYuXoQRKfDBFA1WEjWgP6LHhwBZeNK1VTsfTFUHCdrfp1bgwQ9xv5ski8PX9rL2dZXvgGDnw", origin);
+        let child = hd_key.derive_child("1").unwrap();
+        assert_eq!("xpub6ASuArnXKPbfEwhqN6e3mwBcDTgzisQN1wXN9BJcM47sSikHjJf3UFHKkNAWbWMiGj7Wf5uMash7SyYq527Hqck2AxYysAA7xmALppuCkwQ", child.get_bip32_key());
+        assert_eq!("0'/1", child.get_origin().unwrap().get_path().unwrap());
+        assert_eq!(
+            Some([0x34, 0x42, 0x19, 0x3e]),
+            child.get_origin().unwrap().get_source_fingerprint()
+        );
+
+        let origin = CryptoKeyPath::from_path("m/0'/1/2'".to_string(), None).unwrap();
+        let hd_key = from_xpub("xpub6D4BDPcP2GT577Vvch3R8wDkScZWzQzMMUm3PWbmWvVJrZwQY4VUNgqFJPMM3No2dFDFGTsxxpG5uJh7n7epu4trkrX7x7DogT5Uv6fcLW5", origin);
+        let child = hd_key.derive_child("m/2/1000000000").unwrap();
+        assert_eq!("xpub6H1LXWLaKsWFhvm6RVpEL9P4KfRZSW7abD2ttkWP3SSQvnyA8FSVqNTEcYFgJS2UaFcxupHiYkro49S8yGasTvXEYBVPamhGW6cFJodrTHy", child.get_bip32_key());
+        assert_eq!(
+            child.get_key(),
+            hd_key
+                .derive_child("2")
+                .unwrap()
+                .derive_child("1000000000")
+                .unwrap()
+                .get_key()
+        );
+
+        assert!(hd_key.derive_child("0'").is_err());
+        assert!(hd_key.derive_child("0/*").is_err());
+    }
+
+    #[test]
+    fn test_derive_ed25519_child() {
+        // CIP-19 test vectors: account 0 of the CIP-3 Icarus root of "test walk nut penalty
+        // hip pave soap entry language right filter choice". Its 0/0 child is the published
+        // payment key addr_vk1w0l2sr2zgfm26ztc6nl9xy8ghsk5sh6ldwemlpmp9xylzy4dtf7st80zhd.
+        let hd_key = CryptoHDKey::new_extended_key(
+            Some(false),
+            Vec::from_hex("cf779aa32f35083707808532471cb64ee41426c9bbd46134dac2ac5b2a0ec0e9")
+                .unwrap(),
+            Some(
+                Vec::from_hex("8fa5fcd46abd9d46d4d8a97a8f3465e2c4e8f3c9dad9ff66823a161ecadca604")
+                    .unwrap(),
+            ),
+            None,
+            Some(CryptoKeyPath::from_path("m/1852'/1815'/0'".to_string(), None).unwrap()),
+            None,
+            None,
+            None,
+            None,
+        );
+        let child = hd_key.derive_ed25519_child("0").unwrap();
+        assert_eq!(
+            "73fea80d424276ad0978d4fe5310e8bc2d485f5f6bb3bf87612989f112ad5a7d",
+            hex::encode(child.derive_ed25519_child("0").unwrap().get_key())
+        );
+        assert_eq!(
+            "1852'/1815'/0'/0",
+            child.get_origin().unwrap().get_path().unwrap()
+        );
+        assert_eq!(None, child.get_parent_fingerprint());
+        assert_eq!(
+            hd_key.derive_ed25519_child("0/5").unwrap().get_key(),
+            child.derive_ed25519_child("5").unwrap().get_key()
+        );
+        assert!(hd_key.derive_ed25519_child("0'").is_err());
+        assert!(hd_key.derive_child("0").is_err());
+    }
 }
@@ -6,6 +6,8 @@ use crate::types::Fingerprint;
 use alloc::string::{String, ToString};
 use alloc::vec::Vec;
 use alloc::{format, vec};
+use core::fmt;
+use core::str::FromStr;
 use minicbor::data::{Int, Type};
 use minicbor::encode::Write;
 use minicbor::Encoder;
@@ -19,6 +21,8 @@ pub struct PathComponent {
     index: Option<u32>,
     wildcard: bool,
     hardened: bool,
+    range: Option<(u32, u32)>,
+    pair: Option<[(u32, bool); 2]>,
 }
 
 impl PathComponent {
@@ -26,26 +30,66 @@ impl PathComponent {
     pub fn new(index: Option<u32>, hardened: bool) -> Result<PathComponent, String> {
         match index {
             Some(x) => {
-                if x & PathComponent::HARDEN_BIT != 0 {
-                    return Err(format!(
-                        "Invalid index {} - most significant bit cannot be set",
-                        x
-                    ));
-                }
+                PathComponent::check_index(x)?;
                 Ok(PathComponent {
                     index,
                     wildcard: false,
                     hardened,
+                    range: None,
+                    pair: None,
                 })
             }
             None => Ok(PathComponent {
                 index,
                 wildcard: true,
                 hardened,
+                range: None,
+                pair: None,
             }),
         }
     }
 
+    /// An inclusive range of child indexes, `low` must be lower than `high`.
+    pub fn new_range(low: u32, high: u32, hardened: bool) -> Result<PathComponent, String> {
+        PathComponent::check_index(low)?;
+        PathComponent::check_index(high)?;
+        if low >= high {
+            return Err(format!("Invalid range {low}-{high}"));
+        }
+        Ok(PathComponent {
+            index: None,
+            wildcard: false,
+            hardened,
+            range: Some((low, high)),
+            pair: None,
+        })
+    }
+
+    /// A BIP-389 multipath step such as `<0;1>`, typically the receive and change branches.
+    pub fn new_pair(first: (u32, bool), second: (u32, bool)) -> Result<PathComponent, String> {
+        PathComponent::check_index(first.0)?;
+        PathComponent::check_index(second.0)?;
+        if first == second {
+            return Err(format!("Invalid multipath <{};{}>", first.0, second.0));
+        }
+        Ok(PathComponent {
+            index: None,
+            wildcard: false,
+            hardened: false,
+            range: None,
+            pair: Some([first, second]),
+        })
+    }
+
+    fn check_index(index: u32) -> Result<(), String> {
+        if index & PathComponent::HARDEN_BIT != 0 {
+            return Err(format!(
+                "Invalid index {index} - most significant bit cannot be set"
+            ));
+        }
+        Ok(())
+    }
+
     pub fn get_index(&self) -> Option<u32> {
         self.index
     }
@@ -57,13 +101,117 @@ impl PathComponent {
         })
     }
 
+    pub fn get_range(&self) -> Option<(u32, u32)> {
+        self.range
+    }
+
+    pub fn get_pair(&self) -> Option<[(u32, bool); 2]> {
+        self.pair
+    }
+
     pub fn is_wildcard(&self) -> bool {
         self.wildcard
     }
 
+    pub fn is_range(&self) -> bool {
+        self.range.is_some()
+    }
+
+    pub fn is_pair(&self) -> bool {
+        self.pair.is_some()
+    }
+
     pub fn is_hardened(&self) -> bool {
         self.hardened
     }
+
+    fn format_index(index: u32, hardened: bool) -> String {
+        match hardened {
+            true => format!("{index}'"),
+            false => format!("{index}"),
+        }
+    }
+
+    fn parse_index(value: &str) -> Result<(u32, bool), String> {
+        let (number, hardened) = match value.strip_suffix(['\'', 'h', 'H']) {
+            Some(number) => (number, true),
+            None => (value, false),
+        };
+        if number.is_empty() || !number.chars().all(|c| c.is_ascii_digit()) {
+            return Err(format!("Invalid index: {value}"));
+        }
+        let index = number
+            .parse::<u32>()
+            .map_err(|_| format!("Invalid index: {value}"))?;
+        PathComponent::check_index(index)?;
+        Ok((index, hardened))
+    }
+}
+
+impl FromStr for PathComponent {
+    type Err = String;
+
+    /// Parses one path step: `44'`, `0`, `*`, `*'`, `0-9`, `0'-9'` or `<0;1>`.
+    /// Hardened steps may be marked with `'`, `h` or `H`.
+    fn from_str(value: &str) -> Result<PathComponent, String> {
+        if let Some(inner) = value.strip_prefix('<').and_then(|v| v.strip_suffix('>')) {
+            let items = inner
+                .split(';')
+                .map(PathComponent::parse_index)
+                .collect::<Result<Vec<(u32, bool)>, String>>()?;
+            return match items.as_slice() {
+                [first, second] => PathComponent::new_pair(*first, *second),
+                _ => Err(format!("Only two-way multipath is supported: {value}")),
+            };
+        }
+        if let Some(marker) = value.strip_prefix('*') {
+            return match marker {
+                "" => PathComponent::new(None, false),
+                "'" | "h" | "H" => PathComponent::new(None, true),
+                _ => Err(format!("Invalid index: {value}")),
+            };
+        }
+        if let Some((low, high)) = value.split_once('-') {
+            let (low, low_hardened) = PathComponent::parse_index(low)?;
+            let (high, high_hardened) = PathComponent::parse_index(high)?;
+            if low_hardened != high_hardened {
+                return Err(format!("Invalid range: {value}"));
+            }
+            return PathComponent::new_range(low, high, high_hardened);
+        }
+        let (index, hardened) = PathComponent::parse_index(value)?;
+        PathComponent::new(Some(index), hardened)
+    }
+}
+
+impl fmt::Display for PathComponent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some([(first, first_hardened), (second, second_hardened)]) = self.pair {
+            return write!(
+                f,
+                "<{};{}>",
+                PathComponent::format_index(first, first_hardened),
+                PathComponent::format_index(second, second_hardened)
+            );
+        }
+        if let Some((low, high)) = self.range {
+            return write!(
+                f,
+                "{}-{}",
+                PathComponent::format_index(low, self.hardened),
+                PathComponent::format_index(high, self.hardened)
+            );
+        }
+        match (self.wildcard, self.hardened) {
+            (true, true) => write!(f, "*'"),
+            (true, false) => write!(f, "*"),
+            (false, hardened) => write!(
+                f,
+                "{}",
+                PathComponent::format_index(self.index.unwrap_or_default(), hardened)
+            ),
+        }
+    }
 }
 
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
@@ -101,50 +249,58 @@ impl CryptoKeyPath {
         Some(
             self.components
                 .iter()
-                .map::<String, fn(&PathComponent) -> String>(|component| {
-                    match (component.wildcard, component.hardened) {
-                        (true, true) => "*'".to_string(),
-                        (true, false) => "*".to_string(),
-                        (false, true) => format!("{}'", component.index.unwrap()),
-                        (false, false) => format!("{}", component.index.unwrap()),
-                    }
-                })
+                .map(|component| component.to_string())
                 .collect::<Vec<String>>()
                 .join("/"),
         )
     }
 
-    pub fn from_path(path: String, fingerprint: Option<Fingerprint>) -> Result<Self, String> {
-        let remove_prefix = path.replace("M/", "").replace("m/", "");
-        let chunks = remove_prefix
-            .split('/')
-            .map(|split| match split.chars().last() {
-                Some('\'') => {
-                    let mut remove_quote = split.to_string();
-                    remove_quote.pop();
-                    let index = remove_quote
-                        .parse()
-                        .map_err(|_| format!("Invalid index: {}", remove_quote))?;
-                    Ok(PathComponent {
-                        hardened: true,
-                        index: Some(index),
-                        wildcard: false,
-                    })
-                }
-                Some(_) => {
-                    let num = split.to_string();
-                    let index = num.parse().map_err(|_| format!("Invalid index: {}", num))?;
-                    Ok(PathComponent {
-                        hardened: false,
-                        index: Some(index),
-                        wildcard: false,
-                    })
+    pub fn is_multipath(&self) -> bool {
+        self.components.iter().any(|component| component.is_pair())
+    }
+
+    /// Returns the single path selected by `branch` (0 or 1) in every multipath step.
+    pub fn get_branch(&self, branch: usize) -> Option<CryptoKeyPath> {
+        let components = self
+            .components
+            .iter()
+            .map(|component| match component.pair {
+                Some(pair) => {
+                    let (index, hardened) = *pair.get(branch)?;
+                    PathComponent::new(Some(index), hardened).ok()
                 }
-                _ => Err("Invalid Path".to_string()),
+                None => Some(*component),
             })
-            .collect::<Result<Vec<PathComponent>, String>>()?;
+            .collect::<Option<Vec<PathComponent>>>()?;
+        Some(CryptoKeyPath {
+            components,
+            source_fingerprint: self.source_fingerprint,
+            depth: self.depth,
+        })
+    }
+
+    pub fn from_path(path: String, fingerprint: Option<Fingerprint>) -> Result<Self, String> {
+        let path = path.trim();
+        let remove_prefix = match path {
+            "" => return Err("Invalid Path".to_string()),
+            "m" | "M" => "",
+            _ => path
+                .strip_prefix("m/")
+                .or_else(|| path.strip_prefix("M/"))
+                .unwrap_or(path),
+        };
+        let components = match remove_prefix {
+            "" => vec![],
+            _ => remove_prefix
+                .split('/')
+                .map(|split| match split {
+                    "" => Err("Invalid Path".to_string()),
+                    _ => PathComponent::from_str(split),
+                })
+                .collect::<Result<Vec<PathComponent>, String>>()?,
+        };
         Ok(CryptoKeyPath {
-            components: chunks,
+            components,
             source_fingerprint: fingerprint,
             depth: None,
         })
@@ -175,9 +331,24 @@ impl<C> minicbor::Encode<C> for CryptoKeyPath {
             Int::try_from(COMPONENTS)
                 .map_err(|e| minicbor::encode::Error::message(e.to_string()))?,
         )?
-        .array(2 * self.components.len() as u64)?;
+        .array(
+            self.components
+                .iter()
+                .map(|component| if component.is_pair() { 1 } else { 2 })
+                .sum(),
+        )?;
         for component in self.components.iter() {
-            if component.is_wildcard() {
+            if let Some([(first, first_hardened), (second, second_hardened)]) = component.pair {
+                e.array(4)?
+                    .int(Int::from(first))?
+                    .bool(first_hardened)?
+                    .int(Int::from(second))?
+                    .bool(second_hardened)?;
+                continue;
+            }
+            if let Some((low, high)) = component.range {
+                e.array(2)?.int(Int::from(low))?.int(Int::from(high))?;
+            } else if component.is_wildcard() {
                 e.array(0)?;
             } else {
                 match component.index {
@@ -233,13 +404,38 @@ impl<'b, C> minicbor::Decode<'b, C> for CryptoKeyPath {
                     let mut hardened = false;
                     let mut previous_type: Type = Type::Null;
                     let mut path_index: Option<u32> = None;
+                    let mut range: Option<(u32, u32)> = None;
+                    let read_index = |d: &mut minicbor::Decoder<'b>| {
+                        u32::try_from(d.int()?)
+                            .map_err(|e| minicbor::decode::Error::message(e.to_string()))
+                    };
                     cbor_array(d, obj, |_index, _obj, d| {
                         let data_type = cbor_type(d.datatype()?);
                         match data_type {
-                            Type::Array => {
-                                d.array()?;
-                                previous_type = Type::Array;
-                            }
+                            Type::Array => match d.array()? {
+                                Some(2) => {
+                                    range = Some((read_index(d)?, read_index(d)?));
+                                    previous_type = Type::Array;
+                                }
+                                Some(4) => {
+                                    let first = (read_index(d)?, d.bool()?);
+                                    let second = (read_index(d)?, d.bool()?);
+                                    path_component.push(
+                                        PathComponent::new_pair(first, second)
+                                            .map_err(minicbor::decode::Error::message)?,
+                                    );
+                                    previous_type = Type::Null;
+                                }
+                                Some(0) => {
+                                    range = None;
+                                    previous_type = Type::Array;
+                                }
+                                _ => {
+                                    return Err(minicbor::decode::Error::message(
+                                        "invalid path component",
+                                    ))
+                                }
+                            },
                             Type::Int => {
                                 path_index = Some(u32::try_from(d.int()?).map_err(|e| {
                                     minicbor::decode::Error::message(e.to_string())
@@ -250,10 +446,15 @@ impl<'b, C> minicbor::Decode<'b, C> for CryptoKeyPath {
                                 hardened = d.bool()?;
                                 match previous_type {
                                     Type::Array => {
-                                        path_component
-                                            .push(PathComponent::new(None, hardened).map_err(
-                                                |e| minicbor::decode::Error::message(e),
-                                            )?);
+                                        let component = match range.take() {
+                                            Some((low, high)) => {
+                                                PathComponent::new_range(low, high, hardened)
+                                            }
+                                            None => PathComponent::new(None, hardened),
+                                        };
+                                        path_component.push(
+                                            component.map_err(minicbor::decode::Error::message)?,
+                                        );
                                     }
                                     Type::Int => {
                                         path_component.push(
@@ -306,6 +507,7 @@ impl FromCbor<CryptoKeyPath> for CryptoKeyPath {
 mod tests {
     use crate::crypto_key_path::{CryptoKeyPath, PathComponent};
     use crate::traits::{From as FromCbor, RegistryItem, To};
+    use alloc::string::ToString;
     use alloc::vec;
     use alloc::vec::Vec;
     use hex::FromHex;
@@ -382,4 +584,51 @@ mod tests {
         let crypto = CryptoKeyPath::from_cbor(bytes).unwrap();
         assert_eq!(crypto.get_path().unwrap(), "44'/118'/0'/0/0");
     }
+
+    #[test]
+    fn test_from_path() {
+        let crypto = CryptoKeyPath::from_path("m/48h/0H/0'/2h/<0;1>/*".to_string(), None).unwrap();
+        assert_eq!(crypto.get_path().unwrap(), "48'/0'/0'/2'/<0;1>/*");
+        assert!(crypto.is_multipath());
+        assert_eq!(
+            crypto.get_branch(1).unwrap().get_path().unwrap(),
+            "48'/0'/0'/2'/1/*"
+        );
+        assert!(crypto.get_branch(2).is_none());
+
+        for path in ["44'/0'/0'/0/*'", "0-9/1'-5'", "<0';1'>/7", "0/*"] {
+            let crypto = CryptoKeyPath::from_path(path.to_string(), None).unwrap();
+            assert_eq!(crypto.get_path().unwrap(), path);
+        }
+        assert!(CryptoKeyPath::from_path("m".to_string(), None)
+            .unwrap()
+            .get_components()
+            .is_empty());
+
+        assert!(CryptoKeyPath::from_path("".to_string(), None).is_err());
+        assert!(CryptoKeyPath::from_path("m/44'//0".to_string(), None).is_err());
+        assert!(CryptoKeyPath::from_path("m/0x1".to_string(), None).is_err());
+        assert!(CryptoKeyPath::from_path("m/5-1".to_string(), None).is_err());
+        assert!(CryptoKeyPath::from_path("m/0'-9".to_string(), None).is_err());
+        assert!(CryptoKeyPath::from_path("m/<0;1;2>".to_string(), None).is_err());
+        assert!(CryptoKeyPath::from_path("m/2147483648".to_string(), None).is_err());
+    }
+
+    #[test]
+    fn test_range_and_pair() {
+        // 84'/0'/0'/<0;1>/1-9/*
+        let hex = "a1018b1854f500f500f58400f401f4820109f480f4";
+        let crypto = CryptoKeyPath::try_from(Vec::from_hex(hex).unwrap()).unwrap();
+        let components = crypto.get_components();
+        assert_eq!(components[3].get_pair(), Some([(0, false), (1, false)]));
+        assert_eq!(components[4].get_range(), Some((1, 9)));
+        assert!(components[5].is_wildcard());
+        assert_eq!(crypto.get_path().unwrap(), "84'/0'/0'/<0;1>/1-9/*");
+
+        let encoded: Vec<u8> = CryptoKeyPath::from_path(crypto.get_path().unwrap(), None)
+            .unwrap()
+            .try_into()
+            .unwrap();
+        assert_eq!(hex, hex::encode(encoded));
+    }
 }
@@ -1,16 +1,94 @@
 use crate::crypto_ec_key::CryptoECKey;
 use crate::crypto_hd_key::CryptoHDKey;
 use crate::error::{URError, URResult};
+use crate::miniscript::Miniscript;
 use crate::multi_key::MultiKey;
 use crate::registry_types::{RegistryType, CRYPTO_ECKEY, CRYPTO_HDKEY, CRYPTO_OUTPUT};
 use crate::script_expression::ScriptExpression;
 use crate::traits::{From as FromCbor, RegistryItem, To};
+use alloc::boxed::Box;
 use alloc::string::ToString;
 use alloc::vec;
 use alloc::vec::Vec;
 use minicbor::data::{Tag, Type};
 use minicbor::encode::Write;
-use minicbor::{Decoder, Encoder};
+use minicbor::{Decode, Decoder, Encoder};
+
+/// A taproot script tree as used in `tr(KEY,{...})`. Leaves are script outputs such as
+/// `pk(KEY)`, `multi_a(...)` or `miniscript`, branches are encoded as a two element array.
+#[derive(Clone, Debug)]
+pub enum TapTree {
+    Leaf(Box<CryptoOutput>),
+    Branch(Box<TapTree>, Box<TapTree>),
+}
+
+impl TapTree {
+    pub fn get_leaves(&self) -> Vec<CryptoOutput> {
+        match self {
+            TapTree::Leaf(leaf) => vec![*leaf.clone()],
+            TapTree::Branch(left, right) => {
+                let mut leaves = left.get_leaves();
+                leaves.extend(right.get_leaves());
+                leaves
+            }
+        }
+    }
+}
+
+impl<C> minicbor::Encode<C> for TapTree {
+    fn encode<W: Write>(
+        &self,
+        e: &mut Encoder<W>,
+        ctx: &mut C,
+    ) -> Result<(), minicbor::encode::Error<W::Error>> {
+        match self {
+            TapTree::Leaf(leaf) => CryptoOutput::encode(leaf, e, ctx),
+            TapTree::Branch(left, right) => {
+                e.array(2)?;
+                TapTree::encode(left, e, ctx)?;
+                TapTree::encode(right, e, ctx)
+            }
+        }
+    }
+}
+
+/// The maximum depth of a taproot script tree (BIP-341).
+const MAX_TAP_TREE_DEPTH: usize = 128;
+
+impl TapTree {
+    fn decode_nested<'b, C>(
+        d: &mut Decoder<'b>,
+        ctx: &mut C,
+        depth: usize,
+    ) -> Result<Self, minicbor::decode::Error> {
+        if depth > MAX_TAP_TREE_DEPTH {
+            return Err(minicbor::decode::Error::message(
+                "tap tree is nested too deeply",
+            ));
+        }
+        if d.datatype()? != Type::Array {
+            return Ok(TapTree::Leaf(Box::new(CryptoOutput::decode_nested(
+                d,
+                ctx,
+                depth + 1,
+            )?)));
+        }
+        if d.array()? != Some(2) {
+            return Err(minicbor::decode::Error::message(
+                "tap tree branch must have two nodes",
+            ));
+        }
+        let left = TapTree::decode_nested(d, ctx, depth + 1)?;
+        let right = TapTree::decode_nested(d, ctx, depth + 1)?;
+        Ok(TapTree::Branch(Box::new(left), Box::new(right)))
+    }
+}
+
+impl<'b, C> minicbor::Decode<'b, C> for TapTree {
+    fn decode(d: &mut Decoder<'b>, ctx: &mut C) -> Result<Self, minicbor::decode::Error> {
+        TapTree::decode_nested(d, ctx, 0)
+    }
+}
 
 #[derive(Clone, Debug, Default)]
 pub struct CryptoOutput {
@@ -18,6 +96,8 @@ pub struct CryptoOutput {
     ec_key: Option<CryptoECKey>,
     hd_key: Option<CryptoHDKey>,
     multi_key: Option<MultiKey>,
+    tap_tree: Option<TapTree>,
+    miniscript: Option<Miniscript>,
 }
 
 impl CryptoOutput {
@@ -36,6 +116,37 @@ impl CryptoOutput {
             ec_key,
             hd_key,
             multi_key,
+            tap_tree: None,
+            miniscript: None,
+        }
+    }
+
+    /// `tr(KEY,{tree})`, the internal key is either `ec_key` or `hd_key`.
+    pub fn new_taproot_tree(
+        ec_key: Option<CryptoECKey>,
+        hd_key: Option<CryptoHDKey>,
+        tap_tree: TapTree,
+    ) -> Self {
+        CryptoOutput {
+            script_expressions: vec![ScriptExpression::Taproot],
+            ec_key,
+            hd_key,
+            tap_tree: Some(tap_tree),
+            ..Default::default()
+        }
+    }
+
+    /// `script_expressions` are the wrappers around the fragment, e.g. `[WitnessScriptHash]`,
+    /// the trailing `Miniscript` expression is appended automatically.
+    pub fn new_miniscript(
+        mut script_expressions: Vec<ScriptExpression>,
+        miniscript: Miniscript,
+    ) -> Self {
+        script_expressions.push(ScriptExpression::Miniscript);
+        CryptoOutput {
+            script_expressions,
+            miniscript: Some(miniscript),
+            ..Default::default()
         }
     }
 
@@ -54,6 +165,45 @@ impl CryptoOutput {
     pub fn get_multi_key(&self) -> Option<MultiKey> {
         self.multi_key.clone()
     }
+
+    pub fn get_tap_tree(&self) -> Option<TapTree> {
+        self.tap_tree.clone()
+    }
+
+    pub fn get_miniscript(&self) -> Option<Miniscript> {
+        self.miniscript.clone()
+    }
+
+    fn encode_key<W: Write, C>(
+        &self,
+        e: &mut Encoder<W>,
+        ctx: &mut C,
+    ) -> Result<(), minicbor::encode::Error<W::Error>> {
+        if let Some(ec_key) = &self.ec_key {
+            e.tag(Tag::Unassigned(CRYPTO_ECKEY.get_tag()))?;
+            minicbor::Encode::encode(ec_key, e, ctx)?;
+        }
+
+        if let Some(hd_key) = &self.hd_key {
+            e.tag(Tag::Unassigned(CRYPTO_HDKEY.get_tag()))?;
+            minicbor::Encode::encode(hd_key, e, ctx)?;
+        }
+        Ok(())
+    }
+
+    fn decode_key<C>(
+        &mut self,
+        d: &mut Decoder,
+        ctx: &mut C,
+        tag_value: u64,
+    ) -> Result<(), minicbor::decode::Error> {
+        if tag_value == CRYPTO_ECKEY.get_tag() {
+            self.ec_key = Some(minicbor::Decode::decode(d, ctx)?);
+        } else if tag_value == CRYPTO_HDKEY.get_tag() {
+            self.hd_key = Some(minicbor::Decode::decode(d, ctx)?);
+        }
+        Ok(())
+    }
 }
 
 impl RegistryItem for CryptoOutput {
@@ -68,29 +218,53 @@ impl<C> minicbor::Encode<C> for CryptoOutput {
         e: &mut Encoder<W>,
         ctx: &mut C,
     ) -> Result<(), minicbor::encode::Error<W::Error>> {
+        #[cfg(not(feature = "output-extensions"))]
+        if self
+            .script_expressions
+            .iter()
+            .any(ScriptExpression::is_extension)
+        {
+            return Err(minicbor::encode::Error::message(
+                "script expression is not registered in crypto-output",
+            ));
+        }
         for script_expression in &self.script_expressions {
             e.tag(Tag::Unassigned(script_expression.get_tag_value() as u64))?;
         }
-        if let Some(ec_key) = &self.ec_key {
-            e.tag(Tag::Unassigned(CRYPTO_ECKEY.get_tag()))?;
-            CryptoECKey::encode(ec_key, e, ctx)?;
+        if let Some(tap_tree) = &self.tap_tree {
+            e.array(2)?;
+            self.encode_key(e, ctx)?;
+            return TapTree::encode(tap_tree, e, ctx);
         }
 
-        if let Some(hd_key) = &self.hd_key {
-            e.tag(Tag::Unassigned(CRYPTO_HDKEY.get_tag()))?;
-            CryptoHDKey::encode(hd_key, e, ctx)?;
-        }
+        self.encode_key(e, ctx)?;
 
         if let Some(multi_key) = &self.multi_key {
             MultiKey::encode(multi_key, e, ctx)?;
         }
 
+        if let Some(miniscript) = &self.miniscript {
+            Miniscript::encode(miniscript, e, ctx)?;
+        }
+
         Ok(())
     }
 }
 
 impl<'b, C> minicbor::Decode<'b, C> for CryptoOutput {
     fn decode(d: &mut Decoder<'b>, ctx: &mut C) -> Result<Self, minicbor::decode::Error> {
+        CryptoOutput::decode_nested(d, ctx, 0)
+    }
+}
+
+impl CryptoOutput {
+    /// `depth` counts the tap tree levels above this output, as a leaf may itself be a `tr()`
+    /// output whose tree continues the nesting.
+    fn decode_nested<'b, C>(
+        d: &mut Decoder<'b>,
+        ctx: &mut C,
+        depth: usize,
+    ) -> Result<Self, minicbor::decode::Error> {
         let mut result = CryptoOutput::default();
         let mut script_expressions: Vec<ScriptExpression> = vec![];
         let mut last_tag_value: u64 = 0;
@@ -103,17 +277,37 @@ impl<'b, C> minicbor::Decode<'b, C> for CryptoOutput {
                 last_tag_value = n;
             }
         }
-        let is_multi_key = !script_expressions.is_empty()
-            && (script_expressions[script_expressions.len() - 1] == ScriptExpression::MultiSig
-                || script_expressions[script_expressions.len() - 1]
-                    == ScriptExpression::SortedMultiSig);
+        let last_expression = script_expressions.last().cloned();
         result.script_expressions = script_expressions;
-        if is_multi_key {
-            result.multi_key = Some(MultiKey::decode(d, ctx)?);
-        } else if last_tag_value == CRYPTO_ECKEY.get_tag() {
-            result.ec_key = Some(CryptoECKey::decode(d, ctx)?);
-        } else if last_tag_value == CRYPTO_HDKEY.get_tag() {
-            result.hd_key = Some(CryptoHDKey::decode(d, ctx)?);
+        match last_expression {
+            Some(expression) if expression.is_multi_key() => {
+                result.multi_key = Some(MultiKey::decode(d, ctx)?);
+            }
+            Some(ScriptExpression::Miniscript) => {
+                result.miniscript = Some(Miniscript::decode(d, ctx)?);
+            }
+            Some(ScriptExpression::Taproot) if d.datatype()? == Type::Array => {
+                if d.array()? != Some(2) {
+                    return Err(minicbor::decode::Error::message(
+                        "taproot with script tree must have internal key and tree",
+                    ));
+                }
+                let tag_value = match d.tag()? {
+                    Tag::Unassigned(n)
+                        if n == CRYPTO_ECKEY.get_tag() || n == CRYPTO_HDKEY.get_tag() =>
+                    {
+                        n
+                    }
+                    _ => {
+                        return Err(minicbor::decode::Error::message(
+                            "taproot internal key must be crypto-eckey or crypto-hdkey",
+                        ))
+                    }
+                };
+                result.decode_key(d, ctx, tag_value)?;
+                result.tap_tree = Some(TapTree::decode_nested(d, ctx, depth)?);
+            }
+            _ => result.decode_key(d, ctx, last_tag_value)?,
         }
         Ok(result)
     }
@@ -134,14 +328,21 @@ impl FromCbor<CryptoOutput> for CryptoOutput {
 #[cfg(test)]
 mod tests {
     use crate::crypto_ec_key::CryptoECKey;
-    use crate::crypto_output::CryptoOutput;
+    use crate::crypto_output::{CryptoOutput, TapTree};
     use crate::multi_key::MultiKey;
     use crate::script_expression::ScriptExpression;
     use crate::traits::{From as FromCbor, RegistryItem, To};
+    use alloc::boxed::Box;
+    use alloc::format;
+    use alloc::string::ToString;
     use alloc::vec;
     use alloc::vec::Vec;
     use hex::FromHex;
 
+    fn ec_key(hex: &str) -> CryptoECKey {
+        CryptoECKey::new(None, None, Vec::from_hex(hex).unwrap())
+    }
+
     #[test]
     fn test_encode() {
         let script_expressions = vec![ScriptExpression::PublicKeyHash];
@@ -234,4 +435,212 @@ mod tests {
             crypto.get_script_expressions()
         );
     }
+
+    #[test]
+    fn test_taproot_tree() {
+        let internal_key =
+            ec_key("03fff97bd5755eeea420453a14355235d382f6472f8568a18b2f057a1460297556");
+        let pk_leaf = |key: &str| {
+            TapTree::Leaf(Box::new(CryptoOutput::new(
+                vec![ScriptExpression::PublicKey],
+                Some(ec_key(key)),
+                None,
+                None,
+            )))
+        };
+        let tree = TapTree::Branch(
+            Box::new(pk_leaf(
+                "022f01e5e15cca351daff3843fb70f3c2f0a1bdd05e5af888a67784ef3e10a2a01",
+            )),
+            Box::new(pk_leaf(
+                "03acd484e2f0c7f65309ad178a9f559abde09796974c57e714c35f110dfc27ccbe",
+            )),
+        );
+        let crypto = CryptoOutput::new_taproot_tree(Some(internal_key), None, tree);
+        let bytes: Vec<u8> = crypto.try_into().unwrap();
+        assert_eq!(
+            "d9019982d90132a103582103fff97bd5755eeea420453a14355235d382f6472f8568a18b2f057a1460297556\
+            82d90192d90132a1035821022f01e5e15cca351daff3843fb70f3c2f0a1bdd05e5af888a67784ef3e10a2a01\
+            d90192d90132a103582103acd484e2f0c7f65309ad178a9f559abde09796974c57e714c35f110dfc27ccbe",
+            hex::encode(&bytes)
+        );
+
+        let crypto = CryptoOutput::try_from(bytes).unwrap();
+        assert_eq!(
+            vec![ScriptExpression::Taproot],
+            crypto.get_script_expressions()
+        );
+        assert!(crypto.get_ec_key().is_some());
+        let leaves = crypto.get_tap_tree().unwrap().get_leaves();
+        assert_eq!(2, leaves.len());
+        assert_eq!(
+            vec![ScriptExpression::PublicKey],
+            leaves[1].get_script_expressions()
+        );
+
+        // The internal key must be a key, not a script expression or another tree.
+        let bytes = Vec::from_hex(
+            "d9019982d90192d90132a103582103fff97bd5755eeea420453a14355235d382f6472f8568a18b2f057a1460297556\
+            d90192d90132a1035821022f01e5e15cca351daff3843fb70f3c2f0a1bdd05e5af888a67784ef3e10a2a01",
+        )
+        .unwrap();
+        assert!(CryptoOutput::try_from(bytes).is_err());
+    }
+
+    #[test]
+    fn test_taproot_tree_depth() {
+        let taproot = "d9019982d90132a103582103fff97bd5755eeea420453a14355235d382f6472f8568a18b2f057a1460297556";
+        let leaf = "d90192d90132a1035821022f01e5e15cca351daff3843fb70f3c2f0a1bdd05e5af888a67784ef3e10a2a01";
+        let spine = |depth: usize| {
+            Vec::from_hex(format!(
+                "{taproot}{}{leaf}{}",
+                "82".repeat(depth),
+                leaf.repeat(depth)
+            ))
+            .unwrap()
+        };
+        let crypto = CryptoOutput::try_from(spine(128)).unwrap();
+        assert_eq!(129, crypto.get_tap_tree().unwrap().get_leaves().len());
+        assert!(CryptoOutput::try_from(spine(129))
+            .unwrap_err()
+            .to_string()
+            .contains("tap tree is nested too deeply"));
+
+        let mut bytes = Vec::from_hex(taproot).unwrap();
+        bytes.extend(vec![0x82; 2_000_000]);
+        assert!(CryptoOutput::try_from(bytes)
+            .unwrap_err()
+            .to_string()
+            .contains("tap tree is nested too deeply"));
+
+        // A leaf that is itself a `tr()` output continues the nesting of the outer tree.
+        let bytes = Vec::from_hex(format!("{}{leaf}", taproot.repeat(200_000))).unwrap();
+        assert!(CryptoOutput::try_from(bytes)
+            .unwrap_err()
+            .to_string()
+            .contains("tap tree is nested too deeply"));
+    }
+
+    #[cfg(not(feature = "output-extensions"))]
+    #[test]
+    fn test_unregistered_expressions() {
+        let crypto = CryptoOutput::new(
+            vec![ScriptExpression::RawTaproot],
+            Some(ec_key(
+                "03fff97bd5755eeea420453a14355235d382f6472f8568a18b2f057a1460297556",
+            )),
+            None,
+            None,
+        );
+        assert!(TryInto::<Vec<u8>>::try_into(crypto).is_err());
+
+        let bytes = Vec::from_hex(
+            "d9019bd90132a103582103fff97bd5755eeea420453a14355235d382f6472f8568a18b2f057a1460297556",
+        )
+        .unwrap();
+        assert_eq!(
+            vec![ScriptExpression::Undefine(411)],
+            CryptoOutput::try_from(bytes)
+                .unwrap()
+                .get_script_expressions()
+        );
+    }
+
+    #[cfg(feature = "output-extensions")]
+    #[test]
+    fn test_taproot_tree_multi_a() {
+        let internal_key =
+            ec_key("03fff97bd5755eeea420453a14355235d382f6472f8568a18b2f057a1460297556");
+        let pk_leaf = CryptoOutput::new(
+            vec![ScriptExpression::PublicKey],
+            Some(ec_key(
+                "022f01e5e15cca351daff3843fb70f3c2f0a1bdd05e5af888a67784ef3e10a2a01",
+            )),
+            None,
+            None,
+        );
+        let multi_a_leaf = CryptoOutput::new(
+            vec![ScriptExpression::SortedMultiA],
+            None,
+            None,
+            Some(MultiKey::new(
+                1,
+                Some(vec![
+                    ec_key("022f01e5e15cca351daff3843fb70f3c2f0a1bdd05e5af888a67784ef3e10a2a01"),
+                    ec_key("03acd484e2f0c7f65309ad178a9f559abde09796974c57e714c35f110dfc27ccbe"),
+                ]),
+                None,
+            )),
+        );
+        let tree = TapTree::Branch(
+            Box::new(TapTree::Leaf(Box::new(pk_leaf))),
+            Box::new(TapTree::Leaf(Box::new(multi_a_leaf))),
+        );
+        let crypto = CryptoOutput::new_taproot_tree(Some(internal_key), None, tree);
+        let bytes: Vec<u8> = crypto.try_into().unwrap();
+        assert_eq!(
+            "d9019982d90132a103582103fff97bd5755eeea420453a14355235d382f6472f8568a18b2f057a1460297556\
+            82d90192d90132a1035821022f01e5e15cca351daff3843fb70f3c2f0a1bdd05e5af888a67784ef3e10a2a01\
+            d9019da201010282d90132a1035821022f01e5e15cca351daff3843fb70f3c2f0a1bdd05e5af888a67784ef3e10a2a01\
+            d90132a103582103acd484e2f0c7f65309ad178a9f559abde09796974c57e714c35f110dfc27ccbe",
+            hex::encode(&bytes)
+        );
+
+        let crypto = CryptoOutput::try_from(bytes).unwrap();
+        assert_eq!(
+            vec![ScriptExpression::Taproot],
+            crypto.get_script_expressions()
+        );
+        assert!(crypto.get_ec_key().is_some());
+        let leaves = crypto.get_tap_tree().unwrap().get_leaves();
+        assert_eq!(2, leaves.len());
+        assert_eq!(
+            vec![ScriptExpression::PublicKey],
+            leaves[0].get_script_expressions()
+        );
+        assert_eq!(
+            vec![ScriptExpression::SortedMultiA],
+            leaves[1].get_script_expressions()
+        );
+        assert_eq!(1, leaves[1].get_multi_key().unwrap().get_threshold());
+    }
+
+    #[cfg(feature = "output-extensions")]
+    #[test]
+    fn test_miniscript() {
+        use crate::miniscript::{Miniscript, MiniscriptKey};
+        use alloc::string::ToString;
+
+        let miniscript = Miniscript::new(
+            "or_d(pk(@0),and_v(v:pkh(@1),older(144)))".to_string(),
+            vec![
+                MiniscriptKey::ECKey(ec_key(
+                    "022f01e5e15cca351daff3843fb70f3c2f0a1bdd05e5af888a67784ef3e10a2a01",
+                )),
+                MiniscriptKey::ECKey(ec_key(
+                    "03acd484e2f0c7f65309ad178a9f559abde09796974c57e714c35f110dfc27ccbe",
+                )),
+            ],
+        )
+        .unwrap();
+        let crypto =
+            CryptoOutput::new_miniscript(vec![ScriptExpression::WitnessScriptHash], miniscript);
+        let bytes: Vec<u8> = crypto.try_into().unwrap();
+        assert_eq!("d90191d9019ea201", hex::encode(&bytes[..8]));
+
+        let crypto = CryptoOutput::try_from(bytes).unwrap();
+        assert_eq!(
+            vec![
+                ScriptExpression::WitnessScriptHash,
+                ScriptExpression::Miniscript
+            ],
+            crypto.get_script_expressions()
+        );
+        let miniscript = crypto.get_miniscript().unwrap();
+        assert_eq!(
+            "or_d(pk(@0),and_v(v:pkh(@1),older(144)))",
+            miniscript.get_fragment()
+        );
+        assert_eq!(2, miniscript.get_keys().len());
+    }
 }
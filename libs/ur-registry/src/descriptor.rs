@@ -0,0 +1,109 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+const INPUT_CHARSET: &str = "0123456789()[],'/*abcdefgh@:$%{}IJKLMNOPQRSTUVWXYZ&+-.;<=>?!^_|~ijklmnopqrstuvwxyzABCDEFGH`#\"\\ ";
+const CHECKSUM_CHARSET: &[u8] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+fn poly_mod(mut c: u64, val: u64) -> u64 {
+    let c0 = c >> 35;
+    c = ((c & 0x7ffffffff) << 5) ^ val;
+    if c0 & 1 != 0 {
+        c ^= 0xf5dee51989;
+    }
+    if c0 & 2 != 0 {
+        c ^= 0xa9fdca3312;
+    }
+    if c0 & 4 != 0 {
+        c ^= 0x1bab10e32d;
+    }
+    if c0 & 8 != 0 {
+        c ^= 0x3706b1677a;
+    }
+    if c0 & 16 != 0 {
+        c ^= 0x644d626ffd;
+    }
+    c
+}
+
+/// Computes the BIP-380 checksum of an output descriptor without its `#` suffix.
+pub fn checksum(descriptor: &str) -> Result<String, String> {
+    let mut c: u64 = 1;
+    let mut class: u64 = 0;
+    let mut class_count = 0;
+    for ch in descriptor.chars() {
+        let pos = INPUT_CHARSET
+            .find(ch)
+            .ok_or(format!("Invalid descriptor character: {ch}"))? as u64;
+        c = poly_mod(c, pos & 31);
+        class = class * 3 + (pos >> 5);
+        class_count += 1;
+        if class_count == 3 {
+            c = poly_mod(c, class);
+            class = 0;
+            class_count = 0;
+        }
+    }
+    if class_count > 0 {
+        c = poly_mod(c, class);
+    }
+    for _ in 0..8 {
+        c = poly_mod(c, 0);
+    }
+    c ^= 1;
+    let checksum = (0..8)
+        .map(|i| CHECKSUM_CHARSET[((c >> (5 * (7 - i))) & 31) as usize])
+        .collect::<Vec<u8>>();
+    Ok(String::from_utf8_lossy(&checksum).into_owned())
+}
+
+/// Appends `#checksum` to a descriptor.
+pub fn with_checksum(descriptor: &str) -> Result<String, String> {
+    Ok(format!("{}#{}", descriptor, checksum(descriptor)?))
+}
+
+/// Strips and verifies an optional `#checksum` suffix, returning the bare descriptor.
+pub fn strip_checksum(descriptor: &str) -> Result<&str, String> {
+    match descriptor.rsplit_once('#') {
+        Some((body, sum)) => {
+            if checksum(body)? != sum {
+                return Err(format!("Invalid descriptor checksum: {sum}"));
+            }
+            Ok(body)
+        }
+        None => Ok(descriptor),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checksum() {
+        assert_eq!(
+            "cjjspncu",
+            checksum("wpkh([d34db33f/84h/0h/0h]xpub6DJ2dNUysrn5Vt36jH2KLBT2i1auw1tTSSomg8PhqNiUtx8QX2SvC9nrHu81fT41fvDUnhMjEzQgXnQjKEu3oaqMSzhSrHMxyyoEAmUHQbY/0/*)")
+                .unwrap()
+        );
+        assert_eq!(
+            "89f8spxm",
+            checksum("raw(deadbeef)").unwrap()
+        );
+        assert!(checksum("raw(deadbeef)\u{7}").is_err());
+    }
+
+    #[test]
+    fn test_strip_checksum() {
+        assert_eq!(
+            "raw(deadbeef)",
+            strip_checksum("raw(deadbeef)#89f8spxm").unwrap()
+        );
+        assert_eq!("raw(deadbeef)", strip_checksum("raw(deadbeef)").unwrap());
+        assert!(strip_checksum("raw(deadbeef)#89f8spxn").is_err());
+        assert_eq!(
+            "raw(deadbeef)#89f8spxm",
+            with_checksum("raw(deadbeef)").unwrap()
+        );
+    }
+}
@@ -19,6 +19,7 @@ pub mod crypto_key_path;
 pub mod crypto_output;
 pub mod crypto_psbt;
 pub mod crypto_psbt_extend;
+pub mod descriptor;
 pub mod ergo;
 pub mod error;
 pub mod ethereum;
@@ -28,8 +29,10 @@ pub mod kaspa;
 pub mod keystone;
 mod macros;
 mod macros_impl;
+pub mod miniscript;
 pub mod monero;
 pub mod multi_key;
+pub mod multisig_wallet_config;
 pub mod near;
 pub mod pb;
 pub mod registry_types;
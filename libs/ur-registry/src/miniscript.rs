@@ -0,0 +1,338 @@
+use crate::cbor::{cbor_array, cbor_map};
+use crate::crypto_ec_key::CryptoECKey;
+use crate::crypto_hd_key::CryptoHDKey;
+use crate::registry_types::{CRYPTO_ECKEY, CRYPTO_HDKEY};
+use crate::traits::RegistryItem;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use minicbor::data::{Int, Tag};
+use minicbor::encode::Write;
+use minicbor::{Decoder, Encoder};
+
+const FRAGMENT_KEY: u8 = 1;
+const KEYS_KEY: u8 = 2;
+
+const KEY_FRAGMENTS: [&str; 4] = ["pk_k", "pk_h", "pk", "pkh"];
+const MULTI_FRAGMENTS: [&str; 2] = ["multi", "multi_a"];
+const LOCK_FRAGMENTS: [&str; 2] = ["older", "after"];
+const HASH_FRAGMENTS: [(&str, usize); 4] = [
+    ("sha256", 64),
+    ("hash256", 64),
+    ("ripemd160", 40),
+    ("hash160", 40),
+];
+const LOGIC_FRAGMENTS: [(&str, usize); 8] = [
+    ("andor", 3),
+    ("and_v", 2),
+    ("and_b", 2),
+    ("and_n", 2),
+    ("or_b", 2),
+    ("or_c", 2),
+    ("or_d", 2),
+    ("or_i", 2),
+];
+const WRAPPERS: &str = "asctdvjnlu";
+
+#[derive(Clone, Debug)]
+pub enum MiniscriptKey {
+    ECKey(CryptoECKey),
+    HDKey(CryptoHDKey),
+}
+
+/// A miniscript fragment such as `and_v(v:pk(@0),older(144))`, where `@N` refers to the
+/// N-th entry of `keys`.
+#[derive(Clone, Debug, Default)]
+pub struct Miniscript {
+    fragment: String,
+    keys: Vec<MiniscriptKey>,
+}
+
+struct Node<'a> {
+    name: &'a str,
+    args: Vec<Node<'a>>,
+}
+
+/// Fragments nest no deeper than a taproot script tree (BIP-341).
+const MAX_DEPTH: usize = 128;
+
+fn parse_node(input: &str, depth: usize) -> Result<(Node, &str), String> {
+    if depth > MAX_DEPTH {
+        return Err("Miniscript is nested too deeply".to_string());
+    }
+    let end = input.find(['(', ')', ',']).unwrap_or(input.len());
+    let name = input[..end].trim();
+    if name.is_empty() {
+        return Err(format!("Invalid miniscript near: {input}"));
+    }
+    let mut rest = &input[end..];
+    let mut args = vec![];
+    if let Some(inner) = rest.strip_prefix('(') {
+        rest = inner;
+        loop {
+            let (arg, remaining) = parse_node(rest, depth + 1)?;
+            args.push(arg);
+            match remaining.chars().next() {
+                Some(',') => rest = &remaining[1..],
+                Some(')') => {
+                    rest = &remaining[1..];
+                    break;
+                }
+                _ => return Err("Unbalanced parentheses in miniscript".to_string()),
+            }
+        }
+    }
+    Ok((Node { name, args }, rest))
+}
+
+impl Miniscript {
+    pub fn new(fragment: String, keys: Vec<MiniscriptKey>) -> Result<Self, String> {
+        let miniscript = Miniscript { fragment, keys };
+        miniscript.validate()?;
+        Ok(miniscript)
+    }
+
+    pub fn get_fragment(&self) -> String {
+        self.fragment.clone()
+    }
+
+    pub fn get_keys(&self) -> Vec<MiniscriptKey> {
+        self.keys.clone()
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        let (node, rest) = parse_node(&self.fragment, 0)?;
+        if !rest.is_empty() {
+            return Err(format!("Unexpected miniscript suffix: {rest}"));
+        }
+        let mut used = vec![false; self.keys.len()];
+        self.validate_node(&node, &mut used)?;
+        if used.iter().any(|used| !used) {
+            return Err("Miniscript has unused keys".to_string());
+        }
+        Ok(())
+    }
+
+    fn validate_key(&self, arg: &Node, used: &mut [bool]) -> Result<(), String> {
+        let index = arg
+            .name
+            .strip_prefix('@')
+            .and_then(|index| index.parse::<usize>().ok())
+            .filter(|index| *index < self.keys.len() && arg.args.is_empty())
+            .ok_or(format!("Invalid key placeholder: {}", arg.name))?;
+        used[index] = true;
+        Ok(())
+    }
+
+    fn validate_number(arg: &Node) -> Result<u32, String> {
+        arg.name
+            .parse::<u32>()
+            .ok()
+            .filter(|_| arg.args.is_empty())
+            .ok_or(format!("Invalid number: {}", arg.name))
+    }
+
+    fn validate_node(&self, node: &Node, used: &mut [bool]) -> Result<(), String> {
+        let name = match node.name.split_once(':') {
+            Some((wrappers, name)) => {
+                if wrappers.is_empty() || !wrappers.chars().all(|c| WRAPPERS.contains(c)) {
+                    return Err(format!("Invalid miniscript wrappers: {wrappers}"));
+                }
+                name
+            }
+            None => node.name,
+        };
+        let args = &node.args;
+        if name == "0" || name == "1" {
+            if !args.is_empty() {
+                return Err(format!("Invalid miniscript fragment: {}", node.name));
+            }
+            return Ok(());
+        }
+        if KEY_FRAGMENTS.contains(&name) && args.len() == 1 {
+            return self.validate_key(&args[0], used);
+        }
+        if MULTI_FRAGMENTS.contains(&name) && args.len() >= 2 {
+            let threshold = Self::validate_number(&args[0])? as usize;
+            if threshold == 0 || threshold > args.len() - 1 {
+                return Err(format!("Invalid threshold for {name}"));
+            }
+            return args[1..]
+                .iter()
+                .try_for_each(|arg| self.validate_key(arg, used));
+        }
+        if LOCK_FRAGMENTS.contains(&name) && args.len() == 1 {
+            let value = Self::validate_number(&args[0])?;
+            if value == 0 || value >= 0x80000000 {
+                return Err(format!("Invalid {name} value: {value}"));
+            }
+            return Ok(());
+        }
+        if let Some((_, len)) = HASH_FRAGMENTS.iter().find(|(n, _)| *n == name) {
+            let hash = args.first().map(|arg| arg.name).unwrap_or_default();
+            if args.len() != 1 || hash.len() != *len || hex::decode(hash).is_err() {
+                return Err(format!("Invalid {name} hash: {hash}"));
+            }
+            return Ok(());
+        }
+        if let Some((_, count)) = LOGIC_FRAGMENTS.iter().find(|(n, _)| *n == name) {
+            if args.len() != *count {
+                return Err(format!("{name} expects {count} arguments"));
+            }
+            return args
+                .iter()
+                .try_for_each(|arg| self.validate_node(arg, used));
+        }
+        if name == "thresh" && args.len() >= 2 {
+            let threshold = Self::validate_number(&args[0])? as usize;
+            if threshold == 0 || threshold > args.len() - 1 {
+                return Err("Invalid threshold for thresh".to_string());
+            }
+            return args[1..]
+                .iter()
+                .try_for_each(|arg| self.validate_node(arg, used));
+        }
+        Err(format!("Invalid miniscript fragment: {}", node.name))
+    }
+}
+
+impl<C> minicbor::Encode<C> for Miniscript {
+    fn encode<W: Write>(
+        &self,
+        e: &mut Encoder<W>,
+        ctx: &mut C,
+    ) -> Result<(), minicbor::encode::Error<W::Error>> {
+        e.map(2)?;
+        e.int(Int::from(FRAGMENT_KEY))?.str(&self.fragment)?;
+        e.int(Int::from(KEYS_KEY))?.array(self.keys.len() as u64)?;
+        for key in &self.keys {
+            match key {
+                MiniscriptKey::ECKey(ec_key) => {
+                    e.tag(Tag::Unassigned(CryptoECKey::get_registry_type().get_tag()))?;
+                    CryptoECKey::encode(ec_key, e, ctx)?;
+                }
+                MiniscriptKey::HDKey(hd_key) => {
+                    e.tag(Tag::Unassigned(CryptoHDKey::get_registry_type().get_tag()))?;
+                    CryptoHDKey::encode(hd_key, e, ctx)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'b, C> minicbor::Decode<'b, C> for Miniscript {
+    fn decode(d: &mut Decoder<'b>, ctx: &mut C) -> Result<Self, minicbor::decode::Error> {
+        let mut result = Miniscript::default();
+        cbor_map(d, &mut result, |key, obj, d| {
+            let key =
+                u8::try_from(key).map_err(|e| minicbor::decode::Error::message(e.to_string()))?;
+            match key {
+                FRAGMENT_KEY => {
+                    obj.fragment = d.str()?.to_string();
+                }
+                KEYS_KEY => {
+                    cbor_array(d, obj, |_index, obj, d| {
+                        match d.tag()? {
+                            Tag::Unassigned(n) if n == CRYPTO_ECKEY.get_tag() => {
+                                obj.keys
+                                    .push(MiniscriptKey::ECKey(CryptoECKey::decode(d, ctx)?));
+                            }
+                            Tag::Unassigned(n) if n == CRYPTO_HDKEY.get_tag() => {
+                                obj.keys
+                                    .push(MiniscriptKey::HDKey(CryptoHDKey::decode(d, ctx)?));
+                            }
+                            _ => {
+                                return Err(minicbor::decode::Error::message(
+                                    "invalid miniscript key",
+                                ))
+                            }
+                        }
+                        Ok(())
+                    })?;
+                }
+                _ => {}
+            }
+            Ok(())
+        })?;
+        result
+            .validate()
+            .map_err(minicbor::decode::Error::message)?;
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use hex::FromHex;
+
+    fn ec_key(byte: u8) -> MiniscriptKey {
+        let mut key = vec![0x02];
+        key.extend_from_slice(&[byte; 32]);
+        MiniscriptKey::ECKey(CryptoECKey::new(None, None, key))
+    }
+
+    #[test]
+    fn test_validate() {
+        let keys = vec![ec_key(1), ec_key(2), ec_key(3)];
+        assert!(Miniscript::new(
+            "or_d(multi(2,@0,@1),and_v(v:pk(@2),older(4032)))".to_string(),
+            keys.clone()
+        )
+        .is_ok());
+        assert!(Miniscript::new(
+            "andor(pk(@0),sha256(6c60f404f8167a38fc70eaf8aa17ac351023bef86bcb9d1086a19afe95bd5333),and_v(v:pkh(@1),after(1700000000)))".to_string(),
+            keys[..2].to_vec()
+        )
+        .is_ok());
+
+        assert_eq!(
+            "Invalid key placeholder: @3",
+            Miniscript::new("multi(2,@0,@3)".to_string(), keys.clone()).unwrap_err()
+        );
+        assert_eq!(
+            "Miniscript has unused keys",
+            Miniscript::new("pk(@0)".to_string(), keys.clone()).unwrap_err()
+        );
+        assert_eq!(
+            "Invalid threshold for multi",
+            Miniscript::new("multi(4,@0,@1,@2)".to_string(), keys.clone()).unwrap_err()
+        );
+        assert_eq!(
+            "Invalid miniscript fragment: xor",
+            Miniscript::new("xor(pk(@0),pk(@1))".to_string(), keys[..2].to_vec()).unwrap_err()
+        );
+        assert_eq!(
+            "Unbalanced parentheses in miniscript",
+            Miniscript::new("and_v(v:pk(@0),older(1)".to_string(), keys[..1].to_vec()).unwrap_err()
+        );
+
+        let nested = format!("{}pk(@0){}", "and_v(".repeat(200_000), ")".repeat(200_000));
+        assert_eq!(
+            "Miniscript is nested too deeply",
+            Miniscript::new(nested, keys[..1].to_vec()).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn test_encode_decode() {
+        let miniscript =
+            Miniscript::new("and_v(v:pk(@0),older(144))".to_string(), vec![ec_key(1)]).unwrap();
+        let bytes = minicbor::to_vec(miniscript.clone()).unwrap();
+        assert_eq!(
+            "a201781a616e645f7628763a706b284030292c6f6c64657228313434292902\
+            81d90132a103582102010101010101010101010101010101010101010101010101010101010101\
+            0101",
+            hex::encode(&bytes)
+        );
+        let decoded: Miniscript = minicbor::decode(&bytes).unwrap();
+        assert_eq!(miniscript.get_fragment(), decoded.get_fragment());
+        assert_eq!(1, decoded.get_keys().len());
+
+        let invalid = Vec::from_hex("a20167706b284030290280").unwrap();
+        assert!(minicbor::decode::<Miniscript>(&invalid).is_err());
+    }
+}
@@ -0,0 +1,791 @@
+use crate::crypto_coin_info::{CoinType, CryptoCoinInfo, Network};
+use crate::crypto_hd_key::CryptoHDKey;
+use crate::crypto_key_path::CryptoKeyPath;
+use crate::crypto_output::CryptoOutput;
+use crate::descriptor;
+use crate::multi_key::MultiKey;
+use crate::script_expression::ScriptExpression;
+use crate::types::Fingerprint;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::str::FromStr;
+
+const MAX_COSIGNERS: usize = 15;
+const BSMS_VERSION: &str = "BSMS 1.0";
+const BSMS_PATH_RESTRICTIONS: &str = "/0/*,/1/*";
+const BSMS_NO_PATH_RESTRICTIONS: &str = "No path restrictions";
+
+const MAINNET_VERSIONS: [[u8; 4]; 5] = [
+    [0x04, 0x88, 0xB2, 0x1E], // xpub
+    [0x04, 0x9D, 0x7C, 0xB2], // ypub
+    [0x04, 0xB2, 0x47, 0x46], // zpub
+    [0x02, 0x95, 0xB4, 0x3F], // Ypub
+    [0x02, 0xAA, 0x7E, 0xD3], // Zpub
+];
+const TESTNET_VERSIONS: [[u8; 4]; 5] = [
+    [0x04, 0x35, 0x87, 0xCF], // tpub
+    [0x04, 0x4A, 0x52, 0x62], // upub
+    [0x04, 0x5F, 0x1C, 0xF6], // vpub
+    [0x02, 0x42, 0x89, 0xEF], // Upub
+    [0x02, 0x57, 0x54, 0x83], // Vpub
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MultisigFormat {
+    P2sh,
+    P2shP2wsh,
+    P2wsh,
+}
+
+impl FromStr for MultisigFormat {
+    type Err = String;
+
+    fn from_str(format: &str) -> Result<Self, String> {
+        match format.trim().to_uppercase().as_str() {
+            "P2SH" => Ok(MultisigFormat::P2sh),
+            "P2SH-P2WSH" | "P2WSH-P2SH" => Ok(MultisigFormat::P2shP2wsh),
+            "P2WSH" => Ok(MultisigFormat::P2wsh),
+            x => Err(format!("Invalid multisig format: {x}")),
+        }
+    }
+}
+
+impl MultisigFormat {
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            MultisigFormat::P2sh => "P2SH",
+            MultisigFormat::P2shP2wsh => "P2SH-P2WSH",
+            MultisigFormat::P2wsh => "P2WSH",
+        }
+    }
+
+    pub fn get_script_expressions(&self) -> Vec<ScriptExpression> {
+        match self {
+            MultisigFormat::P2sh => vec![ScriptExpression::ScriptHash],
+            MultisigFormat::P2shP2wsh => vec![
+                ScriptExpression::ScriptHash,
+                ScriptExpression::WitnessScriptHash,
+            ],
+            MultisigFormat::P2wsh => vec![ScriptExpression::WitnessScriptHash],
+        }
+    }
+
+    fn from_script_expressions(expressions: &[ScriptExpression]) -> Result<Self, String> {
+        match expressions {
+            [ScriptExpression::ScriptHash] => Ok(MultisigFormat::P2sh),
+            [
+                ScriptExpression::ScriptHash,
+                ScriptExpression::WitnessScriptHash,
+            ] => Ok(MultisigFormat::P2shP2wsh),
+            [ScriptExpression::WitnessScriptHash] => Ok(MultisigFormat::P2wsh),
+            _ => Err("Unsupported multisig script expressions".to_string()),
+        }
+    }
+
+    fn wrap_descriptor(&self, multi: &str) -> String {
+        match self {
+            MultisigFormat::P2sh => format!("sh({multi})"),
+            MultisigFormat::P2shP2wsh => format!("sh(wsh({multi}))"),
+            MultisigFormat::P2wsh => format!("wsh({multi})"),
+        }
+    }
+}
+
+/// A multisig wallet setup shared between a coordinator and the Keystone device,
+/// built from the `CryptoHDKey` of every cosigner.
+#[derive(Clone, Debug)]
+pub struct MultisigWalletConfig {
+    name: String,
+    threshold: u32,
+    format: MultisigFormat,
+    sorted: bool,
+    network: Network,
+    cosigners: Vec<CryptoHDKey>,
+}
+
+impl MultisigWalletConfig {
+    pub fn new(
+        name: String,
+        threshold: u32,
+        format: MultisigFormat,
+        sorted: bool,
+        network: Network,
+        cosigners: Vec<CryptoHDKey>,
+    ) -> Result<Self, String> {
+        let config = MultisigWalletConfig {
+            name,
+            threshold,
+            format,
+            sorted,
+            network,
+            cosigners,
+        };
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn get_name(&self) -> String {
+        self.name.clone()
+    }
+    pub fn get_threshold(&self) -> u32 {
+        self.threshold
+    }
+    pub fn get_total(&self) -> u32 {
+        self.cosigners.len() as u32
+    }
+    pub fn get_format(&self) -> MultisigFormat {
+        self.format
+    }
+    pub fn is_sorted(&self) -> bool {
+        self.sorted
+    }
+    pub fn get_network(&self) -> Network {
+        self.network.clone()
+    }
+    pub fn get_cosigners(&self) -> Vec<CryptoHDKey> {
+        self.cosigners.clone()
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("Multisig wallet name is empty".to_string());
+        }
+        let total = self.cosigners.len();
+        if !(2..=MAX_COSIGNERS).contains(&total) {
+            return Err(format!("Invalid number of cosigners: {total}"));
+        }
+        if self.threshold == 0 || self.threshold as usize > total {
+            return Err(format!("Invalid policy: {} of {}", self.threshold, total));
+        }
+        for (index, cosigner) in self.cosigners.iter().enumerate() {
+            validate_cosigner(cosigner).map_err(|e| format!("Cosigner {}: {}", index + 1, e))?;
+            if self.cosigners[..index]
+                .iter()
+                .any(|other| other.get_key() == cosigner.get_key())
+            {
+                return Err(format!("Cosigner {}: duplicated key", index + 1));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn to_crypto_output(&self) -> CryptoOutput {
+        let mut script_expressions = self.format.get_script_expressions();
+        script_expressions.push(match self.sorted {
+            true => ScriptExpression::SortedMultiSig,
+            false => ScriptExpression::MultiSig,
+        });
+        CryptoOutput::new(
+            script_expressions,
+            None,
+            None,
+            Some(MultiKey::new(
+                self.threshold,
+                None,
+                Some(self.cosigners.clone()),
+            )),
+        )
+    }
+
+    pub fn from_crypto_output(name: String, output: &CryptoOutput) -> Result<Self, String> {
+        let mut script_expressions = output.get_script_expressions();
+        let sorted = match script_expressions.pop() {
+            Some(ScriptExpression::SortedMultiSig) => true,
+            Some(ScriptExpression::MultiSig) => false,
+            _ => return Err("Output is not a multisig output".to_string()),
+        };
+        let format = MultisigFormat::from_script_expressions(&script_expressions)?;
+        let multi_key = output
+            .get_multi_key()
+            .ok_or("Output has no multi key".to_string())?;
+        let cosigners = multi_key
+            .get_hd_keys()
+            .ok_or("Output has no extended keys".to_string())?;
+        let network = cosigners
+            .first()
+            .and_then(|key| key.get_use_info())
+            .map(|info| info.get_network())
+            .unwrap_or(Network::MainNet);
+        Self::new(
+            name,
+            multi_key.get_threshold(),
+            format,
+            sorted,
+            network,
+            cosigners,
+        )
+    }
+
+    /// Parses the "Name/Policy/Format/Derivation/xfp: xpub" text format used by
+    /// Keystone, Coldcard and most coordinators.
+    pub fn from_text(content: &str) -> Result<Self, String> {
+        let mut name: Option<String> = None;
+        let mut policy: Option<(u32, u32)> = None;
+        let mut format = MultisigFormat::P2sh;
+        let mut derivation: Option<String> = None;
+        let mut network: Option<Network> = None;
+        let mut cosigners: Vec<CryptoHDKey> = vec![];
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line
+                .split_once(':')
+                .ok_or(format!("Invalid line: {line}"))?;
+            let (key, value) = (key.trim(), value.trim());
+            match key.to_lowercase().as_str() {
+                "name" => name = Some(value.to_string()),
+                "policy" => policy = Some(parse_policy(value)?),
+                "format" => format = MultisigFormat::from_str(value)?,
+                "derivation" => derivation = Some(value.to_string()),
+                _ => {
+                    let xfp = parse_fingerprint(key)?;
+                    let path = derivation
+                        .as_ref()
+                        .ok_or(format!("Missing derivation for {key}"))?;
+                    let (cosigner, testnet) = parse_extended_key(value, xfp, path)?;
+                    let cosigner_network = if testnet {
+                        Network::TestNet
+                    } else {
+                        Network::MainNet
+                    };
+                    if network.get_or_insert(cosigner_network.clone()) != &cosigner_network {
+                        return Err("Cosigners are on different networks".to_string());
+                    }
+                    cosigners.push(cosigner);
+                }
+            }
+        }
+        let (threshold, total) = policy.ok_or("Missing policy".to_string())?;
+        if total as usize != cosigners.len() {
+            return Err(format!(
+                "Policy expects {} cosigners, found {}",
+                total,
+                cosigners.len()
+            ));
+        }
+        Self::new(
+            name.ok_or("Missing name".to_string())?,
+            threshold,
+            format,
+            true,
+            network.unwrap_or(Network::MainNet),
+            cosigners,
+        )
+    }
+
+    pub fn to_text(&self) -> Result<String, String> {
+        if !self.sorted {
+            return Err("Text format only supports sorted multisig".to_string());
+        }
+        let paths = self
+            .cosigners
+            .iter()
+            .map(format_derivation)
+            .collect::<Result<Vec<String>, String>>()?;
+        let shared_path = paths.windows(2).all(|pair| pair[0] == pair[1]);
+        let mut content = format!(
+            "Name: {}\nPolicy: {} of {}\n",
+            self.name,
+            self.threshold,
+            self.cosigners.len()
+        );
+        if shared_path {
+            content.push_str(&format!("Derivation: {}\n", paths[0]));
+        }
+        content.push_str(&format!("Format: {}\n", self.format.get_name()));
+        for (cosigner, path) in self.cosigners.iter().zip(paths.iter()) {
+            content.push('\n');
+            if !shared_path {
+                content.push_str(&format!("Derivation: {path}\n"));
+            }
+            content.push_str(&format!(
+                "{}: {}\n",
+                hex::encode_upper(source_fingerprint(cosigner)?),
+                self.encode_extended_key(cosigner)?
+            ));
+        }
+        Ok(content)
+    }
+
+    /// Parses a BIP-129 descriptor record, returning the config and the first address
+    /// listed in the record. The wallet name is not part of BSMS and must be supplied.
+    ///
+    /// The first address is returned as written and is not checked against the cosigners;
+    /// callers must derive the first receive address from the config and compare it before
+    /// trusting the record.
+    pub fn from_bsms(name: String, content: &str) -> Result<(Self, String), String> {
+        let lines = content
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<&str>>();
+        if lines.len() != 4 || lines[0] != BSMS_VERSION {
+            return Err("Invalid BSMS descriptor record".to_string());
+        }
+        if lines[2] != BSMS_PATH_RESTRICTIONS && lines[2] != BSMS_NO_PATH_RESTRICTIONS {
+            return Err(format!("Unsupported path restrictions: {}", lines[2]));
+        }
+        let body = descriptor::strip_checksum(lines[1])?;
+        let (format, inner) = if let Some(inner) = unwrap_call(body, "sh") {
+            match unwrap_call(inner, "wsh") {
+                Some(inner) => (MultisigFormat::P2shP2wsh, inner),
+                None => (MultisigFormat::P2sh, inner),
+            }
+        } else if let Some(inner) = unwrap_call(body, "wsh") {
+            (MultisigFormat::P2wsh, inner)
+        } else {
+            return Err("Unsupported descriptor script".to_string());
+        };
+        let (sorted, args) = match unwrap_call(inner, "sortedmulti") {
+            Some(args) => (true, args),
+            None => (
+                false,
+                unwrap_call(inner, "multi").ok_or("Descriptor is not a multisig".to_string())?,
+            ),
+        };
+        let mut args = args.split(',');
+        let threshold = args
+            .next()
+            .and_then(|v| v.trim().parse::<u32>().ok())
+            .ok_or("Invalid multisig threshold".to_string())?;
+        let mut network: Option<bool> = None;
+        let cosigners = args
+            .map(|key| {
+                let (cosigner, testnet) = parse_descriptor_key(key.trim())?;
+                if *network.get_or_insert(testnet) != testnet {
+                    return Err("Cosigners are on different networks".to_string());
+                }
+                Ok(cosigner)
+            })
+            .collect::<Result<Vec<CryptoHDKey>, String>>()?;
+        let network = match network {
+            Some(true) => Network::TestNet,
+            _ => Network::MainNet,
+        };
+        let config = Self::new(name, threshold, format, sorted, network, cosigners)?;
+        Ok((config, lines[3].to_string()))
+    }
+
+    pub fn to_descriptor(&self) -> Result<String, String> {
+        let keys = self
+            .cosigners
+            .iter()
+            .map(|cosigner| {
+                Ok(format!(
+                    "[{}/{}]{}/**",
+                    hex::encode(source_fingerprint(cosigner)?),
+                    cosigner
+                        .get_origin()
+                        .and_then(|origin| origin.get_path())
+                        .ok_or("Cosigner has no derivation path".to_string())?,
+                    self.encode_extended_key(cosigner)?
+                ))
+            })
+            .collect::<Result<Vec<String>, String>>()?;
+        let multi = format!(
+            "{}({},{})",
+            match self.sorted {
+                true => "sortedmulti",
+                false => "multi",
+            },
+            self.threshold,
+            keys.join(",")
+        );
+        descriptor::with_checksum(&self.format.wrap_descriptor(&multi))
+    }
+
+    /// Builds a BIP-129 descriptor record. The first receive address is derived by the
+    /// coordinator and passed in so that signers can verify it; it is written as given and
+    /// not checked against the cosigners.
+    pub fn to_bsms(&self, first_address: &str) -> Result<String, String> {
+        Ok(format!(
+            "{}\n{}\n{}\n{}",
+            BSMS_VERSION,
+            self.to_descriptor()?,
+            BSMS_PATH_RESTRICTIONS,
+            first_address
+        ))
+    }
+
+    fn encode_extended_key(&self, cosigner: &CryptoHDKey) -> Result<String, String> {
+        let origin = cosigner
+            .get_origin()
+            .ok_or("Cosigner has no derivation path".to_string())?;
+        let components = origin.get_components();
+        let index = components
+            .last()
+            .and_then(|component| component.get_canonical_index())
+            .unwrap_or(0);
+        let mut data = match self.network.is_test_network() {
+            true => TESTNET_VERSIONS[0].to_vec(),
+            false => MAINNET_VERSIONS[0].to_vec(),
+        };
+        data.push(components.len() as u8);
+        data.extend_from_slice(&cosigner.get_parent_fingerprint().unwrap_or([0; 4]));
+        data.extend_from_slice(&index.to_be_bytes());
+        data.extend_from_slice(
+            &cosigner
+                .get_chain_code()
+                .ok_or("Cosigner has no chain code".to_string())?,
+        );
+        data.extend_from_slice(&cosigner.get_key());
+        Ok(bs58::encode(data).with_check().into_string())
+    }
+}
+
+fn validate_cosigner(cosigner: &CryptoHDKey) -> Result<(), String> {
+    if cosigner.is_private_key() || cosigner.is_master() {
+        return Err("key must be an extended public key".to_string());
+    }
+    let key = cosigner.get_key();
+    if key.len() != 33 || (key[0] != 0x02 && key[0] != 0x03) {
+        return Err("invalid public key".to_string());
+    }
+    if cosigner.get_chain_code().map(|c| c.len()) != Some(32) {
+        return Err("invalid chain code".to_string());
+    }
+    let origin = cosigner
+        .get_origin()
+        .ok_or("missing derivation path".to_string())?;
+    if origin.get_source_fingerprint().is_none() {
+        return Err("missing master fingerprint".to_string());
+    }
+    if origin
+        .get_components()
+        .iter()
+        .any(|component| component.is_wildcard() || component.is_range() || component.is_pair())
+    {
+        return Err("derivation path must not contain wildcards or multipath steps".to_string());
+    }
+    Ok(())
+}
+
+fn source_fingerprint(cosigner: &CryptoHDKey) -> Result<Fingerprint, String> {
+    cosigner
+        .get_origin()
+        .and_then(|origin| origin.get_source_fingerprint())
+        .ok_or("Cosigner has no master fingerprint".to_string())
+}
+
+fn format_derivation(cosigner: &CryptoHDKey) -> Result<String, String> {
+    cosigner
+        .get_origin()
+        .and_then(|origin| origin.get_path())
+        .map(|path| format!("m/{path}"))
+        .ok_or("Cosigner has no derivation path".to_string())
+}
+
+fn parse_policy(policy: &str) -> Result<(u32, u32), String> {
+    let (threshold, total) = policy
+        .to_lowercase()
+        .split_once("of")
+        .map(|(m, n)| (m.trim().parse::<u32>(), n.trim().parse::<u32>()))
+        .ok_or(format!("Invalid policy: {policy}"))?;
+    match (threshold, total) {
+        (Ok(m), Ok(n)) => Ok((m, n)),
+        _ => Err(format!("Invalid policy: {policy}")),
+    }
+}
+
+fn parse_fingerprint(xfp: &str) -> Result<Fingerprint, String> {
+    let bytes = hex::decode(xfp).map_err(|_| format!("Invalid fingerprint: {xfp}"))?;
+    bytes
+        .as_slice()
+        .try_into()
+        .map_err(|_| format!("Invalid fingerprint: {xfp}"))
+}
+
+fn unwrap_call<'a>(expression: &'a str, name: &str) -> Option<&'a str> {
+    expression
+        .strip_prefix(name)?
+        .strip_prefix('(')?
+        .strip_suffix(')')
+}
+
+/// Parses `[xfp/path]xpub/**` (or with `/<0;1>/*` or no suffix) from a descriptor.
+fn parse_descriptor_key(key: &str) -> Result<(CryptoHDKey, bool), String> {
+    let (origin, xpub) = key
+        .strip_prefix('[')
+        .and_then(|key| key.split_once(']'))
+        .ok_or(format!("Key has no origin: {key}"))?;
+    let (xfp, path) = origin.split_once('/').unwrap_or((origin, ""));
+    let xpub = xpub
+        .strip_suffix("/**")
+        .or_else(|| xpub.strip_suffix("/<0;1>/*"))
+        .unwrap_or(xpub);
+    parse_extended_key(xpub, parse_fingerprint(xfp)?, path)
+}
+
+/// Decodes a base58 extended public key (any SLIP-132 version) into a `CryptoHDKey`
+/// whose origin is `path`, returning whether the key is a testnet key.
+fn parse_extended_key(
+    xpub: &str,
+    xfp: Fingerprint,
+    path: &str,
+) -> Result<(CryptoHDKey, bool), String> {
+    let data = bs58::decode(xpub)
+        .with_check(None)
+        .into_vec()
+        .map_err(|_| format!("Invalid extended public key: {xpub}"))?;
+    if data.len() != 78 {
+        return Err(format!("Invalid extended public key: {xpub}"));
+    }
+    let version: [u8; 4] = data[0..4].try_into().unwrap();
+    let testnet = if MAINNET_VERSIONS.contains(&version) {
+        false
+    } else if TESTNET_VERSIONS.contains(&version) {
+        true
+    } else {
+        return Err(format!("Unsupported extended public key version: {xpub}"));
+    };
+    let depth = data[4];
+    let parent_fingerprint: Fingerprint = data[5..9].try_into().unwrap();
+    let child_number = u32::from_be_bytes(data[9..13].try_into().unwrap());
+    let chain_code = data[13..45].to_vec();
+    let key = data[45..78].to_vec();
+
+    let origin = CryptoKeyPath::from_path(path.to_string(), Some(xfp))?;
+    let components = origin.get_components();
+    let expected_index = components
+        .last()
+        .and_then(|component| component.get_canonical_index())
+        .unwrap_or(0);
+    if components.len() != depth as usize || expected_index != child_number {
+        return Err(format!(
+            "Extended public key does not match derivation {path}"
+        ));
+    }
+    let network = if testnet {
+        Network::TestNet
+    } else {
+        Network::MainNet
+    };
+    let cosigner = CryptoHDKey::new_extended_key(
+        Some(false),
+        key,
+        Some(chain_code),
+        Some(CryptoCoinInfo::new(Some(CoinType::Bitcoin), Some(network))),
+        Some(CryptoKeyPath::new(
+            components,
+            Some(xfp),
+            Some(depth as u32),
+        )),
+        None,
+        Some(parent_fingerprint),
+        None,
+        None,
+    );
+    validate_cosigner(&cosigner)?;
+    Ok((cosigner, testnet))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto_key_path::PathComponent;
+    use alloc::vec;
+
+    fn cosigner(xfp: [u8; 4], key_byte: u8, account: u32) -> CryptoHDKey {
+        let mut key = vec![0x02];
+        key.extend_from_slice(&[key_byte; 32]);
+        CryptoHDKey::new_extended_key(
+            Some(false),
+            key,
+            Some(vec![key_byte ^ 0xff; 32]),
+            Some(CryptoCoinInfo::new(
+                Some(CoinType::Bitcoin),
+                Some(Network::MainNet),
+            )),
+            Some(CryptoKeyPath::new(
+                vec![
+                    PathComponent::new(Some(48), true).unwrap(),
+                    PathComponent::new(Some(0), true).unwrap(),
+                    PathComponent::new(Some(account), true).unwrap(),
+                    PathComponent::new(Some(2), true).unwrap(),
+                ],
+                Some(xfp),
+                Some(4),
+            )),
+            None,
+            Some([0x11, 0x22, 0x33, key_byte]),
+            None,
+            None,
+        )
+    }
+
+    fn config() -> MultisigWalletConfig {
+        MultisigWalletConfig::new(
+            "Family Vault".to_string(),
+            2,
+            MultisigFormat::P2wsh,
+            true,
+            Network::MainNet,
+            vec![
+                cosigner([0x73, 0xc5, 0xda, 0x0a], 1, 0),
+                cosigner([0xc4, 0x53, 0x58, 0xfa], 2, 0),
+                cosigner([0x52, 0x71, 0xc0, 0x71], 3, 0),
+            ],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_text_round_trip() {
+        let config = config();
+        let text = config.to_text().unwrap();
+        assert!(text.starts_with(
+            "Name: Family Vault\nPolicy: 2 of 3\nDerivation: m/48'/0'/0'/2'\nFormat: P2WSH\n\n73C5DA0A: xpub"
+        ));
+
+        let parsed = MultisigWalletConfig::from_text(&text).unwrap();
+        assert_eq!("Family Vault", parsed.get_name());
+        assert_eq!(2, parsed.get_threshold());
+        assert_eq!(3, parsed.get_total());
+        assert_eq!(MultisigFormat::P2wsh, parsed.get_format());
+        assert_eq!(Network::MainNet, parsed.get_network());
+        let cosigners = parsed.get_cosigners();
+        assert_eq!(config.get_cosigners()[1].get_key(), cosigners[1].get_key());
+        assert_eq!(
+            config.get_cosigners()[1].get_bip32_key(),
+            cosigners[1].get_bip32_key()
+        );
+        assert_eq!(
+            Some([0xc4, 0x53, 0x58, 0xfa]),
+            cosigners[1].get_origin().unwrap().get_source_fingerprint()
+        );
+        assert_eq!(text, parsed.to_text().unwrap());
+    }
+
+    #[test]
+    fn test_text_per_cosigner_derivation() {
+        let config = config();
+        let xpubs = config
+            .get_cosigners()
+            .iter()
+            .map(|c| c.get_bip32_key())
+            .collect::<Vec<String>>();
+        let text = format!(
+            "# Keystone Multisig setup file\n#\nName: Vault\nPolicy: 2 of 2\nFormat: P2WSH-P2SH\n\nDerivation: m/48h/0h/0h/2h\n73C5DA0A: {}\nDerivation: m/48'/0'/0'/2'\nc45358fa: {}\n",
+            xpubs[0], xpubs[1]
+        );
+        let parsed = MultisigWalletConfig::from_text(&text).unwrap();
+        assert_eq!(MultisigFormat::P2shP2wsh, parsed.get_format());
+        assert_eq!(2, parsed.get_total());
+
+        let wrong_depth = text.replace("m/48'/0'/0'/2'", "m/48'/0'/0'");
+        assert_eq!(
+            "Extended public key does not match derivation m/48'/0'/0'",
+            MultisigWalletConfig::from_text(&wrong_depth).unwrap_err()
+        );
+        let wrong_policy = text.replace("2 of 2", "3 of 2");
+        assert_eq!(
+            "Invalid policy: 3 of 2",
+            MultisigWalletConfig::from_text(&wrong_policy).unwrap_err()
+        );
+        let missing_cosigner = text.replace("2 of 2", "2 of 3");
+        assert_eq!(
+            "Policy expects 3 cosigners, found 2",
+            MultisigWalletConfig::from_text(&missing_cosigner).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn test_bsms_round_trip() {
+        let config = config();
+        let bsms = config
+            .to_bsms("bc1qxyz0000000000000000000000000000000000")
+            .unwrap();
+        let lines = bsms.lines().collect::<Vec<&str>>();
+        assert_eq!("BSMS 1.0", lines[0]);
+        assert!(lines[1].starts_with("wsh(sortedmulti(2,[73c5da0a/48'/0'/0'/2']xpub"));
+        assert_eq!("/0/*,/1/*", lines[2]);
+
+        let (parsed, address) =
+            MultisigWalletConfig::from_bsms("Imported".to_string(), &bsms).unwrap();
+        assert_eq!("bc1qxyz0000000000000000000000000000000000", address);
+        assert_eq!(
+            config.to_descriptor().unwrap(),
+            parsed.to_descriptor().unwrap()
+        );
+        assert!(parsed.is_sorted());
+
+        let tampered = bsms.replace("sortedmulti(2,", "sortedmulti(1,");
+        assert!(
+            MultisigWalletConfig::from_bsms("Imported".to_string(), &tampered)
+                .unwrap_err()
+                .starts_with("Invalid descriptor checksum")
+        );
+    }
+
+    #[test]
+    fn test_crypto_output_round_trip() {
+        let config = config();
+        let output = config.to_crypto_output();
+        assert_eq!(
+            vec![
+                ScriptExpression::WitnessScriptHash,
+                ScriptExpression::SortedMultiSig
+            ],
+            output.get_script_expressions()
+        );
+        let bytes: Vec<u8> = output.try_into().unwrap();
+        let decoded = CryptoOutput::try_from(bytes).unwrap();
+        let parsed =
+            MultisigWalletConfig::from_crypto_output("Family Vault".to_string(), &decoded).unwrap();
+        assert_eq!(config.to_text().unwrap(), parsed.to_text().unwrap());
+    }
+
+    #[test]
+    fn test_validate() {
+        let duplicated = MultisigWalletConfig::new(
+            "Vault".to_string(),
+            1,
+            MultisigFormat::P2sh,
+            true,
+            Network::MainNet,
+            vec![cosigner([1, 2, 3, 4], 1, 0), cosigner([1, 2, 3, 4], 1, 0)],
+        );
+        assert_eq!("Cosigner 2: duplicated key", duplicated.unwrap_err());
+
+        let mut private = cosigner([1, 2, 3, 4], 2, 0);
+        private = CryptoHDKey::new_extended_key(
+            Some(true),
+            private.get_key(),
+            private.get_chain_code(),
+            None,
+            private.get_origin(),
+            None,
+            None,
+            None,
+            None,
+        );
+        let with_private = MultisigWalletConfig::new(
+            "Vault".to_string(),
+            1,
+            MultisigFormat::P2sh,
+            true,
+            Network::MainNet,
+            vec![cosigner([1, 2, 3, 4], 1, 0), private],
+        );
+        assert_eq!(
+            "Cosigner 2: key must be an extended public key",
+            with_private.unwrap_err()
+        );
+
+        let unsorted = MultisigWalletConfig::new(
+            "Vault".to_string(),
+            1,
+            MultisigFormat::P2sh,
+            false,
+            Network::MainNet,
+            vec![cosigner([1, 2, 3, 4], 1, 0), cosigner([1, 2, 3, 5], 2, 0)],
+        )
+        .unwrap();
+        assert!(unsorted.to_text().is_err());
+        assert!(unsorted.to_descriptor().unwrap().starts_with("sh(multi(1,"));
+    }
+}
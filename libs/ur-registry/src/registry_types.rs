@@ -9,6 +9,7 @@ pub enum URType {
     CryptoAccount(String),
     EthSignRequest(String),
     EthBatchSignRequest(String),
+    EthBatchSignature(String),
     SolSignRequest(String),
     StellarSignRequest(String),
     NearSignRequest(String),
@@ -39,6 +40,7 @@ pub enum URType {
     AvaxSignRequest(String),
     IotaSignRequest(String),
     IotaSignHashRequest(String),
+    IotaSignature(String),
     ErgoSignRequest(String),
     SolSignature(String),
     TronSignRequest(String),
@@ -58,6 +60,7 @@ impl URType {
             "keystone-sign-request" => Ok(URType::KeystoneSignRequest(type_str.to_string())),
             "eth-sign-request" => Ok(URType::EthSignRequest(type_str.to_string())),
             "eth-batch-sign-request" => Ok(URType::EthBatchSignRequest(type_str.to_string())),
+            "eth-batch-signature" => Ok(URType::EthBatchSignature(type_str.to_string())),
             "sol-sign-request" => Ok(URType::SolSignRequest(type_str.to_string())),
             "stellar-sign-request" => Ok(URType::StellarSignRequest(type_str.to_string())),
             "arweave-sign-request" => Ok(URType::ArweaveSignRequest(type_str.to_string())),
@@ -95,6 +98,7 @@ impl URType {
             "avax-sign-request" => Ok(URType::AvaxSignRequest(type_str.to_string())),
             "iota-sign-hash-request" => Ok(URType::IotaSignHashRequest(type_str.to_string())),
             "iota-sign-request" => Ok(URType::IotaSignRequest(type_str.to_string())),
+            "iota-signature" => Ok(URType::IotaSignature(type_str.to_string())),
             "ergo-sign-request" => Ok(URType::ErgoSignRequest(type_str.to_string())),
             "sol-signature" => Ok(URType::SolSignature(type_str.to_string())),
             "kaspa-pskt" => Ok(URType::KaspaPskt(type_str.to_string())),
@@ -113,6 +117,7 @@ impl URType {
             URType::KeystoneSignRequest(type_str) => type_str.to_string(),
             URType::EthSignRequest(type_str) => type_str.to_string(),
             URType::EthBatchSignRequest(type_str) => type_str.to_string(),
+            URType::EthBatchSignature(type_str) => type_str.to_string(),
             URType::SolSignRequest(type_str) => type_str.to_string(),
             URType::StellarSignRequest(type_str) => type_str.to_string(),
             URType::NearSignRequest(type_str) => type_str.to_string(),
@@ -142,6 +147,7 @@ impl URType {
             URType::AvaxSignRequest(type_str) => type_str.to_string(),
             URType::IotaSignRequest(type_str) => type_str.to_string(),
             URType::IotaSignHashRequest(type_str) => type_str.to_string(),
+            URType::IotaSignature(type_str) => type_str.to_string(),
             URType::ErgoSignRequest(type_str) => type_str.to_string(),
             URType::SolSignature(type_str) => type_str.to_string(),
             URType::KaspaPskt(type_str) => type_str.to_string(),
@@ -65,6 +65,25 @@ const COSIGNER: ScriptExpressionValue = ScriptExpressionValue {
     tag_value: 410,
     expression: "cosigner",
 };
+// Keystone extensions, not assigned in BCR-2020-010. They are only read and written with the
+// `output-extensions` feature, so the default crypto-output encoding stays within the
+// registered tags.
+const RAW_TAPROOT: ScriptExpressionValue = ScriptExpressionValue {
+    tag_value: 411,
+    expression: "rawtr",
+};
+const MULTI_A: ScriptExpressionValue = ScriptExpressionValue {
+    tag_value: 412,
+    expression: "multi_a",
+};
+const SORTED_MULTI_A: ScriptExpressionValue = ScriptExpressionValue {
+    tag_value: 413,
+    expression: "sortedmulti_a",
+};
+const MINISCRIPT: ScriptExpressionValue = ScriptExpressionValue {
+    tag_value: 414,
+    expression: "miniscript",
+};
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum ScriptExpression {
@@ -80,10 +99,36 @@ pub enum ScriptExpression {
     RawScript,
     Taproot,
     Cosigner,
+    RawTaproot,
+    MultiA,
+    SortedMultiA,
+    Miniscript,
     Undefine(u64),
 }
 
 impl ScriptExpression {
+    /// Expressions whose key material is encoded as a `MultiKey`.
+    pub fn is_multi_key(&self) -> bool {
+        matches!(
+            self,
+            ScriptExpression::MultiSig
+                | ScriptExpression::SortedMultiSig
+                | ScriptExpression::MultiA
+                | ScriptExpression::SortedMultiA
+        )
+    }
+
+    /// Keystone extensions that have no tag assigned in BCR-2020-010.
+    pub fn is_extension(&self) -> bool {
+        matches!(
+            self,
+            ScriptExpression::RawTaproot
+                | ScriptExpression::MultiA
+                | ScriptExpression::SortedMultiA
+                | ScriptExpression::Miniscript
+        )
+    }
+
     pub fn from(tag_value: u64) -> Self {
         match tag_value {
             400 => ScriptExpression::ScriptHash,
@@ -98,6 +143,14 @@ impl ScriptExpression {
             408 => ScriptExpression::RawScript,
             409 => ScriptExpression::Taproot,
             410 => ScriptExpression::Cosigner,
+            #[cfg(feature = "output-extensions")]
+            411 => ScriptExpression::RawTaproot,
+            #[cfg(feature = "output-extensions")]
+            412 => ScriptExpression::MultiA,
+            #[cfg(feature = "output-extensions")]
+            413 => ScriptExpression::SortedMultiA,
+            #[cfg(feature = "output-extensions")]
+            414 => ScriptExpression::Miniscript,
             _ => ScriptExpression::Undefine(tag_value),
         }
     }
@@ -116,6 +169,10 @@ impl ScriptExpression {
             ScriptExpression::RawScript => RAW_SCRIPT.get_tag_value(),
             ScriptExpression::Taproot => TAPROOT.get_tag_value(),
             ScriptExpression::Cosigner => COSIGNER.get_tag_value(),
+            ScriptExpression::RawTaproot => RAW_TAPROOT.get_tag_value(),
+            ScriptExpression::MultiA => MULTI_A.get_tag_value(),
+            ScriptExpression::SortedMultiA => SORTED_MULTI_A.get_tag_value(),
+            ScriptExpression::Miniscript => MINISCRIPT.get_tag_value(),
             ScriptExpression::Undefine(tag_value) => *tag_value as u32,
         }
     }
@@ -134,6 +191,10 @@ impl ScriptExpression {
             ScriptExpression::RawScript => RAW_SCRIPT.get_expression(),
             ScriptExpression::Taproot => TAPROOT.get_expression(),
             ScriptExpression::Cosigner => COSIGNER.get_expression(),
+            ScriptExpression::RawTaproot => RAW_TAPROOT.get_expression(),
+            ScriptExpression::MultiA => MULTI_A.get_expression(),
+            ScriptExpression::SortedMultiA => SORTED_MULTI_A.get_expression(),
+            ScriptExpression::Miniscript => MINISCRIPT.get_expression(),
             ScriptExpression::Undefine(tag_value) => {
                 format!("tag value is {}, undefine expression", tag_value)
             }